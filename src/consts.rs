@@ -37,6 +37,23 @@ pub const NS_ACTION_UNIT_LIST_FILTER_CLEAR: &str = concat!(WIN, ACTION_UNIT_LIST
 pub const ACTION_UNIT_PROPERTIES_DISPLAY: &str = "unit_properties";
 pub const APP_ACTION_UNIT_PROPERTIES_DISPLAY: &str = concat!(APP, ACTION_UNIT_PROPERTIES_DISPLAY);
 
+pub const ACTION_TEMPLATE_INSTANCES: &str = "template_instances";
+pub const ACTION_TIMER_SCHEDULE: &str = "timer_schedule";
+pub const ACTION_TIMER_HISTORY: &str = "timer_history";
+pub const ACTION_JOURNAL_MAINTENANCE: &str = "journal_maintenance";
+pub const ACTION_COREDUMPS: &str = "coredumps";
+pub const ACTION_AUDIT_HISTORY: &str = "audit_history";
+pub const ACTION_BUS_CONNECTIONS: &str = "bus_connections";
+pub const ACTION_OTHER_USERS: &str = "other_users";
+pub const ACTION_LOGIN_SESSIONS: &str = "login_sessions";
+pub const ACTION_TARGETS: &str = "targets";
+pub const ACTION_MANAGER_ENVIRONMENT: &str = "manager_environment";
+pub const ACTION_NOTIFICATION_OPEN_UNIT: &str = "notification_open_unit";
+pub const APP_ACTION_NOTIFICATION_OPEN_UNIT: &str = concat!(APP, ACTION_NOTIFICATION_OPEN_UNIT);
+pub const ACTION_NOTIFICATION_OPEN_JOURNAL: &str = "notification_open_journal";
+pub const APP_ACTION_NOTIFICATION_OPEN_JOURNAL: &str =
+    concat!(APP, ACTION_NOTIFICATION_OPEN_JOURNAL);
+
 pub const ACTION_UNIT_LIST_FILTER: &str = "unit_list_filter";
 pub const NS_ACTION_UNIT_LIST_FILTER: &str = concat!(WIN, ACTION_UNIT_LIST_FILTER);
 
@@ -49,6 +66,7 @@ pub const WIN_ACTION_SAVE_UNIT_FILE: &str = concat!(WIN, ACTION_SAVE_UNIT_FILE);
 pub const CLASS_SUCCESS: &str = "success";
 pub const CLASS_WARNING: &str = "warning";
 pub const CLASS_ERROR: &str = "error";
+pub const CLASS_ACCENT: &str = "accent";
 
 pub const FILTER_MARK: char = '⭐';
 
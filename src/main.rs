@@ -26,7 +26,7 @@ use gtk::{
     prelude::*,
 };
 use std::env;
-use systemd::data::UnitInfo;
+use systemd::{backend::FixtureBackend, data::UnitInfo};
 use systemd_gui::new_settings;
 use tracing::{debug, error, info, warn};
 use tracing_subscriber::EnvFilter;
@@ -141,6 +141,12 @@ fn main() -> glib::ExitCode {
         info!("id {preferred_color_scheme_id:?} color {preferred_color_scheme:?}");
         style_manager.set_color_scheme(preferred_color_scheme);
         load_css(&style_manager);
+
+        widget::notifications::start_unit_monitor(application);
+        // the saved buses are host ones, not the fixture backend units
+        if systemd::backend::backend().is_live() {
+            widget::bus_connections::config::register_saved_bus_connections();
+        }
     });
 
     app.connect_activate(move |application| {
@@ -148,9 +154,11 @@ fn main() -> glib::ExitCode {
 
         //Start the Proxy after the app is loaded
         #[cfg(not(any(feature = "flatpak", feature = "appimage")))]
-        crate::systemd::runtime().spawn(async move {
-            systemd::init_proxy_async(run_mode).await;
-        });
+        if !args.fixture {
+            crate::systemd::runtime().spawn(async move {
+                systemd::init_proxy_async(run_mode).await;
+            });
+        }
     });
 
     //to not transfer args to gtk4
@@ -262,6 +270,10 @@ struct Args {
     #[arg(short, long, default_value_t = false)]
     create: bool,
 
+    /// Show in-memory demo units instead of the systemd ones (for demos and screenshots)
+    #[arg(long, default_value_t = false)]
+    fixture: bool,
+
     #[command(subcommand)]
     command: Option<Command>,
 }
@@ -289,6 +301,10 @@ fn handle_args() -> (Option<UnitInfo>, UnitDBusLevel, RunMode, Args) {
 
     let run_mode = RunMode::from_flags(args.dev, args.normal);
 
+    if args.fixture {
+        systemd::backend::set_backend(Box::new(FixtureBackend::default()));
+    }
+
     let current_level = PREFERENCES.dbus_level();
 
     debug!("Current level: {current_level:?}");
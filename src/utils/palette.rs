@@ -179,3 +179,22 @@ pub fn blue() -> Palette<'static> {
 pub fn dark_blue() -> Palette<'static> {
     Palette::Blue2
 }
+
+/// Distinct colors to tell apart the units of a merged journal
+pub fn unit_tag(idx: u8) -> Palette<'static> {
+    let dark = is_dark();
+    match (idx % 6, dark) {
+        (0, true) => Palette::Blue2,
+        (0, false) => Palette::Blue4,
+        (1, true) => Palette::Green2,
+        (1, false) => Palette::Green5,
+        (2, true) => Palette::Orange2,
+        (2, false) => Palette::Orange4,
+        (3, true) => Palette::Purple2,
+        (3, false) => Palette::Purple4,
+        (4, true) => Palette::Brown2,
+        (4, false) => Palette::Brown4,
+        (_, true) => Palette::Yellow2,
+        (_, false) => Palette::Yellow5,
+    }
+}
@@ -1,27 +1,44 @@
 use crate::{
     consts::{
-        ACTION_APP_CREATE_UNIT, ACTION_APP_PROPERTIES_SELECTOR, ACTION_DAEMON_RELOAD,
-        ACTION_FIND_IN_TEXT_TOGGLE, ACTION_LIST_BOOT, ACTION_PROPERTIES_SELECTOR_GENERAL,
+        ACTION_APP_CREATE_UNIT, ACTION_APP_PROPERTIES_SELECTOR, ACTION_AUDIT_HISTORY,
+        ACTION_BUS_CONNECTIONS, ACTION_COREDUMPS, ACTION_DAEMON_RELOAD, ACTION_FIND_IN_TEXT_TOGGLE,
+        ACTION_JOURNAL_MAINTENANCE, ACTION_LIST_BOOT, ACTION_LOGIN_SESSIONS,
+        ACTION_MANAGER_ENVIRONMENT, ACTION_OTHER_USERS, ACTION_PROPERTIES_SELECTOR_GENERAL,
+        ACTION_TARGETS, ACTION_TEMPLATE_INSTANCES, ACTION_TIMER_HISTORY, ACTION_TIMER_SCHEDULE,
         ACTION_UNIT_PROPERTIES_DISPLAY, ACTION_WIN_CHANGE_BUS, APP_ACTION_LIST_BOOT,
         APP_ACTION_PROPERTIES_SELECTOR_GENERAL, APP_ACTION_SEARCH_UNITS,
         APP_ACTION_UNIT_PROPERTIES_DISPLAY, WIN_ACTION_SAVE_UNIT_FILE,
     },
+    format2,
     systemd::{data::UnitInfo, journal_data::Boot},
     systemd_gui::{self},
     widget::{
         InterPanelMessage,
+        audit_history::AuditHistoryWindow,
+        bus_connections::BusConnectionsWindow,
+        coredumps::CoredumpsWindow,
         creator::UnitCreatorWindow,
+        disable_host_only_actions,
         info_window::InfoWindow,
         journal::list_boots::ListBootsWindow,
+        journal_maintenance::JournalMaintenanceWindow,
+        login_sessions::LoginSessionsWindow,
+        manager_environment::ManagerEnvironmentWindow,
+        other_users::OtherUsersWindow,
         preferences::data::{DbusLevel, KEY_PREF_ORIENTATION_MODE, OrientationMode, PREFERENCES},
         replace_tags,
         signals_dialog::SignalsWindow,
+        targets::TargetsWindow,
+        template_instances::TemplateInstancesWindow,
+        timer_history::TimerHistoryWindow,
+        timer_schedule::TimerScheduleWindow,
         unit_control_panel::UnitControlPanel,
         unit_list::{UnitCuratedList, UnitListPanel},
         unit_properties_selector::UnitPropertiesSelectorDialog,
     },
 };
 use adw::subclass::prelude::*;
+use gettextrs::pgettext;
 use glib::{self, VariantTy, closure::IntoClosureReturnValue, types::StaticType, value::ToValue};
 use gtk::{
     gio::{self, prelude::*},
@@ -582,6 +599,164 @@ impl AppWindowImpl {
                 .build()
         };
 
+        let template_instances = {
+            let app_window = self.obj().clone();
+
+            gio::ActionEntry::builder(ACTION_TEMPLATE_INSTANCES)
+                .activate(move |_, _action, _variant| {
+                    let Some(selected_unit) = app_window.selected_unit() else {
+                        warn!("Can't display template instances, No unit selected");
+                        return;
+                    };
+
+                    match TemplateInstancesWindow::new(&app_window, &selected_unit) {
+                        Ok(window) => window.present(),
+                        Err(err) => {
+                            warn!("Template instances {err:?}");
+                            let msg = format2!(
+                                pgettext(
+                                    "template",
+                                    "<unit>{}</unit> is not a template or an instance unit"
+                                ),
+                                selected_unit.primary()
+                            );
+                            app_window.add_toast_message(&msg, true, None);
+                        }
+                    }
+                })
+                .build()
+        };
+
+        let timer_schedule = {
+            let app_window = self.obj().clone();
+
+            gio::ActionEntry::builder(ACTION_TIMER_SCHEDULE)
+                .activate(move |_, _action, _variant| {
+                    let window = TimerScheduleWindow::new(&app_window);
+                    window.present();
+                })
+                .build()
+        };
+
+        let coredumps = {
+            let app_window = self.obj().clone();
+
+            gio::ActionEntry::builder(ACTION_COREDUMPS)
+                .activate(move |_, _action, _variant| {
+                    // Focus on the selected unit if it crashed recently
+                    let unit_filter = app_window
+                        .selected_unit()
+                        .filter(|unit| unit.crashes() > 0)
+                        .map(|unit| unit.primary());
+
+                    let window = CoredumpsWindow::new(&app_window, unit_filter);
+                    window.present();
+                })
+                .build()
+        };
+
+        let audit_history = {
+            let app_window = self.obj().clone();
+
+            gio::ActionEntry::builder(ACTION_AUDIT_HISTORY)
+                .activate(move |_, _action, _variant| {
+                    let window = AuditHistoryWindow::new(&app_window);
+                    window.present();
+                })
+                .build()
+        };
+
+        let bus_connections = {
+            let app_window = self.obj().clone();
+
+            gio::ActionEntry::builder(ACTION_BUS_CONNECTIONS)
+                .activate(move |_, _action, _variant| {
+                    let window = BusConnectionsWindow::new(&app_window);
+                    window.present();
+                })
+                .build()
+        };
+
+        let other_users = {
+            let app_window = self.obj().clone();
+
+            gio::ActionEntry::builder(ACTION_OTHER_USERS)
+                .activate(move |_, _action, _variant| {
+                    let window = OtherUsersWindow::new(&app_window);
+                    window.present();
+                })
+                .build()
+        };
+
+        let login_sessions = {
+            let app_window = self.obj().clone();
+
+            gio::ActionEntry::builder(ACTION_LOGIN_SESSIONS)
+                .activate(move |_, _action, _variant| {
+                    let window = LoginSessionsWindow::new(&app_window);
+                    window.present();
+                })
+                .build()
+        };
+
+        let targets = {
+            let app_window = self.obj().clone();
+
+            gio::ActionEntry::builder(ACTION_TARGETS)
+                .activate(move |_, _action, _variant| {
+                    let window = TargetsWindow::new(&app_window);
+                    window.present();
+                })
+                .build()
+        };
+
+        let manager_environment = {
+            let app_window = self.obj().clone();
+
+            gio::ActionEntry::builder(ACTION_MANAGER_ENVIRONMENT)
+                .activate(move |_, _action, _variant| {
+                    let window = ManagerEnvironmentWindow::new(&app_window);
+                    window.present();
+                })
+                .build()
+        };
+
+        let journal_maintenance = {
+            let app_window = self.obj().clone();
+
+            gio::ActionEntry::builder(ACTION_JOURNAL_MAINTENANCE)
+                .activate(move |_, _action, _variant| {
+                    let window = JournalMaintenanceWindow::new(&app_window);
+                    window.present();
+                })
+                .build()
+        };
+
+        let timer_history = {
+            let app_window = self.obj().clone();
+
+            gio::ActionEntry::builder(ACTION_TIMER_HISTORY)
+                .activate(move |_, _action, _variant| {
+                    let Some(selected_unit) = app_window.selected_unit() else {
+                        warn!("Can't display timer history, No unit selected");
+                        return;
+                    };
+
+                    match TimerHistoryWindow::new(&app_window, &selected_unit) {
+                        Ok(window) => window.present(),
+                        Err(err) => {
+                            warn!("Timer history {err:?}");
+                            let msg = format2!(
+                                pgettext("timer history", "<unit>{}</unit> is not a timer"),
+                                selected_unit.primary()
+                            );
+                            app_window.add_toast_message(&msg, true, None);
+                        }
+                    }
+                })
+                .build()
+        };
+
         const ACTION_APP_QUIT: &str = "app.quit";
 
         let quit = gio::ActionEntry::builder(&ACTION_APP_QUIT[4..])
@@ -601,9 +776,37 @@ impl AppWindowImpl {
             print_debug,
             display_unit_properties,
             create_unit,
+            template_instances,
+            timer_schedule,
+            timer_history,
+            journal_maintenance,
+            coredumps,
+            audit_history,
+            bus_connections,
+            other_users,
+            login_sessions,
+            targets,
+            manager_environment,
             quit,
         ]);
 
+        disable_host_only_actions(
+            application,
+            &[
+                ACTION_LIST_BOOT,
+                ACTION_TIMER_SCHEDULE,
+                ACTION_TIMER_HISTORY,
+                ACTION_JOURNAL_MAINTENANCE,
+                ACTION_COREDUMPS,
+                ACTION_AUDIT_HISTORY,
+                ACTION_BUS_CONNECTIONS,
+                ACTION_OTHER_USERS,
+                ACTION_LOGIN_SESSIONS,
+                ACTION_TARGETS,
+                ACTION_MANAGER_ENVIRONMENT,
+            ],
+        );
+
         application.set_accels_for_action(APP_ACTION_SEARCH_UNITS, &["<Ctrl>f"]);
         application.set_accels_for_action("app.open_info", &["<Ctrl>t"]);
         application.set_accels_for_action("app.open_dependencies", &["<Ctrl>d"]);
@@ -3,7 +3,7 @@ use std::env;
 use std::fmt::Write;
 
 use crate::consts::ACTION_DAEMON_RELOAD_BUS;
-use crate::widget::close_window_shortcut;
+use crate::widget::{close_window_shortcut, disable_host_only_actions};
 use crate::{
     analyze::build_analyze_window,
     consts::ACTION_DAEMON_RELOAD,
@@ -218,6 +218,8 @@ pub fn on_startup(app: &adw::Application) {
         signals,
         daemon_reload_all_units_with_bus,
     ]);
+
+    disable_host_only_actions(app, &["analyze_blame", "systemd_info", "signals"]);
 }
 
 fn daemon_reload_with_dialog(
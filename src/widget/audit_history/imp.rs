@@ -0,0 +1,276 @@
+use super::AuditHistoryWindow;
+use crate::{
+    format2,
+    systemd::{self, journal_data::AuditEntry},
+    widget::{
+        close_window_shortcut,
+        preferences::data::PREFERENCES,
+        timer_history::{Getter, set_column_factory},
+    },
+};
+use adw::subclass::window::AdwWindowImpl;
+use gettextrs::pgettext;
+use gtk::{
+    gio,
+    glib::{self, BoxedAnyObject},
+    prelude::*,
+    subclass::{
+        prelude::*,
+        widget::{
+            CompositeTemplateCallbacksClass, CompositeTemplateClass,
+            CompositeTemplateInitializingExt, WidgetImpl,
+        },
+    },
+};
+use std::cell::{OnceCell, Ref};
+use tracing::warn;
+
+/// Most recent audit entries listed in the window
+const MAX_AUDIT_ENTRIES: usize = 2000;
+/// Hash characters displayed
+const SHORT_HASH_LEN: usize = 12;
+
+#[derive(Default, gtk::CompositeTemplate)]
+#[template(resource = "/io/github/plrigaux/sysd-manager/audit_history.ui")]
+pub struct AuditHistoryWindowImp {
+    #[template_child]
+    summary_label: TemplateChild<gtk::Label>,
+
+    #[template_child]
+    search_entry: TemplateChild<gtk::SearchEntry>,
+
+    #[template_child]
+    spinner: TemplateChild<adw::Spinner>,
+
+    #[template_child]
+    filter_list_model: TemplateChild<gtk::FilterListModel>,
+
+    #[template_child]
+    time_column: TemplateChild<gtk::ColumnViewColumn>,
+
+    #[template_child]
+    caller_column: TemplateChild<gtk::ColumnViewColumn>,
+
+    #[template_child]
+    pid_column: TemplateChild<gtk::ColumnViewColumn>,
+
+    #[template_child]
+    method_column: TemplateChild<gtk::ColumnViewColumn>,
+
+    #[template_child]
+    unit_column: TemplateChild<gtk::ColumnViewColumn>,
+
+    #[template_child]
+    file_column: TemplateChild<gtk::ColumnViewColumn>,
+
+    #[template_child]
+    content_column: TemplateChild<gtk::ColumnViewColumn>,
+
+    #[template_child]
+    authorized_column: TemplateChild<gtk::ColumnViewColumn>,
+
+    #[template_child]
+    result_column: TemplateChild<gtk::ColumnViewColumn>,
+
+    store: OnceCell<gio::ListStore>,
+    filter: OnceCell<gtk::CustomFilter>,
+}
+
+#[gtk::template_callbacks]
+impl AuditHistoryWindowImp {
+    #[template_callback]
+    fn refresh_button_clicked(&self, _button: gtk::Button) {
+        self.refresh();
+    }
+
+    #[template_callback]
+    fn search_entry_changed(&self, _entry: gtk::SearchEntry) {
+        if let Some(filter) = self.filter.get() {
+            filter.changed(gtk::FilterChange::Different);
+        }
+    }
+
+    pub(super) fn refresh(&self) {
+        let window = self.obj().clone();
+
+        self.spinner.set_visible(true);
+        glib::spawn_future_local(async move {
+            let result =
+                gio::spawn_blocking(|| systemd::list_audit_entries(MAX_AUDIT_ENTRIES)).await;
+
+            let imp = window.imp();
+            imp.spinner.set_visible(false);
+
+            let entries = match result {
+                Ok(Ok(entries)) => entries,
+                Ok(Err(err)) => {
+                    warn!("Audit entries {err:?}");
+                    imp.summary_label.set_text(&err.human_error_type());
+                    return;
+                }
+                Err(err) => {
+                    warn!("Audit entries join {err:?}");
+                    return;
+                }
+            };
+
+            let denied = entries.iter().filter(|entry| !entry.authorized).count();
+            let failed = entries
+                .iter()
+                .filter(|entry| entry.authorized && !entry.is_success())
+                .count();
+
+            imp.summary_label.set_text(&format2!(
+                //audit summary
+                pgettext("audit", "{} calls through the proxy, {} denied, {} failed"),
+                entries.len(),
+                denied,
+                failed
+            ));
+
+            let store = imp.store.get().expect("store set");
+            store.remove_all();
+            for entry in entries {
+                store.append(&BoxedAnyObject::new(entry));
+            }
+        });
+    }
+
+    fn setup_factories(&self) {
+        let timestamp_style = PREFERENCES.timestamp_style();
+
+        let columns: [(&gtk::ColumnViewColumn, Getter<AuditEntry>); 9] = [
+            (
+                &self.time_column,
+                Box::new(move |entry: &AuditEntry| timestamp_style.usec_formated(entry.timestamp)),
+            ),
+            (
+                &self.caller_column,
+                Box::new(|entry: &AuditEntry| caller_name(entry.caller_uid)),
+            ),
+            (
+                &self.pid_column,
+                Box::new(|entry: &AuditEntry| {
+                    entry
+                        .caller_pid
+                        .map(|pid| pid.to_string())
+                        .unwrap_or_default()
+                }),
+            ),
+            (
+                &self.method_column,
+                Box::new(|entry: &AuditEntry| entry.method.clone()),
+            ),
+            (
+                &self.unit_column,
+                Box::new(|entry: &AuditEntry| entry.unit.clone()),
+            ),
+            (
+                &self.file_column,
+                Box::new(|entry: &AuditEntry| entry.file_path.clone()),
+            ),
+            (&self.content_column, Box::new(content_change)),
+            (
+                &self.authorized_column,
+                Box::new(|entry: &AuditEntry| {
+                    if entry.authorized {
+                        pgettext("audit", "Yes")
+                    } else {
+                        pgettext("audit", "No")
+                    }
+                }),
+            ),
+            (
+                &self.result_column,
+                Box::new(|entry: &AuditEntry| entry.result.clone()),
+            ),
+        ];
+
+        for (column, getter) in columns {
+            set_column_factory(column, getter, |entry: &AuditEntry| !entry.is_success());
+        }
+    }
+}
+
+#[glib::object_subclass]
+impl ObjectSubclass for AuditHistoryWindowImp {
+    const NAME: &'static str = "AuditHistoryWindow";
+    type Type = AuditHistoryWindow;
+    type ParentType = adw::Window;
+
+    fn class_init(klass: &mut Self::Class) {
+        klass.bind_template();
+        klass.bind_template_callbacks();
+    }
+
+    fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+        obj.init_template();
+    }
+}
+
+impl ObjectImpl for AuditHistoryWindowImp {
+    fn constructed(&self) {
+        self.parent_constructed();
+        close_window_shortcut(self.obj().as_ref());
+
+        let store = gio::ListStore::new::<BoxedAnyObject>();
+
+        let search_entry = self.search_entry.downgrade();
+        let filter = gtk::CustomFilter::new(move |object| {
+            let Some(text) = search_entry.upgrade().map(|entry| entry.text()) else {
+                return true;
+            };
+            if text.is_empty() {
+                return true;
+            }
+
+            let Some(entry) = object.downcast_ref::<BoxedAnyObject>() else {
+                return false;
+            };
+            let entry: Ref<AuditEntry> = entry.borrow();
+            entry.matches(&text)
+        });
+
+        self.filter_list_model.set_model(Some(&store));
+        self.filter_list_model.set_filter(Some(&filter));
+        let _ = self.store.set(store);
+        let _ = self.filter.set(filter);
+
+        self.setup_factories();
+    }
+}
+
+impl WidgetImpl for AuditHistoryWindowImp {}
+impl WindowImpl for AuditHistoryWindowImp {}
+impl AdwWindowImpl for AuditHistoryWindowImp {}
+
+fn caller_name(uid: Option<u32>) -> String {
+    let Some(uid) = uid else {
+        return String::new();
+    };
+
+    match uzers::get_user_by_uid(uid) {
+        Some(user) => format!("{} ({uid})", user.name().to_string_lossy()),
+        None => uid.to_string(),
+    }
+}
+
+fn short_hash(hash: &str) -> &str {
+    &hash[..hash.len().min(SHORT_HASH_LEN)]
+}
+
+fn content_change(entry: &AuditEntry) -> String {
+    if entry.content_sha256.is_empty() {
+        return String::new();
+    }
+
+    if entry.previous_sha256.is_empty() {
+        return format!("∅ → {}", short_hash(&entry.content_sha256));
+    }
+
+    format!(
+        "{} → {}",
+        short_hash(&entry.previous_sha256),
+        short_hash(&entry.content_sha256)
+    )
+}
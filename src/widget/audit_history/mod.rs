@@ -0,0 +1,25 @@
+mod imp;
+
+use crate::widget::app_window::AppWindow;
+use gtk::{
+    glib::{self},
+    prelude::*,
+    subclass::prelude::ObjectSubclassIsExt,
+};
+
+/// Window listing the privileged calls made through the proxy, from the journal
+glib::wrapper! {
+    pub struct AuditHistoryWindow(ObjectSubclass<imp::AuditHistoryWindowImp>)
+    @extends adw::Window, gtk::Window, gtk::Widget,
+    @implements gtk::Accessible,  gtk::Buildable,  gtk::ConstraintTarget,
+    gtk::Native, gtk::Root, gtk::ShortcutManager;
+}
+
+impl AuditHistoryWindow {
+    pub fn new(app_window: &AppWindow) -> Self {
+        let obj: AuditHistoryWindow = glib::Object::new();
+        obj.set_transient_for(Some(app_window));
+        obj.imp().refresh();
+        obj
+    }
+}
@@ -0,0 +1,114 @@
+use crate::widget::unit_properties_selector::save::{
+    get_sysd_manager_config_dir, save_to_toml_file,
+};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use tracing::{error, info, warn};
+
+const BUS_CONNECTIONS: &str = "bus_connections.toml";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct BusConnectionsConfig {
+    pub connections: Vec<BusConnection>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct BusConnection {
+    pub label: String,
+    pub address: String,
+}
+
+/// Register the saved connections, to call before the first unit listing
+pub fn register_saved_bus_connections() {
+    for connection in load_bus_connections_config().connections {
+        if let Err(err) =
+            systemd::custom_bus::add_custom_bus(&connection.label, &connection.address)
+        {
+            warn!("Saved bus connection {:?} {err:?}", connection.label);
+        }
+    }
+}
+
+/// Save the registered connections
+pub fn save_bus_connections() {
+    let connections = systemd::custom_bus::custom_buses()
+        .into_iter()
+        .map(|bus| BusConnection {
+            label: bus.label,
+            address: bus.address,
+        })
+        .collect();
+
+    systemd::runtime().spawn(save_bus_connections_config_async(BusConnectionsConfig {
+        connections,
+    }));
+}
+
+async fn save_bus_connections_config_async(config: BusConnectionsConfig) {
+    let sysd_manager_config_dir = get_sysd_manager_config_dir();
+
+    if let Err(e) = tokio::fs::create_dir_all(&sysd_manager_config_dir).await {
+        error!(
+            "Failed to create config directory {:?}: {}",
+            sysd_manager_config_dir, e
+        );
+        return;
+    }
+
+    let config_path = sysd_manager_config_dir.join(BUS_CONNECTIONS);
+
+    if let Err(e) = save_to_toml_file(&config, &config_path).await {
+        error!(
+            "Failed to save bus connections to TOML file: {:?} {:?}",
+            config_path, e
+        );
+    } else {
+        info!("Bus connections saved to {:?}", config_path);
+    }
+}
+
+fn load_bus_connections_config() -> BusConnectionsConfig {
+    let config_path = get_sysd_manager_config_dir().join(BUS_CONNECTIONS);
+
+    if !config_path.exists() {
+        return BusConnectionsConfig::default();
+    }
+
+    match fs::read_to_string(&config_path) {
+        Ok(toml_str) => match toml::from_str::<BusConnectionsConfig>(&toml_str) {
+            Ok(config) => config,
+            Err(e) => {
+                error!("Failed to parse TOML from {:?}: {}", config_path, e);
+                BusConnectionsConfig::default()
+            }
+        },
+        Err(e) => {
+            error!("Failed to read {:?}: {}", config_path, e);
+            BusConnectionsConfig::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bus_connections_toml() {
+        let config = BusConnectionsConfig {
+            connections: vec![BusConnection {
+                label: "web".to_owned(),
+                address: "unix:path=/var/lib/machines/web/run/dbus/system_bus_socket".to_owned(),
+            }],
+        };
+
+        let toml_str = toml::to_string(&config).unwrap();
+        let parsed: BusConnectionsConfig = toml::from_str(&toml_str).unwrap();
+        assert_eq!(parsed, config);
+
+        let empty: BusConnectionsConfig = toml::from_str("").unwrap();
+        assert!(empty.connections.is_empty());
+    }
+}
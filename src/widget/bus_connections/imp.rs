@@ -0,0 +1,190 @@
+use super::{BusConnectionsWindow, config};
+use crate::{
+    consts::{ACTION_WIN_REFRESH_UNIT_LIST, DESTRUCTIVE_ACTION, FLAT},
+    format2,
+    systemd::{self, custom_bus::CustomBus},
+    upgrade,
+    widget::{app_window::AppWindow, close_window_shortcut},
+};
+use adw::{prelude::*, subclass::window::AdwWindowImpl};
+use gettextrs::pgettext;
+use gtk::{
+    glib::{self},
+    subclass::{
+        prelude::*,
+        widget::{
+            CompositeTemplateCallbacksClass, CompositeTemplateClass,
+            CompositeTemplateInitializingExt, WidgetImpl,
+        },
+    },
+};
+use std::cell::{OnceCell, RefCell};
+use tracing::warn;
+
+#[derive(Default, gtk::CompositeTemplate)]
+#[template(resource = "/io/github/plrigaux/sysd-manager/bus_connections.ui")]
+pub struct BusConnectionsWindowImp {
+    #[template_child]
+    connections_group: TemplateChild<adw::PreferencesGroup>,
+
+    #[template_child]
+    label_row: TemplateChild<adw::EntryRow>,
+
+    #[template_child]
+    address_row: TemplateChild<adw::EntryRow>,
+
+    #[template_child]
+    status_label: TemplateChild<gtk::Label>,
+
+    app_window: OnceCell<AppWindow>,
+    rows: RefCell<Vec<adw::ActionRow>>,
+}
+
+#[gtk::template_callbacks]
+impl BusConnectionsWindowImp {
+    pub(super) fn set_app_window(&self, app_window: &AppWindow) {
+        self.app_window
+            .set(app_window.clone())
+            .expect("app_window set once");
+    }
+
+    #[template_callback]
+    fn test_button_clicked(&self, button: gtk::Button) {
+        let address = self.address_row.text().to_string();
+        let window = self.obj().clone();
+        button.set_sensitive(false);
+
+        glib::spawn_future_local(async move {
+            let (sender, receiver) = tokio::sync::oneshot::channel();
+            systemd::runtime().spawn(async move {
+                let response = systemd::custom_bus::probe_custom_bus(&address).await;
+                if let Err(e) = sender.send(response) {
+                    warn!("Channel closed unexpectedly: {e:?}");
+                }
+            });
+
+            let response = receiver.await;
+            button.set_sensitive(true);
+
+            let status_label = &window.imp().status_label;
+            match response {
+                Ok(Ok(version)) => status_label.set_text(&format2!(
+                    pgettext("bus", "Connected to systemd {}"),
+                    version
+                )),
+                Ok(Err(err)) => {
+                    warn!("Probe bus {err:?}");
+                    status_label.set_text(&format2!(
+                        pgettext("bus", "Connection failed: {}"),
+                        err.to_string()
+                    ));
+                }
+                Err(err) => warn!("Tokio channel dropped {err:?}"),
+            }
+        });
+    }
+
+    #[template_callback]
+    fn add_button_clicked(&self, _button: gtk::Button) {
+        let label = self.label_row.text();
+        let address = self.address_row.text();
+
+        match systemd::custom_bus::add_custom_bus(&label, &address) {
+            Ok(_level) => {
+                config::save_bus_connections();
+                self.label_row.set_text("");
+                self.address_row.set_text("");
+                self.status_label.set_text(&format2!(
+                    pgettext("bus", "Connection {} added"),
+                    label.trim()
+                ));
+                self.refresh_unit_list();
+                self.fill_connections();
+            }
+            Err(err) => {
+                warn!("Add bus connection {err:?}");
+                self.status_label.set_text(&err.to_string());
+            }
+        }
+    }
+
+    pub(super) fn fill_connections(&self) {
+        for row in self.rows.borrow_mut().drain(..) {
+            self.connections_group.remove(&row);
+        }
+
+        for bus in systemd::custom_bus::custom_buses() {
+            let row = connection_row(&bus);
+
+            let remove_button = gtk::Button::builder()
+                .icon_name("user-trash-symbolic")
+                .tooltip_text(pgettext("bus", "Remove the connection"))
+                .css_classes([FLAT, DESTRUCTIVE_ACTION])
+                .valign(gtk::Align::Center)
+                .build();
+            row.add_suffix(&remove_button);
+
+            let window = self.obj().downgrade();
+            remove_button.connect_clicked(move |_| {
+                let window = upgrade!(window);
+                let imp = window.imp();
+                systemd::custom_bus::remove_custom_bus(bus.level);
+                config::save_bus_connections();
+                imp.status_label.set_text(&format2!(
+                    pgettext("bus", "Connection {} removed"),
+                    &bus.label
+                ));
+                imp.refresh_unit_list();
+                imp.fill_connections();
+            });
+
+            self.connections_group.add(&row);
+            self.rows.borrow_mut().push(row);
+        }
+    }
+
+    fn refresh_unit_list(&self) {
+        let Some(app_window) = self.app_window.get() else {
+            return;
+        };
+
+        if let Err(err) = app_window.activate_action(ACTION_WIN_REFRESH_UNIT_LIST, None) {
+            warn!("call action {ACTION_WIN_REFRESH_UNIT_LIST} error: {err}");
+        }
+    }
+}
+
+#[glib::object_subclass]
+impl ObjectSubclass for BusConnectionsWindowImp {
+    const NAME: &'static str = "BusConnectionsWindow";
+    type Type = BusConnectionsWindow;
+    type ParentType = adw::Window;
+
+    fn class_init(klass: &mut Self::Class) {
+        klass.bind_template();
+        klass.bind_template_callbacks();
+    }
+
+    fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+        obj.init_template();
+    }
+}
+
+impl ObjectImpl for BusConnectionsWindowImp {
+    fn constructed(&self) {
+        self.parent_constructed();
+        close_window_shortcut(self.obj().as_ref());
+    }
+}
+
+impl WidgetImpl for BusConnectionsWindowImp {}
+impl WindowImpl for BusConnectionsWindowImp {}
+impl AdwWindowImpl for BusConnectionsWindowImp {}
+
+fn connection_row(bus: &CustomBus) -> adw::ActionRow {
+    adw::ActionRow::builder()
+        .title(&bus.label)
+        .subtitle(&bus.address)
+        .subtitle_selectable(true)
+        .build()
+}
@@ -0,0 +1,28 @@
+pub mod config;
+mod imp;
+
+use crate::widget::app_window::AppWindow;
+use gtk::{
+    glib::{self},
+    prelude::*,
+    subclass::prelude::ObjectSubclassIsExt,
+};
+
+/// Window managing the connections to the systemd managers at other bus addresses
+glib::wrapper! {
+    pub struct BusConnectionsWindow(ObjectSubclass<imp::BusConnectionsWindowImp>)
+    @extends adw::Window, gtk::Window, gtk::Widget,
+    @implements gtk::Accessible,  gtk::Buildable,  gtk::ConstraintTarget,
+    gtk::Native, gtk::Root, gtk::ShortcutManager;
+}
+
+impl BusConnectionsWindow {
+    pub fn new(app_window: &AppWindow) -> Self {
+        let obj: BusConnectionsWindow = glib::Object::new();
+        obj.set_transient_for(Some(app_window));
+        let imp = obj.imp();
+        imp.set_app_window(app_window);
+        imp.fill_connections();
+        obj
+    }
+}
@@ -31,7 +31,6 @@ use gtk::{
     },
 };
 use std::cell::OnceCell;
-use strum::IntoEnumIterator;
 use tracing::error;
 use tracing::{info, warn};
 
@@ -683,7 +682,9 @@ impl ObjectImpl for EnableUnitDialogImp {
         self.run_stop_mode_combo.set_model(Some(&model));
 
         let mut levels_string = Vec::new();
-        for level in UnitDBusLevel::iter() {
+        for level in
+            UnitDBusLevel::iter().filter(|level| !level.is_custom() && !level.is_other_user())
+        {
             levels_string.push(level.nice_label());
         }
 
@@ -717,7 +718,7 @@ impl WindowImpl for EnableUnitDialogImp {
         let _ = settings
             .set_string(
                 SAVE_CONTEXT_ENABLE_UNIT_FILE_DBUS_LEVEL,
-                dbus_level.as_str(),
+                &dbus_level.as_str(),
             )
             .map_err(settings_error);
         let _ = settings
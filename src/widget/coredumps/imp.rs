@@ -0,0 +1,296 @@
+use super::{
+    CRASH_LOOP_COUNT, CoredumpsWindow, count_crashes, recent_crashes_since,
+    stack_trace::CoredumpStackTraceWindow,
+};
+use crate::{
+    format2,
+    systemd::{self, journal_data::Coredump},
+    upgrade,
+    widget::{
+        close_window_shortcut,
+        preferences::data::PREFERENCES,
+        timer_history::{Getter, set_column_factory},
+    },
+};
+use adw::subclass::window::AdwWindowImpl;
+use gettextrs::pgettext;
+use gtk::{
+    gio,
+    glib::{self, BoxedAnyObject},
+    prelude::*,
+    subclass::{
+        prelude::*,
+        widget::{
+            CompositeTemplateCallbacksClass, CompositeTemplateClass,
+            CompositeTemplateInitializingExt, WidgetImpl,
+        },
+    },
+};
+use std::cell::{OnceCell, Ref};
+use tracing::warn;
+
+/// Most recent crashes listed in the window
+const MAX_COREDUMPS: usize = 1000;
+
+struct CoredumpRow {
+    coredump: Coredump,
+    /// Recent crashes of the coredump unit
+    unit_crashes: u32,
+}
+
+#[derive(Default, gtk::CompositeTemplate)]
+#[template(resource = "/io/github/plrigaux/sysd-manager/coredumps.ui")]
+pub struct CoredumpsWindowImp {
+    #[template_child]
+    summary_label: TemplateChild<gtk::Label>,
+
+    #[template_child]
+    search_entry: TemplateChild<gtk::SearchEntry>,
+
+    #[template_child]
+    spinner: TemplateChild<adw::Spinner>,
+
+    #[template_child]
+    stack_trace_button: TemplateChild<gtk::Button>,
+
+    #[template_child]
+    single_selection: TemplateChild<gtk::SingleSelection>,
+
+    #[template_child]
+    filter_list_model: TemplateChild<gtk::FilterListModel>,
+
+    #[template_child]
+    time_column: TemplateChild<gtk::ColumnViewColumn>,
+
+    #[template_child]
+    unit_column: TemplateChild<gtk::ColumnViewColumn>,
+
+    #[template_child]
+    bus_column: TemplateChild<gtk::ColumnViewColumn>,
+
+    #[template_child]
+    executable_column: TemplateChild<gtk::ColumnViewColumn>,
+
+    #[template_child]
+    signal_column: TemplateChild<gtk::ColumnViewColumn>,
+
+    #[template_child]
+    pid_column: TemplateChild<gtk::ColumnViewColumn>,
+
+    #[template_child]
+    uid_column: TemplateChild<gtk::ColumnViewColumn>,
+
+    #[template_child]
+    recent_crashes_column: TemplateChild<gtk::ColumnViewColumn>,
+
+    store: OnceCell<gio::ListStore>,
+    filter: OnceCell<gtk::CustomFilter>,
+}
+
+#[gtk::template_callbacks]
+impl CoredumpsWindowImp {
+    pub(super) fn set_unit_filter(&self, unit_filter: &str) {
+        self.search_entry.set_text(unit_filter);
+    }
+
+    #[template_callback]
+    fn refresh_button_clicked(&self, _button: gtk::Button) {
+        self.refresh();
+    }
+
+    #[template_callback]
+    fn search_entry_changed(&self, _entry: gtk::SearchEntry) {
+        if let Some(filter) = self.filter.get() {
+            filter.changed(gtk::FilterChange::Different);
+        }
+    }
+
+    #[template_callback]
+    fn stack_trace_button_clicked(&self, _button: gtk::Button) {
+        if let Some(row) = self
+            .single_selection
+            .selected_item()
+            .and_downcast::<BoxedAnyObject>()
+        {
+            self.show_stack_trace(&row.borrow::<CoredumpRow>().coredump);
+        }
+    }
+
+    #[template_callback]
+    fn coredumps_view_activated(&self, _view: gtk::ColumnView, position: u32) {
+        if let Some(row) = self
+            .filter_list_model
+            .item(position)
+            .and_downcast::<BoxedAnyObject>()
+        {
+            self.show_stack_trace(&row.borrow::<CoredumpRow>().coredump);
+        }
+    }
+
+    fn show_stack_trace(&self, coredump: &Coredump) {
+        let window = CoredumpStackTraceWindow::new(&*self.obj(), coredump);
+        window.present();
+    }
+
+    pub(super) fn refresh(&self) {
+        let window = self.obj().clone();
+
+        self.spinner.set_visible(true);
+        glib::spawn_future_local(async move {
+            let result = gio::spawn_blocking(|| systemd::list_coredumps(0, MAX_COREDUMPS)).await;
+
+            let imp = window.imp();
+            imp.spinner.set_visible(false);
+
+            let coredumps = match result {
+                Ok(Ok(coredumps)) => coredumps,
+                Ok(Err(err)) => {
+                    warn!("Coredumps {err:?}");
+                    imp.summary_label.set_text(&err.human_error_type());
+                    return;
+                }
+                Err(err) => {
+                    warn!("Coredumps join {err:?}");
+                    return;
+                }
+            };
+
+            let crashes = count_crashes(&coredumps, recent_crashes_since());
+            let crash_loops = crashes
+                .values()
+                .filter(|count| **count >= CRASH_LOOP_COUNT)
+                .count();
+
+            imp.summary_label.set_text(&format2!(
+                //coredumps summary
+                pgettext(
+                    "coredumps",
+                    "{} crashes, {} units crashed in the last 24 hours, {} crash looping"
+                ),
+                coredumps.len(),
+                crashes.len(),
+                crash_loops
+            ));
+
+            let store = imp.store.get().expect("store set");
+            store.remove_all();
+            for coredump in coredumps {
+                let unit_crashes = crashes
+                    .get(&(coredump.level, coredump.unit.clone()))
+                    .copied()
+                    .unwrap_or_default();
+
+                store.append(&BoxedAnyObject::new(CoredumpRow {
+                    coredump,
+                    unit_crashes,
+                }));
+            }
+        });
+    }
+
+    fn setup_factories(&self) {
+        let timestamp_style = PREFERENCES.timestamp_style();
+
+        let columns: [(&gtk::ColumnViewColumn, Getter<CoredumpRow>); 8] = [
+            (
+                &self.time_column,
+                Box::new(move |row: &CoredumpRow| {
+                    timestamp_style.usec_formated(row.coredump.timestamp)
+                }),
+            ),
+            (
+                &self.unit_column,
+                Box::new(|row: &CoredumpRow| row.coredump.unit.clone()),
+            ),
+            (
+                &self.bus_column,
+                Box::new(|row: &CoredumpRow| row.coredump.level.as_str().into_owned()),
+            ),
+            (
+                &self.executable_column,
+                Box::new(|row: &CoredumpRow| row.coredump.executable.clone()),
+            ),
+            (
+                &self.signal_column,
+                Box::new(|row: &CoredumpRow| row.coredump.signal.clone()),
+            ),
+            (
+                &self.pid_column,
+                Box::new(|row: &CoredumpRow| row.coredump.pid.to_string()),
+            ),
+            (
+                &self.uid_column,
+                Box::new(|row: &CoredumpRow| row.coredump.uid.to_string()),
+            ),
+            (
+                &self.recent_crashes_column,
+                Box::new(|row: &CoredumpRow| row.unit_crashes.to_string()),
+            ),
+        ];
+
+        for (column, getter) in columns {
+            set_column_factory(column, getter, |row: &CoredumpRow| {
+                row.unit_crashes >= CRASH_LOOP_COUNT
+            });
+        }
+    }
+}
+
+#[glib::object_subclass]
+impl ObjectSubclass for CoredumpsWindowImp {
+    const NAME: &'static str = "CoredumpsWindow";
+    type Type = CoredumpsWindow;
+    type ParentType = adw::Window;
+
+    fn class_init(klass: &mut Self::Class) {
+        klass.bind_template();
+        klass.bind_template_callbacks();
+    }
+
+    fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+        obj.init_template();
+    }
+}
+
+impl ObjectImpl for CoredumpsWindowImp {
+    fn constructed(&self) {
+        self.parent_constructed();
+        close_window_shortcut(self.obj().as_ref());
+
+        let store = gio::ListStore::new::<BoxedAnyObject>();
+
+        let search_entry = self.search_entry.downgrade();
+        let filter = gtk::CustomFilter::new(move |object| {
+            let Some(text) = search_entry.upgrade().map(|entry| entry.text()) else {
+                return true;
+            };
+            if text.is_empty() {
+                return true;
+            }
+
+            let Some(row) = object.downcast_ref::<BoxedAnyObject>() else {
+                return false;
+            };
+            let row: Ref<CoredumpRow> = row.borrow();
+            row.coredump.matches(&text)
+        });
+
+        self.filter_list_model.set_model(Some(&store));
+        self.filter_list_model.set_filter(Some(&filter));
+        let _ = self.store.set(store);
+        let _ = self.filter.set(filter);
+
+        self.setup_factories();
+
+        let stack_trace_button = self.stack_trace_button.downgrade();
+        self.single_selection
+            .connect_selected_item_notify(move |selection| {
+                let stack_trace_button = upgrade!(stack_trace_button);
+                stack_trace_button.set_sensitive(selection.selected_item().is_some());
+            });
+    }
+}
+
+impl WidgetImpl for CoredumpsWindowImp {}
+impl WindowImpl for CoredumpsWindowImp {}
+impl AdwWindowImpl for CoredumpsWindowImp {}
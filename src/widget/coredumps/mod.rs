@@ -0,0 +1,67 @@
+mod imp;
+mod stack_trace;
+
+use crate::{systemd::journal_data::Coredump, widget::app_window::AppWindow};
+use base::enums::UnitDBusLevel;
+use gtk::{
+    glib::{self},
+    prelude::*,
+    subclass::prelude::ObjectSubclassIsExt,
+};
+use std::{
+    collections::HashMap,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// How far back a crash is recent, 24 hours
+pub const RECENT_CRASHES_USEC: u64 = 24 * 60 * 60 * 1_000_000;
+/// Recent crashes of a unit from which it is considered crash looping
+pub const CRASH_LOOP_COUNT: u32 = 3;
+
+/// Start of the recent crashes period, in realtime µs
+pub fn recent_crashes_since() -> u64 {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_micros() as u64);
+    now.saturating_sub(RECENT_CRASHES_USEC)
+}
+
+/// Crash count per unit, the user session ones are the current user's only
+pub fn count_crashes(
+    coredumps: &[Coredump],
+    since_usec: u64,
+) -> HashMap<(UnitDBusLevel, String), u32> {
+    let uid = uzers::get_current_uid();
+    let mut crashes = HashMap::new();
+
+    for coredump in coredumps.iter().filter(|coredump| {
+        coredump.timestamp >= since_usec
+            && !coredump.unit.is_empty()
+            && (coredump.level == UnitDBusLevel::System || coredump.uid == uid)
+    }) {
+        *crashes
+            .entry((coredump.level, coredump.unit.clone()))
+            .or_insert(0) += 1;
+    }
+
+    crashes
+}
+
+/// Window listing the `systemd-coredump` crashes, filterable by unit
+glib::wrapper! {
+    pub struct CoredumpsWindow(ObjectSubclass<imp::CoredumpsWindowImp>)
+    @extends adw::Window, gtk::Window, gtk::Widget,
+    @implements gtk::Accessible,  gtk::Buildable,  gtk::ConstraintTarget,
+    gtk::Native, gtk::Root, gtk::ShortcutManager;
+}
+
+impl CoredumpsWindow {
+    pub fn new(app_window: &AppWindow, unit_filter: Option<String>) -> Self {
+        let obj: CoredumpsWindow = glib::Object::new();
+        obj.set_transient_for(Some(app_window));
+        let imp = obj.imp();
+        imp.set_unit_filter(unit_filter.as_deref().unwrap_or_default());
+        imp.refresh();
+        obj
+    }
+}
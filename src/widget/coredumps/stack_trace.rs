@@ -0,0 +1,107 @@
+use crate::systemd::journal_data::Coredump;
+use gettextrs::pgettext;
+use gtk::{
+    glib::{self},
+    prelude::*,
+    subclass::prelude::ObjectSubclassIsExt,
+};
+
+glib::wrapper! {
+    pub struct CoredumpStackTraceWindow(ObjectSubclass<imp::CoredumpStackTraceWindowImp>)
+    @extends adw::Window, gtk::Window, gtk::Widget,
+    @implements gtk::Accessible,  gtk::Buildable,  gtk::ConstraintTarget,
+    gtk::Native, gtk::Root, gtk::ShortcutManager;
+}
+
+impl CoredumpStackTraceWindow {
+    pub fn new(parent: &impl IsA<gtk::Window>, coredump: &Coredump) -> Self {
+        let obj: CoredumpStackTraceWindow = glib::Object::new();
+        obj.set_transient_for(Some(parent));
+
+        let title = if coredump.unit.is_empty() {
+            &coredump.command
+        } else {
+            &coredump.unit
+        };
+        obj.set_title(Some(&format!(
+            "{} - {title}",
+            pgettext("coredumps", "Stack Trace")
+        )));
+
+        obj.imp().load(coredump.cursor.clone());
+        obj
+    }
+}
+
+mod imp {
+    use super::CoredumpStackTraceWindow;
+    use crate::{systemd, widget::close_window_shortcut};
+    use adw::subclass::window::AdwWindowImpl;
+    use gtk::{
+        gio,
+        glib::{self},
+        prelude::*,
+        subclass::{
+            prelude::*,
+            widget::{CompositeTemplateClass, CompositeTemplateInitializingExt, WidgetImpl},
+        },
+    };
+    use tracing::warn;
+
+    #[derive(Default, gtk::CompositeTemplate)]
+    #[template(resource = "/io/github/plrigaux/sysd-manager/coredump_stack_trace.ui")]
+    pub struct CoredumpStackTraceWindowImp {
+        #[template_child]
+        text_view: TemplateChild<gtk::TextView>,
+    }
+
+    impl CoredumpStackTraceWindowImp {
+        pub(super) fn load(&self, cursor: String) {
+            let text_view = self.text_view.get();
+            glib::spawn_future_local(async move {
+                let text =
+                    match gio::spawn_blocking(move || systemd::fetch_coredump_stack_trace(&cursor))
+                        .await
+                    {
+                        Ok(Ok(text)) => text,
+                        Ok(Err(err)) => {
+                            warn!("Coredump stack trace {err:?}");
+                            err.human_error_type()
+                        }
+                        Err(err) => {
+                            warn!("Coredump stack trace join {err:?}");
+                            return;
+                        }
+                    };
+
+                text_view.buffer().set_text(&text);
+            });
+        }
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for CoredumpStackTraceWindowImp {
+        const NAME: &'static str = "CoredumpStackTraceWindow";
+        type Type = CoredumpStackTraceWindow;
+        type ParentType = adw::Window;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+        }
+
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for CoredumpStackTraceWindowImp {
+        fn constructed(&self) {
+            self.parent_constructed();
+            close_window_shortcut(self.obj().as_ref());
+        }
+    }
+
+    impl WidgetImpl for CoredumpStackTraceWindowImp {}
+    impl WindowImpl for CoredumpStackTraceWindowImp {}
+    impl AdwWindowImpl for CoredumpStackTraceWindowImp {}
+}
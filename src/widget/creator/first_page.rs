@@ -221,8 +221,8 @@ mod imp {
             {
                 let level: UnitDBusLevel = (&state).into();
                 let set = match level {
-                    UnitDBusLevel::System | UnitDBusLevel::Both => window.system_file_list(),
                     UnitDBusLevel::UserSession => window.session_file_list(),
+                    _ => window.system_file_list(),
                 };
 
                 match window.creation_type() {
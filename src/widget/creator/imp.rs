@@ -66,7 +66,7 @@ pub struct UnitCreatorWindowImp {
     #[property(get, set=Self::set_page_type, default)]
     pub(super) page_type: Cell<PageType>,
 
-    #[property(get, set=Self::set_bus_level, default)]
+    #[property(get, set=Self::set_bus_level)]
     pub(super) level: Cell<UnitDBusLevel>,
 
     pub(super) system_file_list: RefCell<HashSet<String>>,
@@ -137,8 +137,8 @@ impl UnitCreatorWindowImp {
         let level = self.level.get();
         {
             let set = match level {
-                UnitDBusLevel::System | UnitDBusLevel::Both => self.system_file_list.borrow(),
                 UnitDBusLevel::UserSession => self.session_file_list.borrow(),
+                _ => self.system_file_list.borrow(),
             };
             if !set.is_empty() {
                 return;
@@ -161,14 +161,14 @@ impl UnitCreatorWindowImp {
         match response {
             Ok(systemd::ListUnitResponse::File(_, list)) => {
                 let (mut set, model) = match level {
-                    UnitDBusLevel::System | UnitDBusLevel::Both => (
-                        self.system_file_list.borrow_mut(),
-                        self.system_file_list_model.borrow().clone(),
-                    ),
                     UnitDBusLevel::UserSession => (
                         self.session_file_list.borrow_mut(),
                         self.session_file_list_model.borrow().clone(),
                     ),
+                    _ => (
+                        self.system_file_list.borrow_mut(),
+                        self.system_file_list_model.borrow().clone(),
+                    ),
                 };
 
                 for ufile in list.into_iter() {
@@ -201,8 +201,8 @@ impl UnitCreatorWindowImp {
         let level = self.level.get();
 
         match level {
-            UnitDBusLevel::System | UnitDBusLevel::Both => self.system_file_list.borrow(),
             UnitDBusLevel::UserSession => self.session_file_list.borrow(),
+            _ => self.system_file_list.borrow(),
         }
     }
 
@@ -210,10 +210,8 @@ impl UnitCreatorWindowImp {
         let level = self.level.get();
 
         match level {
-            UnitDBusLevel::System | UnitDBusLevel::Both => {
-                self.system_file_list_model.borrow().clone()
-            }
             UnitDBusLevel::UserSession => self.session_file_list_model.borrow().clone(),
+            _ => self.system_file_list_model.borrow().clone(),
         }
     }
 
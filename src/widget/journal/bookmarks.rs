@@ -0,0 +1,93 @@
+use crate::widget::unit_properties_selector::save::{
+    get_sysd_manager_config_dir, save_to_toml_file,
+};
+use base::enums::UnitDBusLevel;
+use serde::{Deserialize, Serialize};
+use std::{fs, path::PathBuf};
+use tracing::{error, info};
+
+const BOOKMARKS_DIR: &str = "journal_bookmarks";
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct JournalBookmarks {
+    pub bookmarks: Vec<JournalBookmark>,
+}
+
+/// A journal entry kept with a user note
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+#[serde(default)]
+pub struct JournalBookmark {
+    pub cursor: String,
+    /// Realtime µs of the entry
+    pub timestamp: u64,
+    pub note: String,
+    pub message: String,
+}
+
+/// One file per unit
+fn bookmarks_path(unit_name: &str, level: UnitDBusLevel) -> PathBuf {
+    get_sysd_manager_config_dir()
+        .join(BOOKMARKS_DIR)
+        .join(format!("{}-{unit_name}.toml", level.as_str()))
+}
+
+pub(super) fn save_bookmarks(
+    unit_name: &str,
+    level: UnitDBusLevel,
+    bookmarks: Vec<JournalBookmark>,
+) {
+    let config_path = bookmarks_path(unit_name, level);
+    systemd::runtime().spawn(save_bookmarks_async(config_path, bookmarks));
+}
+
+async fn save_bookmarks_async(config_path: PathBuf, bookmarks: Vec<JournalBookmark>) {
+    let config = JournalBookmarks { bookmarks };
+
+    let Some(bookmarks_dir) = config_path.parent() else {
+        return;
+    };
+
+    if let Err(e) = tokio::fs::create_dir_all(bookmarks_dir).await {
+        error!(
+            "Failed to create bookmarks directory {:?}: {}",
+            bookmarks_dir, e
+        );
+        return;
+    }
+
+    if let Err(e) = save_to_toml_file(&config, &config_path).await {
+        error!(
+            "Failed to save journal bookmarks to TOML file: {:?} {:?}",
+            config_path, e
+        );
+    } else {
+        info!("Journal bookmarks saved to {:?}", config_path);
+    }
+}
+
+/// The unit bookmarks, oldest entry first
+pub(super) fn load_bookmarks(unit_name: &str, level: UnitDBusLevel) -> Vec<JournalBookmark> {
+    let config_path = bookmarks_path(unit_name, level);
+
+    if !config_path.exists() {
+        return vec![];
+    }
+
+    match fs::read_to_string(&config_path) {
+        Ok(toml_str) => match toml::from_str::<JournalBookmarks>(&toml_str) {
+            Ok(config) => {
+                let mut bookmarks = config.bookmarks;
+                bookmarks.sort_by_key(|bookmark| bookmark.timestamp);
+                bookmarks
+            }
+            Err(e) => {
+                error!("Failed to parse TOML from {:?}: {}", config_path, e);
+                vec![]
+            }
+        },
+        Err(e) => {
+            error!("Failed to read bookmarks file {:?}: {}", config_path, e);
+            vec![]
+        }
+    }
+}
@@ -0,0 +1,316 @@
+use crate::{
+    format2,
+    systemd::{
+        BootFilter,
+        journal_data::{FieldMatches, JournalSource},
+    },
+};
+use base::enums::UnitDBusLevel;
+use gettextrs::pgettext;
+use gtk::{
+    glib::{self},
+    prelude::*,
+    subclass::prelude::ObjectSubclassIsExt,
+};
+
+/// The journal panel query to export
+#[derive(Debug, Clone)]
+pub(super) struct ExportQuery {
+    pub source: JournalSource,
+    pub unit_names: Vec<String>,
+    pub level: UnitDBusLevel,
+    pub boot_filter: BootFilter,
+    pub field_matches: FieldMatches,
+    pub min_priority: Option<u8>,
+    pub since: Option<u64>,
+    pub until: Option<u64>,
+}
+
+/// Window exporting the journal panel query to a file in the background
+glib::wrapper! {
+    pub struct JournalExportWindow(ObjectSubclass<imp::JournalExportWindowImp>)
+    @extends adw::Window, gtk::Window, gtk::Widget,
+    @implements gtk::Accessible,  gtk::Buildable,  gtk::ConstraintTarget,
+    gtk::Native, gtk::Root, gtk::ShortcutManager;
+}
+
+impl JournalExportWindow {
+    pub(super) fn new(parent: Option<&gtk::Window>, query: ExportQuery) -> Self {
+        let obj: JournalExportWindow = glib::Object::new();
+        obj.set_transient_for(parent);
+        obj.imp().set_query(query);
+        obj
+    }
+}
+
+mod imp {
+    use super::{ExportQuery, JournalExportWindow, entries_text, query_description};
+    use crate::{
+        systemd::{
+            self,
+            journal_data::{EventRange, WhatGrab},
+            journal_export::JournalExportFormat,
+        },
+        widget::close_window_shortcut,
+    };
+    use adw::{prelude::*, subclass::window::AdwWindowImpl};
+    use gettextrs::pgettext;
+    use gtk::{
+        gio,
+        glib::{self},
+        subclass::{
+            prelude::*,
+            widget::{
+                CompositeTemplateCallbacksClass, CompositeTemplateClass,
+                CompositeTemplateInitializingExt, WidgetImpl,
+            },
+        },
+    };
+    use std::{
+        cell::OnceCell,
+        path::PathBuf,
+        sync::{
+            Arc,
+            atomic::{AtomicBool, AtomicUsize, Ordering},
+        },
+        time::Duration,
+    };
+    use strum::IntoEnumIterator;
+    use tracing::{info, warn};
+
+    const PROGRESS_REFRESH: Duration = Duration::from_millis(200);
+
+    #[derive(Default, gtk::CompositeTemplate)]
+    #[template(resource = "/io/github/plrigaux/sysd-manager/journal_export.ui")]
+    pub struct JournalExportWindowImp {
+        #[template_child]
+        window_title: TemplateChild<adw::WindowTitle>,
+
+        #[template_child]
+        query_row: TemplateChild<adw::ActionRow>,
+
+        #[template_child]
+        format_row: TemplateChild<adw::ComboRow>,
+
+        #[template_child]
+        progress_bar: TemplateChild<gtk::ProgressBar>,
+
+        #[template_child]
+        export_button: TemplateChild<gtk::Button>,
+
+        #[template_child]
+        cancel_button: TemplateChild<gtk::Button>,
+
+        query: OnceCell<ExportQuery>,
+        cancel: Arc<AtomicBool>,
+    }
+
+    #[gtk::template_callbacks]
+    impl JournalExportWindowImp {
+        pub(super) fn set_query(&self, query: ExportQuery) {
+            self.window_title.set_subtitle(&query.unit_names.join(", "));
+            self.query_row.set_subtitle(&query_description(&query));
+            self.query.set(query).expect("query set once");
+        }
+
+        #[template_callback]
+        fn cancel_button_clicked(&self, _button: gtk::Button) {
+            self.cancel.store(true, Ordering::Relaxed);
+        }
+
+        #[template_callback]
+        fn export_button_clicked(&self, _button: gtk::Button) {
+            let Some(query) = self.query.get() else {
+                return;
+            };
+
+            let format = JournalExportFormat::iter()
+                .nth(self.format_row.selected() as usize)
+                .unwrap_or(JournalExportFormat::Short);
+
+            let name = match query.unit_names.as_slice() {
+                [unit_name] => unit_name.as_str(),
+                _ => "journal",
+            };
+            let initial_name = format!("{name}.{}", format.extension());
+            let file_dialog = gtk::FileDialog::builder()
+                .title(pgettext("journal export", "Export Journal"))
+                .accept_label(pgettext("journal export", "Export"))
+                .initial_name(initial_name)
+                .build();
+
+            let window = self.obj().clone();
+            file_dialog.save(
+                Some(&*self.obj()),
+                None::<&gio::Cancellable>,
+                move |result| match result {
+                    Ok(file) => {
+                        let Some(path) = file.path() else {
+                            warn!("Export file has no path");
+                            return;
+                        };
+
+                        window.imp().export(format, path);
+                    }
+                    Err(e) => warn!("Export File Selection Error {e:?}"),
+                },
+            );
+        }
+
+        fn export(&self, format: JournalExportFormat, path: PathBuf) {
+            let Some(query) = self.query.get().cloned() else {
+                return;
+            };
+
+            self.cancel.store(false, Ordering::Relaxed);
+            self.export_button.set_sensitive(false);
+            self.cancel_button.set_sensitive(true);
+
+            let progress = Arc::new(AtomicUsize::new(0));
+
+            let progress_source = {
+                let progress = progress.clone();
+                let progress_bar = self.progress_bar.get();
+                glib::timeout_add_local(PROGRESS_REFRESH, move || {
+                    progress_bar.pulse();
+                    progress_bar.set_text(Some(&entries_text(progress.load(Ordering::Relaxed))));
+                    glib::ControlFlow::Continue
+                })
+            };
+
+            let cancel = self.cancel.clone();
+            let window = self.obj().clone();
+            glib::spawn_future_local(async move {
+                let path_ = path.clone();
+                let progress_ = progress.clone();
+                let result = gio::spawn_blocking(move || {
+                    // 0 no limit
+                    let range = EventRange::new(WhatGrab::Newer, 0, None, None).with_filters(
+                        query.min_priority,
+                        query.since,
+                        query.until,
+                    );
+
+                    systemd::export_unit_journal(
+                        &query.source,
+                        &query.unit_names,
+                        query.level,
+                        query.boot_filter,
+                        &query.field_matches,
+                        &range,
+                        format,
+                        &path_,
+                        &progress_,
+                        &cancel,
+                    )
+                })
+                .await;
+
+                progress_source.remove();
+                let imp = window.imp();
+                imp.export_button.set_sensitive(true);
+                imp.cancel_button.set_sensitive(false);
+
+                match result {
+                    Ok(Ok(count)) => {
+                        info!("Journal exported {count} entries to {path:?}");
+                        imp.progress_bar.set_fraction(1.0);
+                        imp.progress_bar.set_text(Some(&format!(
+                            "{} — {}",
+                            entries_text(count),
+                            path.display()
+                        )));
+                    }
+                    Ok(Err(err)) => {
+                        warn!("Journal export {err:?}");
+                        imp.progress_bar.set_fraction(0.0);
+                        imp.progress_bar.set_text(Some(&err.human_error_type()));
+                    }
+                    Err(err) => warn!("Journal export join {err:?}"),
+                }
+            });
+        }
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for JournalExportWindowImp {
+        const NAME: &'static str = "JournalExportWindow";
+        type Type = JournalExportWindow;
+        type ParentType = adw::Window;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+            klass.bind_template_callbacks();
+        }
+
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for JournalExportWindowImp {
+        fn constructed(&self) {
+            self.parent_constructed();
+            close_window_shortcut(self.obj().as_ref());
+
+            let format_labels: Vec<String> = JournalExportFormat::iter()
+                .map(|format| format.label())
+                .collect();
+            let format_labels: Vec<&str> =
+                format_labels.iter().map(|label| label.as_str()).collect();
+            self.format_row
+                .set_model(Some(&gtk::StringList::new(&format_labels)));
+        }
+    }
+
+    impl WidgetImpl for JournalExportWindowImp {}
+
+    impl WindowImpl for JournalExportWindowImp {
+        // Stop a running export when the window closes
+        fn close_request(&self) -> glib::Propagation {
+            self.cancel.store(true, Ordering::Relaxed);
+
+            self.parent_close_request();
+            glib::Propagation::Proceed
+        }
+    }
+
+    impl AdwWindowImpl for JournalExportWindowImp {}
+}
+
+fn entries_text(count: usize) -> String {
+    //journal export progress
+    format2!(pgettext("journal export", "{} entries written"), count)
+}
+
+fn query_description(query: &ExportQuery) -> String {
+    let mut description = match &query.boot_filter {
+        BootFilter::Current => pgettext("journal export", "Current boot"),
+        BootFilter::All => pgettext("journal export", "All boots"),
+        BootFilter::Id(boot_id) => format!("{} {boot_id}", pgettext("journal export", "Boot")),
+        BootFilter::Invocation(invocation_id) => {
+            format!("{} {invocation_id}", pgettext("journal export", "Run"))
+        }
+    };
+
+    if let Some(min_priority) = query.min_priority {
+        description.push_str(&format!(
+            ", {} ≤ {min_priority}",
+            pgettext("journal export", "priority")
+        ));
+    }
+
+    if query.since.is_some() || query.until.is_some() {
+        description.push_str(&format!(", {}", pgettext("journal export", "time range")));
+    }
+
+    if !query.field_matches.is_empty() {
+        description.push_str(&format!(
+            ", {} {}",
+            query.field_matches.len(),
+            pgettext("journal export", "field filters")
+        ));
+    }
+
+    description
+}
@@ -0,0 +1,236 @@
+use crate::widget::unit_properties_selector::save::{
+    get_sysd_manager_config_dir, save_to_toml_file,
+};
+use gtk::prelude::*;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    sync::{LazyLock, RwLock},
+};
+use tracing::{error, info, warn};
+
+const HIGHLIGHT_RULES: &str = "journal_highlight_rules.toml";
+const TAG_HIGHLIGHT_PREFIX: &str = "highlight_";
+
+static RULES: LazyLock<RwLock<Vec<HighlightRule>>> =
+    LazyLock::new(|| RwLock::new(load_highlight_rules()));
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct HighlightRules {
+    pub rules: Vec<HighlightRule>,
+}
+
+/// Journal message parts matching `pattern` are displayed with the rule style
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct HighlightRule {
+    pub enabled: bool,
+    /// Regular expression
+    pub pattern: String,
+    /// CSS color, like `#e01b24`
+    pub foreground: Option<String>,
+    pub background: Option<String>,
+    pub bold: bool,
+    /// Only applied to the events of this unit, all units if empty
+    pub unit: String,
+}
+
+impl Default for HighlightRule {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            pattern: String::new(),
+            foreground: None,
+            background: None,
+            bold: true,
+            unit: String::new(),
+        }
+    }
+}
+
+impl HighlightRule {
+    fn applies_to(&self, unit_name: Option<&String>) -> bool {
+        self.unit.is_empty() || unit_name.is_some_and(|unit_name| *unit_name == self.unit)
+    }
+}
+
+/// A rule ready to be applied on a journal text buffer
+pub(super) struct Highlight {
+    regex: Regex,
+    rule: HighlightRule,
+    tag: gtk::TextTag,
+}
+
+pub fn highlight_rules() -> Vec<HighlightRule> {
+    RULES.read().expect("highlight rules lock").clone()
+}
+
+pub fn set_highlight_rules(rules: Vec<HighlightRule>) {
+    *RULES.write().expect("highlight rules lock") = rules.clone();
+    systemd::runtime().spawn(save_highlight_rules_async(rules));
+}
+
+async fn save_highlight_rules_async(rules: Vec<HighlightRule>) {
+    let config = HighlightRules { rules };
+
+    let sysd_manager_config_dir = get_sysd_manager_config_dir();
+
+    if let Err(e) = tokio::fs::create_dir_all(&sysd_manager_config_dir).await {
+        error!(
+            "Failed to create config directory {:?}: {}",
+            sysd_manager_config_dir, e
+        );
+        return;
+    }
+
+    let config_path = sysd_manager_config_dir.join(HIGHLIGHT_RULES);
+
+    if let Err(e) = save_to_toml_file(&config, &config_path).await {
+        error!(
+            "Failed to save highlight rules to TOML file: {:?} {:?}",
+            config_path, e
+        );
+    } else {
+        info!("Highlight rules saved to {:?}", config_path);
+    }
+}
+
+fn load_highlight_rules() -> Vec<HighlightRule> {
+    let config_path = get_sysd_manager_config_dir().join(HIGHLIGHT_RULES);
+
+    if !config_path.exists() {
+        return vec![];
+    }
+
+    match fs::read_to_string(&config_path) {
+        Ok(toml_str) => match toml::from_str::<HighlightRules>(&toml_str) {
+            Ok(config) => config.rules,
+            Err(e) => {
+                error!("Failed to parse TOML from {:?}: {}", config_path, e);
+                vec![]
+            }
+        },
+        Err(e) => {
+            error!(
+                "Failed to read highlight rules file {:?}: {}",
+                config_path, e
+            );
+            vec![]
+        }
+    }
+}
+
+/// The enabled and valid rules, their text tags updated to the rule styles
+pub(super) fn highlights(buffer: &gtk::TextBuffer) -> Vec<Highlight> {
+    let tag_table = buffer.tag_table();
+    let mut highlights = Vec::new();
+
+    for (idx, rule) in highlight_rules().into_iter().enumerate() {
+        if !rule.enabled || rule.pattern.is_empty() {
+            continue;
+        }
+
+        let regex = match Regex::new(&rule.pattern) {
+            Ok(regex) => regex,
+            Err(err) => {
+                warn!("Highlight rule {:?} {err}", rule.pattern);
+                continue;
+            }
+        };
+
+        let tag_name = format!("{TAG_HIGHLIGHT_PREFIX}{idx}");
+        let tag = match tag_table.lookup(&tag_name) {
+            Some(tag) => tag,
+            None => {
+                let tag = gtk::TextTag::new(Some(&tag_name));
+                tag_table.add(&tag);
+                tag
+            }
+        };
+
+        tag.set_foreground(rule.foreground.as_deref());
+        tag.set_foreground_set(rule.foreground.is_some());
+        tag.set_background(rule.background.as_deref());
+        tag.set_background_set(rule.background.is_some());
+        tag.set_weight(if rule.bold { 700 } else { 400 });
+        tag.set_weight_set(rule.bold);
+        // Over the priority and ANSI colors
+        tag.set_priority(tag_table.size() - 1);
+
+        highlights.push(Highlight { regex, rule, tag });
+    }
+
+    highlights
+}
+
+/// Apply the rules on the buffer text from `start_offset` to `end`
+pub(super) fn apply(
+    highlights: &[Highlight],
+    buffer: &gtk::TextBuffer,
+    start_offset: i32,
+    end: &gtk::TextIter,
+    unit_name: Option<&String>,
+) {
+    let start = buffer.iter_at_offset(start_offset);
+    let text = buffer.text(&start, end, false);
+
+    for highlight in highlights
+        .iter()
+        .filter(|highlight| highlight.rule.applies_to(unit_name))
+    {
+        for found in highlight.regex.find_iter(&text) {
+            if found.is_empty() {
+                continue;
+            }
+
+            let match_start = start_offset + text[..found.start()].chars().count() as i32;
+            let match_end = match_start + found.as_str().chars().count() as i32;
+
+            buffer.apply_tag(
+                &highlight.tag,
+                &buffer.iter_at_offset(match_start),
+                &buffer.iter_at_offset(match_end),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rule_applies_to() {
+        let rule = HighlightRule::default();
+        assert!(rule.applies_to(None));
+
+        let rule = HighlightRule {
+            unit: "nginx.service".to_owned(),
+            ..Default::default()
+        };
+        assert!(rule.applies_to(Some(&"nginx.service".to_owned())));
+        assert!(!rule.applies_to(Some(&"sshd.service".to_owned())));
+        assert!(!rule.applies_to(None));
+    }
+
+    #[test]
+    fn test_rules_toml() {
+        let rules = HighlightRules {
+            rules: vec![HighlightRule {
+                pattern: r"req-[0-9a-f]+".to_owned(),
+                foreground: Some("#e01b24".to_owned()),
+                ..Default::default()
+            }],
+        };
+
+        let toml_str = toml::to_string(&rules).unwrap();
+        let parsed: HighlightRules = toml::from_str(&toml_str).unwrap();
+        assert_eq!(parsed.rules, rules.rules);
+
+        let parsed: HighlightRules = toml::from_str("[[rules]]\npattern = \"E[0-9]+\"\n").unwrap();
+        assert!(parsed.rules[0].enabled);
+        assert!(parsed.rules[0].bold);
+        assert_eq!(parsed.rules[0].foreground, None);
+    }
+}
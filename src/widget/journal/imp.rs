@@ -1,14 +1,19 @@
 use crate::{
     consts::{
-        ACTION_WIN_KEY_JOURNAL_WRAP_WORD, APP_ACTION_LIST_BOOT, CLASS_ERROR, CLASS_SUCCESS,
-        CLASS_WARNING, SETTING_FIND_IN_TEXT_OPEN,
+        ACTION_WIN_KEY_JOURNAL_WRAP_WORD, APP_ACTION_LIST_BOOT, CLASS_ACCENT, CLASS_ERROR,
+        CLASS_SUCCESS, CLASS_WARNING, SETTING_FIND_IN_TEXT_OPEN,
     },
     systemd::{
         BootFilter,
         data::UnitInfo,
+        enums::UnitType,
+        invocation::{self, RunResult, UnitRun},
         journal_data::{
-            EventRange, JournalEvent, JournalEventChunk, JournalEventChunkInfo, WhatGrab,
+            EventRange, FieldMatches, JournalEvent, JournalEventChunk, JournalEventChunkInfo,
+            JournalFacet, JournalSource, WhatGrab,
         },
+        journal_export::KEY_CURSOR,
+        time_handling,
     },
     systemd_gui::{self},
     upgrade,
@@ -21,13 +26,20 @@ use crate::{
     widget::{
         InterPanelMessage,
         app_window::AppWindow,
-        journal::colorize::{self, Token},
+        journal::{
+            bookmarks::{self, JournalBookmark},
+            colorize::{self, Token},
+            export::{ExportQuery, JournalExportWindow},
+            highlight::{self, Highlight},
+        },
         preferences::data::{
             KEY_PREF_JOURNAL_DISPLAY_FOLLOW, KEY_PREF_JOURNAL_DISPLAY_ORDER, PREFERENCES,
         },
         text_search::{self, TextSearchEntry},
     },
 };
+use adw::prelude::*;
+use base::enums::UnitDBusLevel;
 use gettextrs::pgettext;
 use gtk::{
     TemplateChild, gio, glib,
@@ -43,6 +55,9 @@ use gtk::{
 };
 use std::{
     cell::{Cell, OnceCell, RefCell},
+    collections::VecDeque,
+    path::PathBuf,
+    rc::Rc,
     thread,
 };
 use systemd::journal_data::BOOT_IDX;
@@ -50,11 +65,37 @@ use tracing::{debug, error, info, warn};
 
 const PANEL_EMPTY: &str = "empty";
 const PANEL_JOURNAL: &str = "journal";
+const PANEL_REMOTE: &str = "remote";
 /*const PANEL_SPINNER: &str = "spinner"; */
 
 const ASCD: &str = "view-sort-ascending";
 const DESC: &str = "view-sort-descending";
 
+/// How far back in the journal the unit runs are listed
+const MAX_RUN_RECORDS: usize = 20_000;
+const MAX_RUNS: usize = 50;
+const MAX_MERGED_UNITS: usize = 32;
+/// Events shown before and after a bookmarked one
+const BOOKMARK_CONTEXT: usize = 50;
+const TAG_BOOKMARK: &str = "bookmark";
+const TAG_CATALOG: &str = "catalog";
+/// Marks the events having a catalog explanation, click on it to show it
+const CATALOG_GLYPH: &str = "ⓘ ";
+const MARK_BOOKMARK: &str = "bookmark";
+
+const FACET_FIELDS: [&str; 7] = [
+    "PRIORITY",
+    "SYSLOG_IDENTIFIER",
+    "_COMM",
+    "_PID",
+    "_EXE",
+    "CODE_FILE",
+    "_TRANSPORT",
+];
+/// Number of most recent entries scanned to count the field values
+const MAX_FACET_ENTRIES: usize = 20_000;
+const MAX_FACET_VALUES: usize = 30;
+
 const KEY_ASCENDING: &str = "Ascending";
 const KEY_DESCENDING: &str = "Descending";
 
@@ -120,6 +161,72 @@ pub struct JournalPanelImp {
     #[template_child]
     journal_boot_id_entry: TemplateChild<adw::EntryRow>,
 
+    #[template_child]
+    journal_previous_run_button: TemplateChild<gtk::Button>,
+
+    #[template_child]
+    journal_run_dropdown: TemplateChild<gtk::DropDown>,
+
+    #[template_child]
+    journal_next_run_button: TemplateChild<gtk::Button>,
+
+    #[template_child]
+    journal_filter_button: TemplateChild<gtk::MenuButton>,
+
+    #[template_child]
+    journal_priority_dropdown: TemplateChild<gtk::DropDown>,
+
+    #[template_child]
+    journal_since_entry: TemplateChild<adw::EntryRow>,
+
+    #[template_child]
+    journal_until_entry: TemplateChild<adw::EntryRow>,
+
+    #[template_child]
+    journal_filter_apply_button: TemplateChild<gtk::Button>,
+
+    #[template_child]
+    journal_source_popover: TemplateChild<gtk::Popover>,
+
+    #[template_child]
+    journal_local_source_button: TemplateChild<gtk::Button>,
+
+    #[template_child]
+    offline_box: TemplateChild<gtk::Box>,
+
+    #[template_child]
+    offline_source_label: TemplateChild<gtk::Label>,
+
+    #[template_child]
+    offline_unit_dropdown: TemplateChild<gtk::DropDown>,
+
+    #[template_child]
+    journal_merge_button: TemplateChild<gtk::MenuButton>,
+
+    #[template_child]
+    merge_list: TemplateChild<gtk::ListBox>,
+
+    #[template_child]
+    merge_unit_entry: TemplateChild<adw::EntryRow>,
+
+    #[template_child]
+    merge_add_members_button: TemplateChild<gtk::Button>,
+
+    #[template_child]
+    journal_bookmarks_popover: TemplateChild<gtk::Popover>,
+
+    #[template_child]
+    bookmarks_list: TemplateChild<gtk::ListBox>,
+
+    #[template_child]
+    journal_facets_button: TemplateChild<gtk::ToggleButton>,
+
+    #[template_child]
+    facets_clear_button: TemplateChild<gtk::Button>,
+
+    #[template_child]
+    facets_list: TemplateChild<gtk::ListBox>,
+
     #[template_child]
     follow_check: TemplateChild<gtk::CheckButton>,
 
@@ -138,6 +245,32 @@ pub struct JournalPanelImp {
 
     boot_filter: RefCell<BootFilter>,
 
+    /// Recent runs of the unit, newest first
+    runs: RefCell<Vec<UnitRun>>,
+    runs_loaded: Cell<bool>,
+    loading_runs: Cell<bool>,
+
+    field_matches: RefCell<FieldMatches>,
+
+    /// Units whose events are interleaved, the selected unit only if empty
+    merged_units: RefCell<Vec<String>>,
+
+    /// Journal files opened instead of the system journal
+    source: RefCell<JournalSource>,
+    /// Units found in the opened journal files, and the one picked
+    offline_units: RefCell<Vec<(String, UnitDBusLevel)>>,
+    offline_unit: RefCell<Option<(String, UnitDBusLevel)>>,
+    loading_offline_units: Cell<bool>,
+
+    /// Least important priority shown, from 0 (emerg) to 7 (debug)
+    min_priority: Cell<Option<u8>>,
+    /// Realtime µs bounds of the shown events
+    since: Cell<Option<u64>>,
+    until: Cell<Option<u64>>,
+
+    /// Timestamp (`None` for boot separators) and number of lines of each displayed event, top to bottom
+    event_lines: RefCell<VecDeque<(Option<u64>, u32)>>,
+
     time_old_new: Cell<Option<(u64, u64)>>,
 
     //old_to_recent_order: Cell<bool>,
@@ -154,6 +287,227 @@ impl JournalPanelImp {
     fn refresh_journal_clicked(&self, _button: &gtk::Button) {
         info!("journal refresh button click");
         self.clean_refresh();
+        self.load_runs();
+        self.load_facets();
+    }
+
+    #[template_callback]
+    fn facets_clear_clicked(&self) {
+        self.field_matches.borrow_mut().clear();
+        self.field_matches_changed();
+    }
+
+    #[template_callback]
+    fn journal_time_filter_changed(&self) {
+        let since_valid = validate_time_entry(&self.journal_since_entry);
+        let until_valid = validate_time_entry(&self.journal_until_entry);
+        self.journal_filter_apply_button
+            .set_sensitive(since_valid && until_valid);
+    }
+
+    #[template_callback]
+    fn journal_filter_apply_clicked(&self) {
+        let since = parse_time_entry(&self.journal_since_entry);
+        let until = parse_time_entry(&self.journal_until_entry);
+
+        let (Some(since), Some(until)) = (since, until) else {
+            return;
+        };
+
+        let min_priority = match self.journal_priority_dropdown.selected() {
+            0 | gtk::INVALID_LIST_POSITION => None,
+            selected => Some((selected - 1) as u8),
+        };
+
+        self.journal_filter_button.popdown();
+        self.set_filters(min_priority, since, until);
+    }
+
+    #[template_callback]
+    fn journal_filter_clear_clicked(&self) {
+        self.journal_priority_dropdown.set_selected(0);
+        self.journal_since_entry.set_text("");
+        self.journal_until_entry.set_text("");
+
+        self.journal_filter_button.popdown();
+        self.set_filters(None, None, None);
+    }
+
+    #[template_callback]
+    fn merge_unit_entry_activated(&self) {
+        let unit_name = self.merge_unit_entry.text().trim().to_owned();
+        if unit_name.is_empty() {
+            return;
+        }
+
+        self.merge_unit_entry.set_text("");
+        self.add_merged_units(vec![unit_name]);
+    }
+
+    #[template_callback]
+    fn merge_add_selected_clicked(&self) {
+        let Some(unit) = self.unit.borrow().clone() else {
+            return;
+        };
+
+        self.add_merged_units(vec![unit.primary()]);
+    }
+
+    #[template_callback]
+    fn merge_add_members_clicked(&self) {
+        let Some(unit) = self.unit.borrow().clone() else {
+            return;
+        };
+
+        let journal_panel = self.obj().downgrade();
+        let level = unit.dbus_level();
+        let primary_name = unit.primary();
+
+        glib::spawn_future_local(async move {
+            let primary_name_ = primary_name.clone();
+            let members =
+                gio::spawn_blocking(move || systemd::fetch_unit_members(level, &primary_name_))
+                    .await;
+
+            let journal_panel = upgrade!(journal_panel);
+            match members {
+                Ok(Ok(members)) => {
+                    let mut unit_names = vec![primary_name];
+                    unit_names.extend(members);
+                    journal_panel.imp().add_merged_units(unit_names);
+                }
+                Ok(Err(err)) => warn!("Fetch unit members {err:?}"),
+                Err(err) => warn!("Fetch unit members join {err:?}"),
+            }
+        });
+    }
+
+    #[template_callback]
+    fn merge_clear_clicked(&self) {
+        self.set_merged_units(vec![]);
+    }
+
+    #[template_callback]
+    fn journal_bookmarks_popover_show(&self) {
+        let bookmarks = self
+            .bookmark_unit()
+            .map(|(unit_name, level)| bookmarks::load_bookmarks(&unit_name, level))
+            .unwrap_or_default();
+        self.fill_bookmarks(&bookmarks);
+    }
+
+    #[template_callback]
+    fn journal_open_directory_clicked(&self) {
+        self.journal_source_popover.popdown();
+
+        let file_dialog = gtk::FileDialog::builder()
+            .title(pgettext("journal", "Open Journal Directory"))
+            .accept_label(pgettext("journal", "Open"))
+            .build();
+
+        let journal_panel = self.obj().downgrade();
+        file_dialog.select_folder(
+            self.parent_window().as_ref(),
+            None::<&gio::Cancellable>,
+            move |result| match result {
+                Ok(folder) => {
+                    let journal_panel = upgrade!(journal_panel);
+                    if let Some(path) = folder.path() {
+                        journal_panel
+                            .imp()
+                            .set_source(JournalSource::Directory(path));
+                    }
+                }
+                Err(e) => warn!("Journal Directory Selection Error {e:?}"),
+            },
+        );
+    }
+
+    #[template_callback]
+    fn journal_open_files_clicked(&self) {
+        self.journal_source_popover.popdown();
+
+        let filter = gtk::FileFilter::new();
+        filter.set_name(Some(&pgettext("journal", "Journal files")));
+        filter.add_suffix("journal");
+        filter.add_suffix("journal~");
+
+        let filters = gio::ListStore::new::<gtk::FileFilter>();
+        filters.append(&filter);
+
+        let file_dialog = gtk::FileDialog::builder()
+            .title(pgettext("journal", "Open Journal Files"))
+            .accept_label(pgettext("journal", "Open"))
+            .filters(&filters)
+            .build();
+
+        let journal_panel = self.obj().downgrade();
+        file_dialog.open_multiple(
+            self.parent_window().as_ref(),
+            None::<&gio::Cancellable>,
+            move |result| match result {
+                Ok(files) => {
+                    let journal_panel = upgrade!(journal_panel);
+                    let files: Vec<PathBuf> = files
+                        .iter::<gio::File>()
+                        .filter_map(|file| file.ok().and_then(|file| file.path()))
+                        .collect();
+
+                    if !files.is_empty() {
+                        journal_panel.imp().set_source(JournalSource::Files(files));
+                    }
+                }
+                Err(e) => warn!("Journal Files Selection Error {e:?}"),
+            },
+        );
+    }
+
+    #[template_callback]
+    fn journal_local_source_clicked(&self) {
+        self.journal_source_popover.popdown();
+        self.set_source(JournalSource::Local);
+    }
+
+    #[template_callback]
+    fn journal_export_clicked(&self) {
+        let Some((unit_names, level)) = self.query_units() else {
+            info!("No unit to export");
+            return;
+        };
+
+        let query = ExportQuery {
+            source: self.source.borrow().clone(),
+            unit_names,
+            level,
+            boot_filter: self.boot_filter.borrow().clone(),
+            field_matches: self.field_matches.borrow().clone(),
+            min_priority: self.min_priority.get(),
+            since: self.since.get(),
+            until: self.until.get(),
+        };
+
+        let window = JournalExportWindow::new(self.parent_window().as_ref(), query);
+        window.present();
+    }
+
+    #[template_callback]
+    fn journal_previous_run_clicked(&self) {
+        let selected = self.journal_run_dropdown.selected();
+        let runs_len = self.runs.borrow().len() as u32;
+
+        // Index 0 is "All runs", older runs are at the end
+        if selected < runs_len {
+            self.journal_run_dropdown.set_selected(selected + 1);
+        }
+    }
+
+    #[template_callback]
+    fn journal_next_run_clicked(&self) {
+        let selected = self.journal_run_dropdown.selected();
+
+        if selected > 1 {
+            self.journal_run_dropdown.set_selected(selected - 1);
+        }
     }
 
     #[template_callback]
@@ -220,6 +574,7 @@ impl JournalPanelImp {
                 self.journal_boot_id_entry.set_text(&boot_id);
                 self.journal_boot_id_entry.add_css_class(CLASS_SUCCESS);
             }
+            BootFilter::Invocation(_) => {}
         }
     }
 
@@ -358,6 +713,10 @@ impl JournalPanelImp {
 
         self.update_journal_according_to_display_order();
 
+        if visible && !self.runs_loaded.get() {
+            self.load_runs();
+        }
+
         if visible && let Some(text_search_entry) = self.text_search_entry.get() {
             text_search_entry.set_text_view(&self.journal_text_view);
         }
@@ -377,11 +736,26 @@ impl JournalPanelImp {
 
         let old_unit = self.unit.replace(Some(unit.clone()));
 
+        self.merge_add_members_button.set_sensitive(matches!(
+            unit.unit_type(),
+            UnitType::Slice | UnitType::Target
+        ));
+
         //Assume that the ne unit is not None
         if old_unit.is_none_or(|o_unit| o_unit.primary() != unit.primary()) {
+            if matches!(*self.boot_filter.borrow(), BootFilter::Invocation(_)) {
+                self.boot_filter.replace(BootFilter::Current);
+            }
+            self.field_matches.borrow_mut().clear();
+            self.facets_clear_button.set_sensitive(false);
             self.new_text_view();
             self.set_or_send_cancelling(None);
             self.update_journal_according_to_display_order(); //TODO CHECK if needed to be include tin if clause 
+            self.runs_loaded.set(false);
+            if self.visible_on_page.get() {
+                self.load_runs();
+                self.load_facets();
+            }
         }
     }
 
@@ -408,13 +782,18 @@ impl JournalPanelImp {
             return;
         }
 
-        let binding = self.unit.borrow();
-        let Some(unit) = binding.as_ref() else {
+        let Some((unit_names, level)) = self.query_units() else {
             info!("No unit file");
             self.panel_stack.set_visible_child_name(PANEL_EMPTY);
             return;
         };
 
+        if level.is_custom() && self.source.borrow().is_local() {
+            info!("The journal of {unit_names:?} is on the machine of its bus");
+            self.panel_stack.set_visible_child_name(PANEL_REMOTE);
+            return;
+        }
+
         //self.unit_journal_loaded.set(true); // maybe wait at the full loaded
 
         let journal_max_events_batch_size: usize =
@@ -436,17 +815,15 @@ impl JournalPanelImp {
             journal_max_events_batch_size,
             oldest_event_time,
             newest_event_time,
-        );
+        )
+        .with_filters(self.min_priority.get(), self.since.get(), self.until.get());
 
-        info!(
-            "Journal unit {:?} boot filter \"{boot_filter:?}\" Range {range:#?}",
-            unit.primary()
-        );
+        info!("Journal units {unit_names:?} boot filter \"{boot_filter:?}\" Range {range:#?}");
 
         let journal_panel = self.obj().downgrade();
         let journal_refresh_button = self.journal_refresh_button.downgrade();
-        let level = unit.dbus_level();
-        let primary_name = unit.primary();
+        let source = self.source.borrow().clone();
+        let field_matches = self.field_matches.borrow().clone();
 
         glib::spawn_future_local(async move {
             let journal_panel = upgrade!(journal_panel);
@@ -460,9 +837,11 @@ impl JournalPanelImp {
                 let message_max_char = PREFERENCES.journal_event_max_size() as usize;
                 let timestamp_style = PREFERENCES.timestamp_style();
                 match systemd::get_unit_journal(
-                    primary_name,
+                    &source,
+                    &unit_names,
                     level,
                     boot_filter,
+                    &field_matches,
                     range,
                     message_max_char,
                     timestamp_style,
@@ -523,22 +902,30 @@ impl JournalPanelImp {
 
         let what_grab = journal_events.what_grab;
 
-        let (text_iter, journal_events_iter): (
+        let (text_iter, journal_events_iter, at_start): (
             gtk::TextIter,
             Box<dyn Iterator<Item = &JournalEvent>>,
+            bool,
         ) = match (what_grab, display_order) {
-            (WhatGrab::Newer, JournalDisplayOrder::Ascending) => {
-                (text_buffer.start_iter(), Box::new(journal_events.iter()))
-            }
-            (WhatGrab::Newer, JournalDisplayOrder::Descending) => {
-                (text_buffer.end_iter(), Box::new(journal_events.iter()))
-            }
-            (WhatGrab::Older, JournalDisplayOrder::Ascending) => {
-                (text_buffer.end_iter(), Box::new(journal_events.iter()))
-            }
+            (WhatGrab::Newer, JournalDisplayOrder::Ascending) => (
+                text_buffer.start_iter(),
+                Box::new(journal_events.iter()),
+                true,
+            ),
+            (WhatGrab::Newer, JournalDisplayOrder::Descending) => (
+                text_buffer.end_iter(),
+                Box::new(journal_events.iter()),
+                false,
+            ),
+            (WhatGrab::Older, JournalDisplayOrder::Ascending) => (
+                text_buffer.end_iter(),
+                Box::new(journal_events.iter()),
+                false,
+            ),
             (WhatGrab::Older, JournalDisplayOrder::Descending) => (
                 text_buffer.start_iter(),
                 Box::new(journal_events.iter().rev()),
+                true,
             ),
         };
 
@@ -577,10 +964,30 @@ impl JournalPanelImp {
         };
 
         let journal_color = PREFERENCES.journal_colors();
-        let mut journal_filler = JournalFiller::new(journal_color);
+        let unit_names = self
+            .query_units()
+            .map(|(unit_names, _level)| unit_names)
+            .unwrap_or_default();
+        let mut journal_filler = JournalFiller::new(journal_color, &writer.buffer, unit_names);
 
+        let mut event_lines = Vec::with_capacity(journal_events.len());
         for journal_event in journal_events_iter {
             journal_filler.fill_journal_event(journal_event, &mut writer);
+
+            let timestamp = (journal_event.priority != BOOT_IDX).then_some(journal_event.timestamp);
+            let lines = journal_event.message.lines().count().max(1) as u32;
+            event_lines.push((timestamp, lines));
+        }
+
+        {
+            let mut displayed_lines = self.event_lines.borrow_mut();
+            if at_start {
+                for line in event_lines.into_iter().rev() {
+                    displayed_lines.push_front(line);
+                }
+            } else {
+                displayed_lines.extend(event_lines);
+            }
         }
 
         info!("Finish adding {} journal events!", journal_events.len());
@@ -642,14 +1049,27 @@ impl JournalPanelImp {
     }
 
     fn continuous_entry(&self) {
-        let binding = self.unit.borrow();
-        let Some(unit_ref) = binding.as_ref() else {
+        if matches!(*self.boot_filter.borrow(), BootFilter::Invocation(_)) {
+            info!("No follow on a single run");
+            return;
+        }
+
+        if self.until.get().is_some() {
+            info!("No follow with an upper time bound");
+            return;
+        }
+
+        if !self.source.borrow().is_local() {
+            info!("No follow on journal files");
+            return;
+        }
+
+        let Some((unit_names, level)) = self.query_units() else {
             info!("No unit file");
             return;
         };
 
         //self.unit_journal_loaded.set(true); // maybe wait at the full loaded
-        let unit = unit_ref.clone();
 
         let journal_max_events_batch_size: usize =
             PREFERENCES.journal_max_events_batch_size() as usize;
@@ -664,7 +1084,8 @@ impl JournalPanelImp {
             journal_max_events_batch_size,
             oldest_event_time,
             newest_event_time,
-        );
+        )
+        .with_filters(self.min_priority.get(), self.since.get(), None);
 
         let (journal_continuous_sender, journal_continuous_receiver) = std::sync::mpsc::channel();
 
@@ -678,14 +1099,14 @@ impl JournalPanelImp {
 
         self.set_or_send_cancelling(Some(journal_continuous_sender));
 
-        let unit_name = unit.primary();
-        let level = unit.dbus_level();
+        let field_matches = self.field_matches.borrow().clone();
         thread::spawn(move || {
             let message_max_char = PREFERENCES.journal_event_max_size() as usize;
             let timestamp_style = PREFERENCES.timestamp_style();
             systemd::get_unit_journal_continuous(
-                unit_name,
+                unit_names,
                 level,
+                field_matches,
                 range,
                 journal_continuous_receiver,
                 sender,
@@ -775,6 +1196,7 @@ impl JournalPanelImp {
         self.time_old_new.set(None);
 
         let buffer = self.journal_text_view.buffer();
+        self.event_lines.borrow_mut().clear();
 
         //remove any marks
 
@@ -789,72 +1211,978 @@ impl JournalPanelImp {
     }
 
     fn update_boot_filter(&self, boot_filter: BootFilter) {
+        if !matches!(boot_filter, BootFilter::Invocation(_)) {
+            self.loading_runs.set(true);
+            self.journal_run_dropdown.set_selected(0);
+            self.loading_runs.set(false);
+            self.update_run_buttons();
+        }
+
         let replaced = self.boot_filter.replace(boot_filter.clone());
 
         if replaced != boot_filter {
             //filter updated
             self.clean_refresh();
+            self.load_facets();
         }
     }
 
-    pub(crate) fn focus_text_search(&self) {
-        // text_search::focus_on_text_entry(&self.text_search_bar)
+    /// Merge more units, starting with the selected one
+    fn add_merged_units(&self, unit_names: Vec<String>) {
+        let mut merged_units = self.merged_units.borrow().clone();
+
+        if merged_units.is_empty()
+            && let Some(unit) = self.unit.borrow().as_ref()
+        {
+            merged_units.push(unit.primary());
+        }
+
+        for unit_name in unit_names {
+            if merged_units.len() >= MAX_MERGED_UNITS {
+                warn!("Can't merge more than {MAX_MERGED_UNITS} units");
+                break;
+            }
+
+            if !merged_units.contains(&unit_name) {
+                merged_units.push(unit_name);
+            }
+        }
+
+        self.set_merged_units(merged_units);
     }
 
-    fn set_wrap_word(&self, wrap: bool) {
-        if wrap {
-            self.journal_text_view
-                .set_wrap_mode(gtk::WrapMode::WordChar)
+    fn set_merged_units(&self, merged_units: Vec<String>) {
+        if *self.merged_units.borrow() == merged_units {
+            return;
+        }
+
+        self.merge_list.remove_all();
+        for (idx, unit_name) in merged_units.iter().enumerate() {
+            let tag = gtk::Label::builder()
+                .use_markup(true)
+                .label(format!(
+                    "<span color=\"{}\">●</span>",
+                    palette::unit_tag(idx as u8).get_color()
+                ))
+                .build();
+
+            let remove_button = gtk::Button::builder()
+                .icon_name("list-remove-symbolic")
+                .valign(gtk::Align::Center)
+                .css_classes(["flat"])
+                .tooltip_text(pgettext("journal", "Remove"))
+                .build();
+
+            let row = adw::ActionRow::builder()
+                .title(glib::markup_escape_text(unit_name))
+                .build();
+            row.add_prefix(&tag);
+            row.add_suffix(&remove_button);
+            self.merge_list.append(&row);
+
+            let journal_panel = self.obj().downgrade();
+            let unit_name = unit_name.clone();
+            remove_button.connect_clicked(move |_| {
+                let journal_panel = upgrade!(journal_panel);
+                let imp = journal_panel.imp();
+                let mut merged_units = imp.merged_units.borrow().clone();
+                merged_units.retain(|name| *name != unit_name);
+                imp.set_merged_units(merged_units);
+            });
+        }
+
+        if merged_units.is_empty() {
+            self.journal_merge_button.remove_css_class(CLASS_ACCENT);
         } else {
-            self.journal_text_view.set_wrap_mode(gtk::WrapMode::None)
+            self.journal_merge_button.add_css_class(CLASS_ACCENT);
         }
-    }
-}
 
-// The central trait for subclassing a GObject
-#[glib::object_subclass]
-impl ObjectSubclass for JournalPanelImp {
-    const NAME: &'static str = "JournalPanel";
-    type Type = super::JournalPanel;
-    type ParentType = gtk::Box;
+        self.merged_units.replace(merged_units);
 
-    fn class_init(klass: &mut Self::Class) {
-        // The layout manager determines how child widgets are laid out.
-        klass.bind_template();
-        klass.bind_template_callbacks();
-    }
+        if matches!(*self.boot_filter.borrow(), BootFilter::Invocation(_)) {
+            self.boot_filter.replace(BootFilter::Current);
+        }
+        self.field_matches.borrow_mut().clear();
+        self.facets_clear_button.set_sensitive(false);
 
-    fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
-        obj.init_template();
+        self.clean_refresh();
+        self.load_runs();
+        self.load_facets();
     }
-}
 
-#[glib::derived_properties]
-impl ObjectImpl for JournalPanelImp {
-    fn constructed(&self) {
-        self.parent_constructed();
+    /// The unit the bookmarks are stored for, cursors are only kept for the system journal
+    fn bookmark_unit(&self) -> Option<(String, UnitDBusLevel)> {
+        if !self.source.borrow().is_local() {
+            return None;
+        }
 
-        self.new_text_view();
+        self.unit
+            .borrow()
+            .as_ref()
+            .map(|unit| (unit.primary(), unit.dbus_level()))
+    }
 
-        let settings = systemd_gui::new_settings();
-        settings
-            .bind(
-                KEY_PREF_JOURNAL_DISPLAY_FOLLOW,
-                &self.follow_check.clone(),
-                "active",
-            )
-            .build();
+    fn add_bookmark(&self, bookmark: JournalBookmark) {
+        let Some((unit_name, level)) = self.bookmark_unit() else {
+            return;
+        };
 
-        settings
-            .bind(
-                &ACTION_WIN_KEY_JOURNAL_WRAP_WORD[4..],
-                self.obj().as_ref(),
-                "wrap-word",
-            )
-            .build();
+        let mut bookmarks = bookmarks::load_bookmarks(&unit_name, level);
+        match bookmarks.iter_mut().find(|b| b.cursor == bookmark.cursor) {
+            Some(existing) => existing.note = bookmark.note,
+            None => bookmarks.push(bookmark),
+        }
 
-        let display_order = settings.string(KEY_PREF_JOURNAL_DISPLAY_ORDER);
-        let display_order = JournalDisplayOrder::from_key(&display_order);
+        bookmarks::save_bookmarks(&unit_name, level, bookmarks);
+    }
+
+    fn remove_bookmark(&self, cursor: &str) {
+        let Some((unit_name, level)) = self.bookmark_unit() else {
+            return;
+        };
+
+        let mut bookmarks = bookmarks::load_bookmarks(&unit_name, level);
+        bookmarks.retain(|bookmark| bookmark.cursor != cursor);
+        self.fill_bookmarks(&bookmarks);
+
+        bookmarks::save_bookmarks(&unit_name, level, bookmarks);
+    }
+
+    fn fill_bookmarks(&self, bookmarks: &[JournalBookmark]) {
+        self.bookmarks_list.remove_all();
+
+        let timestamp_style = PREFERENCES.timestamp_style();
+        for bookmark in bookmarks {
+            let time = time_handling::get_since_time(bookmark.timestamp, timestamp_style);
+
+            let (title, subtitle) = if bookmark.note.is_empty() {
+                (bookmark.message.as_str(), time)
+            } else {
+                (
+                    bookmark.note.as_str(),
+                    format!("{time} {}", bookmark.message),
+                )
+            };
+
+            let remove_button = gtk::Button::builder()
+                .icon_name("user-trash-symbolic")
+                .valign(gtk::Align::Center)
+                .css_classes(["flat"])
+                .tooltip_text(pgettext("journal", "Remove"))
+                .build();
+
+            let row = adw::ActionRow::builder()
+                .title(glib::markup_escape_text(title))
+                .subtitle(glib::markup_escape_text(&subtitle))
+                .subtitle_lines(2)
+                .activatable(true)
+                .build();
+            row.add_suffix(&remove_button);
+            self.bookmarks_list.append(&row);
+
+            let journal_panel = self.obj().downgrade();
+            let bookmark_ = bookmark.clone();
+            row.connect_activated(move |_| {
+                let journal_panel = upgrade!(journal_panel);
+                let imp = journal_panel.imp();
+                imp.journal_bookmarks_popover.popdown();
+                imp.open_bookmark(&bookmark_);
+            });
+
+            let journal_panel = self.obj().downgrade();
+            let cursor = bookmark.cursor.clone();
+            remove_button.connect_clicked(move |_| {
+                let journal_panel = upgrade!(journal_panel);
+                journal_panel.imp().remove_bookmark(&cursor);
+            });
+        }
+    }
+
+    /// Show the bookmarked event among its neighbours, whatever the boot
+    fn open_bookmark(&self, bookmark: &JournalBookmark) {
+        let Some((unit_names, level)) = self.query_units() else {
+            return;
+        };
+
+        self.set_or_send_cancelling(None);
+        self.boot_filter.replace(BootFilter::All);
+        self.field_matches.borrow_mut().clear();
+        self.facets_clear_button.set_sensitive(false);
+        self.loading_runs.set(true);
+        self.journal_run_dropdown.set_selected(0);
+        self.loading_runs.set(false);
+        self.update_run_buttons();
+        self.new_text_view();
+
+        let journal_panel = self.obj().downgrade();
+        let source = self.source.borrow().clone();
+        let cursor = bookmark.cursor.clone();
+        let timestamp = bookmark.timestamp;
+
+        glib::spawn_future_local(async move {
+            let journal_events = gio::spawn_blocking(move || {
+                let message_max_char = PREFERENCES.journal_event_max_size() as usize;
+                let timestamp_style = PREFERENCES.timestamp_style();
+                systemd::get_unit_journal_around_cursor(
+                    &source,
+                    &unit_names,
+                    level,
+                    &FieldMatches::default(),
+                    &cursor,
+                    BOOKMARK_CONTEXT,
+                    message_max_char,
+                    timestamp_style,
+                )
+            })
+            .await;
+
+            let journal_events = match journal_events {
+                Ok(Ok(journal_events)) => journal_events,
+                Ok(Err(err)) => {
+                    warn!("Journal bookmark events {err:?}");
+                    return;
+                }
+                Err(err) => {
+                    warn!("Journal bookmark events join {err:?}");
+                    return;
+                }
+            };
+
+            let journal_panel = upgrade!(journal_panel);
+            let imp = journal_panel.imp();
+            imp.handle_journal_events(&journal_events);
+            imp.load_facets();
+            imp.highlight_event(timestamp);
+        });
+    }
+
+    /// Highlight the displayed event logged at `timestamp` and scroll to it
+    fn highlight_event(&self, timestamp: u64) {
+        let mut first_line = 0;
+        let mut event = None;
+        for (event_timestamp, lines) in self.event_lines.borrow().iter() {
+            if *event_timestamp == Some(timestamp) {
+                event = Some((first_line, *lines as i32));
+                break;
+            }
+            first_line += *lines as i32;
+        }
+
+        let Some((first_line, lines)) = event else {
+            info!("Bookmarked event at {timestamp} not displayed");
+            return;
+        };
+
+        let buffer = self.journal_text_view.buffer();
+        let tag = match buffer.tag_table().lookup(TAG_BOOKMARK) {
+            Some(tag) => tag,
+            None => {
+                let (red, green, blue) = palette::blue().get_rgb();
+                let tag = gtk::TextTag::builder()
+                    .name(TAG_BOOKMARK)
+                    .paragraph_background(format!("rgba({red},{green},{blue},0.3)"))
+                    .build();
+                buffer.tag_table().add(&tag);
+                tag
+            }
+        };
+
+        let Some(start_iter) = buffer.iter_at_line(first_line) else {
+            return;
+        };
+        let end_iter = buffer
+            .iter_at_line(first_line + lines)
+            .unwrap_or_else(|| buffer.end_iter());
+        buffer.apply_tag(&tag, &start_iter, &end_iter);
+
+        let mark = match buffer.mark(MARK_BOOKMARK) {
+            Some(mark) => {
+                buffer.move_mark(&mark, &start_iter);
+                mark
+            }
+            None => buffer.create_mark(Some(MARK_BOOKMARK), &start_iter, true),
+        };
+
+        let text_view = self.journal_text_view.get();
+        glib::idle_add_local_once(move || {
+            text_view.scroll_to_mark(&mark, 0.0, true, 0.0, 0.5);
+        });
+    }
+
+    fn parent_window(&self) -> Option<gtk::Window> {
+        self.obj().root().and_downcast::<gtk::Window>()
+    }
+
+    /// The units of the query: the merged ones, the one picked among the journal files ones
+    /// when not reading the system journal, or the selected one
+    fn query_units(&self) -> Option<(Vec<String>, UnitDBusLevel)> {
+        if !self.source.borrow().is_local() {
+            return self
+                .offline_unit
+                .borrow()
+                .clone()
+                .map(|(unit_name, level)| (vec![unit_name], level));
+        }
+
+        let unit = self.unit.borrow();
+        let merged_units = self.merged_units.borrow();
+        if !merged_units.is_empty() {
+            let level = unit
+                .as_ref()
+                .map_or(UnitDBusLevel::System, |unit| unit.dbus_level());
+            return Some((merged_units.clone(), level));
+        }
+
+        unit.as_ref()
+            .map(|unit| (vec![unit.primary()], unit.dbus_level()))
+    }
+
+    fn set_source(&self, source: JournalSource) {
+        let local = source.is_local();
+        self.source.replace(source.clone());
+        self.offline_unit.replace(None);
+        self.offline_units.replace(vec![]);
+
+        self.offline_box.set_visible(!local);
+        self.journal_local_source_button.set_sensitive(!local);
+        self.offline_source_label.set_text(&source.paths_label());
+        self.offline_source_label
+            .set_tooltip_text(Some(&source.paths_label()));
+
+        // The boots of the journal files are unrelated to the running one
+        let boot_filter = if local {
+            BootFilter::Current
+        } else {
+            BootFilter::All
+        };
+        self.boot_filter.replace(boot_filter);
+        self.field_matches.borrow_mut().clear();
+        self.facets_clear_button.set_sensitive(false);
+
+        self.clean_refresh();
+        self.load_runs();
+
+        if local {
+            self.load_facets();
+            return;
+        }
+
+        let journal_panel = self.obj().downgrade();
+        glib::spawn_future_local(async move {
+            let source_ = source.clone();
+            let units = gio::spawn_blocking(move || systemd::list_journal_units(&source_)).await;
+
+            let journal_panel = upgrade!(journal_panel);
+            let journal_panel_imp = journal_panel.imp();
+
+            if *journal_panel_imp.source.borrow() != source {
+                // Another source opened meanwhile
+                return;
+            }
+
+            match units {
+                Ok(Ok(units)) => journal_panel_imp.set_offline_units(units),
+                Ok(Err(err)) => {
+                    warn!("List journal units {err:?}");
+                    journal_panel_imp
+                        .offline_source_label
+                        .set_text(&err.human_error_type());
+                    journal_panel_imp.set_offline_units(vec![]);
+                }
+                Err(err) => warn!("List journal units join {err:?}"),
+            }
+        });
+    }
+
+    fn set_offline_units(&self, units: Vec<(String, UnitDBusLevel)>) {
+        let labels: Vec<String> = units
+            .iter()
+            .map(|(unit, level)| match level {
+                UnitDBusLevel::UserSession => {
+                    format!("{unit} ({})", pgettext("journal", "user"))
+                }
+                _ => unit.clone(),
+            })
+            .collect();
+        let labels: Vec<&str> = labels.iter().map(|label| label.as_str()).collect();
+
+        // Keep the selected unit if it is in the journal files
+        let selected = self.unit.borrow().as_ref().and_then(|unit| {
+            let primary = unit.primary();
+            units.iter().position(|(name, _)| *name == primary)
+        });
+
+        self.offline_units.replace(units);
+
+        self.loading_offline_units.set(true);
+        self.offline_unit_dropdown
+            .set_model(Some(&gtk::StringList::new(&labels)));
+        self.offline_unit_dropdown
+            .set_selected(selected.unwrap_or(0) as u32);
+        self.loading_offline_units.set(false);
+
+        self.offline_unit_selected();
+    }
+
+    fn offline_unit_selected(&self) {
+        if self.loading_offline_units.get() {
+            return;
+        }
+
+        let selected = self.offline_unit_dropdown.selected();
+        let unit = self.offline_units.borrow().get(selected as usize).cloned();
+
+        if *self.offline_unit.borrow() == unit {
+            return;
+        }
+
+        self.offline_unit.replace(unit);
+        self.field_matches.borrow_mut().clear();
+        self.facets_clear_button.set_sensitive(false);
+        self.clean_refresh();
+        self.load_facets();
+    }
+
+    fn set_filters(&self, min_priority: Option<u8>, since: Option<u64>, until: Option<u64>) {
+        let filtering = min_priority.is_some() || since.is_some() || until.is_some();
+        if filtering {
+            self.journal_filter_button.add_css_class(CLASS_ACCENT);
+        } else {
+            self.journal_filter_button.remove_css_class(CLASS_ACCENT);
+        }
+
+        let old_filters = (
+            self.min_priority.replace(min_priority),
+            self.since.replace(since),
+            self.until.replace(until),
+        );
+
+        if old_filters != (min_priority, since, until) {
+            self.clean_refresh();
+        }
+    }
+
+    fn field_matches_changed(&self) {
+        self.facets_clear_button
+            .set_sensitive(!self.field_matches.borrow().is_empty());
+        self.clean_refresh();
+        self.load_facets();
+    }
+
+    /// Count the values of the facet fields for the current query
+    fn load_facets(&self) {
+        if !self.journal_facets_button.is_active() {
+            return;
+        }
+
+        let Some((unit_names, level)) = self.query_units() else {
+            return;
+        };
+
+        let journal_panel = self.obj().downgrade();
+        let source = self.source.borrow().clone();
+        let boot_filter = self.boot_filter.borrow().clone();
+        let field_matches = self.field_matches.borrow().clone();
+
+        glib::spawn_future_local(async move {
+            let facets = gio::spawn_blocking(move || {
+                systemd::list_unit_journal_facets(
+                    &source,
+                    &unit_names,
+                    level,
+                    boot_filter,
+                    &field_matches,
+                    &FACET_FIELDS,
+                    MAX_FACET_ENTRIES,
+                )
+            })
+            .await;
+
+            let journal_panel = upgrade!(journal_panel);
+            match facets {
+                Ok(Ok(facets)) => journal_panel.imp().set_facets(facets),
+                Ok(Err(err)) => warn!("Journal facets {err:?}"),
+                Err(err) => warn!("Journal facets join {err:?}"),
+            }
+        });
+    }
+
+    fn set_facets(&self, facets: Vec<JournalFacet>) {
+        self.facets_list.remove_all();
+
+        for facet in facets {
+            if facet.values.is_empty() {
+                continue;
+            }
+
+            let field_matches = self.field_matches.borrow();
+            let expanded = facet
+                .values
+                .iter()
+                .any(|(value, _)| field_matches.contains(&facet.field, value));
+
+            let expander_row = adw::ExpanderRow::builder()
+                .title(&facet.field)
+                .subtitle(format!(
+                    "{} {}",
+                    facet.values.len(),
+                    pgettext("journal", "values")
+                ))
+                .expanded(expanded)
+                .build();
+
+            for (value, count) in facet.values.iter().take(MAX_FACET_VALUES) {
+                let check = gtk::CheckButton::builder()
+                    .active(field_matches.contains(&facet.field, value))
+                    .valign(gtk::Align::Center)
+                    .build();
+
+                let row = adw::ActionRow::builder()
+                    .title(value)
+                    .use_markup(false)
+                    .activatable_widget(&check)
+                    .build();
+                row.add_prefix(&check);
+                row.add_suffix(
+                    &gtk::Label::builder()
+                        .label(count.to_string())
+                        .css_classes(["dim-label"])
+                        .build(),
+                );
+
+                let journal_panel = self.obj().downgrade();
+                let field = facet.field.clone();
+                let value = value.clone();
+                check.connect_toggled(move |_| {
+                    let journal_panel = upgrade!(journal_panel);
+                    let imp = journal_panel.imp();
+                    imp.field_matches.borrow_mut().toggle(&field, &value);
+                    imp.field_matches_changed();
+                });
+
+                expander_row.add_row(&row);
+            }
+
+            self.facets_list.append(&expander_row);
+        }
+    }
+
+    /// The timestamp of the event displayed at the given buffer line
+    fn event_at_line(&self, line: i32) -> Option<u64> {
+        let mut first_line = 0;
+        for (timestamp, lines) in self.event_lines.borrow().iter() {
+            let next_first_line = first_line + *lines as i32;
+            if line < next_first_line {
+                return *timestamp;
+            }
+            first_line = next_first_line;
+        }
+        None
+    }
+
+    /// The text position and the timestamp of the event under the pointer
+    fn event_at_pointer(&self, x: f64, y: f64) -> Option<(gtk::TextIter, u64)> {
+        let text_view = self.journal_text_view.get();
+        let (buffer_x, buffer_y) =
+            text_view.window_to_buffer_coords(gtk::TextWindowType::Widget, x as i32, y as i32);
+
+        let iter = text_view.iter_at_location(buffer_x, buffer_y)?;
+        let timestamp = self.event_at_line(iter.line())?;
+
+        Some((iter, timestamp))
+    }
+
+    /// Show the catalog explanation if the pointer is on the event catalog glyph
+    fn show_event_catalog(&self, x: f64, y: f64) {
+        let Some((iter, timestamp)) = self.event_at_pointer(x, y) else {
+            return;
+        };
+
+        let buffer = self.journal_text_view.buffer();
+        let Some(tag) = buffer.tag_table().lookup(TAG_CATALOG) else {
+            return;
+        };
+
+        if !iter.has_tag(&tag) {
+            return;
+        }
+
+        let Some((unit_names, level)) = self.query_units() else {
+            return;
+        };
+
+        let text_view = self.journal_text_view.get();
+        let source = self.source.borrow().clone();
+        let boot_filter = self.boot_filter.borrow().clone();
+        let field_matches = self.field_matches.borrow().clone();
+
+        glib::spawn_future_local(async move {
+            let catalog = gio::spawn_blocking(move || {
+                systemd::fetch_journal_event_catalog(
+                    &source,
+                    &unit_names,
+                    level,
+                    boot_filter,
+                    &field_matches,
+                    timestamp,
+                )
+            })
+            .await;
+
+            let catalog = match catalog {
+                Ok(Ok(Some(catalog))) => catalog,
+                Ok(Ok(None)) => {
+                    info!("No catalog for the event at {timestamp}");
+                    return;
+                }
+                Ok(Err(err)) => {
+                    warn!("Journal event catalog {err:?}");
+                    return;
+                }
+                Err(err) => {
+                    warn!("Journal event catalog join {err:?}");
+                    return;
+                }
+            };
+
+            let catalog_view = gtk::TextView::builder()
+                .editable(false)
+                .wrap_mode(gtk::WrapMode::WordChar)
+                .left_margin(5)
+                .right_margin(5)
+                .build();
+            catalog_view.buffer().set_text(catalog.trim_end());
+
+            let scrolled_window = gtk::ScrolledWindow::builder()
+                .child(&catalog_view)
+                .hscrollbar_policy(gtk::PolicyType::Never)
+                .min_content_width(500)
+                .max_content_height(500)
+                .propagate_natural_height(true)
+                .build();
+
+            let popover = gtk::Popover::builder()
+                .child(&scrolled_window)
+                .pointing_to(&gtk::gdk::Rectangle::new(x as i32, y as i32, 1, 1))
+                .build();
+            popover.set_parent(&text_view);
+            popover.connect_closed(|popover| popover.unparent());
+            popover.popup();
+        });
+    }
+
+    /// Show all the fields of the event under the pointer
+    fn show_event_details(&self, x: f64, y: f64) {
+        let text_view = self.journal_text_view.get();
+
+        let Some((_iter, timestamp)) = self.event_at_pointer(x, y) else {
+            return;
+        };
+
+        let Some((unit_names, level)) = self.query_units() else {
+            return;
+        };
+
+        let source = self.source.borrow().clone();
+        let boot_filter = self.boot_filter.borrow().clone();
+        let field_matches = self.field_matches.borrow().clone();
+        let can_bookmark = self.bookmark_unit().is_some();
+        let journal_panel = self.obj().downgrade();
+
+        glib::spawn_future_local(async move {
+            let fields = gio::spawn_blocking(move || {
+                systemd::fetch_journal_event_fields(
+                    &source,
+                    &unit_names,
+                    level,
+                    boot_filter,
+                    &field_matches,
+                    timestamp,
+                )
+            })
+            .await;
+
+            let fields = match fields {
+                Ok(Ok(fields)) => fields,
+                Ok(Err(err)) => {
+                    warn!("Journal event fields {err:?}");
+                    return;
+                }
+                Err(err) => {
+                    warn!("Journal event fields join {err:?}");
+                    return;
+                }
+            };
+
+            let details = fields
+                .iter()
+                .map(|(field, value)| format!("{field}={value}"))
+                .collect::<Vec<_>>()
+                .join("\n");
+
+            let details_view = gtk::TextView::builder()
+                .editable(false)
+                .monospace(true)
+                .wrap_mode(gtk::WrapMode::WordChar)
+                .left_margin(5)
+                .right_margin(5)
+                .build();
+            details_view.buffer().set_text(&details);
+
+            let scrolled_window = gtk::ScrolledWindow::builder()
+                .child(&details_view)
+                .hscrollbar_policy(gtk::PolicyType::Never)
+                .min_content_width(500)
+                .min_content_height(300)
+                .max_content_height(500)
+                .propagate_natural_height(true)
+                .build();
+
+            let content = gtk::Box::builder()
+                .orientation(gtk::Orientation::Vertical)
+                .spacing(5)
+                .build();
+            content.append(&scrolled_window);
+
+            let popover = gtk::Popover::builder()
+                .child(&content)
+                .pointing_to(&gtk::gdk::Rectangle::new(x as i32, y as i32, 1, 1))
+                .build();
+
+            let field = |key: &str| {
+                fields
+                    .iter()
+                    .find(|(field, _)| field == key)
+                    .map(|(_, value)| value.clone())
+            };
+
+            if can_bookmark && let Some(cursor) = field(KEY_CURSOR) {
+                let message = field("MESSAGE").unwrap_or_default();
+                let message = message.lines().next().unwrap_or_default().to_owned();
+
+                let note_entry = gtk::Entry::builder()
+                    .placeholder_text(pgettext("journal", "Bookmark note"))
+                    .hexpand(true)
+                    .build();
+
+                let bookmark_button = gtk::Button::builder()
+                    .label(pgettext("journal", "Bookmark"))
+                    .build();
+
+                let bookmark_box = gtk::Box::builder().spacing(5).build();
+                bookmark_box.append(&note_entry);
+                bookmark_box.append(&bookmark_button);
+                content.append(&bookmark_box);
+
+                let add_bookmark = move |note_entry: &gtk::Entry, popover: &gtk::Popover| {
+                    let journal_panel = upgrade!(journal_panel);
+                    journal_panel.imp().add_bookmark(JournalBookmark {
+                        cursor: cursor.clone(),
+                        timestamp,
+                        note: note_entry.text().trim().to_owned(),
+                        message: message.clone(),
+                    });
+                    popover.popdown();
+                };
+                let add_bookmark = Rc::new(add_bookmark);
+
+                {
+                    let add_bookmark = add_bookmark.clone();
+                    let popover = popover.downgrade();
+                    note_entry.connect_activate(move |note_entry| {
+                        let popover = upgrade!(popover);
+                        add_bookmark(note_entry, &popover);
+                    });
+                }
+
+                let popover_ = popover.downgrade();
+                bookmark_button.connect_clicked(move |_| {
+                    let popover = upgrade!(popover_);
+                    add_bookmark(&note_entry, &popover);
+                });
+            }
+
+            popover.set_parent(&text_view);
+            popover.connect_closed(|popover| popover.unparent());
+            popover.popup();
+        });
+    }
+
+    /// Fill the run drop down with the unit recent invocations
+    fn load_runs(&self) {
+        let Some(unit) = self.unit.borrow().clone() else {
+            return;
+        };
+        self.runs_loaded.set(true);
+
+        // Runs are only tracked on the system journal for a single unit of a local bus
+        if !self.source.borrow().is_local()
+            || !self.merged_units.borrow().is_empty()
+            || unit.dbus_level().is_custom()
+        {
+            self.set_runs(vec![]);
+            return;
+        }
+
+        let journal_panel = self.obj().downgrade();
+        let level = unit.dbus_level();
+        let primary_name = unit.primary();
+
+        glib::spawn_future_local(async move {
+            let runs = gio::spawn_blocking(move || {
+                invocation::fetch_unit_runs(&primary_name, level, MAX_RUN_RECORDS)
+            })
+            .await;
+
+            let journal_panel = upgrade!(journal_panel);
+            let mut runs = match runs {
+                Ok(Ok(runs)) => runs,
+                Ok(Err(err)) => {
+                    warn!("Fetch unit runs {err:?}");
+                    vec![]
+                }
+                Err(err) => {
+                    warn!("Fetch unit runs join {err:?}");
+                    vec![]
+                }
+            };
+            runs.truncate(MAX_RUNS);
+
+            journal_panel.imp().set_runs(runs);
+        });
+    }
+
+    fn set_runs(&self, runs: Vec<UnitRun>) {
+        let timestamp_style = PREFERENCES.timestamp_style();
+
+        let mut labels = vec![pgettext("journal", "All runs")];
+        labels.extend(runs.iter().map(|run| {
+            let result = match &run.result {
+                RunResult::Success => pgettext("journal", "success"),
+                RunResult::Failed(result) => result.clone(),
+                RunResult::Running => pgettext("journal", "running"),
+                RunResult::Unknown => pgettext("journal", "unknown"),
+            };
+            format!("{} {result}", timestamp_style.usec_formated(run.start))
+        }));
+
+        let selected = match &*self.boot_filter.borrow() {
+            BootFilter::Invocation(invocation_id) => runs
+                .iter()
+                .position(|run| run.invocation_id == *invocation_id)
+                .map_or(0, |idx| idx as u32 + 1),
+            _ => 0,
+        };
+
+        self.runs.replace(runs);
+
+        let labels: Vec<&str> = labels.iter().map(|label| label.as_str()).collect();
+        self.loading_runs.set(true);
+        self.journal_run_dropdown
+            .set_model(Some(&gtk::StringList::new(&labels)));
+        self.journal_run_dropdown.set_selected(selected);
+        self.loading_runs.set(false);
+
+        self.update_run_buttons();
+    }
+
+    fn run_selected(&self) {
+        if self.loading_runs.get() {
+            return;
+        }
+
+        self.update_run_buttons();
+
+        let selected = self.journal_run_dropdown.selected();
+        let invocation_id = if selected == 0 || selected == gtk::INVALID_LIST_POSITION {
+            None
+        } else {
+            self.runs
+                .borrow()
+                .get(selected as usize - 1)
+                .map(|run| run.invocation_id.clone())
+        };
+
+        match invocation_id {
+            Some(invocation_id) => {
+                self.set_or_send_cancelling(None);
+                self.update_boot_filter(BootFilter::Invocation(invocation_id));
+            }
+            None => {
+                if matches!(*self.boot_filter.borrow(), BootFilter::Invocation(_)) {
+                    self.update_boot_filter(BootFilter::Current);
+                }
+            }
+        }
+    }
+
+    fn update_run_buttons(&self) {
+        let selected = self.journal_run_dropdown.selected();
+        let runs_len = self.runs.borrow().len() as u32;
+
+        self.journal_previous_run_button
+            .set_sensitive(selected < runs_len);
+        self.journal_next_run_button.set_sensitive(selected > 1);
+    }
+
+    pub(crate) fn focus_text_search(&self) {
+        // text_search::focus_on_text_entry(&self.text_search_bar)
+    }
+
+    fn set_wrap_word(&self, wrap: bool) {
+        if wrap {
+            self.journal_text_view
+                .set_wrap_mode(gtk::WrapMode::WordChar)
+        } else {
+            self.journal_text_view.set_wrap_mode(gtk::WrapMode::None)
+        }
+    }
+}
+
+// The central trait for subclassing a GObject
+#[glib::object_subclass]
+impl ObjectSubclass for JournalPanelImp {
+    const NAME: &'static str = "JournalPanel";
+    type Type = super::JournalPanel;
+    type ParentType = gtk::Box;
+
+    fn class_init(klass: &mut Self::Class) {
+        // The layout manager determines how child widgets are laid out.
+        klass.bind_template();
+        klass.bind_template_callbacks();
+    }
+
+    fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+        obj.init_template();
+    }
+}
+
+#[glib::derived_properties]
+impl ObjectImpl for JournalPanelImp {
+    fn constructed(&self) {
+        self.parent_constructed();
+
+        self.new_text_view();
+
+        let settings = systemd_gui::new_settings();
+        settings
+            .bind(
+                KEY_PREF_JOURNAL_DISPLAY_FOLLOW,
+                &self.follow_check.clone(),
+                "active",
+            )
+            .build();
+
+        settings
+            .bind(
+                &ACTION_WIN_KEY_JOURNAL_WRAP_WORD[4..],
+                self.obj().as_ref(),
+                "wrap-word",
+            )
+            .build();
+
+        let display_order = settings.string(KEY_PREF_JOURNAL_DISPLAY_ORDER);
+        let display_order = JournalDisplayOrder::from_key(&display_order);
 
         let sort_toggle_button_content = self
             .journal_toggle_sort_button
@@ -886,6 +2214,39 @@ impl ObjectImpl for JournalPanelImp {
         menu.append_section(None, &section_menu);
         self.journal_text_view.set_extra_menu(Some(&menu));
 
+        let journal_panel = self.obj().clone();
+        self.journal_facets_button
+            .connect_active_notify(move |_| journal_panel.imp().load_facets());
+
+        let details_gesture = gtk::GestureClick::builder()
+            .button(gtk::gdk::BUTTON_PRIMARY)
+            .build();
+        let journal_panel = self.obj().downgrade();
+        details_gesture.connect_pressed(move |_gesture, n_press, x, y| {
+            let journal_panel = upgrade!(journal_panel);
+            match n_press {
+                1 => journal_panel.imp().show_event_catalog(x, y),
+                2 => journal_panel.imp().show_event_details(x, y),
+                _ => {}
+            }
+        });
+        self.journal_text_view.add_controller(details_gesture);
+
+        let journal_panel = self.obj().clone();
+        self.journal_run_dropdown
+            .connect_selected_notify(move |_| journal_panel.imp().run_selected());
+
+        self.offline_unit_dropdown
+            .set_expression(Some(gtk::PropertyExpression::new(
+                gtk::StringObject::static_type(),
+                None::<gtk::Expression>,
+                "string",
+            )));
+
+        let journal_panel = self.obj().clone();
+        self.offline_unit_dropdown
+            .connect_selected_notify(move |_| journal_panel.imp().offline_unit_selected());
+
         let journal_panel = self.obj().clone();
         self.follow_check.connect_active_notify(move |button| {
             let active = button.is_active();
@@ -927,6 +2288,30 @@ fn validate_boot_id(boot_id: &str) -> BootIdValidation {
     }
 }
 
+/// `None` if the entry text is not a valid time, `Some(None)` if empty
+fn parse_time_entry(entry: &adw::EntryRow) -> Option<Option<u64>> {
+    let text = entry.text();
+    let text = text.trim();
+
+    if text.is_empty() {
+        return Some(None);
+    }
+
+    time_handling::parse_local_timestamp(text).map(Some)
+}
+
+fn validate_time_entry(entry: &adw::EntryRow) -> bool {
+    let valid = parse_time_entry(entry).is_some();
+
+    if valid {
+        entry.remove_css_class(CLASS_ERROR);
+    } else {
+        entry.add_css_class(CLASS_ERROR);
+    }
+
+    valid
+}
+
 struct JournalFiller {
     token_buffer: Vec<Token>,
     red: [Token; 2],
@@ -935,10 +2320,13 @@ struct JournalFiller {
     grey: [Token; 1],
     empty: [Token; 0],
     journal_color: bool,
+    highlights: Vec<Highlight>,
+    /// The queried units, to know which highlight rules apply
+    unit_names: Vec<String>,
 }
 
 impl JournalFiller {
-    fn new(journal_color: bool) -> Self {
+    fn new(journal_color: bool, buffer: &gtk::TextBuffer, unit_names: Vec<String>) -> Self {
         let red = TermColor::from(palette::red());
         let red = [Token::FgColor(red), Token::Intensity(Intensity::Bold)];
 
@@ -958,6 +2346,8 @@ impl JournalFiller {
             grey,
             empty: [],
             journal_color,
+            highlights: highlight::highlights(buffer),
+            unit_names,
         }
     }
 
@@ -970,7 +2360,25 @@ impl JournalFiller {
     ///                  DEBUG are colored grey.
     ///
     fn fill_journal_event(&mut self, journal_event: &JournalEvent, writer: &mut UnitInfoWriter) {
-        writer.insert(&journal_event.prefix);
+        match journal_event.unit_tag {
+            Some(unit_tag) if self.journal_color => {
+                let tag_color = [Token::FgColor(TermColor::from(palette::unit_tag(unit_tag)))];
+                colorize::write(
+                    writer,
+                    &journal_event.prefix,
+                    &mut self.token_buffer,
+                    &tag_color,
+                );
+            }
+            _ => writer.insert(&journal_event.prefix),
+        }
+
+        if journal_event.has_catalog {
+            let tag = catalog_tag(&writer.buffer);
+            writer
+                .buffer
+                .insert_with_tags(&mut writer.text_iterator, CATALOG_GLYPH, &[&tag]);
+        }
 
         let priority_format = if self.journal_color {
             let tokens: &[Token] = match journal_event.priority {
@@ -991,14 +2399,23 @@ impl JournalFiller {
             &self.empty
         };
 
+        let unit_name = match journal_event.unit_tag {
+            Some(unit_tag) => self.unit_names.get(unit_tag as usize),
+            None if self.unit_names.len() == 1 => self.unit_names.first(),
+            None => None,
+        };
+
         let mut lines = journal_event.message.lines();
 
         if let Some(line) = lines.next() {
-            if self.journal_color {
-                colorize::write(writer, line, &mut self.token_buffer, priority_format);
-            } else {
-                writer.insert(line);
-            }
+            write_line(
+                writer,
+                line,
+                &mut self.token_buffer,
+                self.journal_color.then_some(priority_format),
+                &self.highlights,
+                unit_name,
+            );
         }
 
         for line in lines {
@@ -1006,15 +2423,60 @@ impl JournalFiller {
 
             let space_padding = " ".repeat(journal_event.prefix.len());
             writer.insert(&space_padding);
-            if self.journal_color {
-                colorize::write(writer, line, &mut self.token_buffer, priority_format);
-            } else {
-                writer.insert(line);
-            }
+            write_line(
+                writer,
+                line,
+                &mut self.token_buffer,
+                self.journal_color.then_some(priority_format),
+                &self.highlights,
+                unit_name,
+            );
         }
         writer.newline();
     }
 }
+
+/// Write a message line, colored if `priority_format` is set, then highlight it
+fn write_line(
+    writer: &mut UnitInfoWriter,
+    line: &str,
+    token_buffer: &mut Vec<Token>,
+    priority_format: Option<&[Token]>,
+    highlights: &[Highlight],
+    unit_name: Option<&String>,
+) {
+    let start_offset = writer.text_iterator.offset();
+
+    match priority_format {
+        Some(priority_format) => colorize::write(writer, line, token_buffer, priority_format),
+        None => writer.insert(line),
+    }
+
+    if !highlights.is_empty() {
+        highlight::apply(
+            highlights,
+            &writer.buffer,
+            start_offset,
+            &writer.text_iterator,
+            unit_name,
+        );
+    }
+}
+
+fn catalog_tag(buffer: &gtk::TextBuffer) -> gtk::TextTag {
+    if let Some(tag) = buffer.tag_table().lookup(TAG_CATALOG) {
+        return tag;
+    }
+
+    let tag = gtk::TextTag::builder()
+        .name(TAG_CATALOG)
+        .foreground(palette::blue().get_color())
+        .weight(700)
+        .build();
+    buffer.tag_table().add(&tag);
+    tag
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -1,4 +1,7 @@
+mod bookmarks;
 mod colorize;
+mod export;
+pub mod highlight;
 mod imp;
 pub mod list_boots;
 //mod journal_row;
@@ -0,0 +1,433 @@
+use super::JournalMaintenanceWindow;
+use crate::{
+    format2,
+    systemd::{
+        self,
+        errors::SystemdErrors,
+        journal_disk::{self, BootUsage, JOURNALD_LIMIT_KEYS, JournalFile, VacuumRequest},
+    },
+    widget::{
+        close_window_shortcut,
+        preferences::data::PREFERENCES,
+        timer_history::{Getter, set_column_factory},
+    },
+};
+use adw::{prelude::*, subclass::window::AdwWindowImpl};
+use gettextrs::pgettext;
+use gtk::{
+    gio,
+    glib::{self, BoxedAnyObject},
+    subclass::{
+        prelude::*,
+        widget::{
+            CompositeTemplateCallbacksClass, CompositeTemplateClass,
+            CompositeTemplateInitializingExt, WidgetImpl,
+        },
+    },
+};
+use std::{
+    cell::{OnceCell, RefCell},
+    collections::BTreeMap,
+};
+use tracing::{info, warn};
+
+const MB: u64 = 1_000_000;
+const SECONDS_PER_DAY: u64 = 86_400;
+
+/// Everything shown in the window, read in one go
+struct DiskReport {
+    files: Vec<JournalFile>,
+    boots: Vec<BootUsage>,
+    limits: BTreeMap<String, String>,
+    usage_reports: Vec<(u64, String)>,
+}
+
+fn fetch_disk_report() -> Result<DiskReport, SystemdErrors> {
+    let files = journal_disk::list_journal_files()?;
+    let boots = systemd::list_boots()?;
+    let mut boots = journal_disk::boots_usage(&boots, &files);
+    boots.reverse();
+
+    let usage_reports = systemd::list_journald_usage_reports().unwrap_or_else(|err| {
+        warn!("Journald usage reports {err:?}");
+        vec![]
+    });
+
+    Ok(DiskReport {
+        files,
+        boots,
+        limits: journal_disk::journald_limits(),
+        usage_reports,
+    })
+}
+
+#[derive(Default, gtk::CompositeTemplate)]
+#[template(resource = "/io/github/plrigaux/sysd-manager/journal_maintenance.ui")]
+pub struct JournalMaintenanceWindowImp {
+    #[template_child]
+    summary_label: TemplateChild<gtk::Label>,
+
+    #[template_child]
+    spinner: TemplateChild<adw::Spinner>,
+
+    #[template_child]
+    files_selection: TemplateChild<gtk::NoSelection>,
+
+    #[template_child]
+    boots_selection: TemplateChild<gtk::NoSelection>,
+
+    #[template_child]
+    file_column: TemplateChild<gtk::ColumnViewColumn>,
+
+    #[template_child]
+    storage_column: TemplateChild<gtk::ColumnViewColumn>,
+
+    #[template_child]
+    state_column: TemplateChild<gtk::ColumnViewColumn>,
+
+    #[template_child]
+    from_column: TemplateChild<gtk::ColumnViewColumn>,
+
+    #[template_child]
+    last_write_column: TemplateChild<gtk::ColumnViewColumn>,
+
+    #[template_child]
+    disk_usage_column: TemplateChild<gtk::ColumnViewColumn>,
+
+    #[template_child]
+    offset_column: TemplateChild<gtk::ColumnViewColumn>,
+
+    #[template_child]
+    boot_id_column: TemplateChild<gtk::ColumnViewColumn>,
+
+    #[template_child]
+    first_entry_column: TemplateChild<gtk::ColumnViewColumn>,
+
+    #[template_child]
+    last_entry_column: TemplateChild<gtk::ColumnViewColumn>,
+
+    #[template_child]
+    boot_files_column: TemplateChild<gtk::ColumnViewColumn>,
+
+    #[template_child]
+    boot_disk_usage_column: TemplateChild<gtk::ColumnViewColumn>,
+
+    #[template_child]
+    size_row: TemplateChild<adw::SpinRow>,
+
+    #[template_child]
+    age_row: TemplateChild<adw::SpinRow>,
+
+    #[template_child]
+    files_row: TemplateChild<adw::SpinRow>,
+
+    #[template_child]
+    rotate_row: TemplateChild<adw::SwitchRow>,
+
+    #[template_child]
+    result_label: TemplateChild<gtk::Label>,
+
+    #[template_child]
+    reports_label: TemplateChild<gtk::Label>,
+
+    #[template_child]
+    limits_group: TemplateChild<adw::PreferencesGroup>,
+
+    limit_rows: RefCell<Vec<(&'static str, adw::ActionRow)>>,
+    files_store: OnceCell<gio::ListStore>,
+    boots_store: OnceCell<gio::ListStore>,
+}
+
+#[gtk::template_callbacks]
+impl JournalMaintenanceWindowImp {
+    #[template_callback]
+    fn refresh_button_clicked(&self, _button: gtk::Button) {
+        self.refresh();
+    }
+
+    #[template_callback]
+    fn rotate_button_clicked(&self, button: gtk::Button) {
+        let window = self.obj().clone();
+        button.set_sensitive(false);
+
+        glib::spawn_future_local(async move {
+            let result = systemd::journal_rotate().await;
+            button.set_sensitive(true);
+
+            let imp = window.imp();
+            match result {
+                Ok(()) => {
+                    info!("Journal rotated");
+                    imp.result_label
+                        .set_text(&pgettext("journal maintenance", "Journal rotated"));
+                    imp.refresh();
+                }
+                Err(err) => {
+                    warn!("Journal rotate {err:?}");
+                    imp.result_label.set_text(&err.human_error_type());
+                }
+            }
+        });
+    }
+
+    #[template_callback]
+    fn vacuum_button_clicked(&self, button: gtk::Button) {
+        let request = self.vacuum_request();
+        if request.is_empty() {
+            self.result_label.set_text(&pgettext(
+                "journal maintenance",
+                "Set at least one vacuum threshold",
+            ));
+            return;
+        }
+
+        let rotate = self.rotate_row.is_active();
+        let window = self.obj().clone();
+        button.set_sensitive(false);
+
+        glib::spawn_future_local(async move {
+            let result = systemd::journal_vacuum(rotate, request).await;
+            button.set_sensitive(true);
+
+            let imp = window.imp();
+            match result {
+                Ok(report) => {
+                    info!("Journal vacuumed {report}");
+                    imp.result_label.set_text(report.trim());
+                    imp.refresh();
+                }
+                Err(err) => {
+                    warn!("Journal vacuum {err:?}");
+                    imp.result_label.set_text(&err.human_error_type());
+                }
+            }
+        });
+    }
+
+    fn vacuum_request(&self) -> VacuumRequest {
+        VacuumRequest {
+            max_size: self.size_row.value() as u64 * MB,
+            max_age: self.age_row.value() as u64 * SECONDS_PER_DAY,
+            max_files: self.files_row.value() as u64,
+        }
+    }
+
+    pub(super) fn refresh(&self) {
+        let window = self.obj().clone();
+
+        self.spinner.set_visible(true);
+        glib::spawn_future_local(async move {
+            let result = gio::spawn_blocking(fetch_disk_report).await;
+
+            let imp = window.imp();
+            imp.spinner.set_visible(false);
+
+            let report = match result {
+                Ok(Ok(report)) => report,
+                Ok(Err(err)) => {
+                    warn!("Journal disk report {err:?}");
+                    imp.summary_label.set_text(&err.human_error_type());
+                    return;
+                }
+                Err(err) => {
+                    warn!("Journal disk report join {err:?}");
+                    return;
+                }
+            };
+
+            imp.summary_label.set_markup(&summary_markup(&report.files));
+
+            for (key, row) in imp.limit_rows.borrow().iter() {
+                match report.limits.get(*key) {
+                    Some(value) => row.set_subtitle(&glib::markup_escape_text(value)),
+                    None => row.set_subtitle(&pgettext("journal maintenance", "default")),
+                }
+            }
+
+            let timestamp_style = PREFERENCES.timestamp_style();
+            let reports = report
+                .usage_reports
+                .iter()
+                .map(|(timestamp, message)| {
+                    format!("{} {message}", timestamp_style.usec_formated(*timestamp))
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            imp.reports_label.set_text(&reports);
+
+            let files_store = imp.files_store.get().expect("store set");
+            files_store.remove_all();
+            for file in report.files {
+                files_store.append(&BoxedAnyObject::new(file));
+            }
+
+            let boots_store = imp.boots_store.get().expect("store set");
+            boots_store.remove_all();
+            for boot in report.boots {
+                boots_store.append(&BoxedAnyObject::new(boot));
+            }
+        });
+    }
+
+    fn setup_factories(&self) {
+        let timestamp_style = PREFERENCES.timestamp_style();
+
+        let file_columns: [(&gtk::ColumnViewColumn, Getter<JournalFile>); 6] = [
+            (
+                &self.file_column,
+                Box::new(|file: &JournalFile| file.file_name()),
+            ),
+            (
+                &self.storage_column,
+                Box::new(|file: &JournalFile| {
+                    if file.runtime {
+                        pgettext("journal maintenance", "volatile")
+                    } else {
+                        pgettext("journal maintenance", "persistent")
+                    }
+                }),
+            ),
+            (
+                &self.state_column,
+                Box::new(|file: &JournalFile| {
+                    if file.archived {
+                        pgettext("journal maintenance", "archived")
+                    } else {
+                        pgettext("journal maintenance", "active")
+                    }
+                }),
+            ),
+            (
+                &self.from_column,
+                Box::new(move |file: &JournalFile| {
+                    file.head
+                        .map(|head| timestamp_style.usec_formated(head))
+                        .unwrap_or_default()
+                }),
+            ),
+            (
+                &self.last_write_column,
+                Box::new(move |file: &JournalFile| timestamp_style.usec_formated(file.modified)),
+            ),
+            (
+                &self.disk_usage_column,
+                Box::new(|file: &JournalFile| glib::format_size(file.disk_usage).to_string()),
+            ),
+        ];
+
+        for (column, getter) in file_columns {
+            set_column_factory(column, getter, |_| false);
+        }
+
+        let boot_columns: [(&gtk::ColumnViewColumn, Getter<BootUsage>); 6] = [
+            (
+                &self.offset_column,
+                Box::new(|usage: &BootUsage| usage.boot.neg_offset().to_string()),
+            ),
+            (
+                &self.boot_id_column,
+                Box::new(|usage: &BootUsage| usage.boot.boot_id.clone()),
+            ),
+            (
+                &self.first_entry_column,
+                Box::new(move |usage: &BootUsage| timestamp_style.usec_formated(usage.boot.first)),
+            ),
+            (
+                &self.last_entry_column,
+                Box::new(move |usage: &BootUsage| timestamp_style.usec_formated(usage.boot.last)),
+            ),
+            (
+                &self.boot_files_column,
+                Box::new(|usage: &BootUsage| usage.files.to_string()),
+            ),
+            (
+                &self.boot_disk_usage_column,
+                Box::new(|usage: &BootUsage| glib::format_size(usage.disk_usage).to_string()),
+            ),
+        ];
+
+        for (column, getter) in boot_columns {
+            set_column_factory(column, getter, |_| false);
+        }
+    }
+}
+
+#[glib::object_subclass]
+impl ObjectSubclass for JournalMaintenanceWindowImp {
+    const NAME: &'static str = "JournalMaintenanceWindow";
+    type Type = JournalMaintenanceWindow;
+    type ParentType = adw::Window;
+
+    fn class_init(klass: &mut Self::Class) {
+        klass.bind_template();
+        klass.bind_template_callbacks();
+    }
+
+    fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+        obj.init_template();
+    }
+}
+
+impl ObjectImpl for JournalMaintenanceWindowImp {
+    fn constructed(&self) {
+        self.parent_constructed();
+        close_window_shortcut(self.obj().as_ref());
+
+        let files_store = gio::ListStore::new::<BoxedAnyObject>();
+        self.files_selection.set_model(Some(&files_store));
+        let _ = self.files_store.set(files_store);
+
+        let boots_store = gio::ListStore::new::<BoxedAnyObject>();
+        self.boots_selection.set_model(Some(&boots_store));
+        let _ = self.boots_store.set(boots_store);
+
+        self.setup_factories();
+
+        // The limit keys are journald.conf settings, not translated
+        let limit_rows = JOURNALD_LIMIT_KEYS
+            .iter()
+            .map(|key| {
+                let row = adw::ActionRow::builder()
+                    .title(*key)
+                    .subtitle_selectable(true)
+                    .build();
+                self.limits_group.add(&row);
+                (*key, row)
+            })
+            .collect();
+        self.limit_rows.replace(limit_rows);
+    }
+}
+
+impl WidgetImpl for JournalMaintenanceWindowImp {}
+impl WindowImpl for JournalMaintenanceWindowImp {}
+impl AdwWindowImpl for JournalMaintenanceWindowImp {}
+
+fn summary_markup(files: &[JournalFile]) -> String {
+    let total = |runtime: bool| {
+        files
+            .iter()
+            .filter(|file| file.runtime == runtime)
+            .fold((0, 0), |(usage, count), file| {
+                (usage + file.disk_usage, count + 1)
+            })
+    };
+
+    let (persistent_usage, persistent_files) = total(false);
+    let (runtime_usage, runtime_files) = total(true);
+
+    format!(
+        "{} <b>{}</b> {}    {} <b>{}</b> {}",
+        pgettext("journal maintenance", "Persistent:"),
+        glib::format_size(persistent_usage),
+        files_text(persistent_files),
+        pgettext("journal maintenance", "Volatile:"),
+        glib::format_size(runtime_usage),
+        files_text(runtime_files),
+    )
+}
+
+fn files_text(count: usize) -> String {
+    //journal maintenance summary
+    format2!(pgettext("journal maintenance", "in {} files"), count)
+}
@@ -0,0 +1,25 @@
+mod imp;
+
+use crate::widget::app_window::AppWindow;
+use gtk::{
+    glib::{self},
+    prelude::*,
+    subclass::prelude::ObjectSubclassIsExt,
+};
+
+/// Window showing the journal disk usage and journald limits, rotating and vacuuming the journal
+glib::wrapper! {
+    pub struct JournalMaintenanceWindow(ObjectSubclass<imp::JournalMaintenanceWindowImp>)
+    @extends adw::Window, gtk::Window, gtk::Widget,
+    @implements gtk::Accessible,  gtk::Buildable,  gtk::ConstraintTarget,
+    gtk::Native, gtk::Root, gtk::ShortcutManager;
+}
+
+impl JournalMaintenanceWindow {
+    pub fn new(app_window: &AppWindow) -> Self {
+        let obj: JournalMaintenanceWindow = glib::Object::new();
+        obj.set_transient_for(Some(app_window));
+        obj.imp().refresh();
+        obj
+    }
+}
@@ -0,0 +1,288 @@
+use super::LoginSessionsWindow;
+use crate::{
+    consts::FLAT,
+    format2,
+    systemd::{
+        self,
+        errors::SystemdErrors,
+        logind::{LoginSession, user_manager_unit},
+        other_user::LoginUser,
+        time_handling::format_timestamp_relative_full,
+    },
+    upgrade,
+    widget::{app_window::AppWindow, close_window_shortcut},
+};
+use adw::{prelude::*, subclass::window::AdwWindowImpl};
+use base::enums::UnitDBusLevel;
+use gettextrs::pgettext;
+use gtk::{
+    glib::{self},
+    subclass::{
+        prelude::*,
+        widget::{
+            CompositeTemplateCallbacksClass, CompositeTemplateClass,
+            CompositeTemplateInitializingExt, WidgetImpl,
+        },
+    },
+};
+use std::{
+    cell::{Cell, OnceCell, RefCell},
+    rc::Rc,
+};
+use tracing::warn;
+
+type LoginState = (Vec<LoginUser>, Vec<LoginSession>);
+
+#[derive(Default, gtk::CompositeTemplate)]
+#[template(resource = "/io/github/plrigaux/sysd-manager/login_sessions.ui")]
+pub struct LoginSessionsWindowImp {
+    #[template_child]
+    users_group: TemplateChild<adw::PreferencesGroup>,
+
+    #[template_child]
+    sessions_group: TemplateChild<adw::PreferencesGroup>,
+
+    #[template_child]
+    status_label: TemplateChild<gtk::Label>,
+
+    app_window: OnceCell<AppWindow>,
+    rows: RefCell<Vec<(adw::PreferencesGroup, adw::ActionRow)>>,
+}
+
+#[gtk::template_callbacks]
+impl LoginSessionsWindowImp {
+    pub(super) fn set_app_window(&self, app_window: &AppWindow) {
+        self.app_window
+            .set(app_window.clone())
+            .expect("app_window set once");
+    }
+
+    #[template_callback]
+    fn refresh_button_clicked(&self, _button: gtk::Button) {
+        self.load();
+    }
+
+    pub(super) fn load(&self) {
+        let window = self.obj().clone();
+        glib::spawn_future_local(async move {
+            let (sender, receiver) = tokio::sync::oneshot::channel();
+            systemd::runtime().spawn(async move {
+                let response: Result<LoginState, SystemdErrors> = async {
+                    let users = systemd::other_user::list_login_users().await?;
+                    let sessions = systemd::logind::list_sessions().await?;
+                    Ok((users, sessions))
+                }
+                .await;
+
+                if let Err(e) = sender.send(response) {
+                    warn!("Channel closed unexpectedly: {e:?}");
+                }
+            });
+
+            let imp = window.imp();
+            match receiver.await {
+                Ok(Ok((users, sessions))) => imp.fill(&users, &sessions),
+                Ok(Err(err)) => {
+                    warn!("List logind users and sessions {err:?}");
+                    imp.status_label.set_text(&format2!(
+                        pgettext("logind", "Can't list the users and sessions: {}"),
+                        err.to_string()
+                    ));
+                }
+                Err(err) => warn!("Tokio channel dropped {err:?}"),
+            }
+        });
+    }
+
+    fn fill(&self, users: &[LoginUser], sessions: &[LoginSession]) {
+        for (group, row) in self.rows.borrow_mut().drain(..) {
+            group.remove(&row);
+        }
+
+        for user in users {
+            let row = self.user_row(user);
+            self.add_row(&self.users_group, row);
+        }
+
+        for session in sessions {
+            self.add_row(&self.sessions_group, session_row(session));
+        }
+    }
+
+    fn add_row(&self, group: &adw::PreferencesGroup, row: adw::ActionRow) {
+        group.add(&row);
+        self.rows.borrow_mut().push((group.clone(), row));
+    }
+
+    fn user_row(&self, user: &LoginUser) -> adw::ActionRow {
+        let row = adw::ActionRow::builder()
+            .title(&user.name)
+            .subtitle(format2!(
+                pgettext("logind", "UID {}, {}"),
+                user.uid,
+                &user.state
+            ))
+            .build();
+
+        let linger_switch = gtk::Switch::builder()
+            .active(user.linger)
+            .valign(gtk::Align::Center)
+            .tooltip_text(pgettext("logind", "Lingering"))
+            .build();
+
+        let reverting = Rc::new(Cell::new(false));
+        let window = self.obj().downgrade();
+        let uid = user.uid;
+        let name = user.name.clone();
+        linger_switch.connect_state_set(move |switch, enable| {
+            if reverting.get() {
+                return glib::Propagation::Proceed;
+            }
+
+            let window = upgrade!(window, glib::Propagation::Proceed);
+            switch.set_sensitive(false);
+            let switch = switch.clone();
+            let reverting = reverting.clone();
+            let name = name.clone();
+            glib::spawn_future_local(async move {
+                let (sender, receiver) = tokio::sync::oneshot::channel();
+                systemd::runtime().spawn(async move {
+                    let response = systemd::logind::set_user_linger(uid, enable).await;
+                    if let Err(e) = sender.send(response) {
+                        warn!("Channel closed unexpectedly: {e:?}");
+                    }
+                });
+
+                let response = receiver.await;
+                switch.set_sensitive(true);
+
+                let status_label = &window.imp().status_label;
+                match response {
+                    Ok(Ok(())) => {
+                        switch.set_state(enable);
+                        let message = if enable {
+                            pgettext("logind", "Lingering enabled for {}")
+                        } else {
+                            pgettext("logind", "Lingering disabled for {}")
+                        };
+                        status_label.set_text(&format2!(message, &name));
+                    }
+                    Ok(Err(err)) => {
+                        warn!("Set linger {uid} {err:?}");
+                        reverting.set(true);
+                        switch.set_active(!enable);
+                        reverting.set(false);
+                        status_label.set_text(&format2!(
+                            pgettext("logind", "Lingering not changed: {}"),
+                            err.to_string()
+                        ));
+                    }
+                    Err(err) => warn!("Tokio channel dropped {err:?}"),
+                }
+            });
+
+            glib::Propagation::Stop
+        });
+
+        let jump_button = gtk::Button::builder()
+            .icon_name("go-jump-symbolic")
+            .tooltip_text(format2!(
+                pgettext("logind", "Show {} in the unit list"),
+                user_manager_unit(uid)
+            ))
+            .css_classes([FLAT])
+            .valign(gtk::Align::Center)
+            .build();
+
+        let window = self.obj().downgrade();
+        jump_button.connect_clicked(move |_| {
+            let window = upgrade!(window);
+            let imp = window.imp();
+            let Some(app_window) = imp.app_window.get() else {
+                return;
+            };
+
+            let unit_name = user_manager_unit(uid);
+            match systemd::fetch_unit(UnitDBusLevel::System, &unit_name) {
+                Ok(unit) => {
+                    app_window.set_unit(Some(&unit));
+                    app_window.present();
+                }
+                Err(err) => {
+                    warn!("Fetch {unit_name} {err:?}");
+                    imp.status_label.set_text(&err.human_error_type());
+                }
+            }
+        });
+
+        row.add_suffix(&linger_switch);
+        row.add_suffix(&jump_button);
+        row
+    }
+}
+
+#[glib::object_subclass]
+impl ObjectSubclass for LoginSessionsWindowImp {
+    const NAME: &'static str = "LoginSessionsWindow";
+    type Type = LoginSessionsWindow;
+    type ParentType = adw::Window;
+
+    fn class_init(klass: &mut Self::Class) {
+        klass.bind_template();
+        klass.bind_template_callbacks();
+    }
+
+    fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+        obj.init_template();
+    }
+}
+
+impl ObjectImpl for LoginSessionsWindowImp {
+    fn constructed(&self) {
+        self.parent_constructed();
+        close_window_shortcut(self.obj().as_ref());
+    }
+}
+
+impl WidgetImpl for LoginSessionsWindowImp {}
+impl WindowImpl for LoginSessionsWindowImp {}
+impl AdwWindowImpl for LoginSessionsWindowImp {}
+
+fn session_row(session: &LoginSession) -> adw::ActionRow {
+    let mut details = Vec::with_capacity(5);
+
+    if !session.seat.is_empty() {
+        details.push(format2!(pgettext("logind", "seat {}"), &session.seat));
+    }
+
+    if !session.tty.is_empty() {
+        details.push(format2!(pgettext("logind", "TTY {}"), &session.tty));
+    }
+
+    details.push(session.state.clone());
+
+    if session.remote {
+        details.push(pgettext("logind", "remote"));
+    }
+
+    if session.idle {
+        if session.idle_since == 0 {
+            details.push(pgettext("logind", "idle"));
+        } else {
+            details.push(format2!(
+                pgettext("logind", "idle since {}"),
+                format_timestamp_relative_full(session.idle_since)
+            ));
+        }
+    }
+
+    adw::ActionRow::builder()
+        .title(format2!(
+            pgettext("logind", "Session {} of {}"),
+            &session.id,
+            &session.user
+        ))
+        .subtitle(details.join(", "))
+        .subtitle_selectable(true)
+        .build()
+}
@@ -0,0 +1,27 @@
+mod imp;
+
+use crate::widget::app_window::AppWindow;
+use gtk::{
+    glib::{self},
+    prelude::*,
+    subclass::prelude::ObjectSubclassIsExt,
+};
+
+/// Window listing the logind users with their lingering, and their sessions
+glib::wrapper! {
+    pub struct LoginSessionsWindow(ObjectSubclass<imp::LoginSessionsWindowImp>)
+    @extends adw::Window, gtk::Window, gtk::Widget,
+    @implements gtk::Accessible,  gtk::Buildable,  gtk::ConstraintTarget,
+    gtk::Native, gtk::Root, gtk::ShortcutManager;
+}
+
+impl LoginSessionsWindow {
+    pub fn new(app_window: &AppWindow) -> Self {
+        let obj: LoginSessionsWindow = glib::Object::new();
+        obj.set_transient_for(Some(app_window));
+        let imp = obj.imp();
+        imp.set_app_window(app_window);
+        imp.load();
+        obj
+    }
+}
@@ -0,0 +1,525 @@
+use super::ManagerEnvironmentWindow;
+use crate::{
+    consts::FLAT,
+    format2,
+    systemd::{
+        self,
+        errors::SystemdErrors,
+        manager_environment::{
+            EffectiveVariable, EnvironmentChanges, EnvironmentSource, Variable, is_valid_name,
+            manager_environment, parse_dotenv, unit_environment,
+        },
+    },
+    upgrade,
+    widget::{app_window::AppWindow, close_window_shortcut_no_escape},
+};
+use adw::{prelude::*, subclass::window::AdwWindowImpl};
+use base::enums::UnitDBusLevel;
+use gettextrs::pgettext;
+use gtk::{
+    gio,
+    glib::{self},
+    subclass::{
+        prelude::*,
+        widget::{
+            CompositeTemplateCallbacksClass, CompositeTemplateClass,
+            CompositeTemplateInitializingExt, WidgetImpl,
+        },
+    },
+};
+use std::{
+    cell::{OnceCell, RefCell},
+    collections::BTreeSet,
+};
+use tracing::warn;
+
+const LEVELS: [UnitDBusLevel; 2] = [UnitDBusLevel::System, UnitDBusLevel::UserSession];
+
+#[derive(Clone)]
+struct VariableRow {
+    row: adw::ActionRow,
+    name_entry: gtk::Entry,
+    value_entry: gtk::Entry,
+}
+
+#[derive(Default, gtk::CompositeTemplate)]
+#[template(resource = "/io/github/plrigaux/sysd-manager/manager_environment.ui")]
+pub struct ManagerEnvironmentWindowImp {
+    #[template_child]
+    level_row: TemplateChild<adw::ComboRow>,
+
+    #[template_child]
+    variables_group: TemplateChild<adw::PreferencesGroup>,
+
+    #[template_child]
+    apply_button: TemplateChild<gtk::Button>,
+
+    #[template_child]
+    unit_group: TemplateChild<adw::PreferencesGroup>,
+
+    #[template_child]
+    unit_row: TemplateChild<adw::EntryRow>,
+
+    #[template_child]
+    status_label: TemplateChild<gtk::Label>,
+
+    variable_rows: RefCell<Vec<VariableRow>>,
+    /// The manager environment as last loaded
+    current: RefCell<Vec<Variable>>,
+    unit_rows: RefCell<Vec<adw::ActionRow>>,
+    level_changed_handler: OnceCell<glib::SignalHandlerId>,
+}
+
+#[gtk::template_callbacks]
+impl ManagerEnvironmentWindowImp {
+    /// Preselect the manager and the unit of the unit selected in the main window
+    pub(super) fn set_app_window(&self, app_window: &AppWindow) {
+        let Some(unit) = app_window.selected_unit() else {
+            return;
+        };
+
+        self.unit_row.set_text(&unit.primary());
+        if let Some(position) = LEVELS.iter().position(|level| *level == unit.dbus_level()) {
+            let handler_id = self
+                .level_changed_handler
+                .get()
+                .expect("level handler connected");
+            self.level_row.block_signal(handler_id);
+            self.level_row.set_selected(position as u32);
+            self.level_row.unblock_signal(handler_id);
+        }
+    }
+
+    #[template_callback]
+    fn add_button_clicked(&self, _button: gtk::Button) {
+        let name_entry = self.add_variable_row("", "");
+        name_entry.grab_focus();
+    }
+
+    #[template_callback]
+    fn import_button_clicked(&self, _button: gtk::Button) {
+        self.import_dotenv();
+    }
+
+    #[template_callback]
+    fn refresh_button_clicked(&self, _button: gtk::Button) {
+        self.load();
+    }
+
+    #[template_callback]
+    fn apply_button_clicked(&self, _button: gtk::Button) {
+        self.apply();
+    }
+
+    #[template_callback]
+    fn unit_row_apply(&self, row: adw::EntryRow) {
+        self.show_unit_environment(row.text().trim());
+    }
+
+    fn level(&self) -> UnitDBusLevel {
+        LEVELS
+            .get(self.level_row.selected() as usize)
+            .copied()
+            .unwrap_or(UnitDBusLevel::System)
+    }
+
+    pub(super) fn load(&self) {
+        let level = self.level();
+        let window = self.obj().clone();
+        glib::spawn_future_local(async move {
+            let (sender, receiver) = tokio::sync::oneshot::channel();
+            systemd::runtime().spawn(async move {
+                let response = manager_environment(level).await;
+                if let Err(e) = sender.send(response) {
+                    warn!("Channel closed unexpectedly: {e:?}");
+                }
+            });
+
+            let response = receiver.await;
+            let imp = window.imp();
+            if imp.level() != level {
+                // the manager changed while loading
+                return;
+            }
+
+            match response {
+                Ok(Ok(variables)) => {
+                    imp.fill(&variables);
+                    imp.current.replace(variables);
+                }
+                Ok(Err(err)) => {
+                    warn!("Manager environment {level:?} {err:?}");
+                    imp.status_label.set_text(&format2!(
+                        pgettext("environment", "Can't fetch the manager environment: {}"),
+                        err.to_string()
+                    ));
+                }
+                Err(err) => warn!("Tokio channel dropped {err:?}"),
+            }
+        });
+    }
+
+    fn fill(&self, variables: &[Variable]) {
+        for variable_row in self.variable_rows.borrow_mut().drain(..) {
+            self.variables_group.remove(&variable_row.row);
+        }
+
+        for (name, value) in variables {
+            self.add_variable_row(name, value);
+        }
+    }
+
+    fn add_variable_row(&self, name: &str, value: &str) -> gtk::Entry {
+        let name_entry = gtk::Entry::builder()
+            .text(name)
+            .placeholder_text(pgettext("environment", "Name"))
+            .valign(gtk::Align::Center)
+            .width_chars(18)
+            .build();
+
+        let value_entry = gtk::Entry::builder()
+            .text(value)
+            .placeholder_text(pgettext("environment", "Value"))
+            .valign(gtk::Align::Center)
+            .hexpand(true)
+            .build();
+
+        let remove_button = gtk::Button::builder()
+            .icon_name("user-trash-symbolic")
+            .tooltip_text(pgettext("environment", "Remove the variable"))
+            .css_classes([FLAT])
+            .valign(gtk::Align::Center)
+            .build();
+
+        let row = adw::ActionRow::new();
+        row.add_prefix(&name_entry);
+        row.add_suffix(&value_entry);
+        row.add_suffix(&remove_button);
+
+        name_entry.connect_changed(|entry| {
+            let text = entry.text();
+            if text.is_empty() || is_valid_name(&text) {
+                entry.remove_css_class("error");
+            } else {
+                entry.add_css_class("error");
+            }
+        });
+
+        let window = self.obj().downgrade();
+        let row_ = row.clone();
+        remove_button.connect_clicked(move |_| {
+            let window = upgrade!(window);
+            let imp = window.imp();
+            imp.variables_group.remove(&row_);
+            imp.variable_rows
+                .borrow_mut()
+                .retain(|variable_row| variable_row.row != row_);
+        });
+
+        self.variables_group.add(&row);
+        self.variable_rows.borrow_mut().push(VariableRow {
+            row: row.clone(),
+            name_entry: name_entry.clone(),
+            value_entry,
+        });
+
+        name_entry
+    }
+
+    /// The variables of the table, rejecting invalid or duplicate names
+    fn edited(&self) -> Result<Vec<Variable>, String> {
+        let mut names = BTreeSet::new();
+        let mut variables = Vec::new();
+
+        for variable_row in self.variable_rows.borrow().iter() {
+            let name = variable_row.name_entry.text().trim().to_owned();
+            let value = variable_row.value_entry.text().to_string();
+
+            if name.is_empty() && value.is_empty() {
+                continue;
+            }
+
+            if !is_valid_name(&name) {
+                return Err(format2!(
+                    pgettext("environment", "Invalid variable name {:?}"),
+                    name
+                ));
+            }
+
+            if !names.insert(name.clone()) {
+                return Err(format2!(
+                    pgettext("environment", "Variable {} is defined twice"),
+                    name
+                ));
+            }
+
+            variables.push((name, value));
+        }
+
+        Ok(variables)
+    }
+
+    fn apply(&self) {
+        let edited = match self.edited() {
+            Ok(edited) => edited,
+            Err(message) => {
+                self.status_label.set_text(&message);
+                return;
+            }
+        };
+
+        let changes = EnvironmentChanges::new(&self.current.borrow(), &edited);
+        if changes.is_empty() {
+            self.status_label
+                .set_text(&pgettext("environment", "No change to apply"));
+            return;
+        }
+
+        self.apply_button.set_sensitive(false);
+        let level = self.level();
+        let window = self.obj().clone();
+        glib::spawn_future_local(async move {
+            let (sender, receiver) = tokio::sync::oneshot::channel();
+            let set_count = changes.set.len();
+            let unset_count = changes.unset.len();
+            systemd::runtime().spawn(async move {
+                let response =
+                    systemd::manager_environment::apply_environment_changes(level, &changes).await;
+                if let Err(e) = sender.send(response) {
+                    warn!("Channel closed unexpectedly: {e:?}");
+                }
+            });
+
+            let response = receiver.await;
+            let imp = window.imp();
+            imp.apply_button.set_sensitive(true);
+
+            match response {
+                Ok(Ok(())) => {
+                    imp.status_label.set_text(&format2!(
+                        pgettext("environment", "{} variables set, {} variables unset"),
+                        set_count,
+                        unset_count
+                    ));
+                    imp.load();
+                }
+                Ok(Err(err)) => {
+                    warn!("Apply manager environment {level:?} {err:?}");
+                    imp.status_label.set_text(&format2!(
+                        pgettext("environment", "Environment not changed: {}"),
+                        err.to_string()
+                    ));
+                }
+                Err(err) => warn!("Tokio channel dropped {err:?}"),
+            }
+        });
+    }
+
+    /// Merge the variables of a dotenv file in the table, they are applied with the others
+    fn import_dotenv(&self) {
+        let file_dialog = gtk::FileDialog::builder()
+            .title(pgettext("environment", "Import a dotenv File"))
+            .accept_label(pgettext("environment", "Import"))
+            .build();
+
+        let window = self.obj().clone();
+        file_dialog.open(
+            Some(&*self.obj()),
+            None::<&gio::Cancellable>,
+            move |result| match result {
+                Ok(file) => {
+                    let Some(path) = file.path() else {
+                        warn!("dotenv file has no path");
+                        return;
+                    };
+
+                    let parsed =
+                        std::fs::read_to_string(&path).map(|content| parse_dotenv(&content));
+
+                    let imp = window.imp();
+                    match parsed {
+                        Ok(variables) => {
+                            imp.merge(&variables);
+                            imp.status_label.set_text(&format2!(
+                                pgettext(
+                                    "environment",
+                                    "{} variables imported from {}, apply to set them"
+                                ),
+                                variables.len(),
+                                path.display().to_string()
+                            ));
+                        }
+                        Err(err) => {
+                            warn!("Import {path:?} {err:?}");
+                            imp.status_label.set_text(&format2!(
+                                pgettext("environment", "Can't import {}: {}"),
+                                path.display().to_string(),
+                                err.to_string()
+                            ));
+                        }
+                    }
+                }
+                Err(e) => warn!("dotenv File Selection Error {e:?}"),
+            },
+        );
+    }
+
+    fn merge(&self, variables: &[Variable]) {
+        for (name, value) in variables {
+            let existing = self
+                .variable_rows
+                .borrow()
+                .iter()
+                .find(|variable_row| variable_row.name_entry.text() == name.as_str())
+                .cloned();
+
+            match existing {
+                Some(variable_row) => variable_row.value_entry.set_text(value),
+                None => {
+                    self.add_variable_row(name, value);
+                }
+            }
+        }
+    }
+
+    fn show_unit_environment(&self, unit_name: &str) {
+        if unit_name.is_empty() {
+            return;
+        }
+
+        let level = self.level();
+        let unit_name = unit_name.to_owned();
+        let window = self.obj().clone();
+        glib::spawn_future_local(async move {
+            let (sender, receiver) = tokio::sync::oneshot::channel();
+            let unit_name_ = unit_name.clone();
+            systemd::runtime().spawn(async move {
+                let response: Result<
+                    (Vec<EffectiveVariable>, Vec<(String, String)>),
+                    SystemdErrors,
+                > = async {
+                    let manager = manager_environment(level).await?;
+                    let unit =
+                        tokio::task::spawn_blocking(move || unit_environment(level, &unit_name_))
+                            .await??;
+                    Ok((unit.effective(&manager), unit.unread_files))
+                }
+                .await;
+
+                if let Err(e) = sender.send(response) {
+                    warn!("Channel closed unexpectedly: {e:?}");
+                }
+            });
+
+            let imp = window.imp();
+            match receiver.await {
+                Ok(Ok((variables, unread_files))) => {
+                    imp.fill_unit_environment(&variables, &unread_files)
+                }
+                Ok(Err(err)) => {
+                    warn!("Unit environment {unit_name} {err:?}");
+                    imp.status_label.set_text(&format2!(
+                        pgettext("environment", "Can't fetch the environment of {}: {}"),
+                        &unit_name,
+                        err.to_string()
+                    ));
+                }
+                Err(err) => warn!("Tokio channel dropped {err:?}"),
+            }
+        });
+    }
+
+    fn fill_unit_environment(
+        &self,
+        variables: &[EffectiveVariable],
+        unread_files: &[(String, String)],
+    ) {
+        for row in self.unit_rows.borrow_mut().drain(..) {
+            self.unit_group.remove(&row);
+        }
+
+        for variable in variables {
+            let source = match &variable.source {
+                EnvironmentSource::Manager => pgettext("environment", "manager"),
+                EnvironmentSource::Unit => "Environment=".to_owned(),
+                EnvironmentSource::File(path) => path.clone(),
+            };
+
+            let subtitle = match &variable.overridden {
+                Some(previous) => format2!(
+                    pgettext("environment", "{}, overrides {:?}"),
+                    source,
+                    previous
+                ),
+                None => source,
+            };
+
+            let row = adw::ActionRow::builder()
+                .title(format!("{}={}", variable.name, variable.value))
+                .title_selectable(true)
+                .subtitle(subtitle)
+                .build();
+            self.unit_group.add(&row);
+            self.unit_rows.borrow_mut().push(row);
+        }
+
+        for (path, reason) in unread_files {
+            let row = adw::ActionRow::builder()
+                .title(path)
+                .subtitle(format2!(
+                    pgettext("environment", "Environment file not read: {}"),
+                    reason
+                ))
+                .css_classes(["warning"])
+                .build();
+            self.unit_group.add(&row);
+            self.unit_rows.borrow_mut().push(row);
+        }
+
+        if variables.is_empty() && unread_files.is_empty() {
+            self.status_label
+                .set_text(&pgettext("environment", "The unit has no environment"));
+        }
+    }
+}
+
+#[glib::object_subclass]
+impl ObjectSubclass for ManagerEnvironmentWindowImp {
+    const NAME: &'static str = "ManagerEnvironmentWindow";
+    type Type = ManagerEnvironmentWindow;
+    type ParentType = adw::Window;
+
+    fn class_init(klass: &mut Self::Class) {
+        klass.bind_template();
+        klass.bind_template_callbacks();
+    }
+
+    fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+        obj.init_template();
+    }
+}
+
+impl ObjectImpl for ManagerEnvironmentWindowImp {
+    fn constructed(&self) {
+        self.parent_constructed();
+        close_window_shortcut_no_escape(self.obj().as_ref());
+
+        let level_labels: Vec<String> = LEVELS.iter().map(|level| level.nice_label()).collect();
+        let level_labels: Vec<&str> = level_labels.iter().map(|label| label.as_str()).collect();
+        self.level_row
+            .set_model(Some(&gtk::StringList::new(&level_labels)));
+
+        let window = self.obj().downgrade();
+        let handler_id = self.level_row.connect_selected_notify(move |_| {
+            let window = upgrade!(window);
+            window.imp().load();
+        });
+        self.level_changed_handler
+            .set(handler_id)
+            .expect("Level handler set once");
+    }
+}
+
+impl WidgetImpl for ManagerEnvironmentWindowImp {}
+impl WindowImpl for ManagerEnvironmentWindowImp {}
+impl AdwWindowImpl for ManagerEnvironmentWindowImp {}
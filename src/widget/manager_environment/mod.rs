@@ -0,0 +1,28 @@
+mod imp;
+
+use crate::widget::app_window::AppWindow;
+use gtk::{
+    glib::{self},
+    prelude::*,
+    subclass::prelude::ObjectSubclassIsExt,
+};
+
+/// Window editing the environment block of the system or user manager, and showing the
+/// environment a unit gets
+glib::wrapper! {
+    pub struct ManagerEnvironmentWindow(ObjectSubclass<imp::ManagerEnvironmentWindowImp>)
+    @extends adw::Window, gtk::Window, gtk::Widget,
+    @implements gtk::Accessible,  gtk::Buildable,  gtk::ConstraintTarget,
+    gtk::Native, gtk::Root, gtk::ShortcutManager;
+}
+
+impl ManagerEnvironmentWindow {
+    pub fn new(app_window: &AppWindow) -> Self {
+        let obj: ManagerEnvironmentWindow = glib::Object::new();
+        obj.set_transient_for(Some(app_window));
+        let imp = obj.imp();
+        imp.set_app_window(app_window);
+        imp.load();
+        obj
+    }
+}
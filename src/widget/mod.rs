@@ -1,15 +1,27 @@
 pub mod app_window;
+pub mod audit_history;
+pub mod bus_connections;
 pub mod clean_dialog;
 pub mod control_action_dialog;
+pub mod coredumps;
 pub mod creator;
 pub mod grid_cell;
 pub mod info_window;
 pub mod journal;
+pub mod journal_maintenance;
 pub mod kill_panel;
+pub mod login_sessions;
+pub mod manager_environment;
 pub mod menu_button;
+pub mod notifications;
+pub mod other_users;
 pub mod preferences;
 pub mod signals_dialog;
+pub mod targets;
+pub mod template_instances;
 pub mod text_search;
+pub mod timer_history;
+pub mod timer_schedule;
 pub mod unit_control_panel;
 pub mod unit_dependencies_panel;
 pub mod unit_file_panel;
@@ -26,7 +38,7 @@ use adw::prelude::AdwDialogExt;
 use base::consts::{FAVORITE_ICON_FILLED, FAVORITE_ICON_OUTLINE};
 use gettextrs::pgettext;
 use glib::object::{Cast, CastNone, IsA};
-use gtk::{gdk, pango::FontDescription, prelude::*};
+use gtk::{gdk, gio, pango::FontDescription, prelude::*};
 use regex::Regex;
 pub(crate) use std::{rc::Rc, sync::OnceLock};
 use tracing::debug;
@@ -75,6 +87,23 @@ pub fn set_favorite_info(is_favorite: bool, unit: &Option<UnitInfo>) -> (&str, S
     (favorite_icon, tooltip)
 }
 
+/// The windows reading the host systemd or journal around the backend can't show the units
+/// of a fixture backend, their actions are disabled with it
+pub fn disable_host_only_actions(action_map: &impl IsA<gio::ActionMap>, action_names: &[&str]) {
+    if crate::systemd::backend::backend().is_live() {
+        return;
+    }
+
+    for action_name in action_names {
+        if let Some(action) = action_map.lookup_action(action_name)
+            && let Some(simple_action) = action.downcast_ref::<gio::SimpleAction>()
+        {
+            debug!("Disable action {action_name}, not available with the backend");
+            simple_action.set_enabled(false);
+        }
+    }
+}
+
 pub fn toast_regex() -> &'static Regex {
     static TOAST_REGEX: OnceLock<Regex> = OnceLock::new();
     TOAST_REGEX
@@ -0,0 +1,215 @@
+use crate::widget::unit_properties_selector::save::{
+    get_sysd_manager_config_dir, save_to_toml_file,
+};
+use base::enums::UnitDBusLevel;
+use gettextrs::pgettext;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashSet,
+    fs,
+    sync::{LazyLock, RwLock},
+};
+use tracing::{error, info};
+
+const NOTIFICATIONS: &str = "notifications.toml";
+
+static CONFIG: LazyLock<RwLock<NotificationConfig>> =
+    LazyLock::new(|| RwLock::new(load_notification_config()));
+
+/// Which units are monitored when they have no per unit setting
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum MonitorMode {
+    #[default]
+    Favorites,
+    All,
+    Off,
+}
+
+impl MonitorMode {
+    pub const MODES: [MonitorMode; 3] =
+        [MonitorMode::Favorites, MonitorMode::All, MonitorMode::Off];
+
+    pub fn label(&self) -> String {
+        match self {
+            MonitorMode::Favorites => pgettext("notification", "Favorite units"),
+            MonitorMode::All => pgettext("notification", "All units"),
+            MonitorMode::Off => pgettext("notification", "Listed units only"),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct NotificationConfig {
+    pub mode: MonitorMode,
+    /// Per unit settings, they win over the mode
+    pub units: Vec<UnitNotification>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct UnitNotification {
+    pub bus: String,
+    pub unit: String,
+    pub notify: bool,
+}
+
+impl Default for UnitNotification {
+    fn default() -> Self {
+        Self {
+            bus: UnitDBusLevel::System.as_str().into_owned(),
+            unit: String::new(),
+            notify: true,
+        }
+    }
+}
+
+impl UnitNotification {
+    /// `None` when the bus of the unit isn't connected
+    pub fn level(&self) -> Option<UnitDBusLevel> {
+        systemd::custom_bus::saved_bus_level(&self.bus)
+    }
+}
+
+impl NotificationConfig {
+    /// Tell if a state change of the unit is notified, `favorites` being the favorite units
+    pub fn notifies(
+        &self,
+        level: UnitDBusLevel,
+        unit_name: &str,
+        favorites: &HashSet<(UnitDBusLevel, String)>,
+    ) -> bool {
+        if let Some(unit) = self
+            .units
+            .iter()
+            .find(|unit| unit.unit == unit_name && unit.level() == Some(level))
+        {
+            return unit.notify;
+        }
+
+        match self.mode {
+            MonitorMode::Favorites => favorites.contains(&(level, unit_name.to_owned())),
+            MonitorMode::All => true,
+            MonitorMode::Off => false,
+        }
+    }
+
+    /// No unit can be notified
+    pub fn is_off(&self) -> bool {
+        self.mode == MonitorMode::Off && !self.units.iter().any(|unit| unit.notify)
+    }
+}
+
+pub fn notification_config() -> NotificationConfig {
+    CONFIG.read().expect("notification config lock").clone()
+}
+
+pub fn set_notification_config(config: NotificationConfig) {
+    *CONFIG.write().expect("notification config lock") = config.clone();
+    systemd::runtime().spawn(save_notification_config_async(config));
+}
+
+async fn save_notification_config_async(config: NotificationConfig) {
+    let sysd_manager_config_dir = get_sysd_manager_config_dir();
+
+    if let Err(e) = tokio::fs::create_dir_all(&sysd_manager_config_dir).await {
+        error!(
+            "Failed to create config directory {:?}: {}",
+            sysd_manager_config_dir, e
+        );
+        return;
+    }
+
+    let config_path = sysd_manager_config_dir.join(NOTIFICATIONS);
+
+    if let Err(e) = save_to_toml_file(&config, &config_path).await {
+        error!(
+            "Failed to save notification config to TOML file: {:?} {:?}",
+            config_path, e
+        );
+    } else {
+        info!("Notification config saved to {:?}", config_path);
+    }
+}
+
+fn load_notification_config() -> NotificationConfig {
+    let config_path = get_sysd_manager_config_dir().join(NOTIFICATIONS);
+
+    if !config_path.exists() {
+        return NotificationConfig::default();
+    }
+
+    match fs::read_to_string(&config_path) {
+        Ok(toml_str) => match toml::from_str::<NotificationConfig>(&toml_str) {
+            Ok(config) => config,
+            Err(e) => {
+                error!("Failed to parse TOML from {:?}: {}", config_path, e);
+                NotificationConfig::default()
+            }
+        },
+        Err(e) => {
+            error!(
+                "Failed to read notification config file {:?}: {}",
+                config_path, e
+            );
+            NotificationConfig::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_notifies() {
+        let favorites = HashSet::from([(UnitDBusLevel::System, "nginx.service".to_owned())]);
+
+        let mut config = NotificationConfig::default();
+        assert!(config.notifies(UnitDBusLevel::System, "nginx.service", &favorites));
+        assert!(!config.notifies(UnitDBusLevel::UserSession, "nginx.service", &favorites));
+        assert!(!config.notifies(UnitDBusLevel::System, "sshd.service", &favorites));
+
+        config.units.push(UnitNotification {
+            bus: "system".to_owned(),
+            unit: "nginx.service".to_owned(),
+            notify: false,
+        });
+        config.units.push(UnitNotification {
+            bus: "system".to_owned(),
+            unit: "sshd.service".to_owned(),
+            notify: true,
+        });
+        assert!(!config.notifies(UnitDBusLevel::System, "nginx.service", &favorites));
+        assert!(config.notifies(UnitDBusLevel::System, "sshd.service", &favorites));
+
+        config.mode = MonitorMode::Off;
+        assert!(config.notifies(UnitDBusLevel::System, "sshd.service", &favorites));
+        assert!(!config.is_off());
+
+        config.units.clear();
+        assert!(config.is_off());
+
+        config.mode = MonitorMode::All;
+        assert!(config.notifies(UnitDBusLevel::UserSession, "any.timer", &favorites));
+    }
+
+    #[test]
+    fn test_config_toml() {
+        let parsed: NotificationConfig = toml::from_str("mode = \"all\"\n").unwrap();
+        assert_eq!(parsed.mode, MonitorMode::All);
+        assert!(parsed.units.is_empty());
+
+        let parsed: NotificationConfig =
+            toml::from_str("[[units]]\nbus = \"user\"\nunit = \"a.service\"\n").unwrap();
+        assert_eq!(parsed.mode, MonitorMode::Favorites);
+        assert_eq!(parsed.units[0].level(), Some(UnitDBusLevel::UserSession));
+        assert!(parsed.units[0].notify);
+
+        // A level id of a previous run
+        let parsed: NotificationConfig =
+            toml::from_str("[[units]]\nbus = \"custom3\"\nunit = \"a.service\"\n").unwrap();
+        assert_eq!(parsed.units[0].level(), None);
+    }
+}
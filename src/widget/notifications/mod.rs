@@ -0,0 +1,335 @@
+pub mod config;
+
+use crate::{
+    consts::{
+        ACTION_NOTIFICATION_OPEN_JOURNAL, ACTION_NOTIFICATION_OPEN_UNIT,
+        APP_ACTION_NOTIFICATION_OPEN_JOURNAL, APP_ACTION_NOTIFICATION_OPEN_UNIT,
+    },
+    format2,
+    systemd::{
+        self, UnitStateChange,
+        enums::{ActiveState, UnitType},
+    },
+    widget::{app_window::AppWindow, unit_list::imp::favorites::load_favorites},
+};
+use adw::prelude::*;
+use base::enums::UnitDBusLevel;
+use config::{MonitorMode, notification_config};
+use gettextrs::pgettext;
+use gtk::{gio, glib};
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+};
+use tokio::sync::mpsc;
+use tracing::{info, warn};
+use zvariant::Value;
+
+thread_local! {
+    static MONITOR: RefCell<Option<UnitMonitor>> = const { RefCell::new(None) };
+}
+
+/// Keeps the application alive while the units are monitored
+struct UnitMonitor {
+    _hold: gio::ApplicationHoldGuard,
+    handle: glib::JoinHandle<()>,
+}
+
+impl Drop for UnitMonitor {
+    fn drop(&mut self) {
+        // Dropping the receiver stops the state watchers
+        self.handle.abort();
+    }
+}
+
+/// Register the notification actions and start monitoring the units
+pub fn start_unit_monitor(application: &adw::Application) {
+    let open_unit = gio::ActionEntry::builder(ACTION_NOTIFICATION_OPEN_UNIT)
+        .parameter_type(Some(&<(String, String)>::static_variant_type()))
+        .activate(|application: &adw::Application, _, parameter| {
+            open_unit(application, parameter, false)
+        })
+        .build();
+
+    let open_journal = gio::ActionEntry::builder(ACTION_NOTIFICATION_OPEN_JOURNAL)
+        .parameter_type(Some(&<(String, String)>::static_variant_type()))
+        .activate(|application: &adw::Application, _, parameter| {
+            open_unit(application, parameter, true)
+        })
+        .build();
+
+    application.add_action_entries([open_unit, open_journal]);
+
+    update_unit_monitor(application);
+}
+
+/// Start or stop the unit monitor according to the notification configuration
+pub fn update_unit_monitor(application: &adw::Application) {
+    // a fixture backend has no unit signal to follow
+    let off = notification_config().is_off() || !systemd::backend::backend().is_live();
+
+    MONITOR.with_borrow_mut(|monitor| {
+        if off {
+            if monitor.take().is_some() {
+                info!("Unit monitor stopped");
+            }
+        } else if monitor.is_none() {
+            info!("Unit monitor started");
+            *monitor = Some(UnitMonitor {
+                _hold: application.hold(),
+                handle: glib::spawn_future_local(monitor_units(application.clone())),
+            });
+        }
+    });
+}
+
+async fn monitor_units(application: adw::Application) {
+    let (sender, mut receiver) = mpsc::channel(100);
+
+    for level in [UnitDBusLevel::System, UnitDBusLevel::UserSession] {
+        let sender = sender.clone();
+        systemd::runtime().spawn(async move {
+            if let Err(err) = systemd::watch_unit_state_changes(level, sender).await {
+                warn!("Unit state watcher {level:?} {err:?}");
+            }
+        });
+    }
+    drop(sender);
+
+    let mut active_states: HashMap<(UnitDBusLevel, String), ActiveState> = HashMap::new();
+    let mut restarts: HashMap<(UnitDBusLevel, String), u32> = HashMap::new();
+
+    while let Some(change) = receiver.recv().await {
+        let key = (change.level, change.unit_name.clone());
+
+        let failed = change.active_state.is_some_and(|state| {
+            let previous = active_states.insert(key.clone(), state);
+            state == ActiveState::Failed && previous != Some(ActiveState::Failed)
+        });
+
+        // The first value seen is the reference
+        let restarted = change.n_restarts.is_some_and(|n_restarts| {
+            restarts
+                .insert(key, n_restarts)
+                .is_some_and(|previous| n_restarts > previous)
+        });
+
+        if failed {
+            unit_failed(&application, &change).await;
+        } else if restarted {
+            unit_restarted(&application, &change);
+        }
+    }
+
+    info!("Unit monitor ended");
+}
+
+fn favorite_units() -> HashSet<(UnitDBusLevel, String)> {
+    load_favorites()
+        .map(|favorites| {
+            favorites
+                .favorites
+                .into_iter()
+                .filter_map(|favorite| Some((favorite.level()?, favorite.unit)))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Tell if the unit is notified, loads the favorites only when needed
+fn notifies(level: UnitDBusLevel, unit_names: &[&str]) -> bool {
+    let config = notification_config();
+    let favorites = if config.mode == MonitorMode::Favorites {
+        favorite_units()
+    } else {
+        HashSet::new()
+    };
+
+    unit_names
+        .iter()
+        .any(|unit_name| config.notifies(level, unit_name, &favorites))
+}
+
+async fn unit_failed(application: &adw::Application, change: &UnitStateChange) {
+    let level = change.level;
+    let unit_name = change.unit_name.clone();
+
+    let timers = if unit_name.ends_with(".service") {
+        gio::spawn_blocking(move || triggering_timers(level, &unit_name))
+            .await
+            .unwrap_or_default()
+    } else {
+        vec![]
+    };
+
+    let mut unit_names = vec![change.unit_name.as_str()];
+    unit_names.extend(timers.iter().map(String::as_str));
+    if !notifies(level, &unit_names) {
+        return;
+    }
+
+    let title = if let Some(timer) = timers.first() {
+        format2!(
+            //notification title
+            pgettext("notification", "The service of the timer {} failed"),
+            timer
+        )
+    } else {
+        format2!(
+            //notification title
+            pgettext("notification", "{} failed"),
+            change.unit_name
+        )
+    };
+
+    let body = match change.result.as_deref() {
+        Some(result) if !result.is_empty() && result != "success" => format2!(
+            //notification body
+            pgettext(
+                "notification",
+                "Unit {} entered the failed state, result: {}"
+            ),
+            change.unit_name,
+            result
+        ),
+        _ => format2!(
+            //notification body
+            pgettext("notification", "Unit {} entered the failed state"),
+            change.unit_name
+        ),
+    };
+
+    send_notification(
+        application,
+        &format!("failed-{}-{}", level.short(), change.unit_name),
+        &title,
+        &body,
+        change,
+    );
+}
+
+fn unit_restarted(application: &adw::Application, change: &UnitStateChange) {
+    if !notifies(change.level, &[change.unit_name.as_str()]) {
+        return;
+    }
+
+    let title = format2!(
+        //notification title
+        pgettext("notification", "{} restarted"),
+        change.unit_name
+    );
+
+    let body = format2!(
+        //notification body
+        pgettext("notification", "Restarted {} times by systemd"),
+        change.n_restarts.unwrap_or_default()
+    );
+
+    // Same id, the new restart notification replaces the previous one
+    send_notification(
+        application,
+        &format!("restart-{}-{}", change.level.short(), change.unit_name),
+        &title,
+        &body,
+        change,
+    );
+}
+
+/// The timers in the service `TriggeredBy`
+fn triggering_timers(level: UnitDBusLevel, unit_name: &str) -> Vec<String> {
+    let value = match systemd::fetch_unit_property_blocking(
+        level,
+        unit_name,
+        UnitType::Unit,
+        "TriggeredBy",
+    ) {
+        Ok(value) => value,
+        Err(err) => {
+            warn!("{unit_name} TriggeredBy {err:?}");
+            return vec![];
+        }
+    };
+
+    let Value::Array(array) = &value as &Value else {
+        return vec![];
+    };
+
+    array
+        .iter()
+        .filter_map(|unit| <&str>::try_from(unit).ok())
+        .filter(|unit| unit.ends_with(".timer"))
+        .map(str::to_owned)
+        .collect()
+}
+
+fn send_notification(
+    application: &adw::Application,
+    id: &str,
+    title: &str,
+    body: &str,
+    change: &UnitStateChange,
+) {
+    let target = (change.level.short().into_owned(), change.unit_name.clone()).to_variant();
+
+    let notification = gio::Notification::new(title);
+    notification.set_body(Some(body));
+    notification.set_priority(gio::NotificationPriority::High);
+    notification
+        .set_default_action_and_target_value(APP_ACTION_NOTIFICATION_OPEN_UNIT, Some(&target));
+    notification.add_button_with_target_value(
+        &pgettext("notification", "Open Unit"),
+        APP_ACTION_NOTIFICATION_OPEN_UNIT,
+        Some(&target),
+    );
+    notification.add_button_with_target_value(
+        &pgettext("notification", "Open Journal"),
+        APP_ACTION_NOTIFICATION_OPEN_JOURNAL,
+        Some(&target),
+    );
+
+    application.send_notification(Some(id), &notification);
+}
+
+/// Select the notification unit in the main window, creating it if it was closed
+fn open_unit(application: &adw::Application, parameter: Option<&glib::Variant>, journal: bool) {
+    let Some((level, unit_name)) =
+        parameter.and_then(|parameter| parameter.get::<(String, String)>())
+    else {
+        warn!("Notification action parameter {parameter:?}");
+        return;
+    };
+
+    let app_window = || {
+        application
+            .windows()
+            .into_iter()
+            .find_map(|window| window.downcast::<AppWindow>().ok())
+    };
+
+    let app_window = match app_window() {
+        Some(app_window) => app_window,
+        None => {
+            application.activate();
+            let Some(app_window) = app_window() else {
+                warn!("No application window");
+                return;
+            };
+            app_window
+        }
+    };
+
+    match systemd::fetch_unit(UnitDBusLevel::from_short(&level), &unit_name) {
+        Ok(unit) => {
+            app_window.set_unit(Some(&unit));
+            if journal {
+                application.activate_action("open_journal", None);
+            }
+        }
+        Err(err) => {
+            warn!("Notification unit {unit_name} {err:?}");
+            app_window.add_toast_message(&err.human_error_type(), false, None);
+        }
+    }
+
+    app_window.present();
+}
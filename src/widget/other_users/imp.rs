@@ -0,0 +1,182 @@
+use super::OtherUsersWindow;
+use crate::{
+    consts::{ACTION_WIN_REFRESH_UNIT_LIST, SUGGESTED_ACTION},
+    format2,
+    systemd::{self, other_user::LoginUser},
+    upgrade,
+    widget::{app_window::AppWindow, close_window_shortcut},
+};
+use adw::{prelude::*, subclass::window::AdwWindowImpl};
+use gettextrs::pgettext;
+use gtk::{
+    glib::{self},
+    subclass::{
+        prelude::*,
+        widget::{
+            CompositeTemplateCallbacksClass, CompositeTemplateClass,
+            CompositeTemplateInitializingExt, WidgetImpl,
+        },
+    },
+};
+use std::cell::{OnceCell, RefCell};
+use tracing::warn;
+
+#[derive(Default, gtk::CompositeTemplate)]
+#[template(resource = "/io/github/plrigaux/sysd-manager/other_users.ui")]
+pub struct OtherUsersWindowImp {
+    #[template_child]
+    users_group: TemplateChild<adw::PreferencesGroup>,
+
+    #[template_child]
+    status_label: TemplateChild<gtk::Label>,
+
+    app_window: OnceCell<AppWindow>,
+    rows: RefCell<Vec<adw::ActionRow>>,
+}
+
+#[gtk::template_callbacks]
+impl OtherUsersWindowImp {
+    pub(super) fn set_app_window(&self, app_window: &AppWindow) {
+        self.app_window
+            .set(app_window.clone())
+            .expect("app_window set once");
+    }
+
+    #[template_callback]
+    fn refresh_button_clicked(&self, _button: gtk::Button) {
+        self.load_users();
+    }
+
+    pub(super) fn load_users(&self) {
+        let window = self.obj().clone();
+        glib::spawn_future_local(async move {
+            let (sender, receiver) = tokio::sync::oneshot::channel();
+            systemd::runtime().spawn(async move {
+                let response = systemd::other_user::list_login_users().await;
+                if let Err(e) = sender.send(response) {
+                    warn!("Channel closed unexpectedly: {e:?}");
+                }
+            });
+
+            let imp = window.imp();
+            match receiver.await {
+                Ok(Ok(users)) => imp.fill_users(&users),
+                Ok(Err(err)) => {
+                    warn!("List login users {err:?}");
+                    imp.status_label.set_text(&format2!(
+                        pgettext("other user", "Can't list the users: {}"),
+                        err.to_string()
+                    ));
+                }
+                Err(err) => warn!("Tokio channel dropped {err:?}"),
+            }
+        });
+    }
+
+    fn fill_users(&self, users: &[LoginUser]) {
+        for row in self.rows.borrow_mut().drain(..) {
+            self.users_group.remove(&row);
+        }
+
+        let attached = systemd::other_user::attached_user();
+
+        for user in users {
+            let row = user_row(user);
+
+            let is_attached = attached.as_ref().is_some_and(|a| a.uid == user.uid);
+            let button = if is_attached {
+                gtk::Button::builder()
+                    .label(pgettext("other user", "Detach"))
+                    .valign(gtk::Align::Center)
+                    .build()
+            } else {
+                gtk::Button::builder()
+                    .label(pgettext("other user", "Attach"))
+                    .css_classes([SUGGESTED_ACTION])
+                    .valign(gtk::Align::Center)
+                    .build()
+            };
+            row.add_suffix(&button);
+
+            let window = self.obj().downgrade();
+            let user = user.clone();
+            let users = users.to_vec();
+            button.connect_clicked(move |_| {
+                let window = upgrade!(window);
+                let imp = window.imp();
+                if is_attached {
+                    systemd::other_user::detach_user();
+                    imp.status_label.set_text(&format2!(
+                        pgettext("other user", "User {} detached"),
+                        &user.name
+                    ));
+                } else {
+                    systemd::other_user::attach_user(user.uid, &user.name);
+                    imp.status_label.set_text(&format2!(
+                        pgettext("other user", "User {} attached"),
+                        &user.name
+                    ));
+                }
+                imp.refresh_unit_list();
+                imp.fill_users(&users);
+            });
+
+            self.users_group.add(&row);
+            self.rows.borrow_mut().push(row);
+        }
+    }
+
+    fn refresh_unit_list(&self) {
+        let Some(app_window) = self.app_window.get() else {
+            return;
+        };
+
+        if let Err(err) = app_window.activate_action(ACTION_WIN_REFRESH_UNIT_LIST, None) {
+            warn!("call action {ACTION_WIN_REFRESH_UNIT_LIST} error: {err}");
+        }
+    }
+}
+
+#[glib::object_subclass]
+impl ObjectSubclass for OtherUsersWindowImp {
+    const NAME: &'static str = "OtherUsersWindow";
+    type Type = OtherUsersWindow;
+    type ParentType = adw::Window;
+
+    fn class_init(klass: &mut Self::Class) {
+        klass.bind_template();
+        klass.bind_template_callbacks();
+    }
+
+    fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+        obj.init_template();
+    }
+}
+
+impl ObjectImpl for OtherUsersWindowImp {
+    fn constructed(&self) {
+        self.parent_constructed();
+        close_window_shortcut(self.obj().as_ref());
+    }
+}
+
+impl WidgetImpl for OtherUsersWindowImp {}
+impl WindowImpl for OtherUsersWindowImp {}
+impl AdwWindowImpl for OtherUsersWindowImp {}
+
+fn user_row(user: &LoginUser) -> adw::ActionRow {
+    let subtitle = if user.linger {
+        format2!(
+            pgettext("other user", "UID {}, {}, lingering enabled"),
+            user.uid,
+            &user.state
+        )
+    } else {
+        format2!(pgettext("other user", "UID {}, {}"), user.uid, &user.state)
+    };
+
+    adw::ActionRow::builder()
+        .title(&user.name)
+        .subtitle(subtitle)
+        .build()
+}
@@ -0,0 +1,27 @@
+mod imp;
+
+use crate::widget::app_window::AppWindow;
+use gtk::{
+    glib::{self},
+    prelude::*,
+    subclass::prelude::ObjectSubclassIsExt,
+};
+
+/// Window attaching the user session manager of another logged in or lingering user
+glib::wrapper! {
+    pub struct OtherUsersWindow(ObjectSubclass<imp::OtherUsersWindowImp>)
+    @extends adw::Window, gtk::Window, gtk::Widget,
+    @implements gtk::Accessible,  gtk::Buildable,  gtk::ConstraintTarget,
+    gtk::Native, gtk::Root, gtk::ShortcutManager;
+}
+
+impl OtherUsersWindow {
+    pub fn new(app_window: &AppWindow) -> Self {
+        let obj: OtherUsersWindow = glib::Object::new();
+        obj.set_transient_for(Some(app_window));
+        let imp = obj.imp();
+        imp.set_app_window(app_window);
+        imp.load_users();
+        obj
+    }
+}
@@ -1,4 +1,5 @@
 use crate::systemd_gui::new_settings;
+use base::enums::UnitDBusLevel;
 use gettextrs::pgettext;
 use glib::{self, GString};
 use gtk::{
@@ -53,6 +54,14 @@ pub enum DbusLevel {
 }
 
 impl DbusLevel {
+    pub fn unit_dbus_level(&self) -> UnitDBusLevel {
+        match self {
+            DbusLevel::UserSession => UnitDBusLevel::UserSession,
+            DbusLevel::System => UnitDBusLevel::System,
+            DbusLevel::SystemAndSession => UnitDBusLevel::Both,
+        }
+    }
+
     pub fn as_str(&self) -> &str {
         match self {
             DbusLevel::UserSession => "session",
@@ -0,0 +1,270 @@
+use crate::{
+    consts::{CLASS_ERROR, DESTRUCTIVE_ACTION, FLAT},
+    widget::journal::highlight::{self, HighlightRule},
+};
+use adw::prelude::*;
+use gettextrs::pgettext;
+use gtk::{gdk, glib};
+use regex::Regex;
+use std::{cell::RefCell, rc::Rc};
+
+type Rules = Rc<RefCell<Vec<HighlightRule>>>;
+type Rows = Rc<RefCell<Vec<adw::ExpanderRow>>>;
+
+/// One expandable row per journal highlight rule, every change is saved
+pub(super) fn build_highlight_rules(group: &adw::PreferencesGroup) {
+    let rules: Rules = Rc::new(RefCell::new(highlight::highlight_rules()));
+    let rows: Rows = Rc::new(RefCell::new(Vec::new()));
+
+    let add_button = gtk::Button::builder()
+        .icon_name("list-add-symbolic")
+        .css_classes([FLAT])
+        .valign(gtk::Align::Center)
+        .tooltip_text(pgettext("preference", "Add a highlight rule"))
+        .build();
+    group.set_header_suffix(Some(&add_button));
+
+    {
+        let group = group.clone();
+        let rules = rules.clone();
+        let rows = rows.clone();
+        add_button.connect_clicked(move |_| {
+            rules.borrow_mut().push(HighlightRule::default());
+            highlight::set_highlight_rules(rules.borrow().clone());
+            fill_rows(&group, &rules, &rows);
+
+            if let Some(row) = rows.borrow().last() {
+                row.set_expanded(true);
+            }
+        });
+    }
+
+    fill_rows(group, &rules, &rows);
+}
+
+fn fill_rows(group: &adw::PreferencesGroup, rules: &Rules, rows: &Rows) {
+    for row in rows.borrow_mut().drain(..) {
+        group.remove(&row);
+    }
+
+    let rule_count = rules.borrow().len();
+    for idx in 0..rule_count {
+        let row = build_rule_row(group, rules, rows, idx);
+        group.add(&row);
+        rows.borrow_mut().push(row);
+    }
+}
+
+fn update_rule(rules: &Rules, idx: usize, change: impl FnOnce(&mut HighlightRule)) {
+    let mut rules_mut = rules.borrow_mut();
+    let Some(rule) = rules_mut.get_mut(idx) else {
+        return;
+    };
+
+    change(rule);
+    highlight::set_highlight_rules(rules_mut.clone());
+}
+
+fn rule_title(rule: &HighlightRule) -> String {
+    if rule.pattern.is_empty() {
+        pgettext("preference", "New rule")
+    } else {
+        glib::markup_escape_text(&rule.pattern).to_string()
+    }
+}
+
+fn rule_subtitle(rule: &HighlightRule) -> String {
+    if rule.unit.is_empty() {
+        pgettext("preference", "All units")
+    } else {
+        glib::markup_escape_text(&rule.unit).to_string()
+    }
+}
+
+fn build_rule_row(
+    group: &adw::PreferencesGroup,
+    rules: &Rules,
+    rows: &Rows,
+    idx: usize,
+) -> adw::ExpanderRow {
+    let rule = rules.borrow()[idx].clone();
+
+    let row = adw::ExpanderRow::builder()
+        .title(rule_title(&rule))
+        .subtitle(rule_subtitle(&rule))
+        .show_enable_switch(true)
+        .enable_expansion(rule.enabled)
+        .build();
+
+    {
+        let rules = rules.clone();
+        row.connect_enable_expansion_notify(move |row| {
+            let enabled = row.enables_expansion();
+            update_rule(&rules, idx, |rule| rule.enabled = enabled);
+        });
+    }
+
+    let pattern_row = adw::EntryRow::builder()
+        .title(pgettext("preference", "Regular expression"))
+        .show_apply_button(true)
+        .build();
+    pattern_row.set_text(&rule.pattern);
+
+    {
+        let rules = rules.clone();
+        let row = row.clone();
+        pattern_row.connect_apply(move |entry| {
+            let pattern = entry.text().to_string();
+            if Regex::new(&pattern).is_err() {
+                entry.add_css_class(CLASS_ERROR);
+                return;
+            }
+
+            entry.remove_css_class(CLASS_ERROR);
+            update_rule(&rules, idx, |rule| {
+                rule.pattern = pattern;
+                row.set_title(&rule_title(rule));
+            });
+        });
+    }
+
+    pattern_row.connect_changed(|entry| {
+        if Regex::new(&entry.text()).is_ok() {
+            entry.remove_css_class(CLASS_ERROR);
+        } else {
+            entry.add_css_class(CLASS_ERROR);
+        }
+    });
+    row.add_row(&pattern_row);
+
+    let unit_row = adw::EntryRow::builder()
+        .title(pgettext("preference", "Only for unit, all units if empty"))
+        .show_apply_button(true)
+        .build();
+    unit_row.set_text(&rule.unit);
+
+    {
+        let rules = rules.clone();
+        let row = row.clone();
+        unit_row.connect_apply(move |entry| {
+            let unit = entry.text().trim().to_owned();
+            update_rule(&rules, idx, |rule| {
+                rule.unit = unit;
+                row.set_subtitle(&rule_subtitle(rule));
+            });
+        });
+    }
+    row.add_row(&unit_row);
+
+    {
+        let rules = rules.clone();
+        let foreground_row = color_row(
+            pgettext("preference", "Text color"),
+            rule.foreground.as_deref(),
+            move |color| update_rule(&rules, idx, |rule| rule.foreground = color),
+        );
+        row.add_row(&foreground_row);
+    }
+
+    {
+        let rules = rules.clone();
+        let background_row = color_row(
+            pgettext("preference", "Background color"),
+            rule.background.as_deref(),
+            move |color| update_rule(&rules, idx, |rule| rule.background = color),
+        );
+        row.add_row(&background_row);
+    }
+
+    let bold_row = adw::SwitchRow::builder()
+        .title(pgettext("preference", "Bold"))
+        .active(rule.bold)
+        .build();
+
+    {
+        let rules = rules.clone();
+        bold_row.connect_active_notify(move |switch| {
+            let bold = switch.is_active();
+            update_rule(&rules, idx, |rule| rule.bold = bold);
+        });
+    }
+    row.add_row(&bold_row);
+
+    let remove_button = gtk::Button::builder()
+        .label(pgettext("preference", "Remove Rule"))
+        .css_classes([DESTRUCTIVE_ACTION])
+        .halign(gtk::Align::End)
+        .margin_top(5)
+        .margin_bottom(5)
+        .margin_end(5)
+        .build();
+
+    {
+        let group = group.clone();
+        let rules = rules.clone();
+        let rows = rows.clone();
+        remove_button.connect_clicked(move |_| {
+            // Rebuild after the click handling, the rows get destroyed
+            let group = group.clone();
+            let rules = rules.clone();
+            let rows = rows.clone();
+            glib::idle_add_local_once(move || {
+                if idx < rules.borrow().len() {
+                    rules.borrow_mut().remove(idx);
+                }
+                highlight::set_highlight_rules(rules.borrow().clone());
+                fill_rows(&group, &rules, &rows);
+            });
+        });
+    }
+    row.add_row(&remove_button);
+
+    row
+}
+
+/// A row with a switch to use the color and a color chooser
+fn color_row(
+    title: String,
+    color: Option<&str>,
+    on_change: impl Fn(Option<String>) + 'static,
+) -> adw::ActionRow {
+    let rgba = color
+        .and_then(|color| gdk::RGBA::parse(color).ok())
+        .unwrap_or(gdk::RGBA::RED);
+
+    let color_button = gtk::ColorDialogButton::builder()
+        .dialog(&gtk::ColorDialog::new())
+        .rgba(&rgba)
+        .sensitive(color.is_some())
+        .valign(gtk::Align::Center)
+        .build();
+
+    let switch = gtk::Switch::builder()
+        .active(color.is_some())
+        .valign(gtk::Align::Center)
+        .build();
+
+    let row = adw::ActionRow::builder().title(title).build();
+    row.add_suffix(&color_button);
+    row.add_suffix(&switch);
+
+    let on_change = Rc::new(on_change);
+
+    {
+        let on_change = on_change.clone();
+        let color_button = color_button.clone();
+        switch.connect_active_notify(move |switch| {
+            let active = switch.is_active();
+            color_button.set_sensitive(active);
+            on_change(active.then(|| color_button.rgba().to_str().to_string()));
+        });
+    }
+
+    color_button.connect_rgba_notify(move |color_button| {
+        if switch.is_active() {
+            on_change(Some(color_button.rgba().to_str().to_string()));
+        }
+    });
+
+    row
+}
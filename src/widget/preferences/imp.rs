@@ -22,6 +22,8 @@ use crate::{
                 KEY_PREF_UNIT_DESCRIPTION_WRAP, KEY_PREF_UNIT_LIST_DISPLAY_COLORS,
             },
             drop_down_elem::{build_pane_orientation_selector, build_preferred_color_scheme},
+            highlight_rules::build_highlight_rules,
+            notifications::build_notification_preferences,
             style_scheme::style_schemes,
         },
     },
@@ -59,6 +61,12 @@ pub struct PreferencesDialogImpl {
     #[template_child]
     journal_event_max_size: TemplateChild<adw::SpinRow>,
 
+    #[template_child]
+    journal_highlight_group: TemplateChild<adw::PreferencesGroup>,
+
+    #[template_child]
+    notifications_page: TemplateChild<adw::PreferencesPage>,
+
     #[template_child]
     timestamp_style: TemplateChild<adw::ComboRow>,
 
@@ -466,6 +474,9 @@ impl ObjectImpl for PreferencesDialogImpl {
         // Load latest window state
         self.load_preferences_values();
 
+        build_highlight_rules(&self.journal_highlight_group);
+        build_notification_preferences(&self.notifications_page);
+
         settings
             .bind(
                 KEY_PREF_UNIT_LIST_DISPLAY_COLORS,
@@ -1,6 +1,8 @@
 pub mod data;
 mod drop_down_elem;
+mod highlight_rules;
 mod imp;
+mod notifications;
 pub mod style_scheme;
 
 use gtk::{glib, subclass::prelude::*};
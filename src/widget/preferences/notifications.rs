@@ -0,0 +1,202 @@
+use crate::{
+    consts::FLAT,
+    widget::notifications::{
+        self,
+        config::{self, MonitorMode, NotificationConfig, UnitNotification},
+    },
+};
+use adw::prelude::*;
+use base::enums::UnitDBusLevel;
+use gettextrs::pgettext;
+use gtk::{gio, glib};
+use std::{cell::RefCell, rc::Rc};
+
+type Config = Rc<RefCell<NotificationConfig>>;
+type Rows = Rc<RefCell<Vec<adw::ActionRow>>>;
+
+const LEVELS: [UnitDBusLevel; 2] = [UnitDBusLevel::System, UnitDBusLevel::UserSession];
+
+/// The monitored units selection and the per unit notification settings
+pub(super) fn build_notification_preferences(page: &adw::PreferencesPage) {
+    let config: Config = Rc::new(RefCell::new(config::notification_config()));
+
+    let mode_group = adw::PreferencesGroup::builder()
+        .title(pgettext("preference", "Unit Notifications"))
+        .description(pgettext(
+            "preference",
+            "Notify when a unit fails, when systemd restarts it or when the service of a timer fails. The monitoring keeps going when the window is closed.",
+        ))
+        .build();
+
+    let labels: Vec<String> = MonitorMode::MODES.iter().map(MonitorMode::label).collect();
+    let labels: Vec<&str> = labels.iter().map(String::as_str).collect();
+
+    let mode_row = adw::ComboRow::builder()
+        .title(pgettext("preference", "Monitored Units"))
+        .model(&gtk::StringList::new(&labels))
+        .selected(
+            MonitorMode::MODES
+                .iter()
+                .position(|mode| *mode == config.borrow().mode)
+                .unwrap_or_default() as u32,
+        )
+        .build();
+
+    {
+        let config = config.clone();
+        mode_row.connect_selected_notify(move |row| {
+            let Some(mode) = MonitorMode::MODES.get(row.selected() as usize) else {
+                return;
+            };
+            update_config(&config, |config| config.mode = *mode);
+        });
+    }
+    mode_group.add(&mode_row);
+    page.add(&mode_group);
+
+    let units_group = adw::PreferencesGroup::builder()
+        .title(pgettext("preference", "Per Unit Settings"))
+        .description(pgettext(
+            "preference",
+            "Override the monitored units selection for some units",
+        ))
+        .build();
+
+    let level_names: Vec<_> = LEVELS.iter().map(|level| level.as_str()).collect();
+    let level_dropdown = gtk::DropDown::from_strings(
+        &level_names
+            .iter()
+            .map(|level| level.as_ref())
+            .collect::<Vec<_>>(),
+    );
+    level_dropdown.set_valign(gtk::Align::Center);
+
+    let add_row = adw::EntryRow::builder()
+        .title(pgettext("preference", "Add a unit"))
+        .show_apply_button(true)
+        .build();
+    add_row.add_suffix(&level_dropdown);
+    units_group.add(&add_row);
+
+    let rows: Rows = Rc::new(RefCell::new(Vec::new()));
+
+    {
+        let config = config.clone();
+        let rows = rows.clone();
+        let units_group = units_group.clone();
+        add_row.connect_apply(move |entry| {
+            let unit = entry.text().trim().to_owned();
+            if unit.is_empty() {
+                return;
+            }
+
+            let level = LEVELS
+                .get(level_dropdown.selected() as usize)
+                .copied()
+                .unwrap_or_default();
+
+            update_config(&config, |config| {
+                config.units.retain(|unit_notification| {
+                    unit_notification.unit != unit || unit_notification.level() != Some(level)
+                });
+                config.units.push(UnitNotification {
+                    bus: level.as_str().into_owned(),
+                    unit,
+                    ..Default::default()
+                });
+            });
+
+            entry.set_text("");
+            fill_rows(&units_group, &config, &rows);
+        });
+    }
+
+    fill_rows(&units_group, &config, &rows);
+    page.add(&units_group);
+}
+
+fn update_config(config: &Config, change: impl FnOnce(&mut NotificationConfig)) {
+    change(&mut config.borrow_mut());
+    config::set_notification_config(config.borrow().clone());
+
+    if let Some(application) = gio::Application::default().and_downcast::<adw::Application>() {
+        notifications::update_unit_monitor(&application);
+    }
+}
+
+fn fill_rows(group: &adw::PreferencesGroup, config: &Config, rows: &Rows) {
+    for row in rows.borrow_mut().drain(..) {
+        group.remove(&row);
+    }
+
+    let unit_count = config.borrow().units.len();
+    for idx in 0..unit_count {
+        let row = build_unit_row(group, config, rows, idx);
+        group.add(&row);
+        rows.borrow_mut().push(row);
+    }
+}
+
+fn build_unit_row(
+    group: &adw::PreferencesGroup,
+    config: &Config,
+    rows: &Rows,
+    idx: usize,
+) -> adw::ActionRow {
+    let unit = config.borrow().units[idx].clone();
+
+    let notify_switch = gtk::Switch::builder()
+        .active(unit.notify)
+        .valign(gtk::Align::Center)
+        .tooltip_text(pgettext("preference", "Notify"))
+        .build();
+
+    {
+        let config = config.clone();
+        notify_switch.connect_active_notify(move |switch| {
+            let notify = switch.is_active();
+            update_config(&config, |config| {
+                if let Some(unit) = config.units.get_mut(idx) {
+                    unit.notify = notify;
+                }
+            });
+        });
+    }
+
+    let remove_button = gtk::Button::builder()
+        .icon_name("user-trash-symbolic")
+        .css_classes([FLAT])
+        .valign(gtk::Align::Center)
+        .tooltip_text(pgettext("preference", "Remove"))
+        .build();
+
+    {
+        let group = group.clone();
+        let config = config.clone();
+        let rows = rows.clone();
+        remove_button.connect_clicked(move |_| {
+            // Rebuild after the click handling, the rows get destroyed
+            let group = group.clone();
+            let config = config.clone();
+            let rows = rows.clone();
+            glib::idle_add_local_once(move || {
+                update_config(&config, |config| {
+                    if idx < config.units.len() {
+                        config.units.remove(idx);
+                    }
+                });
+                fill_rows(&group, &config, &rows);
+            });
+        });
+    }
+
+    let row = adw::ActionRow::builder()
+        .title(glib::markup_escape_text(&unit.unit).as_str())
+        .subtitle(unit.bus.as_str())
+        .activatable_widget(&notify_switch)
+        .build();
+    row.add_suffix(&notify_switch);
+    row.add_suffix(&remove_button);
+
+    row
+}
@@ -163,7 +163,7 @@ impl SignalsWindowImp {
                 .expect("The child has to be an `Inscription`.");
 
             let signal_row: Ref<SystemdSignalRow> = task_object.borrow();
-            time_cell.set_text(Some(signal_row.bus_text()));
+            time_cell.set_text(Some(&signal_row.bus_text()));
         });
 
         self.bus_column.set_factory(Some(&factory));
@@ -0,0 +1,360 @@
+use super::TargetsWindow;
+use crate::{
+    consts::{DESTRUCTIVE_ACTION, FLAT},
+    format2,
+    systemd::{
+        self,
+        errors::SystemdErrors,
+        targets::{ISOLATE_TARGETS, can_isolate, units_stopped_by_isolate},
+    },
+    upgrade,
+    widget::{app_window::AppWindow, close_window_shortcut},
+};
+use adw::{prelude::*, subclass::window::AdwWindowImpl};
+use base::enums::UnitDBusLevel;
+use gettextrs::pgettext;
+use gtk::{
+    glib::{self},
+    subclass::{
+        prelude::*,
+        widget::{
+            CompositeTemplateCallbacksClass, CompositeTemplateClass,
+            CompositeTemplateInitializingExt, WidgetImpl,
+        },
+    },
+};
+use std::cell::{OnceCell, RefCell};
+use tracing::warn;
+
+const CANCEL: &str = "cancel";
+const ISOLATE: &str = "isolate";
+
+#[derive(Default, gtk::CompositeTemplate)]
+#[template(resource = "/io/github/plrigaux/sysd-manager/targets.ui")]
+pub struct TargetsWindowImp {
+    #[template_child]
+    default_row: TemplateChild<adw::ComboRow>,
+
+    #[template_child]
+    apply_button: TemplateChild<gtk::Button>,
+
+    #[template_child]
+    isolate_group: TemplateChild<adw::PreferencesGroup>,
+
+    #[template_child]
+    status_label: TemplateChild<gtk::Label>,
+
+    app_window: OnceCell<AppWindow>,
+    targets: RefCell<Vec<String>>,
+}
+
+#[gtk::template_callbacks]
+impl TargetsWindowImp {
+    pub(super) fn set_app_window(&self, app_window: &AppWindow) {
+        self.app_window
+            .set(app_window.clone())
+            .expect("app_window set once");
+    }
+
+    #[template_callback]
+    fn refresh_button_clicked(&self, _button: gtk::Button) {
+        self.load();
+    }
+
+    #[template_callback]
+    fn apply_button_clicked(&self, _button: gtk::Button) {
+        self.apply_default();
+    }
+
+    pub(super) fn load(&self) {
+        let window = self.obj().clone();
+        glib::spawn_future_local(async move {
+            let (sender, receiver) = tokio::sync::oneshot::channel();
+            systemd::runtime().spawn(async move {
+                let response: Result<(String, Vec<String>), SystemdErrors> = async {
+                    let level = UnitDBusLevel::System;
+                    let default = systemd::targets::get_default_target(level).await?;
+                    let targets = systemd::targets::list_target_files(level).await?;
+                    Ok((default, targets))
+                }
+                .await;
+
+                if let Err(e) = sender.send(response) {
+                    warn!("Channel closed unexpectedly: {e:?}");
+                }
+            });
+
+            let imp = window.imp();
+            match receiver.await {
+                Ok(Ok((default, targets))) => imp.fill(&default, targets),
+                Ok(Err(err)) => {
+                    warn!("Fetch the default target {err:?}");
+                    imp.status_label.set_text(&format2!(
+                        pgettext("targets", "Can't fetch the default target: {}"),
+                        err.to_string()
+                    ));
+                }
+                Err(err) => warn!("Tokio channel dropped {err:?}"),
+            }
+        });
+    }
+
+    fn fill(&self, default: &str, mut targets: Vec<String>) {
+        if !targets.iter().any(|target| target == default) {
+            targets.push(default.to_owned());
+            targets.sort();
+        }
+
+        let labels: Vec<&str> = targets.iter().map(|target| target.as_str()).collect();
+        self.default_row
+            .set_model(Some(&gtk::StringList::new(&labels)));
+
+        if let Some(position) = targets.iter().position(|target| target == default) {
+            self.default_row.set_selected(position as u32);
+        }
+
+        self.default_row.set_subtitle(&format2!(
+            pgettext("targets", "The target reached at boot, currently {}"),
+            default
+        ));
+        self.apply_button.set_sensitive(true);
+        self.targets.replace(targets);
+    }
+
+    fn apply_default(&self) {
+        let Some(target) = self
+            .targets
+            .borrow()
+            .get(self.default_row.selected() as usize)
+            .cloned()
+        else {
+            return;
+        };
+
+        self.apply_button.set_sensitive(false);
+        let window = self.obj().clone();
+        glib::spawn_future_local(async move {
+            let (sender, receiver) = tokio::sync::oneshot::channel();
+            let target_ = target.clone();
+            systemd::runtime().spawn(async move {
+                let response =
+                    systemd::targets::set_default_target(UnitDBusLevel::System, &target_).await;
+                if let Err(e) = sender.send(response) {
+                    warn!("Channel closed unexpectedly: {e:?}");
+                }
+            });
+
+            let response = receiver.await;
+            let imp = window.imp();
+            imp.apply_button.set_sensitive(true);
+
+            match response {
+                Ok(Ok(_changes)) => {
+                    imp.status_label.set_text(&format2!(
+                        pgettext("targets", "Default target set to {}"),
+                        &target
+                    ));
+                    imp.load();
+                }
+                Ok(Err(err)) => {
+                    warn!("Set default target {target} {err:?}");
+                    imp.status_label.set_text(&format2!(
+                        pgettext("targets", "Default target not changed: {}"),
+                        err.to_string()
+                    ));
+                }
+                Err(err) => warn!("Tokio channel dropped {err:?}"),
+            }
+        });
+    }
+
+    fn isolate_row(&self, target: &'static str) -> adw::ActionRow {
+        let row = adw::ActionRow::builder().title(target).build();
+
+        let jump_button = gtk::Button::builder()
+            .icon_name("go-jump-symbolic")
+            .tooltip_text(format2!(
+                pgettext("targets", "Show {} in the unit list"),
+                target
+            ))
+            .css_classes([FLAT])
+            .valign(gtk::Align::Center)
+            .build();
+
+        let isolate_button = gtk::Button::builder()
+            .label(pgettext("targets", "Isolate…"))
+            .css_classes([DESTRUCTIVE_ACTION])
+            .valign(gtk::Align::Center)
+            .build();
+
+        let window = self.obj().downgrade();
+        jump_button.connect_clicked(move |_| {
+            let window = upgrade!(window);
+            let imp = window.imp();
+            let Some(app_window) = imp.app_window.get() else {
+                return;
+            };
+
+            match systemd::fetch_unit(UnitDBusLevel::System, target) {
+                Ok(unit) => {
+                    app_window.set_unit(Some(&unit));
+                    app_window.present();
+                }
+                Err(err) => {
+                    warn!("Fetch {target} {err:?}");
+                    imp.status_label.set_text(&err.human_error_type());
+                }
+            }
+        });
+
+        let window = self.obj().downgrade();
+        isolate_button.connect_clicked(move |button| {
+            let window = upgrade!(window);
+            window.imp().prepare_isolate(button, target);
+        });
+
+        row.add_suffix(&jump_button);
+        row.add_suffix(&isolate_button);
+        row
+    }
+
+    /// Compute the units that would stop before asking for a confirmation
+    fn prepare_isolate(&self, button: &gtk::Button, target: &'static str) {
+        button.set_sensitive(false);
+        let button = button.clone();
+        let window = self.obj().clone();
+        glib::spawn_future_local(async move {
+            let (sender, receiver) = tokio::sync::oneshot::channel();
+            systemd::runtime().spawn_blocking(move || {
+                let response = (|| {
+                    let level = UnitDBusLevel::System;
+                    if !can_isolate(level, target)? {
+                        return Ok(None);
+                    }
+                    units_stopped_by_isolate(level, target).map(Some)
+                })();
+
+                if let Err(e) = sender.send(response) {
+                    warn!("Channel closed unexpectedly: {e:?}");
+                }
+            });
+
+            let response = receiver.await;
+            button.set_sensitive(true);
+
+            let imp = window.imp();
+            match response {
+                Ok(Ok(Some(stopped))) => imp.confirm_isolate(target, &stopped),
+                Ok(Ok(None)) => imp.status_label.set_text(&format2!(
+                    pgettext("targets", "{} does not allow to be isolated"),
+                    target
+                )),
+                Ok(Err(err)) => {
+                    warn!("Prepare isolate {target} {err:?}");
+                    imp.status_label.set_text(&format2!(
+                        pgettext("targets", "Can't isolate {}: {}"),
+                        target,
+                        err.to_string()
+                    ));
+                }
+                Err(err) => warn!("Tokio channel dropped {err:?}"),
+            }
+        });
+    }
+
+    fn confirm_isolate(&self, target: &'static str, stopped: &[String]) {
+        let body = if stopped.is_empty() {
+            pgettext("targets", "No running unit will be stopped.")
+        } else {
+            format2!(
+                pgettext("targets", "These {} running units will be stopped:\n\n{}"),
+                stopped.len(),
+                stopped.join("\n")
+            )
+        };
+
+        let alert = adw::AlertDialog::builder()
+            .heading(format2!(pgettext("targets", "Isolate {}?"), target))
+            .body(body)
+            .close_response(CANCEL)
+            .can_close(true)
+            .build();
+
+        let cancel_label = pgettext("targets", "_Cancel");
+        let isolate_label = pgettext("targets", "_Isolate");
+        alert.add_responses(&[
+            (CANCEL, cancel_label.as_str()),
+            (ISOLATE, isolate_label.as_str()),
+        ]);
+        alert.set_response_appearance(ISOLATE, adw::ResponseAppearance::Destructive);
+
+        let window = self.obj().downgrade();
+        alert.connect_response(Some(ISOLATE), move |_dialog, _response| {
+            let window = upgrade!(window);
+            window.imp().isolate(target);
+        });
+
+        alert.present(Some(&*self.obj()));
+    }
+
+    fn isolate(&self, target: &'static str) {
+        let window = self.obj().clone();
+        glib::spawn_future_local(async move {
+            let (sender, receiver) = tokio::sync::oneshot::channel();
+            systemd::runtime().spawn(async move {
+                let response =
+                    systemd::targets::isolate_target(UnitDBusLevel::System, target).await;
+                if let Err(e) = sender.send(response) {
+                    warn!("Channel closed unexpectedly: {e:?}");
+                }
+            });
+
+            let imp = window.imp();
+            match receiver.await {
+                Ok(Ok(_job)) => imp
+                    .status_label
+                    .set_text(&format2!(pgettext("targets", "Switching to {}"), target)),
+                Ok(Err(err)) => {
+                    warn!("Isolate {target} {err:?}");
+                    imp.status_label.set_text(&format2!(
+                        pgettext("targets", "Can't isolate {}: {}"),
+                        target,
+                        err.to_string()
+                    ));
+                }
+                Err(err) => warn!("Tokio channel dropped {err:?}"),
+            }
+        });
+    }
+}
+
+#[glib::object_subclass]
+impl ObjectSubclass for TargetsWindowImp {
+    const NAME: &'static str = "TargetsWindow";
+    type Type = TargetsWindow;
+    type ParentType = adw::Window;
+
+    fn class_init(klass: &mut Self::Class) {
+        klass.bind_template();
+        klass.bind_template_callbacks();
+    }
+
+    fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+        obj.init_template();
+    }
+}
+
+impl ObjectImpl for TargetsWindowImp {
+    fn constructed(&self) {
+        self.parent_constructed();
+        close_window_shortcut(self.obj().as_ref());
+
+        for target in ISOLATE_TARGETS {
+            self.isolate_group.add(&self.isolate_row(target));
+        }
+    }
+}
+
+impl WidgetImpl for TargetsWindowImp {}
+impl WindowImpl for TargetsWindowImp {}
+impl AdwWindowImpl for TargetsWindowImp {}
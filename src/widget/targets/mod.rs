@@ -0,0 +1,27 @@
+mod imp;
+
+use crate::widget::app_window::AppWindow;
+use gtk::{
+    glib::{self},
+    prelude::*,
+    subclass::prelude::ObjectSubclassIsExt,
+};
+
+/// Window changing the system default target and switching to another target
+glib::wrapper! {
+    pub struct TargetsWindow(ObjectSubclass<imp::TargetsWindowImp>)
+    @extends adw::Window, gtk::Window, gtk::Widget,
+    @implements gtk::Accessible,  gtk::Buildable,  gtk::ConstraintTarget,
+    gtk::Native, gtk::Root, gtk::ShortcutManager;
+}
+
+impl TargetsWindow {
+    pub fn new(app_window: &AppWindow) -> Self {
+        let obj: TargetsWindow = glib::Object::new();
+        obj.set_transient_for(Some(app_window));
+        let imp = obj.imp();
+        imp.set_app_window(app_window);
+        imp.load();
+        obj
+    }
+}
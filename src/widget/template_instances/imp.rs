@@ -0,0 +1,477 @@
+use super::TemplateInstancesWindow;
+use crate::{
+    format2,
+    systemd::{
+        self, ReStartStop,
+        data::UnitInfo,
+        enums::{DisEnableFlags, StartStopMode},
+        template_unit,
+    },
+    upgrade,
+    widget::{app_window::AppWindow, close_window_shortcut},
+};
+use adw::subclass::window::AdwWindowImpl;
+use base::enums::UnitDBusLevel;
+use enumflags2::BitFlag;
+use gettextrs::pgettext;
+use gtk::{
+    gio,
+    glib::{self},
+    prelude::*,
+    subclass::{
+        prelude::*,
+        widget::{
+            CompositeTemplateCallbacksClass, CompositeTemplateClass,
+            CompositeTemplateInitializingExt, WidgetImpl,
+        },
+    },
+};
+use std::cell::{Cell, OnceCell, RefCell};
+use tracing::{info, warn};
+
+#[derive(Default, gtk::CompositeTemplate)]
+#[template(resource = "/io/github/plrigaux/sysd-manager/template_instances.ui")]
+pub struct TemplateInstancesWindowImp {
+    #[template_child]
+    window_title: TemplateChild<adw::WindowTitle>,
+
+    #[template_child]
+    instance_entry: TemplateChild<gtk::Entry>,
+
+    #[template_child]
+    path_check: TemplateChild<gtk::CheckButton>,
+
+    #[template_child]
+    escaped_label: TemplateChild<gtk::Label>,
+
+    #[template_child]
+    start_button: TemplateChild<gtk::Button>,
+
+    #[template_child]
+    enable_button: TemplateChild<gtk::Button>,
+
+    #[template_child]
+    specifiers_label: TemplateChild<gtk::Label>,
+
+    #[template_child]
+    preview_view: TemplateChild<gtk::TextView>,
+
+    #[template_child]
+    single_selection: TemplateChild<gtk::SingleSelection>,
+
+    #[template_child]
+    instance_column: TemplateChild<gtk::ColumnViewColumn>,
+
+    #[template_child]
+    active_column: TemplateChild<gtk::ColumnViewColumn>,
+
+    #[template_child]
+    sub_column: TemplateChild<gtk::ColumnViewColumn>,
+
+    app_window: OnceCell<AppWindow>,
+    level: Cell<UnitDBusLevel>,
+    template: OnceCell<String>,
+    template_content: RefCell<Option<String>>,
+    store: OnceCell<gio::ListStore>,
+}
+
+#[gtk::template_callbacks]
+impl TemplateInstancesWindowImp {
+    pub(super) fn set_template(
+        &self,
+        app_window: &AppWindow,
+        level: UnitDBusLevel,
+        template: &str,
+    ) {
+        self.app_window
+            .set(app_window.clone())
+            .expect("app_window set once");
+        self.level.set(level);
+        self.template
+            .set(template.to_owned())
+            .expect("template set once");
+        self.window_title.set_subtitle(template);
+    }
+
+    fn template(&self) -> &str {
+        self.template.get().expect("template set")
+    }
+
+    fn store(&self) -> &gio::ListStore {
+        self.store.get().expect("store set")
+    }
+
+    fn typed_instance_name(&self) -> Option<String> {
+        let text = self.instance_entry.text();
+        let raw_instance = text.trim();
+        if raw_instance.is_empty() {
+            return None;
+        }
+
+        template_unit::instance_unit_name(
+            self.template(),
+            raw_instance,
+            self.path_check.is_active(),
+        )
+    }
+
+    fn update_preview(&self, unit_name: Option<String>) {
+        let unit_name = unit_name.unwrap_or_else(|| self.template().to_owned());
+        let content = self.template_content.borrow();
+        let content = content.as_deref().unwrap_or_default();
+
+        let resolved = template_unit::resolve_specifiers(content, &unit_name);
+        self.preview_view.buffer().set_text(&resolved);
+
+        let specifiers = template_unit::specifier_values(&unit_name)
+            .into_iter()
+            .map(|(spec, value)| {
+                format!(
+                    "<b>%{spec}</b> {}",
+                    glib::markup_escape_text(if value.is_empty() { "∅" } else { &value })
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("   ");
+
+        self.specifiers_label.set_markup(&specifiers);
+    }
+
+    fn instance_changed(&self) {
+        let name = self.typed_instance_name();
+        let valid = name.is_some();
+        self.start_button.set_sensitive(valid);
+        self.enable_button.set_sensitive(valid);
+        self.escaped_label
+            .set_label(name.as_deref().unwrap_or_default());
+        self.update_preview(name);
+    }
+
+    fn refresh(&self) {
+        fill_instances(self.store(), self.level.get(), self.template());
+    }
+
+    pub(super) fn load(&self) {
+        self.refresh();
+
+        let level = self.level.get();
+        let template = self.template().to_owned();
+        let window = self.obj().clone();
+        glib::spawn_future_local(async move {
+            let content = load_template_content(level, template).await;
+            let imp = window.imp();
+            imp.template_content.replace(content);
+            imp.update_preview(None);
+        });
+    }
+
+    #[template_callback]
+    fn instance_entry_changed(&self, _entry: gtk::Entry) {
+        self.instance_changed();
+    }
+
+    #[template_callback]
+    fn path_check_toggled(&self, _check: gtk::CheckButton) {
+        self.instance_changed();
+    }
+
+    #[template_callback]
+    fn refresh_button_clicked(&self, _button: gtk::Button) {
+        self.refresh();
+    }
+
+    #[template_callback]
+    fn start_button_clicked(&self, button: gtk::Button) {
+        let Some(instance) = self.typed_instance_name() else {
+            return;
+        };
+
+        let level = self.level.get();
+        let window = self.obj().clone();
+        button.set_sensitive(false);
+        glib::spawn_future_local(async move {
+            let (sender, receiver) = tokio::sync::oneshot::channel();
+            let instance2 = instance.clone();
+            systemd::runtime().spawn(async move {
+                let result = systemd::restartstop_unit(
+                    level,
+                    &instance2,
+                    StartStopMode::Fail,
+                    ReStartStop::Start,
+                )
+                .await;
+                let _ = sender.send(result);
+            });
+
+            let result = receiver.await;
+            button.set_sensitive(true);
+
+            let msg = match result {
+                Ok(Ok(_job)) => format2!(
+                    pgettext("template", "Instance <unit>{}</unit> started"),
+                    instance
+                ),
+                Ok(Err(err)) => {
+                    warn!("Start instance {instance:?} error {err:?}");
+                    format2!(
+                        pgettext("template", "Starting instance <unit>{}</unit> failed"),
+                        instance
+                    )
+                }
+                Err(err) => {
+                    warn!("Tokio channel dropped {err:?}");
+                    return;
+                }
+            };
+
+            let imp = window.imp();
+            imp.add_toast_message(&msg, true);
+            imp.refresh();
+        });
+    }
+
+    #[template_callback]
+    fn enable_button_clicked(&self, _button: gtk::Button) {
+        let Some(instance) = self.typed_instance_name() else {
+            return;
+        };
+
+        let level = self.level.get();
+        let window = self.obj().clone();
+        glib::spawn_future_local(async move {
+            let instance2 = instance.clone();
+            let result = gio::spawn_blocking(move || {
+                systemd::enable_unit_file(level, &instance2, DisEnableFlags::empty())
+            })
+            .await;
+
+            let msg = match result {
+                Ok(Ok(response)) => {
+                    info!("Enable instance {instance:?} {response:?}");
+                    format2!(
+                        pgettext("template", "Instance <unit>{}</unit> enabled"),
+                        instance
+                    )
+                }
+                Ok(Err(err)) => {
+                    warn!("Enable instance {instance:?} error {err:?}");
+                    format2!(
+                        pgettext("template", "Enabling instance <unit>{}</unit> failed"),
+                        instance
+                    )
+                }
+                Err(err) => {
+                    warn!("Enable instance join error {err:?}");
+                    return;
+                }
+            };
+
+            window.imp().add_toast_message(&msg, true);
+        });
+    }
+
+    #[template_callback]
+    fn stop_all_button_clicked(&self, button: gtk::Button) {
+        let instances: Vec<String> = self
+            .store()
+            .iter::<UnitInfo>()
+            .filter_map(|unit| unit.ok())
+            .filter(|unit| unit.is_active())
+            .map(|unit| unit.primary())
+            .collect();
+
+        if instances.is_empty() {
+            let msg = format2!(
+                pgettext("template", "No active instance of <unit>{}</unit>"),
+                self.template()
+            );
+            self.add_toast_message(&msg, true);
+            return;
+        }
+
+        let level = self.level.get();
+        let window = self.obj().clone();
+        button.set_sensitive(false);
+        glib::spawn_future_local(async move {
+            let (sender, receiver) = tokio::sync::oneshot::channel();
+            systemd::runtime().spawn(async move {
+                let results =
+                    systemd::stop_template_instances(level, &instances, StartStopMode::Fail).await;
+                let _ = sender.send(results);
+            });
+
+            let results = receiver.await;
+            button.set_sensitive(true);
+            let Ok(results) = results else {
+                warn!("Tokio channel dropped");
+                return;
+            };
+
+            let failed = results
+                .iter()
+                .filter(|(unit, result)| {
+                    result
+                        .as_ref()
+                        .inspect_err(|err| warn!("Stop instance {unit:?} error {err:?}"))
+                        .is_err()
+                })
+                .count();
+
+            let msg = if failed == 0 {
+                format2!(pgettext("template", "{} instances stopped"), results.len())
+            } else {
+                format2!(
+                    pgettext("template", "{} instances stopped, {} failed"),
+                    results.len() - failed,
+                    failed
+                )
+            };
+
+            let imp = window.imp();
+            imp.add_toast_message(&msg, false);
+            imp.refresh();
+        });
+    }
+
+    fn add_toast_message(&self, msg: &str, use_markup: bool) {
+        if let Some(app_window) = self.app_window.get() {
+            app_window.add_toast_message(msg, use_markup, None);
+        }
+    }
+
+    fn setup_factories(&self) {
+        let columns: [(&gtk::ColumnViewColumn, fn(&UnitInfo) -> String); 3] = [
+            (&self.instance_column, |unit| unit.primary()),
+            (&self.active_column, |unit| {
+                unit.active_state().as_str().to_owned()
+            }),
+            (&self.sub_column, |unit| unit.sub_state()),
+        ];
+
+        for (column, getter) in columns {
+            let factory = gtk::SignalListItemFactory::new();
+
+            factory.connect_setup(|_factory, item| {
+                let item = item.downcast_ref::<gtk::ListItem>().unwrap();
+                item.set_child(Some(&gtk::Inscription::default()));
+            });
+
+            factory.connect_bind(move |_factory, item| {
+                let item = item.downcast_ref::<gtk::ListItem>().unwrap();
+                let child = item.child().and_downcast::<gtk::Inscription>().unwrap();
+                let unit = item.item().and_downcast::<UnitInfo>().unwrap();
+                child.set_text(Some(&getter(&unit)));
+            });
+
+            column.set_factory(Some(&factory));
+        }
+    }
+}
+
+#[glib::object_subclass]
+impl ObjectSubclass for TemplateInstancesWindowImp {
+    const NAME: &'static str = "TemplateInstancesWindow";
+    type Type = TemplateInstancesWindow;
+    type ParentType = adw::Window;
+
+    fn class_init(klass: &mut Self::Class) {
+        klass.bind_template();
+        klass.bind_template_callbacks();
+    }
+
+    fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+        obj.init_template();
+    }
+}
+
+impl ObjectImpl for TemplateInstancesWindowImp {
+    fn constructed(&self) {
+        self.parent_constructed();
+        close_window_shortcut(self.obj().as_ref());
+
+        let store = gio::ListStore::new::<UnitInfo>();
+        self.single_selection.set_model(Some(&store));
+        let _ = self.store.set(store);
+
+        self.setup_factories();
+
+        let window = self.obj().downgrade();
+        self.single_selection
+            .connect_selected_item_notify(move |selection| {
+                let window = upgrade!(window);
+                let name = selection
+                    .selected_item()
+                    .and_downcast::<UnitInfo>()
+                    .map(|unit| unit.primary());
+                window.imp().update_preview(name);
+            });
+    }
+}
+
+impl WidgetImpl for TemplateInstancesWindowImp {}
+impl WindowImpl for TemplateInstancesWindowImp {}
+impl AdwWindowImpl for TemplateInstancesWindowImp {}
+
+async fn load_template_content(level: UnitDBusLevel, template: String) -> Option<String> {
+    let (sender, receiver) = tokio::sync::oneshot::channel();
+    let template2 = template.clone();
+    systemd::runtime().spawn(async move {
+        let path = systemd::fetch_template_file_path(level, &template2).await;
+        let _ = sender.send(path);
+    });
+
+    let file_path = match receiver.await {
+        Ok(Ok(Some(file_path))) => file_path,
+        Ok(Ok(None)) => {
+            warn!("No unit file for template {template:?}");
+            return None;
+        }
+        Ok(Err(err)) => {
+            warn!("Fetch template file path {template:?} error {err:?}");
+            return None;
+        }
+        Err(err) => {
+            warn!("Tokio channel dropped {err:?}");
+            return None;
+        }
+    };
+
+    gio::spawn_blocking(move || systemd::fetch_unit_file_content(Some(&file_path), &template))
+        .await
+        .ok()
+        .and_then(|result| {
+            result
+                .inspect_err(|err| warn!("Template content error {err:?}"))
+                .ok()
+        })
+}
+
+fn fill_instances(store: &gio::ListStore, level: UnitDBusLevel, template: &str) {
+    let store = store.clone();
+    let template = template.to_owned();
+    glib::spawn_future_local(async move {
+        let (sender, receiver) = tokio::sync::oneshot::channel();
+        systemd::runtime().spawn(async move {
+            let instances = systemd::list_template_instances(level, &template).await;
+            let _ = sender.send(instances);
+        });
+
+        let instances = match receiver.await {
+            Ok(Ok(instances)) => instances,
+            Ok(Err(err)) => {
+                warn!("List template instances error {err:?}");
+                return;
+            }
+            Err(err) => {
+                warn!("Tokio channel dropped {err:?}");
+                return;
+            }
+        };
+
+        store.remove_all();
+        for listed_unit in instances {
+            let unit = UnitInfo::from_listed_unit(listed_unit, level);
+            store.append(&unit);
+        }
+    });
+}
@@ -0,0 +1,38 @@
+mod imp;
+
+use crate::{
+    systemd::{data::UnitInfo, errors::SystemdErrors, template_unit},
+    widget::app_window::AppWindow,
+};
+use gtk::{
+    glib::{self},
+    prelude::*,
+    subclass::prelude::ObjectSubclassIsExt,
+};
+
+/// Window to manage the instances of a template unit, e.g. `getty@.service`
+glib::wrapper! {
+    pub struct TemplateInstancesWindow(ObjectSubclass<imp::TemplateInstancesWindowImp>)
+    @extends adw::Window, gtk::Window, gtk::Widget,
+    @implements gtk::Accessible,  gtk::Buildable,  gtk::ConstraintTarget,
+    gtk::Native, gtk::Root, gtk::ShortcutManager;
+}
+
+impl TemplateInstancesWindow {
+    pub fn new(app_window: &AppWindow, unit: &UnitInfo) -> Result<Self, SystemdErrors> {
+        let primary = unit.primary();
+        let Some(template) = template_unit::template_name(&primary) else {
+            return Err(SystemdErrors::Custom(format!(
+                "{primary:?} is neither a template nor an instance unit"
+            )));
+        };
+
+        let obj: TemplateInstancesWindow = glib::Object::new();
+        obj.set_transient_for(Some(app_window));
+        let imp = obj.imp();
+        imp.set_template(app_window, unit.dbus_level(), &template);
+        imp.load();
+
+        Ok(obj)
+    }
+}
@@ -0,0 +1,295 @@
+use super::{Getter, TimerHistoryWindow, set_column_factory};
+use crate::{
+    systemd::{
+        invocation::{RunResult, UnitRun},
+        time_handling::{USEC_PER_MSEC, format_timespan},
+        timer_history::{self, MissedRun, TimerHistory},
+        timer_schedule,
+    },
+    widget::{close_window_shortcut, preferences::data::PREFERENCES},
+};
+use adw::subclass::window::AdwWindowImpl;
+use base::enums::UnitDBusLevel;
+use gettextrs::pgettext;
+use gtk::{
+    gio,
+    glib::{self, BoxedAnyObject},
+    prelude::*,
+    subclass::{
+        prelude::*,
+        widget::{
+            CompositeTemplateCallbacksClass, CompositeTemplateClass,
+            CompositeTemplateInitializingExt, WidgetImpl,
+        },
+    },
+};
+use std::cell::{Cell, OnceCell};
+use tracing::warn;
+
+/// How far back in the journal the history is rebuilt
+const MAX_JOURNAL_RECORDS: usize = 50_000;
+
+#[derive(Default, gtk::CompositeTemplate)]
+#[template(resource = "/io/github/plrigaux/sysd-manager/timer_history.ui")]
+pub struct TimerHistoryWindowImp {
+    #[template_child]
+    summary_label: TemplateChild<gtk::Label>,
+
+    #[template_child]
+    spinner: TemplateChild<adw::Spinner>,
+
+    #[template_child]
+    runs_selection: TemplateChild<gtk::NoSelection>,
+
+    #[template_child]
+    missed_selection: TemplateChild<gtk::NoSelection>,
+
+    #[template_child]
+    start_column: TemplateChild<gtk::ColumnViewColumn>,
+
+    #[template_child]
+    duration_column: TemplateChild<gtk::ColumnViewColumn>,
+
+    #[template_child]
+    result_column: TemplateChild<gtk::ColumnViewColumn>,
+
+    #[template_child]
+    catch_up_column: TemplateChild<gtk::ColumnViewColumn>,
+
+    #[template_child]
+    boot_column: TemplateChild<gtk::ColumnViewColumn>,
+
+    #[template_child]
+    entries_column: TemplateChild<gtk::ColumnViewColumn>,
+
+    #[template_child]
+    invocation_column: TemplateChild<gtk::ColumnViewColumn>,
+
+    #[template_child]
+    scheduled_column: TemplateChild<gtk::ColumnViewColumn>,
+
+    #[template_child]
+    caught_up_column: TemplateChild<gtk::ColumnViewColumn>,
+
+    #[template_child]
+    next_boot_column: TemplateChild<gtk::ColumnViewColumn>,
+
+    level: Cell<UnitDBusLevel>,
+    timer: OnceCell<String>,
+    runs_store: OnceCell<gio::ListStore>,
+    missed_store: OnceCell<gio::ListStore>,
+}
+
+#[gtk::template_callbacks]
+impl TimerHistoryWindowImp {
+    pub(super) fn set_timer(&self, level: UnitDBusLevel, timer: &str) {
+        self.level.set(level);
+        self.timer.set(timer.to_owned()).expect("timer set once");
+
+        self.obj().set_title(Some(&format!(
+            "{} - {timer}",
+            pgettext("timer history", "Timer History")
+        )));
+    }
+
+    #[template_callback]
+    fn refresh_button_clicked(&self, _button: gtk::Button) {
+        self.refresh();
+    }
+
+    pub(super) fn refresh(&self) {
+        let level = self.level.get();
+        let timer = self.timer.get().expect("timer set").clone();
+        let window = self.obj().clone();
+
+        self.spinner.set_visible(true);
+        glib::spawn_future_local(async move {
+            let result = gio::spawn_blocking(move || {
+                let schedule = timer_schedule::fetch_timer_schedule(level, &timer)?;
+                timer_history::fetch_timer_history(&schedule, MAX_JOURNAL_RECORDS)
+            })
+            .await;
+
+            let imp = window.imp();
+            imp.spinner.set_visible(false);
+
+            let history = match result {
+                Ok(Ok(history)) => history,
+                Ok(Err(err)) => {
+                    warn!("Timer history {err:?}");
+                    imp.summary_label.set_text(&err.human_error_type());
+                    return;
+                }
+                Err(err) => {
+                    warn!("Timer history join {err:?}");
+                    return;
+                }
+            };
+
+            imp.summary_label.set_markup(&summary_markup(&history));
+
+            let runs_store = imp.runs_store.get().expect("store set");
+            runs_store.remove_all();
+            for run in history.runs {
+                runs_store.append(&BoxedAnyObject::new(run));
+            }
+
+            let missed_store = imp.missed_store.get().expect("store set");
+            missed_store.remove_all();
+            for missed in history.missed {
+                missed_store.append(&BoxedAnyObject::new(missed));
+            }
+        });
+    }
+
+    fn setup_factories(&self) {
+        let timestamp_style = PREFERENCES.timestamp_style();
+
+        let run_columns: [(&gtk::ColumnViewColumn, Getter<UnitRun>); 7] = [
+            (
+                &self.start_column,
+                Box::new(move |run: &UnitRun| timestamp_style.usec_formated(run.start)),
+            ),
+            (
+                &self.duration_column,
+                Box::new(|run: &UnitRun| {
+                    run.duration()
+                        .map(|duration| format_timespan(duration, USEC_PER_MSEC))
+                        .unwrap_or_default()
+                }),
+            ),
+            (
+                &self.result_column,
+                Box::new(|run: &UnitRun| match &run.result {
+                    RunResult::Success => pgettext("timer history", "success"),
+                    RunResult::Failed(result) => result.clone(),
+                    RunResult::Running => pgettext("timer history", "running"),
+                    RunResult::Unknown => pgettext("timer history", "unknown"),
+                }),
+            ),
+            (
+                &self.catch_up_column,
+                Box::new(|run: &UnitRun| {
+                    if run.catch_up {
+                        pgettext("timer history", "yes")
+                    } else {
+                        String::new()
+                    }
+                }),
+            ),
+            (
+                &self.boot_column,
+                Box::new(|run: &UnitRun| short_boot_id(&run.boot_id)),
+            ),
+            (
+                &self.entries_column,
+                Box::new(|run: &UnitRun| run.entries.to_string()),
+            ),
+            (
+                &self.invocation_column,
+                Box::new(|run: &UnitRun| run.invocation_id.clone()),
+            ),
+        ];
+
+        for (column, getter) in run_columns {
+            set_column_factory(column, getter, |run: &UnitRun| run.result.is_failed());
+        }
+
+        let missed_columns: [(&gtk::ColumnViewColumn, Getter<MissedRun>); 3] = [
+            (
+                &self.scheduled_column,
+                Box::new(move |missed: &MissedRun| timestamp_style.usec_formated(missed.scheduled)),
+            ),
+            (
+                &self.caught_up_column,
+                Box::new(|missed: &MissedRun| {
+                    if missed.caught_up {
+                        pgettext("timer history", "yes")
+                    } else {
+                        pgettext("timer history", "no")
+                    }
+                }),
+            ),
+            (
+                &self.next_boot_column,
+                Box::new(|missed: &MissedRun| short_boot_id(&missed.boot_id)),
+            ),
+        ];
+
+        for (column, getter) in missed_columns {
+            set_column_factory(column, getter, |missed: &MissedRun| !missed.caught_up);
+        }
+    }
+}
+
+#[glib::object_subclass]
+impl ObjectSubclass for TimerHistoryWindowImp {
+    const NAME: &'static str = "TimerHistoryWindow";
+    type Type = TimerHistoryWindow;
+    type ParentType = adw::Window;
+
+    fn class_init(klass: &mut Self::Class) {
+        klass.bind_template();
+        klass.bind_template_callbacks();
+    }
+
+    fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+        obj.init_template();
+    }
+}
+
+impl ObjectImpl for TimerHistoryWindowImp {
+    fn constructed(&self) {
+        self.parent_constructed();
+        close_window_shortcut(self.obj().as_ref());
+
+        let runs_store = gio::ListStore::new::<BoxedAnyObject>();
+        self.runs_selection.set_model(Some(&runs_store));
+        let _ = self.runs_store.set(runs_store);
+
+        let missed_store = gio::ListStore::new::<BoxedAnyObject>();
+        self.missed_selection.set_model(Some(&missed_store));
+        let _ = self.missed_store.set(missed_store);
+
+        self.setup_factories();
+    }
+}
+
+impl WidgetImpl for TimerHistoryWindowImp {}
+impl WindowImpl for TimerHistoryWindowImp {}
+impl AdwWindowImpl for TimerHistoryWindowImp {}
+
+fn summary_markup(history: &TimerHistory) -> String {
+    let mut summary = format!(
+        "{} <b>{}</b>    {} <b>{}</b>    {} <b>{}</b>    {} <b>{}</b>",
+        pgettext("timer history", "Activates:"),
+        glib::markup_escape_text(&history.unit),
+        pgettext("timer history", "Runs:"),
+        history.runs.len(),
+        pgettext("timer history", "Failures:"),
+        history.failures,
+        pgettext("timer history", "Missed:"),
+        history.missed.len(),
+    );
+
+    if let Some(stats) = history.stats {
+        summary.push_str(&format!(
+            "\n{} {} <b>{}</b>    {} <b>{}</b>    {} <b>{}</b>    {} <b>{}</b>",
+            pgettext("timer history", "Duration"),
+            pgettext("timer history", "min:"),
+            format_timespan(stats.min, USEC_PER_MSEC),
+            pgettext("timer history", "median:"),
+            format_timespan(stats.median, USEC_PER_MSEC),
+            pgettext("timer history", "mean:"),
+            format_timespan(stats.mean, USEC_PER_MSEC),
+            pgettext("timer history", "max:"),
+            format_timespan(stats.max, USEC_PER_MSEC),
+        ));
+    }
+
+    summary
+}
+
+fn short_boot_id(boot_id: &str) -> String {
+    boot_id.chars().take(8).collect()
+}
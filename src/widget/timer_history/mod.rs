@@ -0,0 +1,69 @@
+mod imp;
+
+use crate::{
+    systemd::{data::UnitInfo, enums::UnitType, errors::SystemdErrors},
+    widget::app_window::AppWindow,
+};
+use gtk::{
+    glib::{self, BoxedAnyObject},
+    prelude::*,
+    subclass::prelude::ObjectSubclassIsExt,
+};
+use std::cell::Ref;
+
+pub(crate) type Getter<T> = Box<dyn Fn(&T) -> String>;
+
+/// Window reporting the runs of a timer and the missed ones
+glib::wrapper! {
+    pub struct TimerHistoryWindow(ObjectSubclass<imp::TimerHistoryWindowImp>)
+    @extends adw::Window, gtk::Window, gtk::Widget,
+    @implements gtk::Accessible,  gtk::Buildable,  gtk::ConstraintTarget,
+    gtk::Native, gtk::Root, gtk::ShortcutManager;
+}
+
+impl TimerHistoryWindow {
+    pub fn new(app_window: &AppWindow, unit: &UnitInfo) -> Result<Self, SystemdErrors> {
+        let timer = unit.primary();
+        if unit.unit_type() != UnitType::Timer {
+            return Err(SystemdErrors::Custom(format!("{timer:?} is not a timer")));
+        }
+
+        let obj: TimerHistoryWindow = glib::Object::new();
+        obj.set_transient_for(Some(app_window));
+        let imp = obj.imp();
+        imp.set_timer(unit.dbus_level(), &timer);
+        imp.refresh();
+
+        Ok(obj)
+    }
+}
+
+/// Read only text cells, the rows matching `is_error` in red
+pub(crate) fn set_column_factory<T: 'static>(
+    column: &gtk::ColumnViewColumn,
+    getter: Getter<T>,
+    is_error: fn(&T) -> bool,
+) {
+    let factory = gtk::SignalListItemFactory::new();
+
+    factory.connect_setup(|_factory, item| {
+        let item = item.downcast_ref::<gtk::ListItem>().unwrap();
+        item.set_child(Some(&gtk::Inscription::default()));
+    });
+
+    factory.connect_bind(move |_factory, item| {
+        let item = item.downcast_ref::<gtk::ListItem>().unwrap();
+        let child = item.child().and_downcast::<gtk::Inscription>().unwrap();
+        let entry = item.item().and_downcast::<BoxedAnyObject>().unwrap();
+        let value: Ref<T> = entry.borrow();
+        child.set_text(Some(&getter(&value)));
+
+        if is_error(&value) {
+            child.add_css_class("error");
+        } else {
+            child.remove_css_class("error");
+        }
+    });
+
+    column.set_factory(Some(&factory));
+}
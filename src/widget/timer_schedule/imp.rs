@@ -0,0 +1,418 @@
+use super::TimerScheduleWindow;
+use crate::{
+    systemd::{
+        time_handling::{self, USEC_PER_SEC, format_timespan},
+        timer_schedule::{self, TimerCollision, TimerSchedule},
+    },
+    upgrade,
+    widget::{close_window_shortcut, preferences::data::PREFERENCES},
+};
+use adw::subclass::window::AdwWindowImpl;
+use gtk::{
+    cairo, gdk,
+    gio::{self},
+    glib::{self, BoxedAnyObject},
+    prelude::*,
+    subclass::{
+        prelude::*,
+        widget::{
+            CompositeTemplateCallbacksClass, CompositeTemplateClass,
+            CompositeTemplateInitializingExt, WidgetImpl,
+        },
+    },
+};
+use std::cell::{Cell, OnceCell, Ref, RefCell};
+use tracing::warn;
+
+const ROW_HEIGHT: f64 = 22.0;
+const LABEL_WIDTH: f64 = 220.0;
+const AXIS_HEIGHT: f64 = 24.0;
+const RIGHT_MARGIN: f64 = 10.0;
+
+const USEC_PER_HOUR: u64 = 3600 * USEC_PER_SEC;
+const USEC_PER_DAY: u64 = 24 * USEC_PER_HOUR;
+const USEC_PER_WEEK: u64 = 7 * USEC_PER_DAY;
+
+/// Occurrences closer than this window are considered firing at the same time
+const COLLISION_WINDOW: u64 = 60 * USEC_PER_SEC;
+/// Minimum number of timers firing in the same window to be flagged as a collision
+const COLLISION_THRESHOLD: usize = 3;
+const MAX_OCCURRENCES_PER_TIMER: usize = 2000;
+
+struct Occurrence {
+    time: u64,
+    timer: String,
+    unit: String,
+    randomized_delay: u64,
+    collision: usize,
+}
+
+#[derive(Default)]
+struct Schedule {
+    timers: Vec<TimerSchedule>,
+    occurrences: Vec<Vec<u64>>,
+    collisions: Vec<TimerCollision>,
+    from: u64,
+    until: u64,
+}
+
+#[derive(Default, gtk::CompositeTemplate)]
+#[template(resource = "/io/github/plrigaux/sysd-manager/timer_schedule.ui")]
+pub struct TimerScheduleWindowImp {
+    #[template_child]
+    timeline_area: TemplateChild<gtk::DrawingArea>,
+
+    #[template_child]
+    no_selection: TemplateChild<gtk::NoSelection>,
+
+    #[template_child]
+    time_column: TemplateChild<gtk::ColumnViewColumn>,
+
+    #[template_child]
+    timer_column: TemplateChild<gtk::ColumnViewColumn>,
+
+    #[template_child]
+    activates_column: TemplateChild<gtk::ColumnViewColumn>,
+
+    #[template_child]
+    delay_column: TemplateChild<gtk::ColumnViewColumn>,
+
+    #[template_child]
+    simultaneous_column: TemplateChild<gtk::ColumnViewColumn>,
+
+    schedule: RefCell<Schedule>,
+    span: Cell<u64>,
+    store: OnceCell<gio::ListStore>,
+}
+
+#[gtk::template_callbacks]
+impl TimerScheduleWindowImp {
+    #[template_callback]
+    fn refresh_button_clicked(&self, _button: gtk::Button) {
+        self.fill_schedule();
+    }
+
+    #[template_callback]
+    fn day_button_toggled(&self, button: gtk::ToggleButton) {
+        if button.is_active() {
+            self.span.set(USEC_PER_DAY);
+            self.fill_schedule();
+        }
+    }
+
+    #[template_callback]
+    fn week_button_toggled(&self, button: gtk::ToggleButton) {
+        if button.is_active() {
+            self.span.set(USEC_PER_WEEK);
+            self.fill_schedule();
+        }
+    }
+
+    pub(super) fn fill_schedule(&self) {
+        let window = self.obj().clone();
+        let span = self.span.get();
+        let level = PREFERENCES.dbus_level().unit_dbus_level();
+
+        glib::spawn_future_local(async move {
+            let from = time_handling::now_realtime();
+            let until = from + span;
+
+            let result = gio::spawn_blocking(move || {
+                timer_schedule::fetch_timer_schedules(level).map(|mut timers| {
+                    timers.sort_by_key(|timer| timer.next_elapse());
+                    let occurrences: Vec<Vec<u64>> = timers
+                        .iter()
+                        .map(|timer| timer.occurrences(from, until, MAX_OCCURRENCES_PER_TIMER))
+                        .collect();
+                    (timers, occurrences)
+                })
+            })
+            .await;
+
+            let (timers, occurrences) = match result {
+                Ok(Ok(data)) => data,
+                Ok(Err(err)) => {
+                    warn!("Fetch timer schedules {err:?}");
+                    return;
+                }
+                Err(err) => {
+                    warn!("Fetch timer schedules join {err:?}");
+                    return;
+                }
+            };
+
+            let flat: Vec<(String, u64)> = timers
+                .iter()
+                .zip(occurrences.iter())
+                .flat_map(|(timer, times)| times.iter().map(|t| (timer.timer.clone(), *t)))
+                .collect();
+
+            let collisions =
+                timer_schedule::find_collisions(&flat, COLLISION_WINDOW, COLLISION_THRESHOLD);
+
+            let mut rows: Vec<Occurrence> = timers
+                .iter()
+                .zip(occurrences.iter())
+                .flat_map(|(timer, times)| {
+                    times.iter().map(|time| Occurrence {
+                        time: *time,
+                        timer: timer.timer.clone(),
+                        unit: timer.unit.clone(),
+                        randomized_delay: timer.randomized_delay,
+                        collision: collisions
+                            .iter()
+                            .find(|c| {
+                                c.window_start <= *time && *time < c.window_start + COLLISION_WINDOW
+                            })
+                            .map(|c| c.timers.len())
+                            .unwrap_or_default(),
+                    })
+                })
+                .collect();
+            rows.sort_by_key(|o| o.time);
+
+            let imp = window.imp();
+            let store = imp.store.get().expect("store set");
+            store.remove_all();
+            for row in rows {
+                store.append(&BoxedAnyObject::new(row));
+            }
+
+            imp.timeline_area
+                .set_content_height((AXIS_HEIGHT + ROW_HEIGHT * timers.len() as f64) as i32);
+
+            imp.schedule.replace(Schedule {
+                timers,
+                occurrences,
+                collisions,
+                from,
+                until,
+            });
+
+            imp.timeline_area.queue_draw();
+        });
+    }
+
+    fn setup_factories(&self) {
+        let timestamp_style = PREFERENCES.timestamp_style();
+
+        let columns: [(&gtk::ColumnViewColumn, Box<dyn Fn(&Occurrence) -> String>); 5] = [
+            (
+                &self.time_column,
+                Box::new(move |o: &Occurrence| timestamp_style.usec_formated(o.time)),
+            ),
+            (
+                &self.timer_column,
+                Box::new(|o: &Occurrence| o.timer.clone()),
+            ),
+            (
+                &self.activates_column,
+                Box::new(|o: &Occurrence| o.unit.clone()),
+            ),
+            (
+                &self.delay_column,
+                Box::new(|o: &Occurrence| {
+                    if o.randomized_delay == 0 {
+                        String::new()
+                    } else {
+                        format_timespan(o.randomized_delay, USEC_PER_SEC)
+                    }
+                }),
+            ),
+            (
+                &self.simultaneous_column,
+                Box::new(|o: &Occurrence| {
+                    if o.collision == 0 {
+                        String::new()
+                    } else {
+                        o.collision.to_string()
+                    }
+                }),
+            ),
+        ];
+
+        for (column, getter) in columns {
+            let factory = gtk::SignalListItemFactory::new();
+
+            factory.connect_setup(|_factory, item| {
+                let item = item.downcast_ref::<gtk::ListItem>().unwrap();
+                item.set_child(Some(&gtk::Inscription::default()));
+            });
+
+            factory.connect_bind(move |_factory, item| {
+                let item = item.downcast_ref::<gtk::ListItem>().unwrap();
+                let child = item.child().and_downcast::<gtk::Inscription>().unwrap();
+                let entry = item.item().and_downcast::<BoxedAnyObject>().unwrap();
+                let occurrence: Ref<Occurrence> = entry.borrow();
+                child.set_text(Some(&getter(&occurrence)));
+
+                if occurrence.collision > 0 {
+                    child.add_css_class("error");
+                } else {
+                    child.remove_css_class("error");
+                }
+            });
+
+            column.set_factory(Some(&factory));
+        }
+    }
+}
+
+#[glib::object_subclass]
+impl ObjectSubclass for TimerScheduleWindowImp {
+    const NAME: &'static str = "TimerScheduleWindow";
+    type Type = TimerScheduleWindow;
+    type ParentType = adw::Window;
+
+    fn class_init(klass: &mut Self::Class) {
+        klass.bind_template();
+        klass.bind_template_callbacks();
+    }
+
+    fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+        obj.init_template();
+    }
+}
+
+impl ObjectImpl for TimerScheduleWindowImp {
+    fn constructed(&self) {
+        self.parent_constructed();
+        close_window_shortcut(self.obj().as_ref());
+
+        self.span.set(USEC_PER_DAY);
+
+        let store = gio::ListStore::new::<BoxedAnyObject>();
+        self.no_selection.set_model(Some(&store));
+        let _ = self.store.set(store);
+
+        self.setup_factories();
+
+        let window = self.obj().downgrade();
+        self.timeline_area
+            .set_draw_func(move |area, cr, width, height| {
+                let window = upgrade!(window);
+                draw_timeline(
+                    area,
+                    cr,
+                    width as f64,
+                    height as f64,
+                    &window.imp().schedule.borrow(),
+                );
+            });
+    }
+}
+
+impl WidgetImpl for TimerScheduleWindowImp {}
+impl WindowImpl for TimerScheduleWindowImp {}
+impl AdwWindowImpl for TimerScheduleWindowImp {}
+
+fn draw_timeline(
+    area: &gtk::DrawingArea,
+    cr: &cairo::Context,
+    width: f64,
+    height: f64,
+    schedule: &Schedule,
+) {
+    if schedule.until <= schedule.from {
+        return;
+    }
+
+    let fg = area.color();
+    let span = (schedule.until - schedule.from) as f64;
+    let plot_width = (width - LABEL_WIDTH - RIGHT_MARGIN).max(1.0);
+    let x_of =
+        |time: u64| LABEL_WIDTH + (time.saturating_sub(schedule.from) as f64 / span) * plot_width;
+
+    // Collisions bands
+    cr.set_source_rgba(0.9, 0.1, 0.1, 0.25);
+    for collision in &schedule.collisions {
+        let x = x_of(collision.window_start);
+        let w = (COLLISION_WINDOW as f64 / span * plot_width).max(3.0);
+        cr.rectangle(x, 0.0, w, height);
+    }
+    let _ = cr.fill();
+
+    // Axis
+    let (tick, long_span) = if schedule.until - schedule.from > USEC_PER_DAY {
+        (USEC_PER_DAY, true)
+    } else {
+        (USEC_PER_HOUR, false)
+    };
+
+    cr.set_font_size(11.0);
+    let first_tick = schedule.from - schedule.from % tick + tick;
+    let mut t = first_tick;
+    while t < schedule.until {
+        let x = x_of(t);
+        set_source_color(cr, &fg, 0.15);
+        cr.move_to(x, AXIS_HEIGHT);
+        cr.line_to(x, height);
+        let _ = cr.stroke();
+
+        let label = format_tick(t, if long_span { "%a %d %b" } else { "%H:%M" });
+
+        set_source_color(cr, &fg, 0.8);
+        cr.move_to(x + 2.0, AXIS_HEIGHT - 8.0);
+        let _ = cr.show_text(&label);
+
+        t += if long_span || plot_width / (span / tick as f64) > 40.0 {
+            tick
+        } else {
+            tick * 3
+        };
+    }
+
+    for (idx, (timer, times)) in schedule
+        .timers
+        .iter()
+        .zip(schedule.occurrences.iter())
+        .enumerate()
+    {
+        let y = AXIS_HEIGHT + idx as f64 * ROW_HEIGHT;
+
+        if idx % 2 == 0 {
+            set_source_color(cr, &fg, 0.05);
+            cr.rectangle(0.0, y, width, ROW_HEIGHT);
+            let _ = cr.fill();
+        }
+
+        set_source_color(cr, &fg, 0.9);
+        cr.move_to(4.0, y + ROW_HEIGHT - 7.0);
+        let _ = cr.show_text(&timer.timer);
+
+        for time in times {
+            let x = x_of(*time);
+
+            if timer.randomized_delay > 0 {
+                cr.set_source_rgba(0.2, 0.5, 0.9, 0.25);
+                let w = (timer.randomized_delay as f64 / span * plot_width).max(1.0);
+                cr.rectangle(x, y + 3.0, w, ROW_HEIGHT - 6.0);
+                let _ = cr.fill();
+            }
+
+            cr.set_source_rgba(0.2, 0.5, 0.9, 1.0);
+            cr.rectangle(x - 1.0, y + 3.0, 2.0, ROW_HEIGHT - 6.0);
+            let _ = cr.fill();
+        }
+    }
+}
+
+fn set_source_color(cr: &cairo::Context, color: &gdk::RGBA, alpha: f32) {
+    cr.set_source_rgba(
+        color.red() as f64,
+        color.green() as f64,
+        color.blue() as f64,
+        (color.alpha() * alpha) as f64,
+    );
+}
+
+fn format_tick(time: u64, format: &str) -> String {
+    let Ok(date_time) = glib::DateTime::from_unix_local((time / USEC_PER_SEC) as i64) else {
+        return String::new();
+    };
+
+    date_time
+        .format(format)
+        .map(|s| s.to_string())
+        .unwrap_or_default()
+}
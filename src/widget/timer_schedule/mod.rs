@@ -0,0 +1,24 @@
+mod imp;
+
+use crate::widget::app_window::AppWindow;
+use gtk::{
+    glib::{self},
+    prelude::*,
+    subclass::prelude::ObjectSubclassIsExt,
+};
+
+glib::wrapper! {
+    pub struct TimerScheduleWindow(ObjectSubclass<imp::TimerScheduleWindowImp>)
+    @extends adw::Window, gtk::Window, gtk::Widget,
+    @implements gtk::Accessible,  gtk::Buildable,  gtk::ConstraintTarget,
+    gtk::Native, gtk::Root, gtk::ShortcutManager;
+}
+
+impl TimerScheduleWindow {
+    pub fn new(app_window: &AppWindow) -> Self {
+        let obj: TimerScheduleWindow = glib::Object::new();
+        obj.set_transient_for(Some(app_window));
+        obj.imp().fill_schedule();
+        obj
+    }
+}
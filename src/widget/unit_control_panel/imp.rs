@@ -671,7 +671,7 @@ impl UnitControlPanelImpl {
         let mut ck_group: Option<gtk::CheckButton> = None;
 
         for mode in StartStopMode::iter() {
-            if control_type == UnitContolType::Stop && mode == StartStopMode::Isolate {
+            if control_type == UnitContolType::Stop && !mode.allowed_to_stop() {
                 continue;
             }
 
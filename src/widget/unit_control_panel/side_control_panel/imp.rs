@@ -390,10 +390,15 @@ impl SideControlPanelImpl {
     pub fn more_action_popover_shown(&self) {
         let unit_option = self.current_unit();
 
+        // The proxy only starts, stops, restarts, enables and disables the units of another user
+        let other_user = unit_option
+            .as_ref()
+            .is_some_and(|unit| unit.dbus_level().is_other_user());
+
         let sensitive = if let Some(unit) = unit_option {
             self.clean_button
-                .set_sensitive(unit.active_state().is_inactive());
-            true
+                .set_sensitive(!other_user && unit.active_state().is_inactive());
+            !other_user
         } else {
             self.clean_button.set_sensitive(false);
             false
@@ -408,6 +413,7 @@ impl SideControlPanelImpl {
         self.reload_unit_button.set_sensitive(sensitive);
         self.preset_button.set_sensitive(sensitive);
         self.reenable_button.set_sensitive(sensitive);
+        self.link_button.set_sensitive(!other_user);
     }
 
     fn app_window(&self) -> Option<AppWindow> {
@@ -142,6 +142,43 @@ impl UnitFilePanelImp {
         // text_search_entry.set_text_view(self.unit_status_textview.as_ref());
     }
 
+    fn is_custom_bus_unit(&self) -> bool {
+        self.unit
+            .borrow()
+            .as_ref()
+            .is_some_and(|unit| unit.dbus_level().is_custom())
+    }
+
+    /// The files of the units of a custom bus or of another user can't be changed from here
+    fn is_unit_file_editable(&self) -> bool {
+        self.unit.borrow().as_ref().is_some_and(|unit| {
+            let level = unit.dbus_level();
+            !level.is_custom() && !level.is_other_user()
+        })
+    }
+
+    fn set_unit_file_actions_enable(&self, enable: bool) {
+        if let Some(view) = self.unit_file_text.get() {
+            view.set_editable(enable);
+        }
+
+        let Some(app_window) = self.app_window.get() else {
+            return;
+        };
+
+        for action_name in [
+            "create_drop_in_file_runtime",
+            "create_drop_in_file_permanent",
+            "revert_unit_file_full",
+        ] {
+            if let Some(action) = app_window.lookup_action(action_name)
+                && let Some(simple_action) = action.downcast_ref::<gio::SimpleAction>()
+            {
+                simple_action.set_enabled(enable);
+            }
+        }
+    }
+
     fn save_file(&self) {
         let binding = self.unit.borrow();
         let Some(unit) = binding.as_ref() else {
@@ -149,6 +186,16 @@ impl UnitFilePanelImp {
             return;
         };
 
+        if unit.dbus_level().is_custom() {
+            warn!("Unit files of a custom bus are not editable");
+            return;
+        }
+
+        if unit.dbus_level().is_other_user() {
+            warn!("Unit files of another user are not editable");
+            return;
+        }
+
         let buffer = self
             .unit_file_text
             .get()
@@ -328,11 +375,16 @@ impl UnitFilePanelImp {
                         )
                     }
 
-                    _ => (
-                        pgettext("file", "Not able to save file, an error happened!"),
-                        false,
-                        None,
-                    ),
+                    SystemdErrors::ZWritePathNotAllowed(_)
+                    | SystemdErrors::ZWriteSymlinkRejected(_)
+                    | SystemdErrors::ZWriteExtensionRejected(_) => {
+                        (error.human_error_type(), false, None)
+                    }
+
+                    _ => {
+                        let msg = pgettext("file", "Not able to save file: {}");
+                        (format2!(msg, error.human_error_type()), false, None)
+                    }
                 }
             }
         };
@@ -430,6 +482,7 @@ impl UnitFilePanelImp {
         }
 
         self.file_content_selected_index.set(0);
+        self.set_unit_file_actions_enable(self.is_unit_file_editable());
         self.set_file_content_init()
     }
 
@@ -493,6 +546,15 @@ impl UnitFilePanelImp {
     }
 
     fn display_unit_file_content2(&self, unit_name: &str, file_nav: &FileNav) {
+        if self.is_custom_bus_unit() {
+            let message = pgettext(
+                "file",
+                "The files of this unit are on the machine of its bus connection, they can't be displayed or edited here",
+            );
+            self.fill_gui_content(message, true, &file_nav.file_path);
+            return;
+        }
+
         let (file_content, is_error_msg) =
             systemd::fetch_unit_file_content(Some(&file_nav.file_path), unit_name)
                 .map(|content| (content, false))
@@ -1077,11 +1139,10 @@ impl UnitFilePanelImp {
                             )
                         }
 
-                        _ => (
-                            pgettext("file", "Not able to revert unit, an error happened!"),
-                            false,
-                            None,
-                        ),
+                        _ => {
+                            let msg = pgettext("file", "Not able to revert unit: {}");
+                            (format2!(msg, error.human_error_type()), false, None)
+                        }
                     }
                 }
             };
@@ -1154,7 +1215,8 @@ impl ObjectImpl for UnitFilePanelImp {
                 let end = buf.end_iter();
                 let current_text = buf.text(&start, &end, true);
 
-                let allow_save_condition = !imp.all_unit_files.borrow().is_empty()
+                let allow_save_condition = imp.is_unit_file_editable()
+                    && !imp.all_unit_files.borrow().is_empty()
                     && current_text.as_str() != imp.original_file_content.borrow().as_str();
 
                 imp.set_save_file_enable(allow_save_condition);
@@ -1047,7 +1047,14 @@ fn build_preset_filter(
 fn build_bus_level_filter(
     filter_container: &Rc<RefCell<Box<dyn UnitPropertyFilter>>>,
 ) -> (gtk::Box, Vec<FilterWidget>) {
-    build_elem_filter!(filter_container, UnitDBusLevel::iter(), UnitDBusLevel)
+    let levels = UnitDBusLevel::iter()
+        .filter(|level| !level.is_other_user() || systemd::other_user::attached_user().is_some())
+        .chain(
+            systemd::custom_bus::custom_buses()
+                .into_iter()
+                .map(|bus| bus.level),
+        );
+    build_elem_filter!(filter_container, levels, UnitDBusLevel)
 }
 
 pub(crate) fn create_content_box() -> gtk::Box {
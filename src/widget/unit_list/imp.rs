@@ -1,7 +1,7 @@
 mod column_factories;
 #[macro_use]
 mod construct;
-mod favorites;
+pub(crate) mod favorites;
 pub mod pop_menu;
 
 use crate::{
@@ -22,6 +22,7 @@ use crate::{
     widget::{
         InterPanelMessage,
         app_window::AppWindow,
+        coredumps,
         preferences::data::{
             DbusLevel, KEY_PREF_CASE_INSENSITIVE_DEFAULT, KEY_PREF_UNIT_LIST_DISPLAY_COLORS,
             PREFERENCES,
@@ -74,7 +75,9 @@ use std::{
     sync::OnceLock,
     time::Duration,
 };
-use systemd::{SystemdSignal, errors::SystemdErrors, init_signal_watcher, runtime};
+use systemd::{
+    SystemdSignal, custom_bus::CustomBus, errors::SystemdErrors, init_signal_watcher, runtime,
+};
 use tokio::{
     sync::{broadcast::Receiver, mpsc},
     task::AbortHandle,
@@ -757,6 +760,45 @@ impl UnitListPanelImp {
             panel_stack.set_visible_child_name(UNIT_LIST_VIEW_PAGE);
 
             unit_list.imp().fetch_custom_unit_properties();
+            unit_list.imp().fetch_recent_crashes();
+        });
+    }
+
+    /// Count the recent crashes of the listed units, shown as a badge beside their name
+    fn fetch_recent_crashes(&self) {
+        // the crashes are read from the host journal
+        if !systemd::backend::backend().is_live() {
+            return;
+        }
+
+        let units_map = self.units_map.clone();
+
+        glib::spawn_future_local(async move {
+            let since = coredumps::recent_crashes_since();
+            let recent = match gio::spawn_blocking(move || systemd::list_coredumps(since, 0)).await
+            {
+                Ok(Ok(recent)) => recent,
+                Ok(Err(err)) => {
+                    warn!("Recent crashes {err:?}");
+                    return;
+                }
+                Err(err) => {
+                    warn!("Recent crashes join {err:?}");
+                    return;
+                }
+            };
+
+            let crashes = coredumps::count_crashes(&recent, since);
+            for (key, unit) in units_map.borrow().iter() {
+                let count = crashes
+                    .get(&(key.level, key.primary.clone()))
+                    .copied()
+                    .unwrap_or_default();
+
+                if unit.crashes() != count {
+                    unit.set_crashes(count);
+                }
+            }
         });
     }
 
@@ -1842,7 +1884,10 @@ impl UnitListPanelImp {
                     favorites
                         .favorites
                         .into_iter()
-                        .map(|favorite| UnitKey::new_string(favorite.bus.into(), favorite.unit))
+                        .filter_map(|favorite| {
+                            let level = favorite.level()?;
+                            Some(UnitKey::new_string(level, favorite.unit))
+                        })
                         .collect::<Vec<_>>()
                 });
 
@@ -1878,6 +1923,12 @@ impl UnitListPanelImp {
     }
 
     fn process_signals(&self) {
+        let backend = systemd::backend::backend();
+        if !backend.is_live() {
+            info!("No signal processing with the {} backend", backend.name());
+            return;
+        }
+
         let list_panel = self.obj().clone();
         glib::spawn_future_local(async move {
             info!("Start processing signals");
@@ -1955,9 +2006,38 @@ macro_rules! dbus_call {
         ) {
             $handles.push(tokio::spawn($module::$f(UnitDBusLevel::UserSession)));
         }
+
+        for bus in systemd::custom_bus::custom_buses() {
+            let call = $module::$f(bus.level);
+            $handles.push(tokio::spawn(optional_bus_call(bus.level, bus.label, call)));
+        }
+
+        if let Some(user) = systemd::other_user::attached_user() {
+            let call = $module::$f(UnitDBusLevel::OtherUser);
+            $handles.push(tokio::spawn(optional_bus_call(
+                UnitDBusLevel::OtherUser,
+                user.name,
+                call,
+            )));
+        }
     }};
 }
 
+/// An unreachable custom bus or other user only misses its units, the rest of the list stays
+async fn optional_bus_call(
+    level: UnitDBusLevel,
+    label: String,
+    call: impl Future<Output = Result<ListUnitResponse, SystemdErrors>>,
+) -> Result<ListUnitResponse, SystemdErrors> {
+    match call.await {
+        Ok(response) => Ok(response),
+        Err(err) => {
+            warn!("Bus connection {label:?} {err:?}");
+            Ok(ListUnitResponse::Loaded(level, Vec::new()))
+        }
+    }
+}
+
 impl UnitListPanelImp {
     async fn retrieve_unit_list(
         &self,
@@ -1971,14 +2051,27 @@ impl UnitListPanelImp {
         let handle = if view == UnitCuratedList::Favorites {
             let mut system: Vec<String> = Vec::new();
             let mut user_session: Vec<String> = Vec::new();
+            let mut custom: Vec<(CustomBus, Vec<String>)> = systemd::custom_bus::custom_buses()
+                .into_iter()
+                .map(|bus| (bus, Vec::new()))
+                .collect();
+            let mut other_user: Vec<String> = Vec::new();
             for key in self.favorites.borrow().keys() {
                 if key.level == UnitDBusLevel::System {
                     system.push(key.primary.clone());
-                } else {
+                } else if key.level.is_other_user() {
+                    other_user.push(key.primary.clone());
+                } else if key.level.is_custom()
+                    && let Some((_, units)) =
+                        custom.iter_mut().find(|(bus, _)| bus.level == key.level)
+                {
+                    units.push(key.primary.clone());
+                } else if !key.level.is_custom() {
                     user_session.push(key.primary.clone());
                 }
             }
 
+            let attached_user = systemd::other_user::attached_user();
             systemd::runtime().spawn(async move {
                 let mut handles = Vec::with_capacity(4);
 
@@ -2004,6 +2097,27 @@ impl UnitListPanelImp {
                     )));
                 }
 
+                for (bus, units) in custom.into_iter().filter(|(_, units)| !units.is_empty()) {
+                    handles.push(tokio::spawn(optional_bus_call(
+                        bus.level,
+                        bus.label.clone(),
+                        systemd::list_loaded_units_list(bus.level, units.clone()),
+                    )));
+                    let call = systemd::list_unit_files_list(bus.level, units);
+                    handles.push(tokio::spawn(optional_bus_call(bus.level, bus.label, call)));
+                }
+
+                if let Some(user) = attached_user.filter(|_| !other_user.is_empty()) {
+                    let level = UnitDBusLevel::OtherUser;
+                    handles.push(tokio::spawn(optional_bus_call(
+                        level,
+                        user.name.clone(),
+                        systemd::list_loaded_units_list(level, other_user.clone()),
+                    )));
+                    let call = systemd::list_unit_files_list(level, other_user);
+                    handles.push(tokio::spawn(optional_bus_call(level, user.name, call)));
+                }
+
                 send_unit_list(sender_syst, handles).await;
             })
         } else {
@@ -1,6 +1,7 @@
 use std::sync::LazyLock;
 
 use base::enums::UnitDBusLevel;
+use gettextrs::pgettext;
 use gtk::{
     glib::{self, Binding, Quark},
     prelude::*,
@@ -19,7 +20,7 @@ use tracing::{error, warn};
 use crate::{
     consts::*,
     widget::{
-        preferences::data::PREFERENCES, unit_list::column::SysdColumn,
+        coredumps::CRASH_LOOP_COUNT, preferences::data::PREFERENCES, unit_list::column::SysdColumn,
         unit_properties_selector::data_selection::UnitPropertySelection,
     },
 };
@@ -34,6 +35,9 @@ use crate::{
 static BIND_INFO: LazyLock<Quark> = LazyLock::new(|| Quark::from_str("I"));
 static BIND_CSS: LazyLock<Quark> = LazyLock::new(|| Quark::from_str("C"));
 static BIND_CSS2: LazyLock<Quark> = LazyLock::new(|| Quark::from_str("C2"));
+static BIND_BADGE: LazyLock<Quark> = LazyLock::new(|| Quark::from_str("B"));
+static BIND_BADGE_CSS: LazyLock<Quark> = LazyLock::new(|| Quark::from_str("BC"));
+static BIND_BADGE_VISIBLE: LazyLock<Quark> = LazyLock::new(|| Quark::from_str("BV"));
 
 const CSS_CLASSES: &str = "css-classes";
 const TEXT: &str = "text";
@@ -91,6 +95,7 @@ macro_rules! factory_bind_enum {
 }
 
 const ACTIVE_STATE: &str = "active_state";
+const CRASHES: &str = "crashes";
 const CSS_GREY: &str = "grey";
 
 macro_rules! display_inactive {
@@ -151,11 +156,116 @@ fn inactive_display(widget: &impl IsA<gtk::Widget>, unit: &UnitInfo) {
 }
 
 pub fn fac_unit_name(display_color: bool) -> gtk::SignalListItemFactory {
-    common_factory(display_color, UnitInfo::prefix)
+    name_factory(display_color, UnitInfo::prefix)
 }
 
 pub fn fac_unit_name_full(display_color: bool) -> gtk::SignalListItemFactory {
-    common_factory(display_color, UnitInfo::primary)
+    name_factory(display_color, UnitInfo::primary)
+}
+
+/// The unit name followed by a badge counting its recent crashes
+fn name_factory(display_color: bool, func: fn(&UnitInfo) -> String) -> gtk::SignalListItemFactory {
+    let factory = gtk::SignalListItemFactory::new();
+
+    factory.connect_setup(|_factory, object| {
+        let list_item = downcast_list_item!(object);
+        let inscription = gtk::Inscription::builder()
+            .wrap_mode(gtk::pango::WrapMode::None)
+            .hexpand(true)
+            .build();
+
+        let badge = gtk::Label::builder()
+            .visible(false)
+            .tooltip_text(pgettext("list", "Crashes in the last 24 hours"))
+            .build();
+
+        let name_box = gtk::Box::builder().spacing(4).build();
+        name_box.append(&inscription);
+        name_box.append(&badge);
+
+        list_item.set_child(Some(&name_box));
+    });
+
+    factory.connect_bind(move |_factory, object| {
+        let list_item = downcast_list_item!(object);
+        let name_box = list_item
+            .child()
+            .and_downcast::<gtk::Box>()
+            .expect("item.downcast_ref::<gtk::Box>()");
+        let inscription = name_box
+            .first_child()
+            .and_downcast::<gtk::Inscription>()
+            .expect("item.downcast_ref::<gtk::Inscription>()");
+        let badge = name_box
+            .last_child()
+            .and_downcast::<gtk::Label>()
+            .expect("item.downcast_ref::<gtk::Label>()");
+        let unit = list_item
+            .item()
+            .and_downcast::<UnitInfo>()
+            .expect("item.downcast_ref::<gtk::UnitBinding>()");
+
+        inscription.set_text(Some(&func(&unit)));
+
+        if display_color {
+            display_inactive!(inscription, unit);
+
+            let binding = unit
+                .bind_property(ACTIVE_STATE, &inscription, CSS_CLASSES)
+                .transform_to(|_, active_state: ActiveState| {
+                    let css_classes = if active_state.is_inactive() {
+                        [CSS_GREY].to_value()
+                    } else {
+                        [].to_value()
+                    };
+                    Some(css_classes)
+                })
+                .build();
+            store_binding(&name_box, *BIND_CSS, binding);
+        }
+
+        crash_badge_bindings(&name_box, &badge, &unit);
+    });
+
+    factory_connect_unbind!(
+        factory,
+        *BIND_CSS,
+        *BIND_BADGE,
+        *BIND_BADGE_CSS,
+        *BIND_BADGE_VISIBLE
+    );
+
+    factory
+}
+
+fn crash_badge_bindings(name_box: &gtk::Box, badge: &gtk::Label, unit: &UnitInfo) {
+    let binding = unit
+        .bind_property(CRASHES, badge, "label")
+        .transform_to(|_, crashes: u32| Some(format!("💥{crashes}")))
+        .sync_create()
+        .build();
+    store_binding(name_box, *BIND_BADGE, binding);
+
+    let binding = unit
+        .bind_property(CRASHES, badge, CSS_CLASSES)
+        .transform_to(|_, crashes: u32| {
+            let css_classes = if crashes >= CRASH_LOOP_COUNT {
+                ["red", "bold"]
+            } else {
+                ["yellow", "bold"]
+            };
+            Some(css_classes.to_value())
+        })
+        .sync_create()
+        .build();
+    store_binding(name_box, *BIND_BADGE_CSS, binding);
+
+    let binding = unit
+        .bind_property(CRASHES, badge, "visible")
+        .transform_to(|_, crashes: u32| Some(crashes > 0))
+        .sync_create()
+        .build();
+    store_binding(name_box, *BIND_BADGE_VISIBLE, binding);
 }
 
 fn common_factory(
@@ -205,18 +315,30 @@ pub fn fac_bus(display_color: bool) -> gtk::SignalListItemFactory {
     fac_bus.connect_setup(factory_setup);
     if display_color {
         fac_bus.connect_bind(move |_factory, object| {
-            let (inscription, unit) = factory_bind_enum!(object, dbus_level);
+            let (inscription, unit) = factory_bind_bus(object);
             inactive_display(&inscription, &unit)
         });
         factory_connect_unbind!(fac_bus, *BIND_CSS);
     } else {
         fac_bus.connect_bind(move |_factory, object| {
-            factory_bind_enum!(object, dbus_level);
+            factory_bind_bus(object);
         });
     }
     fac_bus
 }
 
+/// The custom buses show their connection label, the other user its name
+fn factory_bind_bus(object: &glib::Object) -> (gtk::Inscription, UnitInfo) {
+    let (inscription, unit) = factory_bind_pre!(object);
+    let level = unit.dbus_level();
+    if level.is_custom() || level.is_other_user() {
+        inscription.set_text(Some(&systemd::custom_bus::bus_label(level)));
+    } else {
+        inscription.set_text(Some(&level.as_str()));
+    }
+    (inscription, unit)
+}
+
 pub fn fac_active(display_color: bool) -> gtk::SignalListItemFactory {
     let fac_active = gtk::SignalListItemFactory::new();
 
@@ -96,6 +96,8 @@ pub fn construct_column_view(
 
 fn show_dbus_level() -> bool {
     PREFERENCES.dbus_level() == DbusLevel::SystemAndSession
+        || systemd::custom_bus::has_custom_buses()
+        || systemd::other_user::attached_user().is_some()
 }
 
 macro_rules! insert {
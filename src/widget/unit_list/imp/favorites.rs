@@ -2,6 +2,7 @@ use crate::widget::{
     unit_list::imp::UnitKey,
     unit_properties_selector::save::{get_sysd_manager_config_dir, save_to_toml_file},
 };
+use base::enums::UnitDBusLevel;
 use serde::{Deserialize, Serialize};
 use std::fs;
 use tracing::{error, info, warn};
@@ -21,16 +22,22 @@ pub struct Favorite {
 }
 
 impl Favorite {
-    fn from(key: &UnitKey) -> Self {
-        Self {
-            bus: key.level.as_str().to_owned(),
+    /// `None` for the levels that can't be found back on the next run
+    fn from(key: &UnitKey) -> Option<Self> {
+        Some(Self {
+            bus: systemd::custom_bus::saved_bus(key.level)?,
             unit: key.primary.to_owned(),
-        }
+        })
+    }
+
+    /// `None` when the bus of the favorite isn't connected
+    pub fn level(&self) -> Option<UnitDBusLevel> {
+        systemd::custom_bus::saved_bus_level(&self.bus)
     }
 }
 
 pub(super) fn save_favorites(favorites: &[&UnitKey]) {
-    let favorites: Vec<Favorite> = favorites.iter().map(|k| Favorite::from(k)).collect();
+    let favorites: Vec<Favorite> = favorites.iter().filter_map(|k| Favorite::from(k)).collect();
     systemd::runtime().spawn(save_favorites_async(favorites));
 }
 
@@ -59,7 +66,7 @@ async fn save_favorites_async(favorites: Vec<Favorite>) {
     }
 }
 
-pub(super) fn load_favorites() -> Option<Favorites> {
+pub(crate) fn load_favorites() -> Option<Favorites> {
     let sysd_manager_config_dir = get_sysd_manager_config_dir();
 
     if !sysd_manager_config_dir.exists() {
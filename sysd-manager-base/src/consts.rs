@@ -24,3 +24,27 @@ pub const MAX_HEART_BEAT_ELAPSE: u64 = 300_000;
 pub const FAVORITE_ICON_FILLED: &str = "bookmark-filled-symbolic";
 pub const FAVORITE_ICON_OUTLINE: &str = "bookmark-outline-symbolic";
 pub const SYSTEMD_ANALYZE: &str = "systemd-analyze";
+
+/// Syslog identifier of the proxy audit journal entries
+pub const AUDIT_SYSLOG_IDENTIFIER: &str = "sysd-manager-audit";
+/// Message id of the proxy audit journal entries
+pub const AUDIT_MESSAGE_ID: &str = "83d6372077bb42e3953f37f4f27a3a5a";
+pub const AUDIT_KEY_CALLER_UID: &str = "SYSD_MANAGER_CALLER_UID";
+pub const AUDIT_KEY_CALLER_PID: &str = "SYSD_MANAGER_CALLER_PID";
+pub const AUDIT_KEY_METHOD: &str = "SYSD_MANAGER_METHOD";
+pub const AUDIT_KEY_ACTION: &str = "SYSD_MANAGER_ACTION";
+pub const AUDIT_KEY_UNIT: &str = "SYSD_MANAGER_UNIT";
+pub const AUDIT_KEY_FILE: &str = "SYSD_MANAGER_FILE";
+pub const AUDIT_KEY_CONTENT_SHA256: &str = "SYSD_MANAGER_CONTENT_SHA256";
+pub const AUDIT_KEY_PREVIOUS_SHA256: &str = "SYSD_MANAGER_PREVIOUS_SHA256";
+pub const AUDIT_KEY_AUTHORIZED: &str = "SYSD_MANAGER_AUTHORIZED";
+pub const AUDIT_KEY_RESULT: &str = "SYSD_MANAGER_RESULT";
+/// Result value of the successful calls
+pub const AUDIT_RESULT_SUCCESS: &str = "success";
+
+/// Allow-list policy of the files the proxy can write
+pub const PROXY_WRITE_POLICY_PATH: &str = "/etc/sysd-manager/proxy-write-policy.toml";
+/// D-Bus errors returned by the proxy when a write is refused by the policy
+pub const PROXY_ERROR_PATH_NOT_ALLOWED: &str = concat!(DBUS_NAME, ".Error.PathNotAllowed");
+pub const PROXY_ERROR_SYMLINK_REJECTED: &str = concat!(DBUS_NAME, ".Error.SymlinkRejected");
+pub const PROXY_ERROR_EXTENSION_REJECTED: &str = concat!(DBUS_NAME, ".Error.ExtensionRejected");
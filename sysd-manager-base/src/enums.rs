@@ -1,28 +1,45 @@
 use gettextrs::{gettext, pgettext};
-use glib::value::ToValue;
-use strum::EnumIter;
+use std::borrow::Cow;
 use tracing::warn;
 
-#[derive(
-    Debug, Copy, Clone, PartialEq, Eq, glib::Enum, Default, EnumIter, Hash, Ord, PartialOrd,
-)]
-#[enum_type(name = "UnitDBusLevel")]
+const CUSTOM_PREFIX: &str = "custom";
+const CUSTOM_SHORT_PREFIX: &str = "c";
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, glib::Boxed, Default, Hash, Ord, PartialOrd)]
+#[boxed_type(name = "UnitDBusLevel")]
 pub enum UnitDBusLevel {
     #[default]
-    #[enum_value(name = "system", nick = "System")]
-    System = 0,
-    #[enum_value(name = "user", nick = "User Session")]
-    UserSession = 1,
-    #[enum_value(name = "both", nick = "System & User")]
-    Both = 2,
+    System,
+    UserSession,
+    Both,
+    /// Manager at a bus address given by the user, like the system bus of a container, with
+    /// the id of its connection
+    Custom(u32),
+    /// User session manager of another user, reached through the proxy
+    OtherUser,
 }
 
+/// The levels that are not a custom connection, the custom ones come and go with their
+/// connections
+const FIXED_LEVELS: [UnitDBusLevel; 4] = [
+    UnitDBusLevel::System,
+    UnitDBusLevel::UserSession,
+    UnitDBusLevel::Both,
+    UnitDBusLevel::OtherUser,
+];
+
 impl UnitDBusLevel {
-    pub fn short(&self) -> &str {
+    pub fn iter() -> impl Iterator<Item = UnitDBusLevel> {
+        FIXED_LEVELS.into_iter()
+    }
+
+    pub fn short(&self) -> Cow<'static, str> {
         match self {
-            UnitDBusLevel::System => "s",
-            UnitDBusLevel::UserSession => "u",
-            UnitDBusLevel::Both => "b",
+            UnitDBusLevel::System => Cow::Borrowed("s"),
+            UnitDBusLevel::UserSession => Cow::Borrowed("u"),
+            UnitDBusLevel::Both => Cow::Borrowed("b"),
+            UnitDBusLevel::Custom(id) => Cow::Owned(format!("{CUSTOM_SHORT_PREFIX}{id}")),
+            UnitDBusLevel::OtherUser => Cow::Borrowed("o"),
         }
     }
 
@@ -37,13 +54,31 @@ impl UnitDBusLevel {
         matches!(self, UnitDBusLevel::UserSession)
     }
 
-    pub fn as_str(&self) -> &'static str {
-        let level_value: &glib::EnumValue = self.to_value().get().expect("it's an enum");
-        level_value.name()
+    pub fn is_custom(&self) -> bool {
+        matches!(self, UnitDBusLevel::Custom(_))
+    }
+
+    pub fn is_other_user(&self) -> bool {
+        matches!(self, UnitDBusLevel::OtherUser)
+    }
+
+    /// The calls go straight to the bus, never through the proxy of the local system bus
+    pub fn bypasses_proxy(&self) -> bool {
+        self.user_session() || self.is_custom()
+    }
+
+    pub fn as_str(&self) -> Cow<'static, str> {
+        match self {
+            UnitDBusLevel::System => Cow::Borrowed("system"),
+            UnitDBusLevel::UserSession => Cow::Borrowed("user"),
+            UnitDBusLevel::Both => Cow::Borrowed("both"),
+            UnitDBusLevel::Custom(id) => Cow::Owned(format!("{CUSTOM_PREFIX}{id}")),
+            UnitDBusLevel::OtherUser => Cow::Borrowed("other_user"),
+        }
     }
 
     //used in browser table
-    pub fn label(&self) -> &'static str {
+    pub fn label(&self) -> Cow<'static, str> {
         self.as_str()
     }
 
@@ -52,6 +87,10 @@ impl UnitDBusLevel {
             //menu option
             UnitDBusLevel::UserSession => pgettext("dbus", "User Session"),
             //menu option
+            UnitDBusLevel::Custom(_) => pgettext("dbus", "Custom Bus"),
+            //menu option
+            UnitDBusLevel::OtherUser => pgettext("dbus", "Other User"),
+            //menu option
             _ => pgettext("dbus", "System"),
         }
     }
@@ -61,7 +100,12 @@ impl UnitDBusLevel {
             "s" => UnitDBusLevel::System,
             "u" => UnitDBusLevel::UserSession,
             "b" => UnitDBusLevel::Both,
-            _ => UnitDBusLevel::System,
+            "o" => UnitDBusLevel::OtherUser,
+            _ => suffix
+                .strip_prefix(CUSTOM_SHORT_PREFIX)
+                .and_then(|id| id.parse().ok())
+                .map(UnitDBusLevel::Custom)
+                .unwrap_or(UnitDBusLevel::System),
         }
     }
 
@@ -70,15 +114,18 @@ impl UnitDBusLevel {
     }
 
     pub fn value(&self) -> i32 {
-        let level_value: &glib::EnumValue = self.to_value().get().expect("it's an enum");
-        level_value.value()
+        self.index() as i32
     }
 
+    /// The bus argument of the proxy calls, the custom connections never go through the proxy
+    /// and all share the same index
     pub fn index(&self) -> u8 {
         match self {
             UnitDBusLevel::System => 0,
             UnitDBusLevel::UserSession => 1,
             UnitDBusLevel::Both => 2,
+            UnitDBusLevel::Custom(_) => 3,
+            UnitDBusLevel::OtherUser => 7,
         }
     }
 
@@ -96,6 +143,14 @@ impl UnitDBusLevel {
                 //instance level user
                 gettext("System & User")
             }
+            UnitDBusLevel::Custom(_) => {
+                //instance level bus address given by the user
+                gettext("Custom Bus")
+            }
+            UnitDBusLevel::OtherUser => {
+                //instance level user session of another user
+                gettext("Other User")
+            }
         }
     }
 }
@@ -106,6 +161,7 @@ impl From<u8> for UnitDBusLevel {
             0 => UnitDBusLevel::System,
             1 => UnitDBusLevel::UserSession,
             2 => UnitDBusLevel::Both,
+            7 => UnitDBusLevel::OtherUser,
             _ => UnitDBusLevel::UserSession,
         }
     }
@@ -123,14 +179,27 @@ impl From<&str> for UnitDBusLevel {
             "user" => UnitDBusLevel::UserSession,
             "system" => UnitDBusLevel::System,
             "both" => UnitDBusLevel::Both,
-            _ => {
-                warn!("Unit dbus Level not found {level:?}");
-                UnitDBusLevel::default()
-            }
+            "other_user" => UnitDBusLevel::OtherUser,
+            _ => match level
+                .strip_prefix(CUSTOM_PREFIX)
+                .and_then(|id| id.parse().ok())
+            {
+                Some(id) => UnitDBusLevel::Custom(id),
+                None => {
+                    warn!("Unit dbus Level not found {level:?}");
+                    UnitDBusLevel::default()
+                }
+            },
         }
     }
 }
 
+impl From<Cow<'_, str>> for UnitDBusLevel {
+    fn from(level: Cow<'_, str>) -> Self {
+        level.as_ref().into()
+    }
+}
+
 impl From<String> for UnitDBusLevel {
     fn from(level: String) -> Self {
         level.as_str().into()
@@ -14,6 +14,19 @@ pub struct DisEnAbleUnitFilesResponse {
     changes: Vec<DisEnAbleUnitFiles>,
 }
 
+impl DisEnAbleUnitFilesResponse {
+    pub fn new(carries_install_info: bool, changes: Vec<DisEnAbleUnitFiles>) -> Self {
+        DisEnAbleUnitFilesResponse {
+            carries_install_info,
+            changes,
+        }
+    }
+
+    pub fn changes(&self) -> &[DisEnAbleUnitFiles] {
+        &self.changes
+    }
+}
+
 #[derive(Debug, Type, Serialize, Deserialize)]
 pub struct QueuedJobs {
     ///The numeric job id
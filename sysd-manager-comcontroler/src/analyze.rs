@@ -10,6 +10,8 @@ pub struct Analyze {
 
 /// Returns the results of `systemd-analyze blame`
 pub async fn blame() -> Result<Vec<Analyze>, SystemdErrors> {
+    crate::backend::ensure_live()?;
+
     let cmd = [SYSTEMD_ANALYZE, "blame"];
     let command_output = super::commander_output(&cmd, None)?.stdout;
 
@@ -0,0 +1,353 @@
+//! The systemd operations the GUI relies on, behind a trait so the source of the units can change.
+//!
+//! [`DBusBackend`] talks to the systemd managers, [`FixtureBackend`] serves deterministic
+//! in-memory units for demos and screenshot tests. The backend is chosen once at startup
+//! with [`set_backend`], the D-Bus one is used when nothing was set.
+
+mod dbus;
+mod fixture;
+
+use crate::{
+    BootFilter, CompleteUnitPropertiesCallParams, Dependency, ListUnitResponse, ReStartStop,
+    UnitProperties, UpdatedUnitInfo,
+    data::{UnitProcess, UnitPropertySetter},
+    enums::{ActiveState, DependencyType, DisEnableFlags, KillWho, StartStopMode, UnitType},
+    errors::SystemdErrors,
+    journal_data::{EventRange, FieldMatches, JournalEventChunk, JournalFacet, JournalSource},
+    time_handling::TimestampStyle,
+};
+use base::{
+    enums::UnitDBusLevel,
+    proxy::{DisEnAbleUnitFiles, DisEnAbleUnitFilesResponse},
+};
+use enumflags2::BitFlags;
+use futures_util::future::BoxFuture;
+use glib::Quark;
+use std::{
+    collections::HashMap,
+    fmt::Debug,
+    sync::{
+        OnceLock,
+        mpsc::{Receiver, Sender},
+    },
+};
+use tracing::{info, warn};
+use zvariant::OwnedValue;
+
+pub use dbus::DBusBackend;
+pub use fixture::{FixtureBackend, FixtureUnit};
+
+static BACKEND: OnceLock<Box<dyn SystemdBackend>> = OnceLock::new();
+
+/// Source of the units, their properties, files, journal and dependencies
+pub trait SystemdBackend: Send + Sync + Debug {
+    /// Short name for the logs
+    fn name(&self) -> &'static str;
+
+    /// Backed by the host systemd and journal, the features outside of this trait need it
+    fn is_live(&self) -> bool;
+
+    // Listing
+
+    fn list_loaded_units(
+        &self,
+        level: UnitDBusLevel,
+    ) -> BoxFuture<'_, Result<ListUnitResponse, SystemdErrors>>;
+
+    fn list_loaded_units_by_patterns<'a>(
+        &'a self,
+        level: UnitDBusLevel,
+        patterns: &'a [&'a str],
+    ) -> BoxFuture<'a, Result<ListUnitResponse, SystemdErrors>>;
+
+    fn list_unit_files(
+        &self,
+        level: UnitDBusLevel,
+    ) -> BoxFuture<'_, Result<ListUnitResponse, SystemdErrors>>;
+
+    fn list_unit_files_by_patterns<'a>(
+        &'a self,
+        level: UnitDBusLevel,
+        patterns: &'a [&'a str],
+    ) -> BoxFuture<'a, Result<ListUnitResponse, SystemdErrors>>;
+
+    // Properties
+
+    fn complete_unit_information<'a>(
+        &'a self,
+        units: &'a [CompleteUnitPropertiesCallParams],
+    ) -> BoxFuture<'a, Result<Vec<UpdatedUnitInfo>, SystemdErrors>>;
+
+    fn get_unit_active_state(
+        &self,
+        level: UnitDBusLevel,
+        primary_name: &str,
+    ) -> Result<ActiveState, SystemdErrors>;
+
+    /// The managed properties asked by the flags, then the custom ones found
+    fn fetch_unit_properties<'a>(
+        &'a self,
+        level: UnitDBusLevel,
+        unit_primary_name: &'a str,
+        path: &'a str,
+        unit_properties: UnitProperties,
+        properties: Vec<(UnitType, &'a str, Quark)>,
+    ) -> BoxFuture<'a, Result<Vec<UnitPropertySetter>, SystemdErrors>>;
+
+    fn fetch_unit_property_blocking(
+        &self,
+        level: UnitDBusLevel,
+        unit_primary_name: &str,
+        unit_type: UnitType,
+        unit_property: &str,
+    ) -> Result<OwnedValue, SystemdErrors>;
+
+    /// All the properties of the unit type interface, and of the unit one if the type extends it
+    fn fetch_system_unit_info_native(
+        &self,
+        level: UnitDBusLevel,
+        object_path: &str,
+        unit_type: UnitType,
+    ) -> Result<Vec<(UnitType, String, OwnedValue)>, SystemdErrors>;
+
+    fn fetch_system_unit_info_native_map(
+        &self,
+        level: UnitDBusLevel,
+        object_path: &str,
+        unit_type: UnitType,
+    ) -> Result<HashMap<String, OwnedValue>, SystemdErrors>;
+
+    fn retreive_unit_processes(
+        &self,
+        level: UnitDBusLevel,
+        unit_name: &str,
+    ) -> Result<Vec<UnitProcess>, SystemdErrors>;
+
+    /// The units grouped by a slice or a target
+    fn fetch_unit_members(
+        &self,
+        level: UnitDBusLevel,
+        unit_name: &str,
+    ) -> Result<Vec<String>, SystemdErrors>;
+
+    // Lifecycle
+
+    /// Returns the job path once the job is done
+    fn restartstop_unit<'a>(
+        &'a self,
+        level: UnitDBusLevel,
+        unit_name: &'a str,
+        mode: StartStopMode,
+        action: ReStartStop,
+    ) -> BoxFuture<'a, Result<String, SystemdErrors>>;
+
+    fn daemon_reload(&self, level: UnitDBusLevel) -> BoxFuture<'_, Result<(), SystemdErrors>>;
+
+    /// Returns the job path
+    fn reload_unit(
+        &self,
+        level: UnitDBusLevel,
+        primary_name: &str,
+        mode: StartStopMode,
+    ) -> Result<String, SystemdErrors>;
+
+    fn kill_unit(
+        &self,
+        level: UnitDBusLevel,
+        primary_name: &str,
+        who: KillWho,
+        signal: i32,
+    ) -> Result<(), SystemdErrors>;
+
+    fn queue_signal_unit(
+        &self,
+        level: UnitDBusLevel,
+        primary_name: &str,
+        who: KillWho,
+        signal: i32,
+        value: i32,
+    ) -> Result<(), SystemdErrors>;
+
+    fn freeze_unit(&self, level: UnitDBusLevel, primary_name: &str) -> Result<(), SystemdErrors>;
+
+    fn thaw_unit(&self, level: UnitDBusLevel, primary_name: &str) -> Result<(), SystemdErrors>;
+
+    fn clean_unit(
+        &self,
+        level: UnitDBusLevel,
+        unit_name: &str,
+        what: &[&str],
+    ) -> Result<(), SystemdErrors>;
+
+    // Unit files
+
+    fn enable_unit_file(
+        &self,
+        level: UnitDBusLevel,
+        unit_file: &str,
+        flags: BitFlags<DisEnableFlags>,
+    ) -> Result<DisEnAbleUnitFilesResponse, SystemdErrors>;
+
+    fn disable_unit_file(
+        &self,
+        level: UnitDBusLevel,
+        unit_file: &str,
+        flags: BitFlags<DisEnableFlags>,
+    ) -> Result<DisEnAbleUnitFilesResponse, SystemdErrors>;
+
+    fn mask_unit_file(
+        &self,
+        level: UnitDBusLevel,
+        primary_name: &str,
+        runtime: bool,
+        force: bool,
+    ) -> Result<Vec<DisEnAbleUnitFiles>, SystemdErrors>;
+
+    fn unmask_unit_file(
+        &self,
+        level: UnitDBusLevel,
+        primary_name: &str,
+        runtime: bool,
+    ) -> Result<Vec<DisEnAbleUnitFiles>, SystemdErrors>;
+
+    fn preset_unit_file(
+        &self,
+        level: UnitDBusLevel,
+        primary_name: &str,
+        runtime: bool,
+        force: bool,
+    ) -> Result<DisEnAbleUnitFilesResponse, SystemdErrors>;
+
+    fn reenable_unit_file(
+        &self,
+        level: UnitDBusLevel,
+        primary_name: &str,
+        runtime: bool,
+        force: bool,
+    ) -> Result<DisEnAbleUnitFilesResponse, SystemdErrors>;
+
+    /// Link a unit file from outside the unit directories
+    fn link_unit_file(
+        &self,
+        level: UnitDBusLevel,
+        unit_file: &str,
+        runtime: bool,
+        force: bool,
+    ) -> Result<Vec<DisEnAbleUnitFiles>, SystemdErrors>;
+
+    fn fetch_drop_in_paths<'a>(
+        &'a self,
+        level: UnitDBusLevel,
+        unit_name: &'a str,
+    ) -> BoxFuture<'a, Result<Vec<String>, SystemdErrors>>;
+
+    fn fetch_unit_file_content(
+        &self,
+        file_path: Option<&str>,
+        unit_primary_name: &str,
+    ) -> Result<String, SystemdErrors>;
+
+    // Journal
+
+    #[allow(clippy::too_many_arguments)]
+    fn get_unit_journal(
+        &self,
+        source: &JournalSource,
+        unit_names: &[String],
+        level: UnitDBusLevel,
+        boot_filter: BootFilter,
+        field_matches: &FieldMatches,
+        range: EventRange,
+        message_max_char: usize,
+        timestamp_style: TimestampStyle,
+    ) -> Result<JournalEventChunk, SystemdErrors>;
+
+    /// The events around the entry at `cursor`, `context` events before and after
+    #[allow(clippy::too_many_arguments)]
+    fn get_unit_journal_around_cursor(
+        &self,
+        source: &JournalSource,
+        unit_names: &[String],
+        level: UnitDBusLevel,
+        field_matches: &FieldMatches,
+        cursor: &str,
+        context: usize,
+        message_max_char: usize,
+        timestamp_style: TimestampStyle,
+    ) -> Result<JournalEventChunk, SystemdErrors>;
+
+    /// Sends the new events until `journal_continuous_receiver` gets a message or is dropped
+    #[allow(clippy::too_many_arguments)]
+    fn get_unit_journal_continuous(
+        &self,
+        unit_names: Vec<String>,
+        level: UnitDBusLevel,
+        field_matches: FieldMatches,
+        range: EventRange,
+        journal_continuous_receiver: Receiver<()>,
+        sender: Sender<JournalEventChunk>,
+        message_max_char: usize,
+        timestamp_style: TimestampStyle,
+        check_for_new_journal_entry: fn(),
+    ) -> Result<(), SystemdErrors>;
+
+    #[allow(clippy::too_many_arguments)]
+    fn list_unit_journal_facets(
+        &self,
+        source: &JournalSource,
+        unit_names: &[String],
+        level: UnitDBusLevel,
+        boot_filter: BootFilter,
+        field_matches: &FieldMatches,
+        fields: &[&str],
+        max_entries: usize,
+    ) -> Result<Vec<JournalFacet>, SystemdErrors>;
+
+    // Dependencies
+
+    fn fetch_unit_dependencies(
+        &self,
+        level: UnitDBusLevel,
+        primary_name: &str,
+        object_path: &str,
+        dependency_type: DependencyType,
+        plain: bool,
+    ) -> Result<Dependency, SystemdErrors>;
+}
+
+/// Select the backend, only the first call counts and it has to happen before any systemd call
+pub fn set_backend(backend: Box<dyn SystemdBackend>) -> bool {
+    let name = backend.name();
+    match BACKEND.set(backend) {
+        Ok(()) => {
+            info!("Systemd backend {name}");
+            true
+        }
+        Err(_) => {
+            warn!(
+                "Systemd backend already set to {}, {name} ignored",
+                self::backend().name()
+            );
+            false
+        }
+    }
+}
+
+/// The selected backend, D-Bus by default
+pub fn backend() -> &'static dyn SystemdBackend {
+    BACKEND.get_or_init(|| Box::new(DBusBackend)).as_ref()
+}
+
+/// Refuse the calls going to the host systemd or journal around the backend, so a fixture
+/// backend never mixes them with its units
+pub fn ensure_live() -> Result<(), SystemdErrors> {
+    let backend = backend();
+    if backend.is_live() {
+        Ok(())
+    } else {
+        Err(SystemdErrors::Custom(format!(
+            "Not available with the {} backend",
+            backend.name()
+        )))
+    }
+}
@@ -0,0 +1,782 @@
+use super::SystemdBackend;
+use crate::{
+    BootFilter, CompleteUnitPropertiesCallParams, Dependency, ListUnitResponse, ReStartStop,
+    UnitProperties, UpdatedUnitInfo,
+    data::{UnitProcess, UnitPropertySetter},
+    enums::{ActiveState, DependencyType, DisEnableFlags, KillWho, StartStopMode, UnitType},
+    errors::SystemdErrors,
+    journal,
+    journal_data::{EventRange, FieldMatches, JournalEventChunk, JournalFacet, JournalSource},
+    other_user,
+    sysdbus::{
+        self,
+        dbus_proxies::{systemd_manager_async, systemd_manager_blocking},
+        watcher::{SystemdSignal, init_signal_watcher},
+    },
+    time_handling::TimestampStyle,
+};
+use base::{
+    enums::UnitDBusLevel,
+    proxy::{DisEnAbleUnitFiles, DisEnAbleUnitFilesResponse},
+};
+use enumflags2::BitFlags;
+use futures_util::future::BoxFuture;
+use glib::Quark;
+use std::{
+    collections::HashMap,
+    sync::mpsc::{Receiver, Sender},
+    time::Duration,
+};
+use tokio::{
+    sync::broadcast::{self, error::RecvError},
+    time::timeout,
+};
+use tracing::{info, warn};
+use zvariant::OwnedValue;
+
+#[cfg(not(any(feature = "flatpak", feature = "appimage")))]
+use crate::{proxy_switcher, sysdbus::to_proxy};
+
+#[cfg(not(any(feature = "flatpak", feature = "appimage")))]
+use base::consts::PROXY_SERVICE;
+
+const DONE: &str = "done";
+const SKIPPED: &str = "skipped";
+const CANCELED: &str = "canceled";
+const TIMEOUT: &str = "timeout";
+const FAILED: &str = "failed";
+const DEPENDENCY: &str = "dependency";
+const INVALID: &str = "invalid";
+
+/// The systemd managers on D-Bus, through the proxy for the privileged system calls
+#[derive(Debug, Default, Clone, Copy)]
+pub struct DBusBackend;
+
+impl SystemdBackend for DBusBackend {
+    fn name(&self) -> &'static str {
+        "dbus"
+    }
+
+    fn is_live(&self) -> bool {
+        true
+    }
+
+    fn list_loaded_units(
+        &self,
+        level: UnitDBusLevel,
+    ) -> BoxFuture<'_, Result<ListUnitResponse, SystemdErrors>> {
+        Box::pin(async move {
+            if level.is_other_user() {
+                return other_user::list_loaded_units().await;
+            }
+
+            let v = systemd_manager_async(level).await?.list_units().await?;
+            Ok(ListUnitResponse::Loaded(level, v))
+        })
+    }
+
+    fn list_loaded_units_by_patterns<'a>(
+        &'a self,
+        level: UnitDBusLevel,
+        patterns: &'a [&'a str],
+    ) -> BoxFuture<'a, Result<ListUnitResponse, SystemdErrors>> {
+        Box::pin(async move {
+            if level.is_other_user() {
+                return other_user::list_loaded_units_by_patterns(patterns).await;
+            }
+
+            let v = systemd_manager_async(level)
+                .await?
+                .list_units_by_patterns(&[], patterns)
+                .await?;
+            Ok(ListUnitResponse::Loaded(level, v))
+        })
+    }
+
+    fn list_unit_files(
+        &self,
+        level: UnitDBusLevel,
+    ) -> BoxFuture<'_, Result<ListUnitResponse, SystemdErrors>> {
+        Box::pin(async move {
+            if level.is_other_user() {
+                return other_user::list_unit_files().await;
+            }
+
+            let v = systemd_manager_async(level)
+                .await?
+                .list_unit_files()
+                .await?;
+            Ok(ListUnitResponse::File(level, v))
+        })
+    }
+
+    fn list_unit_files_by_patterns<'a>(
+        &'a self,
+        level: UnitDBusLevel,
+        patterns: &'a [&'a str],
+    ) -> BoxFuture<'a, Result<ListUnitResponse, SystemdErrors>> {
+        Box::pin(async move {
+            if level.is_other_user() {
+                return other_user::list_unit_files_by_patterns(patterns).await;
+            }
+
+            let v = systemd_manager_async(level)
+                .await?
+                .list_unit_files_by_patterns(&[], patterns)
+                .await?;
+            Ok(ListUnitResponse::File(level, v))
+        })
+    }
+
+    fn complete_unit_information<'a>(
+        &'a self,
+        units: &'a [CompleteUnitPropertiesCallParams],
+    ) -> BoxFuture<'a, Result<Vec<UpdatedUnitInfo>, SystemdErrors>> {
+        Box::pin(async move {
+            let mut updated = sysdbus::complete_unit_information(units).await?;
+
+            let other_user_units: Vec<_> = units
+                .iter()
+                .filter(|params| params.level.is_other_user())
+                .collect();
+            if !other_user_units.is_empty() {
+                updated.extend(other_user::complete_unit_information(&other_user_units).await);
+            }
+            Ok(updated)
+        })
+    }
+
+    fn get_unit_active_state(
+        &self,
+        level: UnitDBusLevel,
+        primary_name: &str,
+    ) -> Result<ActiveState, SystemdErrors> {
+        if level.is_other_user() {
+            return other_user::get_unit_active_state(primary_name);
+        }
+
+        let object_path = sysdbus::unit_dbus_path_from_name(primary_name);
+
+        sysdbus::get_unit_active_state(level, &object_path)
+    }
+
+    fn fetch_unit_properties<'a>(
+        &'a self,
+        level: UnitDBusLevel,
+        unit_primary_name: &'a str,
+        path: &'a str,
+        unit_properties: UnitProperties,
+        properties: Vec<(UnitType, &'a str, Quark)>,
+    ) -> BoxFuture<'a, Result<Vec<UnitPropertySetter>, SystemdErrors>> {
+        Box::pin(sysdbus::fetch_unit_properties(
+            level,
+            unit_primary_name,
+            path,
+            unit_properties,
+            properties,
+        ))
+    }
+
+    fn fetch_unit_property_blocking(
+        &self,
+        level: UnitDBusLevel,
+        unit_primary_name: &str,
+        unit_type: UnitType,
+        unit_property: &str,
+    ) -> Result<OwnedValue, SystemdErrors> {
+        sysdbus::fetch_unit_property_blocking(level, unit_primary_name, unit_type, unit_property)
+    }
+
+    fn fetch_system_unit_info_native(
+        &self,
+        level: UnitDBusLevel,
+        object_path: &str,
+        unit_type: UnitType,
+    ) -> Result<Vec<(UnitType, String, OwnedValue)>, SystemdErrors> {
+        sysdbus::fetch_system_unit_info_native(level, object_path, unit_type)
+    }
+
+    fn fetch_system_unit_info_native_map(
+        &self,
+        level: UnitDBusLevel,
+        object_path: &str,
+        unit_type: UnitType,
+    ) -> Result<HashMap<String, OwnedValue>, SystemdErrors> {
+        sysdbus::fetch_system_unit_info_native_map(level, object_path, unit_type)
+    }
+
+    fn retreive_unit_processes(
+        &self,
+        level: UnitDBusLevel,
+        unit_name: &str,
+    ) -> Result<Vec<UnitProcess>, SystemdErrors> {
+        let unit_processes = sysdbus::retreive_unit_processes(level, unit_name)?
+            .into_iter()
+            .filter_map(|process| {
+                let Some(unit_name) = process.path.rsplit_once('/').map(|a| a.1) else {
+                    warn!("No unit name for path {:?}", process.path);
+                    return None;
+                };
+
+                let unit_name_idx = process.path.len() - unit_name.len();
+                Some(UnitProcess {
+                    path: process.path,
+                    pid: process.pid,
+                    name: process.name,
+                    unit_name: unit_name_idx,
+                })
+            })
+            .collect();
+
+        Ok(unit_processes)
+    }
+
+    fn fetch_unit_members(
+        &self,
+        level: UnitDBusLevel,
+        unit_name: &str,
+    ) -> Result<Vec<String>, SystemdErrors> {
+        sysdbus::unit_members(level, unit_name)
+    }
+
+    fn restartstop_unit<'a>(
+        &'a self,
+        level: UnitDBusLevel,
+        unit_name: &'a str,
+        mode: StartStopMode,
+        action: ReStartStop,
+    ) -> BoxFuture<'a, Result<String, SystemdErrors>> {
+        Box::pin(async move {
+            // no signal from the bus of another user, the job is only queued
+            if level.is_other_user() {
+                return other_user::restartstop_unit(unit_name, mode, &action).await;
+            }
+
+            let watcher = init_signal_watcher(level).await;
+            let job = restartstop_unit_call(level, unit_name, mode, &action).await?;
+            let job_id = job_number(&job).ok_or("Invalid Job Id for job: {job}")?;
+
+            let duration = Duration::from_secs(10);
+            timeout(duration, wait_job_removed(job_id, watcher))
+                .await
+                .map_err(|_err| SystemdErrors::Timeout(duration))
+                .and_then(|res| res.map(|_| job))
+                .inspect(|_job| {
+                    #[cfg(not(any(feature = "flatpak", feature = "appimage")))]
+                    if matches!(action, ReStartStop::Start | ReStartStop::Restart)
+                        && unit_name.starts_with(PROXY_SERVICE)
+                    {
+                        to_proxy::start_heart_beat()
+                    }
+                })
+        })
+    }
+
+    fn daemon_reload(&self, level: UnitDBusLevel) -> BoxFuture<'_, Result<(), SystemdErrors>> {
+        Box::pin(async move {
+            if level.is_other_user() {
+                return other_user::daemon_reload().await;
+            }
+
+            let mut watcher = init_signal_watcher(level).await;
+            daemon_reload_core(level).await?;
+
+            let mut wait_reload = async || {
+                loop {
+                    match watcher.recv().await {
+                        Ok(SystemdSignal::Reloading(_, active)) => {
+                            if active {
+                                info!("Reloading!");
+                            } else {
+                                info!("Reload Finised");
+                                break;
+                            }
+                        }
+                        Ok(_) => {}
+                        Err(RecvError::Lagged(lag)) => info!("Lagged {lag:?}"),
+                        Err(err) => {
+                            warn!("Recev Err {err:?}");
+                            break;
+                        }
+                    }
+                }
+            };
+
+            let duration = Duration::from_secs(10);
+            match timeout(duration, wait_reload()).await {
+                Ok(_) => Ok(()),
+                Err(_err) => Err(SystemdErrors::Timeout(duration)),
+            }
+        })
+    }
+
+    fn reload_unit(
+        &self,
+        level: UnitDBusLevel,
+        primary_name: &str,
+        mode: StartStopMode,
+    ) -> Result<String, SystemdErrors> {
+        sysdbus::reload_unit(level, primary_name, mode.as_str())
+    }
+
+    fn kill_unit(
+        &self,
+        level: UnitDBusLevel,
+        primary_name: &str,
+        who: KillWho,
+        signal: i32,
+    ) -> Result<(), SystemdErrors> {
+        sysdbus::kill_unit(level, primary_name, who, signal)
+    }
+
+    fn queue_signal_unit(
+        &self,
+        level: UnitDBusLevel,
+        primary_name: &str,
+        who: KillWho,
+        signal: i32,
+        value: i32,
+    ) -> Result<(), SystemdErrors> {
+        sysdbus::queue_signal_unit(level, primary_name, who, signal, value)
+    }
+
+    fn freeze_unit(&self, _level: UnitDBusLevel, primary_name: &str) -> Result<(), SystemdErrors> {
+        #[cfg(not(any(feature = "flatpak", feature = "appimage")))]
+        match _level {
+            UnitDBusLevel::System | UnitDBusLevel::Both => {
+                if proxy_switcher::PROXY_SWITCHER.freeze() {
+                    crate::proxy_call_blocking!(freeze_unit, primary_name)
+                } else {
+                    let proxy = sysdbus::dbus_proxies::systemd_manager();
+                    proxy.freeze_unit(primary_name)?;
+                    Ok(())
+                }
+            }
+            _ => systemd_manager_blocking(_level)?
+                .freeze_unit(primary_name)
+                .map_err(|err| err.into()),
+        }
+
+        #[cfg(any(feature = "flatpak", feature = "appimage"))]
+        {
+            let proxy = sysdbus::dbus_proxies::systemd_manager();
+            proxy.freeze_unit(primary_name)?;
+            Ok(())
+        }
+    }
+
+    fn thaw_unit(&self, level: UnitDBusLevel, primary_name: &str) -> Result<(), SystemdErrors> {
+        #[cfg(not(any(feature = "flatpak", feature = "appimage")))]
+        match level {
+            UnitDBusLevel::System | UnitDBusLevel::Both => {
+                if proxy_switcher::PROXY_SWITCHER.thaw() {
+                    crate::proxy_call_blocking!(thaw_unit, primary_name)
+                } else {
+                    let proxy = sysdbus::dbus_proxies::systemd_manager();
+                    proxy.thaw_unit(primary_name)?;
+                    Ok(())
+                }
+            }
+            _ => systemd_manager_blocking(level)?
+                .thaw_unit(primary_name)
+                .map_err(|err| err.into()),
+        }
+
+        #[cfg(any(feature = "flatpak", feature = "appimage"))]
+        {
+            let proxy = systemd_manager_blocking(level)?;
+            proxy.thaw_unit(primary_name)?;
+            Ok(())
+        }
+    }
+
+    fn clean_unit(
+        &self,
+        level: UnitDBusLevel,
+        unit_name: &str,
+        what: &[&str],
+    ) -> Result<(), SystemdErrors> {
+        #[cfg(not(any(feature = "flatpak", feature = "appimage")))]
+        match level {
+            UnitDBusLevel::System | UnitDBusLevel::Both => {
+                if proxy_switcher::PROXY_SWITCHER.clean() {
+                    crate::proxy_call_blocking!(clean_unit, unit_name, what)
+                } else {
+                    let proxy = sysdbus::dbus_proxies::systemd_manager();
+                    proxy.clean_unit(unit_name, what).map_err(|err| err.into())
+                }
+            }
+            _ => systemd_manager_blocking(level)?
+                .clean_unit(unit_name, what)
+                .map_err(|err| err.into()),
+        }
+
+        #[cfg(any(feature = "flatpak", feature = "appimage"))]
+        {
+            systemd_manager_blocking(level)?
+                .clean_unit(unit_name, what)
+                .map_err(|err| err.into())
+        }
+    }
+
+    fn enable_unit_file(
+        &self,
+        level: UnitDBusLevel,
+        unit_file: &str,
+        flags: BitFlags<DisEnableFlags>,
+    ) -> Result<DisEnAbleUnitFilesResponse, SystemdErrors> {
+        if level.is_other_user() {
+            return other_user::enable_unit_file(unit_file, flags);
+        }
+
+        #[cfg(not(any(feature = "flatpak", feature = "appimage")))]
+        match level {
+            UnitDBusLevel::System | UnitDBusLevel::Both => {
+                if proxy_switcher::PROXY_SWITCHER.enable_unit_file() {
+                    crate::proxy_call_blocking!(
+                        enable_unit_files_with_flags,
+                        &[unit_file],
+                        flags.bits_c() as u64
+                    )
+                } else {
+                    sysdbus::dbus_proxies::systemd_manager()
+                        .enable_unit_files_with_flags(&[unit_file], flags.bits_c() as u64)
+                        .map_err(|err| err.into())
+                }
+            }
+            _ => systemd_manager_blocking(level)?
+                .enable_unit_files_with_flags(&[unit_file], flags.bits_c() as u64)
+                .map_err(|err| err.into()),
+        }
+
+        #[cfg(any(feature = "flatpak", feature = "appimage"))]
+        {
+            systemd_manager_blocking(level)?
+                .enable_unit_files_with_flags(&[unit_file], flags.bits_c() as u64)
+                .map_err(|err| err.into())
+        }
+    }
+
+    fn disable_unit_file(
+        &self,
+        level: UnitDBusLevel,
+        unit_file: &str,
+        flags: BitFlags<DisEnableFlags>,
+    ) -> Result<DisEnAbleUnitFilesResponse, SystemdErrors> {
+        info!("{:?} {} {:?}", level, unit_file, flags.bits_c());
+        if level.is_other_user() {
+            return other_user::disable_unit_file(unit_file, flags);
+        }
+
+        #[cfg(not(any(feature = "flatpak", feature = "appimage")))]
+        match level {
+            UnitDBusLevel::System | UnitDBusLevel::Both => {
+                if proxy_switcher::PROXY_SWITCHER.disable_unit_file() {
+                    crate::proxy_call_blocking!(
+                        disable_unit_files_with_flags,
+                        &[unit_file],
+                        flags.bits_c() as u64
+                    )
+                } else {
+                    sysdbus::dbus_proxies::systemd_manager()
+                        .disable_unit_files_with_flags_and_install_info(
+                            &[unit_file],
+                            flags.bits_c() as u64,
+                        )
+                        .map_err(|err| err.into())
+                }
+            }
+            _ => systemd_manager_blocking(level)?
+                .disable_unit_files_with_flags_and_install_info(&[unit_file], flags.bits_c() as u64)
+                .map_err(|err| err.into()),
+        }
+
+        #[cfg(any(feature = "flatpak", feature = "appimage"))]
+        {
+            systemd_manager_blocking(level)?
+                .disable_unit_files_with_flags_and_install_info(&[unit_file], flags.bits_c() as u64)
+                .map_err(|err| err.into())
+        }
+    }
+
+    fn mask_unit_file(
+        &self,
+        level: UnitDBusLevel,
+        primary_name: &str,
+        runtime: bool,
+        force: bool,
+    ) -> Result<Vec<DisEnAbleUnitFiles>, SystemdErrors> {
+        sysdbus::mask_unit_files(level, &[primary_name], runtime, force)
+    }
+
+    fn unmask_unit_file(
+        &self,
+        level: UnitDBusLevel,
+        primary_name: &str,
+        runtime: bool,
+    ) -> Result<Vec<DisEnAbleUnitFiles>, SystemdErrors> {
+        sysdbus::unmask_unit_files(level, &[primary_name], runtime)
+    }
+
+    fn preset_unit_file(
+        &self,
+        level: UnitDBusLevel,
+        primary_name: &str,
+        runtime: bool,
+        force: bool,
+    ) -> Result<DisEnAbleUnitFilesResponse, SystemdErrors> {
+        sysdbus::preset_unit_file(level, &[primary_name], runtime, force)
+    }
+
+    fn reenable_unit_file(
+        &self,
+        level: UnitDBusLevel,
+        primary_name: &str,
+        runtime: bool,
+        force: bool,
+    ) -> Result<DisEnAbleUnitFilesResponse, SystemdErrors> {
+        sysdbus::reenable_unit_file(level, &[primary_name], runtime, force)
+    }
+
+    fn link_unit_file(
+        &self,
+        level: UnitDBusLevel,
+        unit_file: &str,
+        runtime: bool,
+        force: bool,
+    ) -> Result<Vec<DisEnAbleUnitFiles>, SystemdErrors> {
+        sysdbus::link_unit_files(level, &[unit_file], runtime, force)
+    }
+
+    fn fetch_drop_in_paths<'a>(
+        &'a self,
+        level: UnitDBusLevel,
+        unit_name: &'a str,
+    ) -> BoxFuture<'a, Result<Vec<String>, SystemdErrors>> {
+        Box::pin(sysdbus::fetch_drop_in_paths(level, unit_name))
+    }
+
+    fn fetch_unit_file_content(
+        &self,
+        file_path: Option<&str>,
+        unit_primary_name: &str,
+    ) -> Result<String, SystemdErrors> {
+        let Some(file_path) = file_path else {
+            warn!("No file path for {:?}", unit_primary_name);
+            return Ok(String::new());
+        };
+
+        crate::file_open_get_content(file_path, unit_primary_name)
+    }
+
+    fn get_unit_journal(
+        &self,
+        source: &JournalSource,
+        unit_names: &[String],
+        level: UnitDBusLevel,
+        boot_filter: BootFilter,
+        field_matches: &FieldMatches,
+        range: EventRange,
+        message_max_char: usize,
+        timestamp_style: TimestampStyle,
+    ) -> Result<JournalEventChunk, SystemdErrors> {
+        journal::get_unit_journal_events(
+            source,
+            unit_names,
+            level,
+            boot_filter,
+            field_matches,
+            range,
+            message_max_char,
+            timestamp_style,
+        )
+    }
+
+    fn get_unit_journal_around_cursor(
+        &self,
+        source: &JournalSource,
+        unit_names: &[String],
+        level: UnitDBusLevel,
+        field_matches: &FieldMatches,
+        cursor: &str,
+        context: usize,
+        message_max_char: usize,
+        timestamp_style: TimestampStyle,
+    ) -> Result<JournalEventChunk, SystemdErrors> {
+        journal::get_unit_journal_events_around_cursor(
+            source,
+            unit_names,
+            level,
+            field_matches,
+            cursor,
+            context,
+            message_max_char,
+            timestamp_style,
+        )
+    }
+
+    fn get_unit_journal_continuous(
+        &self,
+        unit_names: Vec<String>,
+        level: UnitDBusLevel,
+        field_matches: FieldMatches,
+        range: EventRange,
+        journal_continuous_receiver: Receiver<()>,
+        sender: Sender<JournalEventChunk>,
+        message_max_char: usize,
+        timestamp_style: TimestampStyle,
+        check_for_new_journal_entry: fn(),
+    ) -> Result<(), SystemdErrors> {
+        journal::get_unit_journal_events_continuous(
+            unit_names,
+            level,
+            field_matches,
+            range,
+            journal_continuous_receiver,
+            sender,
+            message_max_char,
+            timestamp_style,
+            check_for_new_journal_entry,
+        )
+    }
+
+    fn list_unit_journal_facets(
+        &self,
+        source: &JournalSource,
+        unit_names: &[String],
+        level: UnitDBusLevel,
+        boot_filter: BootFilter,
+        field_matches: &FieldMatches,
+        fields: &[&str],
+        max_entries: usize,
+    ) -> Result<Vec<JournalFacet>, SystemdErrors> {
+        journal::list_unit_facets(
+            source,
+            unit_names,
+            level,
+            boot_filter,
+            field_matches,
+            fields,
+            max_entries,
+        )
+    }
+
+    fn fetch_unit_dependencies(
+        &self,
+        level: UnitDBusLevel,
+        primary_name: &str,
+        object_path: &str,
+        dependency_type: DependencyType,
+        plain: bool,
+    ) -> Result<Dependency, SystemdErrors> {
+        sysdbus::unit_get_dependencies(level, primary_name, object_path, dependency_type, plain)
+    }
+}
+
+async fn wait_job_removed(
+    job_id: u32,
+    mut watcher: broadcast::Receiver<SystemdSignal>,
+) -> Result<(), SystemdErrors> {
+    loop {
+        match watcher.recv().await {
+            Ok(SystemdSignal::JobRemoved(_level, id, _, _unit, result)) if id == job_id => {
+                match result.as_str() {
+                    DONE => {
+                        break;
+                    }
+                    CANCELED => return Err(SystemdErrors::JobRemoved(CANCELED.to_owned())),
+                    TIMEOUT => return Err(SystemdErrors::JobRemoved(TIMEOUT.to_owned())),
+                    FAILED => return Err(SystemdErrors::JobRemoved(FAILED.to_owned())),
+                    DEPENDENCY => return Err(SystemdErrors::JobRemoved(DEPENDENCY.to_owned())),
+                    SKIPPED => return Err(SystemdErrors::JobRemoved(SKIPPED.to_owned())),
+                    INVALID => return Err(SystemdErrors::JobRemoved(INVALID.to_owned())),
+                    unkown_result => {
+                        warn!("Unknown JobRemoved result {unkown_result}");
+                    }
+                }
+            }
+            Ok(_) => {}
+            Err(RecvError::Lagged(lag)) => info!("Lagged {lag:?}"),
+            Err(err) => {
+                warn!("Recev Err {err:?}");
+                return Err(SystemdErrors::JobRemoved(format!("{err:?}")));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn job_number(job: &str) -> Option<u32> {
+    job.rsplit_once('/').and_then(|(_, job_id)| {
+        job_id
+            .parse::<u32>()
+            .inspect_err(|err| warn!("Job {err:?}"))
+            .ok()
+    })
+}
+
+async fn restartstop_unit_call(
+    level: UnitDBusLevel,
+    unit_name: &str,
+    mode: StartStopMode,
+    action: &ReStartStop,
+) -> Result<String, SystemdErrors> {
+    #[cfg(not(any(feature = "flatpak", feature = "appimage")))]
+    match level {
+        UnitDBusLevel::System | UnitDBusLevel::Both => {
+            use crate::sysdbus::to_proxy::get_proxy_async;
+
+            let proxy = get_proxy_async().await?;
+            if action.use_proxy() && !unit_name.starts_with(PROXY_SERVICE) {
+                match action.action(&proxy, unit_name, mode).await {
+                    Ok(ok) => Ok(ok),
+                    Err(SystemdErrors::ZFdoServiceUnknowm(msg)) => {
+                        warn!("Async ServiceUnkown: {:?} Function: {:?}", msg, action);
+                        to_proxy::lazy_start_proxy_async().await;
+                        action.action(&proxy, unit_name, mode).await
+                    }
+                    Err(err) => Err(err),
+                }
+            } else {
+                let manager = sysdbus::dbus_proxies::system_manager_system_async().await?;
+                action.systemd_action(manager, unit_name, mode).await
+            }
+        }
+
+        _ => {
+            let manager = systemd_manager_async(level).await?;
+            action.systemd_action(&manager, unit_name, mode).await
+        }
+    }
+
+    #[cfg(any(feature = "flatpak", feature = "appimage"))]
+    {
+        let manager = systemd_manager_async(level).await?;
+        action.systemd_action(&manager, unit_name, mode).await
+    }
+}
+
+async fn daemon_reload_core(level: UnitDBusLevel) -> Result<(), SystemdErrors> {
+    #[cfg(not(any(feature = "flatpak", feature = "appimage")))]
+    if level.bypasses_proxy() || !proxy_switcher::PROXY_SWITCHER.reload() {
+        info!("Reloading Daemon - Direct");
+        systemd_manager_async(level)
+            .await?
+            .reload()
+            .await
+            .map_err(|err| err.into())
+    } else {
+        info!("Reloading Daemon - Proxy");
+        crate::proxy_call_async!(reload,)
+    }
+
+    #[cfg(any(feature = "flatpak", feature = "appimage"))]
+    {
+        info!("Reloading Daemon - Direct");
+        systemd_manager_async(level)
+            .await?
+            .reload()
+            .await
+            .map_err(|err| err.into())
+    }
+}
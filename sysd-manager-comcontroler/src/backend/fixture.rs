@@ -0,0 +1,1634 @@
+use super::SystemdBackend;
+use crate::{
+    BootFilter, CompleteUnitPropertiesCallParams, Dependency, ListUnitResponse, ReStartStop,
+    UnitProperties, UnitPropertiesFlags, UpdatedUnitInfo,
+    data::{ListedLoadedUnit, UnitProcess, UnitPropertySetter},
+    enums::{
+        ActiveState, DependencyType, DisEnableFlags, KillWho, LoadState, Preset, StartStopMode,
+        UnitFileStatus, UnitType,
+    },
+    errors::SystemdErrors,
+    journal::make_prefix,
+    journal_data::{
+        EventRange, FieldMatches, JournalEvent, JournalEventChunk, JournalEventChunkInfo,
+        JournalFacet, JournalSource, WhatGrab,
+    },
+    sysdbus::{ListedUnitFile, unit_dbus_path_from_name},
+    time_handling::TimestampStyle,
+};
+use base::{
+    enums::UnitDBusLevel,
+    proxy::{DisEnAbleUnitFiles, DisEnAbleUnitFilesResponse},
+};
+use enumflags2::BitFlags;
+use futures_util::future::BoxFuture;
+use glib::Quark;
+use std::{
+    cmp::Reverse,
+    collections::{BTreeSet, HashMap, HashSet},
+    sync::{
+        Mutex,
+        atomic::{AtomicU32, Ordering},
+        mpsc::{Receiver, Sender},
+    },
+};
+use tracing::{info, warn};
+use zvariant::{OwnedObjectPath, OwnedValue, Str, Value};
+
+const SYSTEM_UNIT_DIR: &str = "/usr/lib/systemd/system";
+const USER_UNIT_DIR: &str = "/usr/lib/systemd/user";
+const JOB_PATH: &str = "/org/freedesktop/systemd1/job/";
+const CONFIG_DIR: &str = "/etc/systemd/system";
+const RUNTIME_CONFIG_DIR: &str = "/run/systemd/system";
+/// Pid of the main process of the first unit
+const MAIN_PID_START: u32 = 2000;
+
+/// First journal event of the fixture, 2025-10-09 08:53:20 UTC
+const JOURNAL_START_USEC: u64 = 1_760_000_000_000_000;
+/// Time between two journal events of a unit
+const JOURNAL_STEP_USEC: u64 = 60_000_000;
+
+/// One unit of the fixture
+#[derive(Debug, Clone)]
+pub struct FixtureUnit {
+    pub name: String,
+    pub description: String,
+    pub level: UnitDBusLevel,
+    pub load_state: LoadState,
+    pub active_state: ActiveState,
+    pub sub_state: String,
+    pub enablement_status: UnitFileStatus,
+    pub preset: String,
+    pub drop_ins: Vec<String>,
+    /// Forward dependencies, the reverse ones are deduced
+    pub wants: Vec<String>,
+    /// Ordering, `Before` is deduced
+    pub after: Vec<String>,
+    pub frozen: bool,
+}
+
+impl FixtureUnit {
+    /// An inactive and disabled system unit
+    pub fn new(name: &str, description: &str) -> Self {
+        FixtureUnit {
+            name: name.to_owned(),
+            description: description.to_owned(),
+            level: UnitDBusLevel::System,
+            load_state: LoadState::Loaded,
+            active_state: ActiveState::Inactive,
+            sub_state: "dead".to_owned(),
+            enablement_status: UnitFileStatus::Disabled,
+            preset: "disabled".to_owned(),
+            drop_ins: Vec::new(),
+            wants: Vec::new(),
+            after: Vec::new(),
+            frozen: false,
+        }
+    }
+
+    pub fn user(mut self) -> Self {
+        self.level = UnitDBusLevel::UserSession;
+        self
+    }
+
+    pub fn active(mut self, sub_state: &str) -> Self {
+        self.active_state = ActiveState::Active;
+        self.sub_state = sub_state.to_owned();
+        self
+    }
+
+    pub fn failed(mut self) -> Self {
+        self.active_state = ActiveState::Failed;
+        self.sub_state = "failed".to_owned();
+        self
+    }
+
+    pub fn enablement(mut self, enablement_status: UnitFileStatus) -> Self {
+        self.enablement_status = enablement_status;
+        if enablement_status == UnitFileStatus::Enabled {
+            self.preset = "enabled".to_owned();
+        }
+        self
+    }
+
+    pub fn drop_in(mut self, file_name: &str) -> Self {
+        let path = format!("{CONFIG_DIR}/{}.d/{file_name}", self.name);
+        self.drop_ins.push(path);
+        self
+    }
+
+    pub fn wants(mut self, units: &[&str]) -> Self {
+        self.wants.extend(units.iter().map(|unit| unit.to_string()));
+        self
+    }
+
+    pub fn after(mut self, units: &[&str]) -> Self {
+        self.after.extend(units.iter().map(|unit| unit.to_string()));
+        self
+    }
+
+    pub fn fragment_path(&self) -> String {
+        let dir = match self.level {
+            UnitDBusLevel::UserSession => USER_UNIT_DIR,
+            _ => SYSTEM_UNIT_DIR,
+        };
+        format!("{dir}/{}", self.name)
+    }
+
+    fn at_level(&self, level: UnitDBusLevel) -> bool {
+        level == UnitDBusLevel::Both || self.level == level
+    }
+
+    fn unit_type(&self) -> &str {
+        self.name.rsplit_once('.').map_or("", |(_, ext)| ext)
+    }
+
+    fn listed_loaded_unit(&self) -> ListedLoadedUnit {
+        let unit_object_path =
+            OwnedObjectPath::try_from(unit_dbus_path_from_name(&self.name)).unwrap_or_default();
+
+        ListedLoadedUnit {
+            primary_unit_name: self.name.clone(),
+            description: self.description.clone(),
+            load_state: self.load_state.as_str().to_owned(),
+            active_state: self.active_state.as_str().to_owned(),
+            sub_state: self.sub_state.clone(),
+            followed_unit: String::new(),
+            unit_object_path,
+            numeric_job_id: 0,
+            job_type: String::new(),
+            job_object_path: OwnedObjectPath::default(),
+        }
+    }
+
+    fn listed_unit_file(&self) -> ListedUnitFile {
+        ListedUnitFile {
+            unit_file_path: self.fragment_path(),
+            enablement_status: self.enablement_status.as_str().to_owned(),
+        }
+    }
+
+    fn updated_unit_info(&self) -> UpdatedUnitInfo {
+        UpdatedUnitInfo {
+            primary: self.name.clone(),
+            description: Some(self.description.clone()),
+            load_state: Some(self.load_state),
+            sub_state: Some(self.sub_state.clone()),
+            active_state: Some(self.active_state),
+            unit_file_preset: Some(self.preset.clone()),
+            valid_unit_name: true,
+            fragment_path: Some(self.fragment_path()),
+            enablement_status: Some(self.enablement_status),
+            level: self.level,
+        }
+    }
+
+    /// The D-Bus properties the fixture knows, all on the unit interface
+    fn properties(&self) -> Vec<(&'static str, OwnedValue)> {
+        let freezer_state = if self.frozen { "frozen" } else { "running" };
+
+        vec![
+            ("Id", str_value(&self.name)),
+            ("Description", str_value(&self.description)),
+            ("LoadState", str_value(self.load_state.as_str())),
+            ("ActiveState", str_value(self.active_state.as_str())),
+            ("SubState", str_value(&self.sub_state)),
+            ("FreezerState", str_value(freezer_state)),
+            ("UnitFileState", str_value(self.enablement_status.as_str())),
+            ("UnitFilePreset", str_value(&self.preset)),
+            ("FragmentPath", str_value(&self.fragment_path())),
+            ("DropInPaths", strv_value(&self.drop_ins)),
+            ("Wants", strv_value(&self.wants)),
+            ("After", strv_value(&self.after)),
+        ]
+    }
+
+    fn property(&self, name: &str) -> Option<OwnedValue> {
+        self.properties()
+            .into_iter()
+            .find(|(key, _)| *key == name)
+            .map(|(_, value)| value)
+    }
+
+    fn managed_property(&self, flag: UnitPropertiesFlags) -> UnitPropertySetter {
+        match flag {
+            UnitPropertiesFlags::EnablementStatus => {
+                UnitPropertySetter::FileState(self.enablement_status)
+            }
+            UnitPropertiesFlags::ActiveStatus => UnitPropertySetter::ActiveState(self.active_state),
+            UnitPropertiesFlags::Description => {
+                UnitPropertySetter::Description(self.description.clone())
+            }
+            UnitPropertiesFlags::LoadState => UnitPropertySetter::LoadState(self.load_state),
+            UnitPropertiesFlags::SubState => UnitPropertySetter::SubState(self.sub_state.clone()),
+            UnitPropertiesFlags::UnitFilePreset => {
+                UnitPropertySetter::UnitFilePreset(Preset::from(self.preset.as_str()))
+            }
+            UnitPropertiesFlags::FragmentPath => {
+                UnitPropertySetter::FragmentPath(self.fragment_path())
+            }
+        }
+    }
+
+    fn cgroup(&self) -> String {
+        match self.level {
+            UnitDBusLevel::UserSession => {
+                format!(
+                    "/user.slice/user-1000.slice/user@1000.service/app.slice/{}",
+                    self.name
+                )
+            }
+            _ => format!("/system.slice/{}", self.name),
+        }
+    }
+
+    fn file_content(&self) -> String {
+        let mut content = format!("[Unit]\nDescription={}\n", self.description);
+        if !self.wants.is_empty() {
+            content.push_str(&format!("Wants={}\n", self.wants.join(" ")));
+        }
+        if !self.after.is_empty() {
+            content.push_str(&format!("After={}\n", self.after.join(" ")));
+        }
+
+        match self.unit_type() {
+            "service" => content.push_str(&format!(
+                "\n[Service]\nExecStart=/usr/bin/{}\n",
+                self.name.trim_end_matches(".service")
+            )),
+            "timer" => content.push_str("\n[Timer]\nOnCalendar=daily\nPersistent=true\n"),
+            "socket" => content.push_str("\n[Socket]\nListenStream=631\n"),
+            _ => {}
+        }
+
+        if self.enablement_status == UnitFileStatus::Enabled {
+            content.push_str("\n[Install]\nWantedBy=multi-user.target\n");
+        }
+
+        content
+    }
+
+    /// Priority and message of the unit events, oldest first
+    fn journal_messages(&self) -> Vec<(u8, String)> {
+        let mut messages = vec![
+            (6, format!("Starting {}...", self.description)),
+            (6, format!("Started {}.", self.description)),
+        ];
+
+        match self.active_state {
+            ActiveState::Failed => {
+                messages.push((
+                    3,
+                    "Main process exited, code=exited, status=1/FAILURE".into(),
+                ));
+                messages.push((4, "Failed with result 'exit-code'.".into()));
+                messages.push((3, format!("Failed to start {}.", self.description)));
+            }
+            ActiveState::Active => {
+                messages.push((7, "Configuration loaded".into()));
+                messages.push((5, "Ready".into()));
+            }
+            _ => {
+                messages.push((6, format!("Stopping {}...", self.description)));
+                messages.push((6, format!("Stopped {}.", self.description)));
+            }
+        }
+
+        messages
+    }
+}
+
+/// One journal entry of the fixture, before being formatted
+struct FixtureEntry {
+    tag: usize,
+    unit_name: String,
+    identifier: String,
+    pid: String,
+    priority: u8,
+    timestamp: u64,
+    message: String,
+}
+
+impl FixtureEntry {
+    fn field(&self, field: &str) -> Option<String> {
+        let value = match field {
+            "PRIORITY" => self.priority.to_string(),
+            "SYSLOG_IDENTIFIER" | "_COMM" => self.identifier.clone(),
+            "_PID" => self.pid.clone(),
+            "_EXE" => format!("/usr/bin/{}", self.identifier),
+            "_SYSTEMD_UNIT" => self.unit_name.clone(),
+            "MESSAGE" => self.message.clone(),
+            _ => return None,
+        };
+        Some(value)
+    }
+}
+
+/// In-memory units for demos and screenshot tests, the answers only depend on the calls made
+#[derive(Debug)]
+pub struct FixtureBackend {
+    units: Mutex<Vec<FixtureUnit>>,
+    next_job: AtomicU32,
+}
+
+impl Default for FixtureBackend {
+    fn default() -> Self {
+        FixtureBackend::with_units(default_units())
+    }
+}
+
+impl FixtureBackend {
+    pub fn with_units(units: Vec<FixtureUnit>) -> Self {
+        FixtureBackend {
+            units: Mutex::new(units),
+            next_job: AtomicU32::new(1),
+        }
+    }
+
+    pub fn unit(&self, level: UnitDBusLevel, name: &str) -> Option<FixtureUnit> {
+        self.units
+            .lock()
+            .expect("fixture units")
+            .iter()
+            .find(|unit| unit.name == name && unit.at_level(level))
+            .cloned()
+    }
+
+    fn unit_at_path(&self, level: UnitDBusLevel, object_path: &str) -> Option<FixtureUnit> {
+        self.units
+            .lock()
+            .expect("fixture units")
+            .iter()
+            .find(|unit| {
+                unit_dbus_path_from_name(&unit.name) == object_path && unit.at_level(level)
+            })
+            .cloned()
+    }
+
+    fn select<T>(
+        &self,
+        level: UnitDBusLevel,
+        patterns: &[&str],
+        map: impl Fn(&FixtureUnit) -> T,
+    ) -> Vec<T> {
+        self.units
+            .lock()
+            .expect("fixture units")
+            .iter()
+            .filter(|unit| unit.at_level(level))
+            .filter(|unit| patterns.is_empty() || patterns.iter().any(|p| glob(p, &unit.name)))
+            .map(map)
+            .collect()
+    }
+
+    fn update<T>(
+        &self,
+        level: UnitDBusLevel,
+        name: &str,
+        update: impl FnOnce(&mut FixtureUnit) -> T,
+    ) -> Result<T, SystemdErrors> {
+        let mut units = self.units.lock().expect("fixture units");
+        let unit = units
+            .iter_mut()
+            .find(|unit| unit.name == name && unit.at_level(level))
+            .ok_or_else(|| no_such_unit(name))?;
+        Ok(update(unit))
+    }
+
+    fn collect_dependencies(
+        &self,
+        level: UnitDBusLevel,
+        dependency: &mut Dependency,
+        dependency_type: DependencyType,
+        visited: &mut HashSet<String>,
+    ) {
+        let units = self.units.lock().expect("fixture units").clone();
+
+        let children: Vec<&FixtureUnit> = units
+            .iter()
+            .filter(|unit| unit.at_level(level))
+            .filter(|unit| {
+                let Some(parent) = units
+                    .iter()
+                    .find(|u| u.name == dependency.unit_name && u.at_level(level))
+                else {
+                    return false;
+                };
+
+                match dependency_type {
+                    DependencyType::Forward => parent.wants.contains(&unit.name),
+                    DependencyType::Reverse => unit.wants.contains(&parent.name),
+                    DependencyType::After => parent.after.contains(&unit.name),
+                    DependencyType::Before => unit.after.contains(&parent.name),
+                }
+            })
+            .collect();
+
+        for child in children {
+            let mut child_dependency = Dependency::new(&child.name);
+            child_dependency.state = child.active_state;
+
+            if visited.insert(child.name.clone()) {
+                self.collect_dependencies(level, &mut child_dependency, dependency_type, visited);
+            }
+            dependency.children.insert(child_dependency);
+        }
+    }
+
+    /// The journal entries of the units, interleaved in time
+    fn journal_entries(&self, unit_names: &[String], level: UnitDBusLevel) -> Vec<FixtureEntry> {
+        let mut entries = Vec::new();
+
+        for (tag, unit_name) in unit_names.iter().enumerate() {
+            let Some(unit) = self.unit(level, unit_name) else {
+                continue;
+            };
+
+            let identifier = unit_name
+                .rsplit_once('.')
+                .map_or(unit_name.as_str(), |(stem, _)| stem);
+            let pid = (1000 + tag * 100).to_string();
+
+            for (idx, (priority, message)) in unit.journal_messages().into_iter().enumerate() {
+                // Interleave the units
+                let timestamp =
+                    JOURNAL_START_USEC + (idx * unit_names.len() + tag) as u64 * JOURNAL_STEP_USEC;
+
+                entries.push(FixtureEntry {
+                    tag,
+                    unit_name: unit_name.clone(),
+                    identifier: identifier.to_owned(),
+                    pid: pid.clone(),
+                    priority,
+                    timestamp,
+                    message,
+                });
+            }
+        }
+
+        entries
+    }
+
+    fn next_job_path(&self) -> String {
+        let job_id = self.next_job.fetch_add(1, Ordering::Relaxed);
+        format!("{JOB_PATH}{job_id}")
+    }
+
+    fn disenable(
+        &self,
+        level: UnitDBusLevel,
+        unit_file: &str,
+        status: UnitFileStatus,
+        change_type: &str,
+    ) -> Result<DisEnAbleUnitFilesResponse, SystemdErrors> {
+        let fragment_path = self.update(level, unit_file, |unit| {
+            unit.enablement_status = status;
+            unit.fragment_path()
+        })?;
+
+        let change = DisEnAbleUnitFiles {
+            change_type: change_type.to_owned(),
+            file_name: format!("{CONFIG_DIR}/multi-user.target.wants/{unit_file}"),
+            destination: fragment_path,
+        };
+
+        Ok(DisEnAbleUnitFilesResponse::new(true, vec![change]))
+    }
+}
+
+impl SystemdBackend for FixtureBackend {
+    fn name(&self) -> &'static str {
+        "fixture"
+    }
+
+    fn is_live(&self) -> bool {
+        false
+    }
+
+    fn list_loaded_units(
+        &self,
+        level: UnitDBusLevel,
+    ) -> BoxFuture<'_, Result<ListUnitResponse, SystemdErrors>> {
+        self.list_loaded_units_by_patterns(level, &[])
+    }
+
+    fn list_loaded_units_by_patterns<'a>(
+        &'a self,
+        level: UnitDBusLevel,
+        patterns: &'a [&'a str],
+    ) -> BoxFuture<'a, Result<ListUnitResponse, SystemdErrors>> {
+        let units = self.select(level, patterns, FixtureUnit::listed_loaded_unit);
+        Box::pin(async move { Ok(ListUnitResponse::Loaded(level, units)) })
+    }
+
+    fn list_unit_files(
+        &self,
+        level: UnitDBusLevel,
+    ) -> BoxFuture<'_, Result<ListUnitResponse, SystemdErrors>> {
+        self.list_unit_files_by_patterns(level, &[])
+    }
+
+    fn list_unit_files_by_patterns<'a>(
+        &'a self,
+        level: UnitDBusLevel,
+        patterns: &'a [&'a str],
+    ) -> BoxFuture<'a, Result<ListUnitResponse, SystemdErrors>> {
+        let files = self.select(level, patterns, FixtureUnit::listed_unit_file);
+        Box::pin(async move { Ok(ListUnitResponse::File(level, files)) })
+    }
+
+    fn complete_unit_information<'a>(
+        &'a self,
+        units: &'a [CompleteUnitPropertiesCallParams],
+    ) -> BoxFuture<'a, Result<Vec<UpdatedUnitInfo>, SystemdErrors>> {
+        let updates = units
+            .iter()
+            .filter_map(|params| self.unit(params.level, &params.unit_name))
+            .map(|unit| unit.updated_unit_info())
+            .collect();
+
+        Box::pin(async move { Ok(updates) })
+    }
+
+    fn get_unit_active_state(
+        &self,
+        level: UnitDBusLevel,
+        primary_name: &str,
+    ) -> Result<ActiveState, SystemdErrors> {
+        self.unit(level, primary_name)
+            .map(|unit| unit.active_state)
+            .ok_or_else(|| no_such_unit(primary_name))
+    }
+
+    fn fetch_unit_properties<'a>(
+        &'a self,
+        level: UnitDBusLevel,
+        unit_primary_name: &'a str,
+        _path: &'a str,
+        unit_properties: UnitProperties,
+        properties: Vec<(UnitType, &'a str, Quark)>,
+    ) -> BoxFuture<'a, Result<Vec<UnitPropertySetter>, SystemdErrors>> {
+        let result = self
+            .unit(level, unit_primary_name)
+            .ok_or_else(|| no_such_unit(unit_primary_name))
+            .map(|unit| {
+                let mut output: Vec<_> = unit_properties
+                    .0
+                    .into_iter()
+                    .map(|flag| unit.managed_property(flag))
+                    .collect();
+
+                for (_unit_type, property, quark) in properties {
+                    match unit.property(property) {
+                        Some(value) => output.push(UnitPropertySetter::Custom(quark, value)),
+                        None => {
+                            warn!("No property {property:?} for {unit_primary_name} in the fixture")
+                        }
+                    }
+                }
+                output
+            });
+
+        Box::pin(async move { result })
+    }
+
+    fn fetch_unit_property_blocking(
+        &self,
+        level: UnitDBusLevel,
+        unit_primary_name: &str,
+        _unit_type: UnitType,
+        unit_property: &str,
+    ) -> Result<OwnedValue, SystemdErrors> {
+        let unit = self
+            .unit(level, unit_primary_name)
+            .ok_or_else(|| no_such_unit(unit_primary_name))?;
+
+        unit.property(unit_property).ok_or_else(|| {
+            SystemdErrors::ZBusFdoError(zbus::fdo::Error::UnknownProperty(format!(
+                "Unknown property {unit_property}"
+            )))
+        })
+    }
+
+    fn fetch_system_unit_info_native(
+        &self,
+        level: UnitDBusLevel,
+        object_path: &str,
+        _unit_type: UnitType,
+    ) -> Result<Vec<(UnitType, String, OwnedValue)>, SystemdErrors> {
+        let unit = self
+            .unit_at_path(level, object_path)
+            .ok_or_else(|| no_such_unit(object_path))?;
+
+        Ok(unit
+            .properties()
+            .into_iter()
+            .map(|(key, value)| (UnitType::Unit, key.to_owned(), value))
+            .collect())
+    }
+
+    fn fetch_system_unit_info_native_map(
+        &self,
+        level: UnitDBusLevel,
+        object_path: &str,
+        _unit_type: UnitType,
+    ) -> Result<HashMap<String, OwnedValue>, SystemdErrors> {
+        let unit = self
+            .unit_at_path(level, object_path)
+            .ok_or_else(|| no_such_unit(object_path))?;
+
+        Ok(unit
+            .properties()
+            .into_iter()
+            .map(|(key, value)| (key.to_owned(), value))
+            .collect())
+    }
+
+    /// The main process of the running services, the other units have none
+    fn retreive_unit_processes(
+        &self,
+        level: UnitDBusLevel,
+        unit_name: &str,
+    ) -> Result<Vec<UnitProcess>, SystemdErrors> {
+        let units = self.units.lock().expect("fixture units");
+        let Some((idx, unit)) = units
+            .iter()
+            .enumerate()
+            .find(|(_, unit)| unit.name == unit_name && unit.at_level(level))
+        else {
+            return Err(no_such_unit(unit_name));
+        };
+
+        if unit.active_state != ActiveState::Active || unit.unit_type() != "service" {
+            return Ok(Vec::new());
+        }
+
+        let path = unit.cgroup();
+        let unit_name_idx = path.len() - unit.name.len();
+        let process = UnitProcess {
+            path,
+            pid: MAIN_PID_START + idx as u32,
+            name: format!("/usr/bin/{}", unit.name.trim_end_matches(".service")),
+            unit_name: unit_name_idx,
+        };
+
+        Ok(vec![process])
+    }
+
+    /// The wanted units, the fixture has no slice
+    fn fetch_unit_members(
+        &self,
+        level: UnitDBusLevel,
+        unit_name: &str,
+    ) -> Result<Vec<String>, SystemdErrors> {
+        let mut members = self
+            .unit(level, unit_name)
+            .map(|unit| unit.wants)
+            .ok_or_else(|| no_such_unit(unit_name))?;
+
+        members.sort();
+        Ok(members)
+    }
+
+    fn restartstop_unit<'a>(
+        &'a self,
+        level: UnitDBusLevel,
+        unit_name: &'a str,
+        mode: StartStopMode,
+        action: ReStartStop,
+    ) -> BoxFuture<'a, Result<String, SystemdErrors>> {
+        info!("Fixture {action:?} {unit_name} {mode:?}");
+
+        let result = self.update(level, unit_name, |unit| match action {
+            ReStartStop::Start | ReStartStop::Restart | ReStartStop::ReloadUnit => {
+                unit.active_state = ActiveState::Active;
+                unit.sub_state = match unit.unit_type() {
+                    "timer" => "waiting",
+                    "socket" => "listening",
+                    "target" => "active",
+                    "mount" => "mounted",
+                    _ => "running",
+                }
+                .to_owned();
+            }
+            ReStartStop::Stop => {
+                unit.active_state = ActiveState::Inactive;
+                unit.sub_state = "dead".to_owned();
+            }
+        });
+
+        let job = result.map(|_| self.next_job_path());
+
+        Box::pin(async move { job })
+    }
+
+    fn daemon_reload(&self, level: UnitDBusLevel) -> BoxFuture<'_, Result<(), SystemdErrors>> {
+        info!("Fixture daemon reload {level:?}");
+        Box::pin(async move { Ok(()) })
+    }
+
+    fn reload_unit(
+        &self,
+        level: UnitDBusLevel,
+        primary_name: &str,
+        mode: StartStopMode,
+    ) -> Result<String, SystemdErrors> {
+        info!("Fixture reload {primary_name} {mode:?}");
+
+        let active = self.update(level, primary_name, |unit| {
+            unit.active_state == ActiveState::Active
+        })?;
+
+        if !active {
+            return Err(SystemdErrors::ZJobTypeNotApplicable(
+                String::new(),
+                format!("Job type reload is not applicable for unit {primary_name}."),
+            ));
+        }
+
+        Ok(self.next_job_path())
+    }
+
+    /// Only the termination signals sent to the main process change the state
+    fn kill_unit(
+        &self,
+        level: UnitDBusLevel,
+        primary_name: &str,
+        who: KillWho,
+        signal: i32,
+    ) -> Result<(), SystemdErrors> {
+        info!("Fixture kill {primary_name} {who:?} signal {signal}");
+
+        self.update(level, primary_name, |unit| {
+            if unit.active_state != ActiveState::Active || matches!(who, KillWho::Control) {
+                return;
+            }
+
+            match signal {
+                libc::SIGKILL => {
+                    unit.active_state = ActiveState::Failed;
+                    unit.sub_state = "failed".to_owned();
+                }
+                libc::SIGTERM | libc::SIGINT => {
+                    unit.active_state = ActiveState::Inactive;
+                    unit.sub_state = "dead".to_owned();
+                }
+                _ => {}
+            }
+        })
+    }
+
+    fn queue_signal_unit(
+        &self,
+        level: UnitDBusLevel,
+        primary_name: &str,
+        who: KillWho,
+        signal: i32,
+        value: i32,
+    ) -> Result<(), SystemdErrors> {
+        info!("Fixture queue signal {primary_name} {who:?} signal {signal} value {value}");
+
+        self.unit(level, primary_name)
+            .map(|_| ())
+            .ok_or_else(|| no_such_unit(primary_name))
+    }
+
+    fn freeze_unit(&self, level: UnitDBusLevel, primary_name: &str) -> Result<(), SystemdErrors> {
+        self.update(level, primary_name, |unit| {
+            if unit.active_state != ActiveState::Active {
+                return Err(SystemdErrors::Custom(format!(
+                    "Unit {primary_name} is not active."
+                )));
+            }
+            unit.frozen = true;
+            Ok(())
+        })?
+    }
+
+    fn thaw_unit(&self, level: UnitDBusLevel, primary_name: &str) -> Result<(), SystemdErrors> {
+        self.update(level, primary_name, |unit| unit.frozen = false)
+    }
+
+    fn clean_unit(
+        &self,
+        level: UnitDBusLevel,
+        unit_name: &str,
+        what: &[&str],
+    ) -> Result<(), SystemdErrors> {
+        info!("Fixture clean {unit_name} {what:?}");
+
+        let cleanable = self.update(level, unit_name, |unit| {
+            matches!(
+                unit.active_state,
+                ActiveState::Inactive | ActiveState::Failed
+            )
+        })?;
+
+        if !cleanable {
+            return Err(SystemdErrors::Custom(format!(
+                "Unit {unit_name} is not inactive or failed, refusing to clean."
+            )));
+        }
+
+        Ok(())
+    }
+
+    fn enable_unit_file(
+        &self,
+        level: UnitDBusLevel,
+        unit_file: &str,
+        flags: BitFlags<DisEnableFlags>,
+    ) -> Result<DisEnAbleUnitFilesResponse, SystemdErrors> {
+        let status = if flags.contains(DisEnableFlags::SdSystemdUnitRuntime) {
+            UnitFileStatus::EnabledRuntime
+        } else {
+            UnitFileStatus::Enabled
+        };
+
+        self.disenable(level, unit_file, status, "symlink")
+    }
+
+    fn disable_unit_file(
+        &self,
+        level: UnitDBusLevel,
+        unit_file: &str,
+        _flags: BitFlags<DisEnableFlags>,
+    ) -> Result<DisEnAbleUnitFilesResponse, SystemdErrors> {
+        self.disenable(level, unit_file, UnitFileStatus::Disabled, "unlink")
+    }
+
+    fn mask_unit_file(
+        &self,
+        level: UnitDBusLevel,
+        primary_name: &str,
+        runtime: bool,
+        _force: bool,
+    ) -> Result<Vec<DisEnAbleUnitFiles>, SystemdErrors> {
+        let status = if runtime {
+            UnitFileStatus::MaskedRuntime
+        } else {
+            UnitFileStatus::Masked
+        };
+        self.update(level, primary_name, |unit| unit.enablement_status = status)?;
+
+        let change = DisEnAbleUnitFiles {
+            change_type: "symlink".to_owned(),
+            file_name: format!("{}/{primary_name}", config_dir(runtime)),
+            destination: "/dev/null".to_owned(),
+        };
+
+        Ok(vec![change])
+    }
+
+    fn unmask_unit_file(
+        &self,
+        level: UnitDBusLevel,
+        primary_name: &str,
+        runtime: bool,
+    ) -> Result<Vec<DisEnAbleUnitFiles>, SystemdErrors> {
+        let was_masked = self.update(level, primary_name, |unit| {
+            let masked = matches!(
+                unit.enablement_status,
+                UnitFileStatus::Masked | UnitFileStatus::MaskedRuntime
+            );
+            if masked {
+                unit.enablement_status = UnitFileStatus::Disabled;
+            }
+            masked
+        })?;
+
+        if !was_masked {
+            return Ok(Vec::new());
+        }
+
+        let change = DisEnAbleUnitFiles {
+            change_type: "unlink".to_owned(),
+            file_name: format!("{}/{primary_name}", config_dir(runtime)),
+            destination: String::new(),
+        };
+
+        Ok(vec![change])
+    }
+
+    fn preset_unit_file(
+        &self,
+        level: UnitDBusLevel,
+        primary_name: &str,
+        _runtime: bool,
+        _force: bool,
+    ) -> Result<DisEnAbleUnitFilesResponse, SystemdErrors> {
+        let preset = self
+            .unit(level, primary_name)
+            .map(|unit| Preset::from(unit.preset.as_str()))
+            .ok_or_else(|| no_such_unit(primary_name))?;
+
+        match preset {
+            Preset::Enabled => {
+                self.disenable(level, primary_name, UnitFileStatus::Enabled, "symlink")
+            }
+            _ => self.disenable(level, primary_name, UnitFileStatus::Disabled, "unlink"),
+        }
+    }
+
+    fn reenable_unit_file(
+        &self,
+        level: UnitDBusLevel,
+        primary_name: &str,
+        runtime: bool,
+        _force: bool,
+    ) -> Result<DisEnAbleUnitFilesResponse, SystemdErrors> {
+        let status = if runtime {
+            UnitFileStatus::EnabledRuntime
+        } else {
+            UnitFileStatus::Enabled
+        };
+
+        self.disenable(level, primary_name, status, "symlink")
+    }
+
+    /// Adds the unit if the fixture doesn't have it yet
+    fn link_unit_file(
+        &self,
+        level: UnitDBusLevel,
+        unit_file: &str,
+        runtime: bool,
+        _force: bool,
+    ) -> Result<Vec<DisEnAbleUnitFiles>, SystemdErrors> {
+        let Some((_, unit_name)) = unit_file
+            .rsplit_once('/')
+            .filter(|(_, name)| !name.is_empty())
+        else {
+            return Err(SystemdErrors::InvalidPath(unit_file.to_owned()));
+        };
+
+        let status = if runtime {
+            UnitFileStatus::LinkedRuntime
+        } else {
+            UnitFileStatus::Linked
+        };
+
+        if self
+            .update(level, unit_name, |unit| unit.enablement_status = status)
+            .is_err()
+        {
+            let mut unit = FixtureUnit::new(unit_name, unit_name).enablement(status);
+            if level == UnitDBusLevel::UserSession {
+                unit = unit.user();
+            }
+            self.units.lock().expect("fixture units").push(unit);
+        }
+
+        let change = DisEnAbleUnitFiles {
+            change_type: "symlink".to_owned(),
+            file_name: format!("{}/{unit_name}", config_dir(runtime)),
+            destination: unit_file.to_owned(),
+        };
+
+        Ok(vec![change])
+    }
+
+    fn fetch_drop_in_paths<'a>(
+        &'a self,
+        level: UnitDBusLevel,
+        unit_name: &'a str,
+    ) -> BoxFuture<'a, Result<Vec<String>, SystemdErrors>> {
+        let drop_ins = self
+            .unit(level, unit_name)
+            .map(|unit| unit.drop_ins)
+            .ok_or_else(|| no_such_unit(unit_name));
+
+        Box::pin(async move { drop_ins })
+    }
+
+    fn fetch_unit_file_content(
+        &self,
+        file_path: Option<&str>,
+        unit_primary_name: &str,
+    ) -> Result<String, SystemdErrors> {
+        let Some(file_path) = file_path else {
+            return Ok(String::new());
+        };
+
+        let units = self.units.lock().expect("fixture units");
+
+        if let Some(unit) = units.iter().find(|unit| unit.fragment_path() == file_path) {
+            return Ok(unit.file_content());
+        }
+
+        if units
+            .iter()
+            .any(|unit| unit.drop_ins.iter().any(|drop_in| drop_in == file_path))
+        {
+            return Ok("[Service]\nRestart=on-failure\n".to_owned());
+        }
+
+        Err(SystemdErrors::NoFilePathforUnit(
+            unit_primary_name.to_owned(),
+        ))
+    }
+
+    fn get_unit_journal(
+        &self,
+        _source: &JournalSource,
+        unit_names: &[String],
+        level: UnitDBusLevel,
+        _boot_filter: BootFilter,
+        _field_matches: &FieldMatches,
+        range: EventRange,
+        message_max_char: usize,
+        timestamp_style: TimestampStyle,
+    ) -> Result<JournalEventChunk, SystemdErrors> {
+        let tagged = unit_names.len() > 1;
+        let mut events: Vec<JournalEvent> = self
+            .journal_entries(unit_names, level)
+            .into_iter()
+            .map(|entry| {
+                let unit_tag = tagged.then_some(entry.unit_name.as_str());
+                let prefix = make_prefix(
+                    entry.timestamp,
+                    entry.identifier.clone(),
+                    entry.pid.clone(),
+                    timestamp_style,
+                    unit_tag,
+                );
+                let message: String = entry.message.chars().take(message_max_char).collect();
+
+                JournalEvent::new_param(entry.priority, entry.timestamp, prefix, message)
+                    .with_unit_tag(tagged.then_some(entry.tag as u8))
+            })
+            .collect();
+
+        events.retain(|event| {
+            range
+                .min_priority
+                .is_none_or(|min_priority| event.priority <= min_priority)
+                && !range.is_before_since(event.timestamp)
+                && !range.is_after_until(event.timestamp)
+        });
+
+        match range.what_grab {
+            WhatGrab::Newer => {
+                events.retain(|event| {
+                    range
+                        .newest_events_time
+                        .is_none_or(|newest| event.timestamp > newest)
+                });
+                events.sort_by_key(|event| event.timestamp);
+            }
+            WhatGrab::Older => {
+                events.retain(|event| {
+                    range
+                        .oldest_events_time
+                        .is_none_or(|oldest| event.timestamp < oldest)
+                });
+                events.sort_by_key(|event| Reverse(event.timestamp));
+            }
+        }
+
+        let info = if events.len() > range.batch_size {
+            JournalEventChunkInfo::ChunkMaxReached
+        } else {
+            JournalEventChunkInfo::NoMore
+        };
+
+        let mut chunk = JournalEventChunk::new_info(range.batch_size, info, range.what_grab);
+        for event in events.into_iter().take(range.batch_size) {
+            chunk.push(event);
+        }
+
+        Ok(chunk)
+    }
+
+    /// The fixture doesn't give cursors, whatever the cursor there is no entry at it
+    fn get_unit_journal_around_cursor(
+        &self,
+        _source: &JournalSource,
+        _unit_names: &[String],
+        _level: UnitDBusLevel,
+        _field_matches: &FieldMatches,
+        cursor: &str,
+        _context: usize,
+        _message_max_char: usize,
+        _timestamp_style: TimestampStyle,
+    ) -> Result<JournalEventChunk, SystemdErrors> {
+        Err(SystemdErrors::JournalError(format!(
+            "No journal entry at cursor {cursor}"
+        )))
+    }
+
+    /// No event is ever appended to the fixture journal, only waits for the end of the tail
+    fn get_unit_journal_continuous(
+        &self,
+        unit_names: Vec<String>,
+        _level: UnitDBusLevel,
+        _field_matches: FieldMatches,
+        _range: EventRange,
+        journal_continuous_receiver: Receiver<()>,
+        _sender: Sender<JournalEventChunk>,
+        _message_max_char: usize,
+        _timestamp_style: TimestampStyle,
+        _check_for_new_journal_entry: fn(),
+    ) -> Result<(), SystemdErrors> {
+        let _ = journal_continuous_receiver.recv();
+        info!("Terminating fixture journal loop for {unit_names:?}.");
+        Ok(())
+    }
+
+    fn list_unit_journal_facets(
+        &self,
+        _source: &JournalSource,
+        unit_names: &[String],
+        level: UnitDBusLevel,
+        _boot_filter: BootFilter,
+        _field_matches: &FieldMatches,
+        fields: &[&str],
+        max_entries: usize,
+    ) -> Result<Vec<JournalFacet>, SystemdErrors> {
+        let mut entries = self.journal_entries(unit_names, level);
+        entries.sort_by_key(|entry| Reverse(entry.timestamp));
+        if max_entries != 0 {
+            entries.truncate(max_entries);
+        }
+
+        let facets = fields
+            .iter()
+            .map(|field| {
+                let mut counts: HashMap<String, usize> = HashMap::new();
+                for value in entries.iter().filter_map(|entry| entry.field(field)) {
+                    *counts.entry(value).or_default() += 1;
+                }
+
+                let mut values: Vec<(String, usize)> = counts.into_iter().collect();
+                values.sort_by(|(a_value, a_count), (b_value, b_count)| {
+                    b_count.cmp(a_count).then_with(|| a_value.cmp(b_value))
+                });
+                JournalFacet {
+                    field: field.to_string(),
+                    values,
+                }
+            })
+            .collect();
+
+        Ok(facets)
+    }
+
+    fn fetch_unit_dependencies(
+        &self,
+        level: UnitDBusLevel,
+        primary_name: &str,
+        _object_path: &str,
+        dependency_type: DependencyType,
+        plain: bool,
+    ) -> Result<Dependency, SystemdErrors> {
+        let unit = self
+            .unit(level, primary_name)
+            .ok_or_else(|| no_such_unit(primary_name))?;
+
+        let mut dependency = Dependency::new(primary_name);
+        dependency.state = unit.active_state;
+
+        let mut visited = HashSet::from([primary_name.to_owned()]);
+        self.collect_dependencies(level, &mut dependency, dependency_type, &mut visited);
+
+        if plain {
+            let mut all_children = BTreeSet::new();
+            flatten(&dependency, &mut all_children);
+            dependency.children = all_children;
+        }
+
+        Ok(dependency)
+    }
+}
+
+/// The units of the demos
+pub fn default_units() -> Vec<FixtureUnit> {
+    vec![
+        FixtureUnit::new("multi-user.target", "Multi-User System")
+            .active("active")
+            .enablement(UnitFileStatus::Static)
+            .wants(&[
+                "sshd.service",
+                "nginx.service",
+                "cups.socket",
+                "backup.timer",
+                "broken.service",
+            ])
+            .after(&["basic.target"]),
+        FixtureUnit::new("basic.target", "Basic System")
+            .active("active")
+            .enablement(UnitFileStatus::Static)
+            .wants(&["tmp.mount"]),
+        FixtureUnit::new("tmp.mount", "Temporary Directory /tmp")
+            .active("mounted")
+            .enablement(UnitFileStatus::Static),
+        FixtureUnit::new("sshd.service", "OpenSSH Daemon")
+            .active("running")
+            .enablement(UnitFileStatus::Enabled)
+            .after(&["basic.target"]),
+        FixtureUnit::new("nginx.service", "A high performance web server")
+            .active("running")
+            .enablement(UnitFileStatus::Enabled)
+            .drop_in("override.conf")
+            .after(&["basic.target", "sshd.service"]),
+        FixtureUnit::new("cups.socket", "CUPS Scheduler")
+            .active("listening")
+            .enablement(UnitFileStatus::Enabled),
+        FixtureUnit::new("cups.service", "CUPS Scheduler").after(&["cups.socket"]),
+        FixtureUnit::new("backup.timer", "Daily backup")
+            .active("waiting")
+            .enablement(UnitFileStatus::Enabled)
+            .wants(&["backup.service"]),
+        FixtureUnit::new("backup.service", "Backup of the home directories")
+            .enablement(UnitFileStatus::Static)
+            .after(&["backup.timer"]),
+        FixtureUnit::new("broken.service", "Service failing at start").failed(),
+        FixtureUnit::new("pipewire.service", "PipeWire Multimedia Service")
+            .user()
+            .active("running")
+            .enablement(UnitFileStatus::Enabled),
+        FixtureUnit::new(
+            "syncthing.service",
+            "Syncthing - Open Source Continuous File Synchronization",
+        )
+        .user(),
+    ]
+}
+
+fn config_dir(runtime: bool) -> &'static str {
+    if runtime {
+        RUNTIME_CONFIG_DIR
+    } else {
+        CONFIG_DIR
+    }
+}
+
+fn str_value(value: &str) -> OwnedValue {
+    OwnedValue::from(Str::from(value))
+}
+
+fn strv_value(values: &[String]) -> OwnedValue {
+    OwnedValue::try_from(Value::from(values.to_vec())).expect("string array without fd")
+}
+
+fn no_such_unit(name: &str) -> SystemdErrors {
+    SystemdErrors::ZNoSuchUnit(String::new(), format!("Unit {name} not loaded."))
+}
+
+fn flatten(parent: &Dependency, all_children: &mut BTreeSet<Dependency>) {
+    for child in parent.children.iter() {
+        flatten(child, all_children);
+        all_children.insert(child.partial_clone());
+    }
+}
+
+/// Shell-style match supporting `*` and `?`, as systemd does for the unit patterns
+fn glob(pattern: &str, name: &str) -> bool {
+    fn matches(pattern: &[char], name: &[char]) -> bool {
+        match (pattern.first(), name.first()) {
+            (None, None) => true,
+            (Some('*'), _) => {
+                matches(&pattern[1..], name) || (!name.is_empty() && matches(pattern, &name[1..]))
+            }
+            (Some('?'), Some(_)) => matches(&pattern[1..], &name[1..]),
+            (Some(p), Some(n)) if p == n => matches(&pattern[1..], &name[1..]),
+            _ => false,
+        }
+    }
+
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    matches(&pattern, &name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture() -> FixtureBackend {
+        FixtureBackend::default()
+    }
+
+    #[test]
+    fn test_glob() {
+        assert!(glob("*.timer", "backup.timer"));
+        assert!(glob("backup.?imer", "backup.timer"));
+        assert!(glob("sshd.service", "sshd.service"));
+        assert!(!glob("*.timer", "backup.service"));
+        assert!(!glob("sshd", "sshd.service"));
+    }
+
+    #[test]
+    fn test_fixture_not_live() {
+        assert!(!fixture().is_live());
+        assert!(crate::backend::DBusBackend.is_live());
+    }
+
+    #[test]
+    fn test_fixture_listing() {
+        let fixture = fixture();
+
+        let response = crate::runtime()
+            .block_on(fixture.list_loaded_units_by_patterns(UnitDBusLevel::System, &["*.timer"]))
+            .unwrap();
+
+        let ListUnitResponse::Loaded(_, units) = response else {
+            panic!("Loaded units expected");
+        };
+        assert_eq!(units.len(), 1);
+        assert_eq!(units[0].primary_unit_name, "backup.timer");
+        assert_eq!(units[0].sub_state, "waiting");
+
+        let response = crate::runtime()
+            .block_on(fixture.list_unit_files(UnitDBusLevel::UserSession))
+            .unwrap();
+        assert_eq!(response.t_len(), 2);
+    }
+
+    #[test]
+    fn test_fixture_lifecycle() {
+        let fixture = fixture();
+        let level = UnitDBusLevel::System;
+
+        let job = crate::runtime()
+            .block_on(fixture.restartstop_unit(
+                level,
+                "sshd.service",
+                StartStopMode::Replace,
+                ReStartStop::Stop,
+            ))
+            .unwrap();
+        assert_eq!(job, format!("{JOB_PATH}1"));
+        assert_eq!(
+            fixture
+                .get_unit_active_state(level, "sshd.service")
+                .unwrap(),
+            ActiveState::Inactive
+        );
+
+        let response = fixture
+            .enable_unit_file(level, "cups.service", DisEnableFlags::empty())
+            .unwrap();
+        assert_eq!(response.changes().len(), 1);
+        assert_eq!(
+            fixture
+                .unit(level, "cups.service")
+                .unwrap()
+                .enablement_status,
+            UnitFileStatus::Enabled
+        );
+
+        let result = crate::runtime().block_on(fixture.restartstop_unit(
+            level,
+            "missing.service",
+            StartStopMode::Replace,
+            ReStartStop::Start,
+        ));
+        assert!(matches!(result, Err(SystemdErrors::ZNoSuchUnit(_, _))));
+    }
+
+    #[test]
+    fn test_fixture_journal() {
+        let fixture = fixture();
+        let units = ["broken.service".to_owned(), "sshd.service".to_owned()];
+
+        let range = EventRange::new(WhatGrab::Older, 3, None, None);
+        let chunk = fixture
+            .get_unit_journal(
+                &JournalSource::Local,
+                &units,
+                UnitDBusLevel::System,
+                BootFilter::Current,
+                &FieldMatches::default(),
+                range,
+                100,
+                TimestampStyle::Unix,
+            )
+            .unwrap();
+
+        assert_eq!(chunk.len(), 3);
+        assert!(matches!(
+            chunk.info(),
+            JournalEventChunkInfo::ChunkMaxReached
+        ));
+        let (newest, oldest) = (chunk.first().unwrap(), chunk.last().unwrap());
+        assert!(newest.timestamp > oldest.timestamp);
+
+        let range =
+            EventRange::new(WhatGrab::Newer, 100, None, None).with_filters(Some(3), None, None);
+        let chunk = fixture
+            .get_unit_journal(
+                &JournalSource::Local,
+                &units,
+                UnitDBusLevel::System,
+                BootFilter::Current,
+                &FieldMatches::default(),
+                range,
+                100,
+                TimestampStyle::Unix,
+            )
+            .unwrap();
+
+        assert_eq!(chunk.len(), 2);
+        assert!(chunk.iter().all(|event| event.unit_tag == Some(0)));
+    }
+
+    #[test]
+    fn test_fixture_dependencies() {
+        let fixture = fixture();
+        let level = UnitDBusLevel::System;
+
+        let dependency = fixture
+            .fetch_unit_dependencies(
+                level,
+                "multi-user.target",
+                "",
+                DependencyType::Forward,
+                false,
+            )
+            .unwrap();
+        assert_eq!(dependency.children.len(), 5);
+        let timer = dependency
+            .children
+            .iter()
+            .find(|child| child.unit_name == "backup.timer")
+            .unwrap();
+        assert_eq!(timer.children.len(), 1);
+
+        let dependency = fixture
+            .fetch_unit_dependencies(
+                level,
+                "multi-user.target",
+                "",
+                DependencyType::Forward,
+                true,
+            )
+            .unwrap();
+        assert_eq!(dependency.children.len(), 6);
+
+        let dependency = fixture
+            .fetch_unit_dependencies(level, "basic.target", "", DependencyType::Before, false)
+            .unwrap();
+        let names: Vec<_> = dependency
+            .children
+            .iter()
+            .map(|d| d.unit_name.as_str())
+            .collect();
+        assert_eq!(
+            names,
+            ["multi-user.target", "nginx.service", "sshd.service"]
+        );
+    }
+
+    #[test]
+    fn test_fixture_properties() {
+        let fixture = fixture();
+        let level = UnitDBusLevel::System;
+
+        let description = fixture
+            .fetch_unit_property_blocking(level, "sshd.service", UnitType::Service, "Description")
+            .unwrap();
+        assert_eq!(String::try_from(description).unwrap(), "OpenSSH Daemon");
+
+        let result =
+            fixture.fetch_unit_property_blocking(level, "sshd.service", UnitType::Service, "Nope");
+        assert!(matches!(result, Err(SystemdErrors::ZBusFdoError(_))));
+
+        let quark = Quark::from_str("SubState");
+        let setters = crate::runtime()
+            .block_on(fixture.fetch_unit_properties(
+                level,
+                "backup.timer",
+                "",
+                UnitProperties(
+                    UnitPropertiesFlags::ActiveStatus | UnitPropertiesFlags::Description,
+                ),
+                vec![
+                    (UnitType::Unit, "SubState", quark),
+                    (UnitType::Unit, "Nope", quark),
+                ],
+            ))
+            .unwrap();
+        assert_eq!(setters.len(), 3);
+        assert!(matches!(
+            setters[2],
+            UnitPropertySetter::Custom(custom_quark, _) if custom_quark == quark
+        ));
+
+        let object_path = unit_dbus_path_from_name("nginx.service");
+        let map = fixture
+            .fetch_system_unit_info_native_map(level, &object_path, UnitType::Service)
+            .unwrap();
+        assert_eq!(
+            String::try_from(map["FragmentPath"].clone()).unwrap(),
+            "/usr/lib/systemd/system/nginx.service"
+        );
+    }
+
+    #[test]
+    fn test_fixture_unit_operations() {
+        let fixture = fixture();
+        let level = UnitDBusLevel::System;
+
+        let result = fixture.freeze_unit(level, "cups.service");
+        assert!(matches!(result, Err(SystemdErrors::Custom(_))));
+        fixture.freeze_unit(level, "nginx.service").unwrap();
+        assert!(fixture.unit(level, "nginx.service").unwrap().frozen);
+        fixture.thaw_unit(level, "nginx.service").unwrap();
+        assert!(!fixture.unit(level, "nginx.service").unwrap().frozen);
+
+        let result = fixture.reload_unit(level, "cups.service", StartStopMode::Replace);
+        assert!(matches!(
+            result,
+            Err(SystemdErrors::ZJobTypeNotApplicable(_, _))
+        ));
+
+        fixture
+            .kill_unit(level, "nginx.service", KillWho::Main, libc::SIGTERM)
+            .unwrap();
+        assert_eq!(
+            fixture
+                .get_unit_active_state(level, "nginx.service")
+                .unwrap(),
+            ActiveState::Inactive
+        );
+
+        assert!(fixture.clean_unit(level, "sshd.service", &["all"]).is_err());
+        fixture
+            .clean_unit(level, "nginx.service", &["all"])
+            .unwrap();
+
+        let changes = fixture
+            .mask_unit_file(level, "cups.service", false, false)
+            .unwrap();
+        assert_eq!(changes[0].destination, "/dev/null");
+        assert_eq!(
+            fixture
+                .unit(level, "cups.service")
+                .unwrap()
+                .enablement_status,
+            UnitFileStatus::Masked
+        );
+        let changes = fixture
+            .unmask_unit_file(level, "cups.service", false)
+            .unwrap();
+        assert_eq!(changes.len(), 1);
+
+        fixture
+            .link_unit_file(level, "/opt/app/app.service", false, false)
+            .unwrap();
+        assert_eq!(
+            fixture
+                .unit(level, "app.service")
+                .unwrap()
+                .enablement_status,
+            UnitFileStatus::Linked
+        );
+    }
+
+    #[test]
+    fn test_fixture_processes_and_members() {
+        let fixture = fixture();
+        let level = UnitDBusLevel::System;
+
+        let processes = fixture
+            .retreive_unit_processes(level, "sshd.service")
+            .unwrap();
+        assert_eq!(processes.len(), 1);
+        assert_eq!(processes[0].unit_name(), "sshd.service");
+        assert!(
+            fixture
+                .retreive_unit_processes(level, "cups.service")
+                .unwrap()
+                .is_empty()
+        );
+
+        let members = fixture
+            .fetch_unit_members(level, "multi-user.target")
+            .unwrap();
+        assert_eq!(members.len(), 5);
+        assert_eq!(members[0], "backup.timer");
+    }
+
+    #[test]
+    fn test_fixture_journal_facets() {
+        let fixture = fixture();
+        let units = ["broken.service".to_owned()];
+
+        let facets = fixture
+            .list_unit_journal_facets(
+                &JournalSource::Local,
+                &units,
+                UnitDBusLevel::System,
+                BootFilter::Current,
+                &FieldMatches::default(),
+                &["PRIORITY", "_PID", "CODE_FILE"],
+                0,
+            )
+            .unwrap();
+
+        assert_eq!(facets.len(), 3);
+        assert_eq!(facets[0].values[0], ("3".to_owned(), 2));
+        assert_eq!(facets[1].values, [("1000".to_owned(), 5)]);
+        assert!(facets[2].values.is_empty());
+    }
+}
@@ -0,0 +1,389 @@
+//! Local expansion of systemd calendar event specifications (`OnCalendar=`).
+//!
+//! Supports the subset of `systemd.time(7)` that covers the vast majority of timers:
+//! shorthands (`daily`, `weekly`, ...), weekday lists and ranges, wildcards, value lists,
+//! ranges (`a..b`) and repetitions (`a/r`, `*/r`) in every date and time component,
+//! plus a trailing `UTC` time zone.
+
+use chrono::{
+    DateTime, Datelike, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeDelta, TimeZone, Utc,
+};
+
+const WEEKDAYS: [&str; 7] = ["mon", "tue", "wed", "thu", "fri", "sat", "sun"];
+
+/// Don't look further than this number of days for the next occurrence
+const MAX_DAYS_LOOKUP: u32 = 366 * 8;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Item {
+    start: u32,
+    end: u32,
+    repeat: u32,
+}
+
+impl Item {
+    fn matches(&self, value: u32) -> bool {
+        if value < self.start || value > self.end {
+            return false;
+        }
+
+        (value - self.start).is_multiple_of(self.repeat.max(1))
+    }
+}
+
+/// One date or time component, an empty list means any value
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+struct Component(Vec<Item>);
+
+impl Component {
+    fn any() -> Self {
+        Component(vec![])
+    }
+
+    fn value(value: u32) -> Self {
+        Component(vec![Item {
+            start: value,
+            end: value,
+            repeat: 1,
+        }])
+    }
+
+    fn matches(&self, value: u32) -> bool {
+        self.0.is_empty() || self.0.iter().any(|item| item.matches(value))
+    }
+
+    fn parse(text: &str, max: u32) -> Result<Self, String> {
+        if text == "*" {
+            return Ok(Component::any());
+        }
+
+        let mut items = Vec::new();
+        for part in text.split(',') {
+            let (range, repeat) = match part.split_once('/') {
+                Some((range, repeat)) => (range, parse_number(repeat)?),
+                None => (part, 1),
+            };
+
+            let (start, end) = if range == "*" {
+                (0, max)
+            } else if let Some((start, end)) = range.split_once("..") {
+                (parse_number(start)?, parse_number(end)?)
+            } else {
+                let start = parse_number(range)?;
+                let end = if repeat > 1 { max } else { start };
+                (start, end)
+            };
+
+            if start > end || end > max {
+                return Err(format!("Invalid range {part:?}"));
+            }
+
+            items.push(Item { start, end, repeat });
+        }
+
+        Ok(Component(items))
+    }
+
+    /// All the matching values in the `min..=max` range
+    fn values(&self, min: u32, max: u32) -> impl Iterator<Item = u32> + '_ {
+        (min..=max).filter(|value| self.matches(*value))
+    }
+}
+
+fn parse_number(text: &str) -> Result<u32, String> {
+    // Drop fractional seconds
+    let text = text.split_once('.').map(|(int, _)| int).unwrap_or(text);
+    text.parse::<u32>()
+        .map_err(|err| format!("Invalid number {text:?}: {err}"))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CalendarSpec {
+    weekdays: u8,
+    years: Component,
+    months: Component,
+    days: Component,
+    hours: Component,
+    minutes: Component,
+    seconds: Component,
+    utc: bool,
+}
+
+impl CalendarSpec {
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let mut spec = spec.trim();
+
+        let mut utc = false;
+        if let Some((head, zone)) = spec.rsplit_once(char::is_whitespace)
+            && zone.eq_ignore_ascii_case("utc")
+        {
+            utc = true;
+            spec = head.trim_end();
+        }
+
+        let normalized = match spec.to_lowercase().as_str() {
+            "minutely" => "*-*-* *:*:00".to_owned(),
+            "hourly" => "*-*-* *:00:00".to_owned(),
+            "daily" => "*-*-* 00:00:00".to_owned(),
+            "monthly" => "*-*-01 00:00:00".to_owned(),
+            "weekly" => "Mon *-*-* 00:00:00".to_owned(),
+            "yearly" | "annually" => "*-01-01 00:00:00".to_owned(),
+            "quarterly" => "*-01,04,07,10-01 00:00:00".to_owned(),
+            "semiannually" => "*-01,07-01 00:00:00".to_owned(),
+            _ => spec.to_owned(),
+        };
+
+        let tokens: Vec<&str> = normalized.split_whitespace().collect();
+        if tokens.is_empty() {
+            return Err("Empty calendar specification".to_owned());
+        }
+
+        let mut weekdays = 0b111_1111;
+        let mut years = Component::any();
+        let mut months = Component::any();
+        let mut days = Component::any();
+        let mut hours = Component::value(0);
+        let mut minutes = Component::value(0);
+        let mut seconds = Component::value(0);
+
+        for token in tokens {
+            if token.starts_with(|c: char| c.is_ascii_alphabetic()) {
+                weekdays = parse_weekdays(token)?;
+            } else if token.contains(':') {
+                let parts: Vec<&str> = token.split(':').collect();
+                match parts.as_slice() {
+                    [h, m] => {
+                        hours = Component::parse(h, 23)?;
+                        minutes = Component::parse(m, 59)?;
+                        seconds = Component::value(0);
+                    }
+                    [h, m, s] => {
+                        hours = Component::parse(h, 23)?;
+                        minutes = Component::parse(m, 59)?;
+                        seconds = Component::parse(s, 59)?;
+                    }
+                    _ => return Err(format!("Invalid time {token:?}")),
+                }
+            } else if token.contains('-') {
+                if token.contains('~') {
+                    return Err(format!("Unsupported last day of month {token:?}"));
+                }
+
+                let parts: Vec<&str> = token.split('-').collect();
+                match parts.as_slice() {
+                    [m, d] => {
+                        months = Component::parse(m, 12)?;
+                        days = Component::parse(d, 31)?;
+                    }
+                    [y, m, d] => {
+                        years = Component::parse(y, 9999)?;
+                        months = Component::parse(m, 12)?;
+                        days = Component::parse(d, 31)?;
+                    }
+                    _ => return Err(format!("Invalid date {token:?}")),
+                }
+            } else {
+                return Err(format!("Unsupported token {token:?}"));
+            }
+        }
+
+        Ok(CalendarSpec {
+            weekdays,
+            years,
+            months,
+            days,
+            hours,
+            minutes,
+            seconds,
+            utc,
+        })
+    }
+
+    fn date_matches(&self, date: NaiveDate) -> bool {
+        let weekday_bit = 1 << date.weekday().num_days_from_monday();
+
+        self.weekdays & weekday_bit != 0
+            && self.years.matches(date.year() as u32)
+            && self.months.matches(date.month())
+            && self.days.matches(date.day())
+    }
+
+    fn to_usec(&self, date_time: NaiveDateTime) -> Option<u64> {
+        let timestamp = if self.utc {
+            Utc.from_local_datetime(&date_time)
+                .earliest()
+                .map(|d| d.timestamp_micros())
+        } else {
+            Local
+                .from_local_datetime(&date_time)
+                .earliest()
+                .map(|d| d.timestamp_micros())
+        };
+
+        timestamp.and_then(|t| u64::try_from(t).ok())
+    }
+
+    /// The next `count` elapse times (realtime µs) strictly after `after_usec`
+    pub fn next_elapses(&self, after_usec: u64, count: usize) -> Vec<u64> {
+        self.next_elapses_until(after_usec, u64::MAX, count)
+    }
+
+    /// The elapse times (realtime µs) strictly after `after_usec` and up to `until_usec`
+    pub fn next_elapses_until(&self, after_usec: u64, until_usec: u64, count: usize) -> Vec<u64> {
+        let mut out = Vec::new();
+        if count == 0 {
+            return out;
+        }
+
+        let Some(after) = DateTime::<Utc>::from_timestamp_micros(after_usec as i64) else {
+            return out;
+        };
+
+        let start_date = if self.utc {
+            after.date_naive()
+        } else {
+            after.with_timezone(&Local).date_naive()
+        } - TimeDelta::days(1);
+
+        for day_offset in 0..MAX_DAYS_LOOKUP {
+            let date = start_date + TimeDelta::days(day_offset as i64);
+
+            if !self.date_matches(date) {
+                continue;
+            }
+
+            for hour in self.hours.values(0, 23) {
+                for minute in self.minutes.values(0, 59) {
+                    for second in self.seconds.values(0, 59) {
+                        let Some(time) = NaiveTime::from_hms_opt(hour, minute, second) else {
+                            continue;
+                        };
+
+                        let Some(usec) = self.to_usec(date.and_time(time)) else {
+                            continue;
+                        };
+
+                        if usec > until_usec {
+                            return out;
+                        }
+
+                        if usec > after_usec {
+                            out.push(usec);
+                            if out.len() >= count {
+                                return out;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        out
+    }
+}
+
+fn weekday_index(text: &str) -> Result<u8, String> {
+    let lower = text.to_lowercase();
+    WEEKDAYS
+        .iter()
+        .position(|day| lower.starts_with(day))
+        .map(|idx| idx as u8)
+        .ok_or_else(|| format!("Invalid weekday {text:?}"))
+}
+
+fn parse_weekdays(text: &str) -> Result<u8, String> {
+    let mut mask = 0;
+
+    for part in text.split(',') {
+        if let Some((start, end)) = part.split_once("..") {
+            let start = weekday_index(start)?;
+            let end = weekday_index(end)?;
+            if start > end {
+                return Err(format!("Invalid weekday range {part:?}"));
+            }
+            for idx in start..=end {
+                mask |= 1 << idx;
+            }
+        } else {
+            mask |= 1 << weekday_index(part)?;
+        }
+    }
+
+    Ok(mask)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utc_usec(y: i32, mo: u32, d: u32, h: u32, mi: u32, s: u32) -> u64 {
+        Utc.with_ymd_and_hms(y, mo, d, h, mi, s)
+            .unwrap()
+            .timestamp_micros() as u64
+    }
+
+    #[test]
+    fn test_parse_shorthand() {
+        assert_eq!(
+            CalendarSpec::parse("daily").unwrap(),
+            CalendarSpec::parse("*-*-* 00:00:00").unwrap()
+        );
+        assert!(CalendarSpec::parse("fortnightly").is_err());
+        assert!(CalendarSpec::parse("*-*~01").is_err());
+    }
+
+    #[test]
+    fn test_daily_utc() {
+        let spec = CalendarSpec::parse("daily UTC").unwrap();
+        let after = utc_usec(2024, 3, 10, 12, 0, 0);
+        let next = spec.next_elapses(after, 2);
+        assert_eq!(
+            next,
+            vec![
+                utc_usec(2024, 3, 11, 0, 0, 0),
+                utc_usec(2024, 3, 12, 0, 0, 0)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_weekdays_and_repeat() {
+        let spec = CalendarSpec::parse("Mon..Fri *-*-* 8..9:00/30 UTC").unwrap();
+        // 2024-03-09 is a Saturday
+        let after = utc_usec(2024, 3, 9, 0, 0, 0);
+        let next = spec.next_elapses(after, 5);
+        assert_eq!(
+            next,
+            vec![
+                utc_usec(2024, 3, 11, 8, 0, 0),
+                utc_usec(2024, 3, 11, 8, 30, 0),
+                utc_usec(2024, 3, 11, 9, 0, 0),
+                utc_usec(2024, 3, 11, 9, 30, 0),
+                utc_usec(2024, 3, 12, 8, 0, 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_month_day_list() {
+        let spec = CalendarSpec::parse("*-01,07-01 06:15 UTC").unwrap();
+        let after = utc_usec(2024, 3, 9, 0, 0, 0);
+        let next = spec.next_elapses(after, 2);
+        assert_eq!(
+            next,
+            vec![
+                utc_usec(2024, 7, 1, 6, 15, 0),
+                utc_usec(2025, 1, 1, 6, 15, 0)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_until() {
+        let spec = CalendarSpec::parse("hourly UTC").unwrap();
+        let after = utc_usec(2024, 3, 9, 0, 30, 0);
+        let until = utc_usec(2024, 3, 9, 3, 0, 0);
+        let next = spec.next_elapses_until(after, until, 100);
+        assert_eq!(next.len(), 3);
+    }
+}
@@ -0,0 +1,249 @@
+//! Named connections to systemd managers reachable at a bus address given by the user,
+//! like the system bus of a container or of a machine through a socket.
+//!
+//! Each connection gets a [`UnitDBusLevel::Custom`] level carrying its id, so its units travel
+//! through the GUI like the system and user session ones. The calls go straight to the bus, the
+//! privileged proxy only serves the local system bus.
+
+use crate::{
+    errors::SystemdErrors,
+    sysdbus::{
+        INTERFACE_SYSTEMD_MANAGER, PATH_SYSTEMD, dbus_proxies::ZPropertiesProxy,
+        forget_custom_connection, watcher::stop_custom_signal_watcher,
+    },
+};
+use base::enums::UnitDBusLevel;
+use std::sync::{
+    RwLock,
+    atomic::{AtomicU32, Ordering},
+};
+use tracing::info;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CustomBus {
+    pub level: UnitDBusLevel,
+    /// Shown in the bus column of the unit list
+    pub label: String,
+    /// D-Bus address, i.e. `unix:path=/var/lib/machines/web/run/dbus/system_bus_socket`
+    pub address: String,
+}
+
+static CUSTOM_BUSES: RwLock<Vec<CustomBus>> = RwLock::new(Vec::new());
+
+/// Saved bus of the custom levels, followed by the connection label
+const SAVED_CONNECTION_PREFIX: &str = "connection:";
+
+/// Never reused, the units of a removed connection can't be taken for the ones of a new one
+static NEXT_CONNECTION_ID: AtomicU32 = AtomicU32::new(1);
+
+/// Register a connection under a new level
+pub fn add_custom_bus(label: &str, address: &str) -> Result<UnitDBusLevel, SystemdErrors> {
+    let label = label.trim();
+    let address = address.trim();
+
+    if label.is_empty() {
+        return Err(SystemdErrors::Custom(
+            "The connection needs a label".to_owned(),
+        ));
+    }
+
+    check_address(address)?;
+
+    let mut buses = CUSTOM_BUSES.write().unwrap();
+
+    if buses.iter().any(|bus| bus.label == label) {
+        return Err(SystemdErrors::Custom(format!(
+            "A connection is already labeled {label:?}"
+        )));
+    }
+
+    let level = UnitDBusLevel::Custom(NEXT_CONNECTION_ID.fetch_add(1, Ordering::Relaxed));
+
+    info!("Add bus connection {label:?} {address:?} as {level:?}");
+    buses.push(CustomBus {
+        level,
+        label: label.to_owned(),
+        address: address.to_owned(),
+    });
+
+    Ok(level)
+}
+
+/// Unregister a connection, closing its cached connections and its signal watcher
+pub fn remove_custom_bus(level: UnitDBusLevel) -> Option<CustomBus> {
+    let removed = {
+        let mut buses = CUSTOM_BUSES.write().unwrap();
+        let position = buses.iter().position(|bus| bus.level == level)?;
+        buses.remove(position)
+    };
+
+    info!("Remove bus connection {:?}", removed.label);
+    stop_custom_signal_watcher(level);
+    forget_custom_connection(level);
+
+    Some(removed)
+}
+
+pub fn custom_buses() -> Vec<CustomBus> {
+    CUSTOM_BUSES.read().unwrap().clone()
+}
+
+pub fn custom_bus(level: UnitDBusLevel) -> Option<CustomBus> {
+    CUSTOM_BUSES
+        .read()
+        .unwrap()
+        .iter()
+        .find(|bus| bus.level == level)
+        .cloned()
+}
+
+pub fn has_custom_buses() -> bool {
+    !CUSTOM_BUSES.read().unwrap().is_empty()
+}
+
+/// Label of the bus, the connection label for the custom levels and the user name for
+/// the attached other user
+pub fn bus_label(level: UnitDBusLevel) -> String {
+    if level.is_other_user()
+        && let Some(user) = crate::other_user::attached_user()
+    {
+        return user.name;
+    }
+
+    match custom_bus(level) {
+        Some(bus) => bus.label,
+        None => level.label().into_owned(),
+    }
+}
+
+/// The bus to save in the settings. The ids of the custom levels change from one run to another,
+/// so they are saved with their connection label. `None` for the other user level, the attached
+/// user changes too.
+pub fn saved_bus(level: UnitDBusLevel) -> Option<String> {
+    match level {
+        UnitDBusLevel::OtherUser => None,
+        UnitDBusLevel::Custom(_) => {
+            custom_bus(level).map(|bus| format!("{SAVED_CONNECTION_PREFIX}{}", bus.label))
+        }
+        level => Some(level.as_str().into_owned()),
+    }
+}
+
+/// The level of a bus saved in the settings, `None` when its connection isn't registered
+pub fn saved_bus_level(bus: &str) -> Option<UnitDBusLevel> {
+    if let Some(label) = bus.strip_prefix(SAVED_CONNECTION_PREFIX) {
+        return CUSTOM_BUSES
+            .read()
+            .unwrap()
+            .iter()
+            .find(|custom_bus| custom_bus.label == label)
+            .map(|custom_bus| custom_bus.level);
+    }
+
+    match UnitDBusLevel::from(bus) {
+        // Level ids of a previous run, they may belong to another connection now
+        UnitDBusLevel::Custom(_) | UnitDBusLevel::OtherUser => None,
+        level => Some(level),
+    }
+}
+
+pub fn check_address(address: &str) -> Result<(), SystemdErrors> {
+    address
+        .parse::<zbus::Address>()
+        .map(|_| ())
+        .map_err(|err| SystemdErrors::Custom(format!("Invalid bus address {address:?}: {err}")))
+}
+
+/// Connect to `address` and return the version of the systemd manager listening there
+pub async fn probe_custom_bus(address: &str) -> Result<String, SystemdErrors> {
+    crate::backend::ensure_live()?;
+
+    check_address(address)?;
+
+    let connection = zbus::connection::Builder::address(address.trim())?
+        .auth_mechanism(zbus::AuthMechanism::External)
+        .build()
+        .await?;
+
+    let properties = ZPropertiesProxy::builder(&connection)
+        .path(PATH_SYSTEMD)?
+        .build()
+        .await?;
+
+    let version = properties.get(INTERFACE_SYSTEMD_MANAGER, "Version").await?;
+
+    Ok(String::try_from(version)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_custom_bus_registry() {
+        let level = add_custom_bus("web", "unix:path=/run/test/web_bus_socket").unwrap();
+        assert!(level.is_custom());
+        assert_eq!(bus_label(level), "web");
+
+        assert!(add_custom_bus("web", "unix:path=/run/test/other").is_err());
+        assert!(add_custom_bus("db", "not an address").is_err());
+        assert!(add_custom_bus(" ", "unix:path=/run/test/other").is_err());
+
+        let removed = remove_custom_bus(level).unwrap();
+        assert_eq!(removed.address, "unix:path=/run/test/web_bus_socket");
+        assert!(custom_bus(level).is_none());
+        assert_eq!(bus_label(level), level.label());
+
+        let levels: Vec<UnitDBusLevel> = (0..8)
+            .map(|idx| add_custom_bus(&format!("bus {idx}"), "unix:path=/run/test/bus").unwrap())
+            .collect();
+        assert!(!levels.contains(&level));
+        for level in levels {
+            remove_custom_bus(level);
+        }
+    }
+
+    #[test]
+    fn test_saved_bus_round_trip() {
+        let first = add_custom_bus("saved web", "unix:path=/run/test/web").unwrap();
+        let second = add_custom_bus("saved db", "unix:path=/run/test/db").unwrap();
+
+        let saved: Vec<_> = [
+            UnitDBusLevel::System,
+            first,
+            second,
+            UnitDBusLevel::UserSession,
+        ]
+        .into_iter()
+        .map(|level| saved_bus(level).unwrap())
+        .collect();
+        assert_eq!(saved[1], "connection:saved web");
+        assert_eq!(saved_bus(UnitDBusLevel::OtherUser), None);
+
+        // Next run, the connections come back in another order with other ids
+        remove_custom_bus(first);
+        remove_custom_bus(second);
+        assert_eq!(saved_bus_level(&saved[1]), None);
+
+        let second = add_custom_bus("saved db", "unix:path=/run/test/db").unwrap();
+        let first = add_custom_bus("saved web", "unix:path=/run/test/web").unwrap();
+
+        let levels: Vec<_> = saved.iter().map(|bus| saved_bus_level(bus)).collect();
+        assert_eq!(
+            levels,
+            vec![
+                Some(UnitDBusLevel::System),
+                Some(first),
+                Some(second),
+                Some(UnitDBusLevel::UserSession)
+            ]
+        );
+
+        // The runtime ids saved before are not trusted
+        assert_eq!(saved_bus_level(first.as_str().as_ref()), None);
+        assert_eq!(saved_bus_level("other_user"), None);
+
+        remove_custom_bus(first);
+        remove_custom_bus(second);
+    }
+}
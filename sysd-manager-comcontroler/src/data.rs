@@ -285,11 +285,15 @@ mod imp {
         #[property(get, set, default)]
         pub(super) enable_status: Cell<UnitFileStatus>,
 
-        #[property(get, set, default)]
+        #[property(get, set)]
         pub(super) dbus_level: Cell<UnitDBusLevel>,
 
         #[property(get, set, default)]
         pub(super) preset: Cell<Preset>,
+
+        /// Recent `systemd-coredump` crashes
+        #[property(get, set)]
+        pub(super) crashes: Cell<u32>,
     }
 
     #[glib::object_subclass]
@@ -798,6 +798,11 @@ impl StartStopMode {
         let enum_value: &glib::EnumValue = self.to_value().get().expect("it's an enum");
         enum_value.value() as u32
     }
+
+    /// "isolate" is invalid for **StopUnit**
+    pub fn allowed_to_stop(&self) -> bool {
+        !matches!(self, StartStopMode::Isolate)
+    }
 }
 
 impl From<&RefCell<String>> for StartStopMode {
@@ -1121,5 +1126,19 @@ mod tests {
         }
         test(UnitDBusLevel::System);
         test(UnitDBusLevel::UserSession);
+
+        let custom = UnitDBusLevel::Custom(12);
+        assert_eq!(custom.as_str(), "custom12");
+        assert_eq!(UnitDBusLevel::from(custom.as_str()), custom);
+        assert_eq!(UnitDBusLevel::from_short(&custom.short()), custom);
+        assert!(custom.is_custom() && custom.bypasses_proxy());
+    }
+
+    #[test]
+    fn test_start_stop_mode_allowed_to_stop() {
+        for mode in StartStopMode::iter() {
+            assert_eq!(mode.allowed_to_stop(), mode != StartStopMode::Isolate);
+            assert_eq!(StartStopMode::from(mode.as_str()), mode);
+        }
     }
 }
@@ -1,4 +1,10 @@
-use base::file::SysdBaseError;
+use base::{
+    consts::{
+        PROXY_ERROR_EXTENSION_REJECTED, PROXY_ERROR_PATH_NOT_ALLOWED, PROXY_ERROR_SYMLINK_REJECTED,
+        PROXY_WRITE_POLICY_PATH,
+    },
+    file::SysdBaseError,
+};
 use gettextrs::pgettext;
 use std::{
     ffi::OsString,
@@ -47,6 +53,9 @@ pub enum SystemdErrors {
     Timeout(Duration),
     JobRemoved(String),
     ZFileNotFound(String),
+    ZWritePathNotAllowed(String),
+    ZWriteSymlinkRejected(String),
+    ZWriteExtensionRejected(String),
 }
 
 impl SystemdErrors {
@@ -78,6 +87,31 @@ impl SystemdErrors {
             SystemdErrors::ZNoSuchUnitProxy(_, detail) => detail.clone(),
             SystemdErrors::ZUnitMasked(_, detail) => detail.clone(),
             SystemdErrors::JobRemoved(reason) => uppercase_first_letter(reason),
+            SystemdErrors::ZMethodError(_, _, detail) if !detail.is_empty() => detail.clone(),
+            SystemdErrors::ZWritePathNotAllowed(detail) => format!(
+                "{} {PROXY_WRITE_POLICY_PATH} ({detail})",
+                //error message proxy write policy
+                pgettext(
+                    "error",
+                    "The proxy only writes unit files and drop-ins inside the unit directories allowed by its write policy:",
+                )
+            ),
+            SystemdErrors::ZWriteSymlinkRejected(detail) => format!(
+                "{} ({detail})",
+                //error message proxy write policy
+                pgettext(
+                    "error",
+                    "The proxy does not write through a symbolic link, edit the file it points to instead",
+                )
+            ),
+            SystemdErrors::ZWriteExtensionRejected(detail) => format!(
+                "{} ({detail})",
+                //error message proxy write policy
+                pgettext(
+                    "error",
+                    "The proxy only writes unit files with a unit type extension and drop-ins ending with .conf",
+                )
+            ),
             _ => self.to_string(),
         }
     }
@@ -177,6 +211,11 @@ impl From<(zbus::Error, &str)> for SystemdErrors {
                     "org.freedesktop.DBus.Error.FileNotFound" => {
                         SystemdErrors::ZFileNotFound(message)
                     }
+                    PROXY_ERROR_PATH_NOT_ALLOWED => SystemdErrors::ZWritePathNotAllowed(message),
+                    PROXY_ERROR_SYMLINK_REJECTED => SystemdErrors::ZWriteSymlinkRejected(message),
+                    PROXY_ERROR_EXTENSION_REJECTED => {
+                        SystemdErrors::ZWriteExtensionRejected(message)
+                    }
                     _ => {
                         SystemdErrors::ZMethodError(method.to_owned(), err_code.to_owned(), message)
                     }
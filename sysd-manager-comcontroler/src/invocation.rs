@@ -0,0 +1,149 @@
+//! The runs of a unit rebuilt from the journal.
+//!
+//! The journal entries are grouped by invocation id (`INVOCATION_ID`, `USER_INVOCATION_ID`
+//! or `_SYSTEMD_INVOCATION_ID`), the manager messages (`MESSAGE_ID`) telling when
+//! each run started, finished and with which result.
+
+use crate::{errors::SystemdErrors, journal, journal_data::UnitJournalRecord};
+use base::enums::UnitDBusLevel;
+use std::collections::HashMap;
+use sysd::id128::Id128;
+
+pub(crate) const MESSAGE_ID_UNIT_STARTING: &str = "7d4958e842da4a758f6c1cdc7b36dcc5";
+pub(crate) const MESSAGE_ID_UNIT_STARTED: &str = "39f53479d3a045ac8e11786248231fbf";
+pub(crate) const MESSAGE_ID_UNIT_STOPPED: &str = "9d1aaa27d60140bd96365438aad20286";
+pub(crate) const MESSAGE_ID_UNIT_FAILED: &str = "be02cf6855d2428ba40df7e9d022f03d";
+pub(crate) const MESSAGE_ID_UNIT_SUCCESS: &str = "7ad2d189f7e94e70a38c781354912448";
+pub(crate) const MESSAGE_ID_UNIT_FAILURE_RESULT: &str = "d9b373ed55a64feb8242e02dbe79a49c";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RunResult {
+    Success,
+    /// Contains the unit result, i.e. `exit-code`, `timeout`, ...
+    Failed(String),
+    Running,
+    Unknown,
+}
+
+impl RunResult {
+    pub fn is_failed(&self) -> bool {
+        matches!(self, RunResult::Failed(_))
+    }
+}
+
+/// One run of a unit, i.e. one invocation id
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnitRun {
+    pub invocation_id: String,
+    pub boot_id: String,
+    pub start: u64,
+    pub finish: Option<u64>,
+    pub result: RunResult,
+    /// Number of journal entries of this invocation
+    pub entries: usize,
+    /// Timer run triggered at boot to catch up occurrences missed while the machine was off
+    pub catch_up: bool,
+}
+
+impl UnitRun {
+    pub fn duration(&self) -> Option<u64> {
+        self.finish.map(|finish| finish.saturating_sub(self.start))
+    }
+}
+
+/// List the runs of a unit, newest first, going back at most `max_records` journal entries
+pub fn fetch_unit_runs(
+    unit: &str,
+    level: UnitDBusLevel,
+    max_records: usize,
+) -> Result<Vec<UnitRun>, SystemdErrors> {
+    crate::backend::ensure_live()?;
+
+    let records = journal::list_unit_records(&[unit], level, max_records)?;
+    let current_boot = Id128::from_boot()?.to_string();
+
+    Ok(build_runs(&records, unit, &current_boot))
+}
+
+/// Group the records of `unit` by invocation id, newest run first
+pub fn build_runs(records: &[UnitJournalRecord], unit: &str, current_boot: &str) -> Vec<UnitRun> {
+    let mut groups: HashMap<&str, Vec<&UnitJournalRecord>> = HashMap::new();
+
+    for record in records
+        .iter()
+        .filter(|record| record.unit == unit && !record.invocation_id.is_empty())
+    {
+        groups
+            .entry(record.invocation_id.as_str())
+            .or_default()
+            .push(record);
+    }
+
+    let mut runs: Vec<UnitRun> = groups
+        .into_iter()
+        .map(|(invocation_id, records)| {
+            let start = records
+                .iter()
+                .find(|r| r.message_id == MESSAGE_ID_UNIT_STARTING)
+                .or_else(|| records.iter().min_by_key(|r| r.timestamp))
+                .map(|r| r.timestamp)
+                .unwrap_or_default();
+
+            let finish = records
+                .iter()
+                .filter(|r| {
+                    matches!(
+                        r.message_id.as_str(),
+                        MESSAGE_ID_UNIT_SUCCESS
+                            | MESSAGE_ID_UNIT_FAILURE_RESULT
+                            | MESSAGE_ID_UNIT_FAILED
+                            | MESSAGE_ID_UNIT_STOPPED
+                    )
+                })
+                .map(|r| r.timestamp)
+                .max();
+
+            let failure = records.iter().find(|r| {
+                r.message_id == MESSAGE_ID_UNIT_FAILURE_RESULT
+                    || r.message_id == MESSAGE_ID_UNIT_FAILED
+            });
+
+            let boot_id = records
+                .first()
+                .map(|r| r.boot_id.clone())
+                .unwrap_or_default();
+
+            let result = if let Some(failure) = failure {
+                let result = if failure.result.is_empty() {
+                    "failed".to_owned()
+                } else {
+                    failure.result.clone()
+                };
+                RunResult::Failed(result)
+            } else if finish.is_some() {
+                RunResult::Success
+            } else if boot_id == current_boot
+                && records
+                    .iter()
+                    .any(|r| r.message_id == MESSAGE_ID_UNIT_STARTED)
+            {
+                RunResult::Running
+            } else {
+                RunResult::Unknown
+            };
+
+            UnitRun {
+                invocation_id: invocation_id.to_owned(),
+                boot_id,
+                start,
+                finish,
+                result,
+                entries: records.len(),
+                catch_up: false,
+            }
+        })
+        .collect();
+
+    runs.sort_by_key(|run| std::cmp::Reverse(run.start));
+    runs
+}
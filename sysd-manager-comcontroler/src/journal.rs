@@ -1,4 +1,12 @@
-use std::{collections::HashSet, ops::DerefMut, sync::mpsc::TryRecvError};
+use std::{
+    collections::{HashMap, HashSet},
+    io::Write,
+    ops::DerefMut,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        mpsc::TryRecvError,
+    },
+};
 
 /// Call systemd journal
 ///
@@ -8,15 +16,29 @@ use std::{collections::HashSet, ops::DerefMut, sync::mpsc::TryRecvError};
 ///
 use crate::{
     errors::SystemdErrors,
+    journal_catalog,
     journal_data::{
-        BOOT_IDX, Boot, EventRange, JournalEvent, JournalEventChunk, JournalEventChunkInfo,
+        AuditEntry, BOOT_IDX, Boot, Coredump, EventRange, FieldMatches, JournalEvent,
+        JournalEventChunk, JournalEventChunkInfo, JournalFacet, JournalSource, UnitJournalRecord,
         WhatGrab,
     },
+    journal_export::{self, ExportEntry, JournalExportFormat},
     time_handling::{TimestampStyle, USEC_PER_SEC},
 };
-use base::enums::UnitDBusLevel;
+use base::{
+    consts::{
+        AUDIT_KEY_ACTION, AUDIT_KEY_AUTHORIZED, AUDIT_KEY_CALLER_PID, AUDIT_KEY_CALLER_UID,
+        AUDIT_KEY_CONTENT_SHA256, AUDIT_KEY_FILE, AUDIT_KEY_METHOD, AUDIT_KEY_PREVIOUS_SHA256,
+        AUDIT_KEY_RESULT, AUDIT_KEY_UNIT, AUDIT_MESSAGE_ID,
+    },
+    enums::UnitDBusLevel,
+};
 use chrono::{Local, Utc};
-use sysd::{Journal, id128::Id128, journal::OpenOptions};
+use sysd::{
+    Journal,
+    id128::Id128,
+    journal::{OpenDirectoryOptions, OpenFilesOptions, OpenOptions},
+};
 use tracing::{debug, info, trace, warn};
 
 use super::BootFilter;
@@ -32,28 +54,37 @@ const KEY_OBJECT_SYSTEMD_USER_UNIT: &str = "OBJECT_SYSTEMD_USER_UNIT";
 const KEY_SYSTEMD_SLICE: &str = "_SYSTEMD_SLICE";
 const KEY_SYSTEMD_USER_SLICE: &str = "_SYSTEMD_USER_SLICE";
 const KEY_BOOT_ID: &str = "_BOOT_ID";
+const KEY_UID: &str = "_UID";
 const KEY_MESSAGE: &str = "MESSAGE";
 const KEY_PRIORITY: &str = "PRIORITY";
 const KEY_PID: &str = "_PID";
 const KEY_COMM: &str = "_COMM";
+const KEY_MESSAGE_ID: &str = "MESSAGE_ID";
+const KEY_INVOCATION_ID: &str = "INVOCATION_ID";
+const KEY_USER_INVOCATION_ID: &str = "USER_INVOCATION_ID";
+const KEY_SYSTEMD_INVOCATION_ID: &str = "_SYSTEMD_INVOCATION_ID";
+const KEY_UNIT_RESULT: &str = "UNIT_RESULT";
+const KEY_JOB_RESULT: &str = "JOB_RESULT";
 
 //pub const EVENT_MAX_ID: u8 = 201;
 
+#[allow(clippy::too_many_arguments)]
 pub(super) fn get_unit_journal_events(
-    primary_name: String,
+    source: &JournalSource,
+    unit_names: &[String],
     level: UnitDBusLevel,
     boot_filter: BootFilter,
+    field_matches: &FieldMatches,
     range: EventRange,
     message_max_char: usize,
     timestamp_style: TimestampStyle,
 ) -> Result<JournalEventChunk, SystemdErrors> {
     let mut out_list = JournalEventChunk::new(range.batch_size + 10, range.what_grab);
 
-    info!("Get journal Event {primary_name:?}");
-    let mut journal_reader = create_journal_reader(&primary_name, level, boot_filter)?;
-
-    let default = "NONE";
-    let default_priority = "7";
+    info!("Get journal Event {unit_names:?}");
+    let mut journal_reader =
+        create_journal_reader(source, unit_names, level, boot_filter, field_matches)?;
+    match_priority(&mut journal_reader, range.min_priority)?;
 
     //let mut index = 0;
     let mut last_boot_id = String::new();
@@ -71,6 +102,26 @@ pub(super) fn get_unit_journal_events(
 
         let time_in_usec = journal_reader.timestamp_usec()?;
 
+        let (out_of_range, before_range) = match range.what_grab {
+            WhatGrab::Newer => (
+                range.is_after_until(time_in_usec),
+                range.is_before_since(time_in_usec),
+            ),
+            WhatGrab::Older => (
+                range.is_before_since(time_in_usec),
+                range.is_after_until(time_in_usec),
+            ),
+        };
+
+        if out_of_range {
+            out_list.set_info(JournalEventChunkInfo::NoMore);
+            break;
+        }
+
+        if before_range {
+            continue;
+        }
+
         //if == 0 no limit
         if range.batch_size != 0 && out_list.len() >= range.batch_size {
             info!(
@@ -86,23 +137,14 @@ pub(super) fn get_unit_journal_events(
             }
         }
 
-        let mut message = get_data(&mut journal_reader, KEY_MESSAGE, default);
-
-        if message_max_char > 0 {
-            message = truncate(message, message_max_char);
-        }
-
-        let pid = get_data(&mut journal_reader, KEY_PID, default);
-        let priority_str = get_data(&mut journal_reader, KEY_PRIORITY, default_priority);
-        let priority = priority_str.parse::<u8>().map_or(7, |u| u);
-
-        let name = get_data(&mut journal_reader, KEY_COMM, default);
-
-        let boot_id = get_data(&mut journal_reader, KEY_BOOT_ID, default);
-
-        let prefix = make_prefix(time_in_usec, name, pid, timestamp_style);
-
-        let journal_event = JournalEvent::new_param(priority, time_in_usec, prefix, message);
+        let (journal_event, boot_id) = read_event(
+            &mut journal_reader,
+            unit_names,
+            level,
+            time_in_usec,
+            message_max_char,
+            timestamp_style,
+        );
 
         if boot_id != last_boot_id {
             if !last_boot_id.is_empty() {
@@ -126,14 +168,390 @@ pub(super) fn get_unit_journal_events(
     Ok(out_list)
 }
 
+/// The events of the query around the entry at `cursor`, at most `context` entries before
+/// and after it, oldest first. Every boot is searched.
+#[allow(clippy::too_many_arguments)]
+pub(super) fn get_unit_journal_events_around_cursor(
+    source: &JournalSource,
+    unit_names: &[String],
+    level: UnitDBusLevel,
+    field_matches: &FieldMatches,
+    cursor: &str,
+    context: usize,
+    message_max_char: usize,
+    timestamp_style: TimestampStyle,
+) -> Result<JournalEventChunk, SystemdErrors> {
+    let mut out_list = JournalEventChunk::new(context * 2 + 2, WhatGrab::Newer);
+
+    info!("Get journal Event {unit_names:?} around cursor {cursor}");
+    let mut journal_reader =
+        create_journal_reader(source, unit_names, level, BootFilter::All, field_matches)?;
+
+    journal_reader.seek_cursor(cursor)?;
+    if journal_reader.next()? == 0 {
+        return Err(SystemdErrors::JournalError(format!(
+            "No journal entry at cursor {cursor}"
+        )));
+    }
+
+    if !journal_reader.test_cursor(cursor)? {
+        // The entry may have been vacuumed, show the closest ones
+        warn!("Journal entry at cursor {cursor} not found");
+    }
+
+    let before = journal_reader.previous_skip(context as u64)? as usize;
+    let max_events = before + context + 1;
+
+    let mut last_boot_id = String::new();
+    let mut count = 0;
+    loop {
+        let time_in_usec = journal_reader.timestamp_usec()?;
+
+        let (journal_event, boot_id) = read_event(
+            &mut journal_reader,
+            unit_names,
+            level,
+            time_in_usec,
+            message_max_char,
+            timestamp_style,
+        );
+
+        if !last_boot_id.is_empty() && boot_id != last_boot_id {
+            let boot_event = JournalEvent::new_param(
+                BOOT_IDX,
+                time_in_usec - 1,
+                String::new(),
+                format!("-- Boot {boot_id} --"),
+            );
+            out_list.push(boot_event);
+        }
+        last_boot_id = boot_id;
+
+        out_list.push(journal_event);
+        count += 1;
+
+        if count >= max_events {
+            out_list.set_info(JournalEventChunkInfo::ChunkMaxReached);
+            break;
+        }
+
+        if journal_reader.next()? == 0 {
+            out_list.set_info(JournalEventChunkInfo::NoMore);
+            break;
+        }
+    }
+
+    Ok(out_list)
+}
+
+/// Lists, newest first, the manager messages about the given units and the log lines
+/// of their processes, across all the boots.
+pub(super) fn list_unit_records(
+    unit_names: &[&str],
+    level: UnitDBusLevel,
+    max_records: usize,
+) -> Result<Vec<UnitJournalRecord>, SystemdErrors> {
+    check_journal_source(&JournalSource::Local, level)?;
+
+    let mut journal_reader = OpenOptions::default()
+        .open()
+        .map_err(|err| SystemdErrors::JournalError(format!("Can't open journal: {err}")))?;
+
+    let (key_unit, key_systemd_unit, key_invocation_id) = match level {
+        UnitDBusLevel::UserSession | UnitDBusLevel::OtherUser => {
+            (KEY_USER_UNIT, KEY_SYSTEMS_USER_UNIT, KEY_USER_INVOCATION_ID)
+        }
+        _ => (KEY_UNIT, KEY_SYSTEMS_UNIT, KEY_INVOCATION_ID),
+    };
+
+    for (idx, unit_name) in unit_names.iter().enumerate() {
+        if idx > 0 {
+            journal_reader.match_or()?;
+        }
+        journal_reader.match_add(key_unit, *unit_name)?;
+        journal_reader.match_or()?;
+        journal_reader.match_add(key_systemd_unit, *unit_name)?;
+    }
+    match_attached_user(&mut journal_reader, level)?;
+
+    journal_reader.seek_tail()?;
+
+    let default = "";
+    let mut records = Vec::new();
+    while journal_reader.previous()? != 0 {
+        if max_records != 0 && records.len() >= max_records {
+            info!("Journal unit records reached the {max_records} limit");
+            break;
+        }
+
+        let timestamp = journal_reader.timestamp_usec()?;
+        let boot_id = get_data(&mut journal_reader, KEY_BOOT_ID, default);
+        let message_id = get_data(&mut journal_reader, KEY_MESSAGE_ID, default);
+
+        let mut unit = get_data(&mut journal_reader, key_unit, default);
+        let mut invocation_id = get_data(&mut journal_reader, key_invocation_id, default);
+        if unit.is_empty() {
+            unit = get_data(&mut journal_reader, key_systemd_unit, default);
+            invocation_id = get_data(&mut journal_reader, KEY_SYSTEMD_INVOCATION_ID, default);
+        }
+
+        let mut result = get_data(&mut journal_reader, KEY_UNIT_RESULT, default);
+        if result.is_empty() {
+            result = get_data(&mut journal_reader, KEY_JOB_RESULT, default);
+        }
+
+        records.push(UnitJournalRecord {
+            timestamp,
+            boot_id,
+            unit,
+            invocation_id,
+            message_id,
+            result,
+        });
+    }
+
+    Ok(records)
+}
+
+/// The units found in the journal `source`, sorted by name.
+///
+/// No matches are involved, so `sd_journal_query_unique()` can be used.
+pub(super) fn list_journal_units(
+    source: &JournalSource,
+) -> Result<Vec<(String, UnitDBusLevel)>, SystemdErrors> {
+    let mut journal_reader = open_journal(source)?;
+
+    let mut units = Vec::new();
+    for (field, level) in [
+        (KEY_SYSTEMS_UNIT, UnitDBusLevel::System),
+        (KEY_SYSTEMS_USER_UNIT, UnitDBusLevel::UserSession),
+    ] {
+        for value in journal_reader.query_unique(field)? {
+            let unit = value
+                .strip_prefix(field)
+                .and_then(|value| value.strip_prefix('='))
+                .unwrap_or(&value);
+
+            if !unit.is_empty() {
+                units.push((unit.to_owned(), level));
+            }
+        }
+    }
+
+    units.sort();
+    units.dedup();
+
+    Ok(units)
+}
+
+/// Count the values of the given fields on, at most, the `max_entries` most recent entries of the query.
+///
+/// `sd_journal_query_unique()` doesn't take the matches into account, so the
+/// entries of the query are scanned instead.
+pub(super) fn list_unit_facets(
+    source: &JournalSource,
+    unit_names: &[String],
+    level: UnitDBusLevel,
+    boot_filter: BootFilter,
+    field_matches: &FieldMatches,
+    fields: &[&str],
+    max_entries: usize,
+) -> Result<Vec<JournalFacet>, SystemdErrors> {
+    let mut journal_reader =
+        create_journal_reader(source, unit_names, level, boot_filter, field_matches)?;
+    journal_reader.seek_tail()?;
+
+    let mut counts: Vec<HashMap<String, usize>> = vec![HashMap::new(); fields.len()];
+    let mut entries = 0;
+    while journal_reader.previous()? != 0 {
+        if max_entries != 0 && entries >= max_entries {
+            info!("Journal facets reached the {max_entries} entries limit");
+            break;
+        }
+        entries += 1;
+
+        for (field, count) in fields.iter().zip(counts.iter_mut()) {
+            let value = get_data(&mut journal_reader, field, "");
+            if !value.is_empty() {
+                *count.entry(value).or_default() += 1;
+            }
+        }
+    }
+
+    let facets = fields
+        .iter()
+        .zip(counts)
+        .map(|(field, count)| {
+            let mut values: Vec<(String, usize)> = count.into_iter().collect();
+            values.sort_by(|(a_value, a_count), (b_value, b_count)| {
+                b_count.cmp(a_count).then_with(|| a_value.cmp(b_value))
+            });
+            JournalFacet {
+                field: field.to_string(),
+                values,
+            }
+        })
+        .collect();
+
+    Ok(facets)
+}
+
+/// Fetch the cursor and every field of the first entry of the query at `timestamp_usec`
+pub(super) fn fetch_journal_event_fields(
+    source: &JournalSource,
+    unit_names: &[String],
+    level: UnitDBusLevel,
+    boot_filter: BootFilter,
+    field_matches: &FieldMatches,
+    timestamp_usec: u64,
+) -> Result<Vec<(String, String)>, SystemdErrors> {
+    let mut journal_reader =
+        create_journal_reader(source, unit_names, level, boot_filter, field_matches)?;
+    journal_reader.seek_realtime_usec(timestamp_usec)?;
+
+    let Some(record) = journal_reader.next_entry()? else {
+        return Err(SystemdErrors::JournalError(format!(
+            "No journal entry at {timestamp_usec}"
+        )));
+    };
+
+    let cursor = journal_reader.cursor()?;
+
+    Ok(
+        std::iter::once((journal_export::KEY_CURSOR.to_owned(), cursor))
+            .chain(record)
+            .collect(),
+    )
+}
+
+/// The catalog explanation of the first entry of the query at `timestamp_usec`,
+/// with the entry fields substituted
+pub(super) fn fetch_journal_event_catalog(
+    source: &JournalSource,
+    unit_names: &[String],
+    level: UnitDBusLevel,
+    boot_filter: BootFilter,
+    field_matches: &FieldMatches,
+    timestamp_usec: u64,
+) -> Result<Option<String>, SystemdErrors> {
+    let fields = fetch_journal_event_fields(
+        source,
+        unit_names,
+        level,
+        boot_filter,
+        field_matches,
+        timestamp_usec,
+    )?;
+
+    let Some((_, message_id)) = fields.iter().find(|(field, _)| field == KEY_MESSAGE_ID) else {
+        return Ok(None);
+    };
+
+    let catalog = journal_catalog::catalog_for_message_id(message_id)
+        .map(|text| journal_catalog::substitute_fields(&text, &fields));
+
+    Ok(catalog)
+}
+
+/// Write the events of the query to `writer`, oldest first.
+///
+/// `progress` holds the number of written entries, setting `cancel` stops the export.
+#[allow(clippy::too_many_arguments)]
+pub(super) fn export_unit_journal(
+    source: &JournalSource,
+    unit_names: &[String],
+    level: UnitDBusLevel,
+    boot_filter: BootFilter,
+    field_matches: &FieldMatches,
+    range: &EventRange,
+    format: JournalExportFormat,
+    writer: &mut dyn Write,
+    progress: &AtomicUsize,
+    cancel: &AtomicBool,
+) -> Result<usize, SystemdErrors> {
+    let mut journal_reader =
+        create_journal_reader(source, unit_names, level, boot_filter, field_matches)?;
+    match_priority(&mut journal_reader, range.min_priority)?;
+
+    if let Some(since) = range.since {
+        journal_reader.seek_realtime_usec(since)?;
+    } else {
+        journal_reader.seek_head()?;
+    }
+
+    // Export the fields whole, not truncated at the default threshold
+    journal_reader.set_data_threshold(0)?;
+
+    let mut count = 0;
+    while journal_reader.next()? != 0 {
+        if cancel.load(Ordering::Relaxed) {
+            info!("Journal export cancelled after {count} entries");
+            return Err(SystemdErrors::Custom("Journal export cancelled".to_owned()));
+        }
+
+        let realtime = journal_reader.timestamp_usec()?;
+        if range.is_before_since(realtime) {
+            continue;
+        }
+
+        if range.is_after_until(realtime) {
+            break;
+        }
+
+        //if == 0 no limit
+        if range.batch_size != 0 && count >= range.batch_size {
+            info!("Journal export reached the {} limit", range.batch_size);
+            break;
+        }
+
+        let (monotonic, _boot_id) = journal_reader.monotonic_timestamp()?;
+        let entry = ExportEntry {
+            cursor: journal_reader.cursor()?,
+            realtime,
+            monotonic,
+            fields: entry_raw_fields(&mut journal_reader)?,
+        };
+
+        journal_export::write_entry(writer, format, &entry)?;
+
+        count += 1;
+        progress.store(count, Ordering::Relaxed);
+    }
+
+    writer.flush()?;
+
+    Ok(count)
+}
+
+/// Every field of the current entry in journal order, repeated ones included, with its raw value
+fn entry_raw_fields(journal_reader: &mut Journal) -> Result<Vec<(String, Vec<u8>)>, SystemdErrors> {
+    journal_reader.restart_data();
+
+    let mut fields = Vec::new();
+    while let Some(field) = journal_reader.enumerate_data()? {
+        let name = String::from_utf8_lossy(field.name()).into_owned();
+        let value = field.value().unwrap_or_default().to_vec();
+        fields.push((name, value));
+    }
+
+    Ok(fields)
+}
+
 fn position_crawler(journal_reader: &mut Journal, range: &EventRange) -> Result<(), SystemdErrors> {
     match range.what_grab {
         WhatGrab::Newer => {
             if let Some(newest_events_time) = range.newest_events_time {
-                journal_reader.seek_realtime_usec(newest_events_time + 1)?;
+                let from = range.since.map_or(newest_events_time + 1, |since| {
+                    since.max(newest_events_time + 1)
+                });
+                journal_reader.seek_realtime_usec(from)?;
             } else {
-                //Go to the end
-                journal_reader.seek_tail()?;
+                //Go to the end, or the end of the time range
+                if let Some(until) = range.until {
+                    journal_reader.seek_realtime_usec(until + 1)?;
+                } else {
+                    journal_reader.seek_tail()?;
+                }
                 //Go back to batch size
                 journal_reader.previous_skip(range.batch_size as u64)?;
             }
@@ -141,6 +559,8 @@ fn position_crawler(journal_reader: &mut Journal, range: &EventRange) -> Result<
         WhatGrab::Older => {
             if let Some(oldest_events_time) = range.oldest_events_time {
                 journal_reader.seek_realtime_usec(oldest_events_time - 1)?;
+            } else if let Some(until) = range.until {
+                journal_reader.seek_realtime_usec(until + 1)?;
             } else {
                 journal_reader.seek_tail()?;
             }
@@ -149,10 +569,28 @@ fn position_crawler(journal_reader: &mut Journal, range: &EventRange) -> Result<
     Ok(())
 }
 
+/// Keep the events at least as important as `min_priority`
+fn match_priority(
+    journal_reader: &mut Journal,
+    min_priority: Option<u8>,
+) -> Result<(), SystemdErrors> {
+    let Some(min_priority) = min_priority else {
+        return Ok(());
+    };
+
+    journal_reader.match_and()?;
+    for priority in 0..=min_priority.min(7) {
+        journal_reader.match_add(KEY_PRIORITY, priority.to_string())?;
+    }
+
+    Ok(())
+}
+
 #[allow(clippy::too_many_arguments)]
 pub fn get_unit_journal_events_continuous(
-    unit_name: String,
+    unit_names: Vec<String>,
     bus_level: UnitDBusLevel,
+    field_matches: FieldMatches,
     range: EventRange,
     journal_continuous_receiver: std::sync::mpsc::Receiver<()>,
     sender: std::sync::mpsc::Sender<JournalEventChunk>,
@@ -161,7 +599,14 @@ pub fn get_unit_journal_events_continuous(
     check_for_new_journal_entry: fn(),
 ) -> Result<(), SystemdErrors> {
     info!("Journal Continuous");
-    let mut journal_reader = create_journal_reader(&unit_name, bus_level, BootFilter::Current)?;
+    let mut journal_reader = create_journal_reader(
+        &JournalSource::Local,
+        &unit_names,
+        bus_level,
+        BootFilter::Current,
+        &field_matches,
+    )?;
+    match_priority(&mut journal_reader, range.min_priority)?;
 
     let default = "NONE".to_string();
     let default_priority = "7".to_string();
@@ -178,7 +623,7 @@ pub fn get_unit_journal_events_continuous(
         loop {
             match journal_continuous_receiver.try_recv() {
                 Ok(_) | Err(TryRecvError::Disconnected) => {
-                    info!("Terminating journal loop for {unit_name:?}.");
+                    info!("Terminating journal loop for {unit_names:?}.");
                     return Ok(());
                 }
                 Err(TryRecvError::Empty) => {}
@@ -241,9 +686,16 @@ pub fn get_unit_journal_events_continuous(
 
         let name = get_data(&mut journal_reader, KEY_COMM, &default);
 
-        let prefix = make_prefix(time_in_usec, name, pid, timestamp_style);
+        let unit_idx = entry_unit_idx(&mut journal_reader, &unit_names, bus_level);
+        let unit_tag = unit_idx.map(|idx| unit_names[idx as usize].as_str());
+
+        let message_id = get_data(&mut journal_reader, KEY_MESSAGE_ID, "");
 
-        let journal_event = JournalEvent::new_param(priority, time_in_usec, prefix, message);
+        let prefix = make_prefix(time_in_usec, name, pid, timestamp_style, unit_tag);
+
+        let journal_event = JournalEvent::new_param(priority, time_in_usec, prefix, message)
+            .with_unit_tag(unit_idx)
+            .with_catalog(journal_catalog::has_catalog(&message_id));
 
         out_list.push(journal_event);
     }
@@ -324,6 +776,132 @@ pub(super) fn list_boots() -> Result<Vec<Boot>, SystemdErrors> {
     Ok(boots)
 }
 
+/// `MESSAGE_ID` of journald messages reporting the disk usage and the limits of a journal
+const JOURNAL_USAGE_MESSAGE_ID: &str = "ec387f577b844b8fa948f33cad9a75e6";
+/// One report for the system journal and one for the runtime journal
+const MAX_JOURNAL_USAGE_REPORTS: usize = 2;
+
+pub(super) fn list_journald_usage_reports() -> Result<Vec<(u64, String)>, SystemdErrors> {
+    let mut journal_reader = open_journal(&JournalSource::Local)?;
+    journal_reader.match_add(KEY_MESSAGE_ID, JOURNAL_USAGE_MESSAGE_ID)?;
+    journal_reader.seek_tail()?;
+
+    let mut reports: Vec<(u64, String)> = Vec::with_capacity(MAX_JOURNAL_USAGE_REPORTS);
+    let mut journals = HashSet::new();
+    while reports.len() < MAX_JOURNAL_USAGE_REPORTS && journal_reader.previous()? != 0 {
+        let message = get_data(&mut journal_reader, KEY_MESSAGE, "");
+
+        // "System Journal (/var/log/journal/...) is 1.2G, max 4.0G, 2.7G free."
+        let journal = message.split(" is ").next().unwrap_or_default().to_owned();
+        if journals.insert(journal) {
+            reports.push((journal_reader.timestamp_usec()?, message));
+        }
+    }
+
+    Ok(reports)
+}
+
+/// `MESSAGE_ID` of the `systemd-coredump` crash reports
+const COREDUMP_MESSAGE_ID: &str = "fc2e22bc6ee647b6b90729ab34a250b1";
+const KEY_COREDUMP_EXE: &str = "COREDUMP_EXE";
+const KEY_COREDUMP_COMM: &str = "COREDUMP_COMM";
+const KEY_COREDUMP_SIGNAL_NAME: &str = "COREDUMP_SIGNAL_NAME";
+const KEY_COREDUMP_SIGNAL: &str = "COREDUMP_SIGNAL";
+const KEY_COREDUMP_PID: &str = "COREDUMP_PID";
+const KEY_COREDUMP_UID: &str = "COREDUMP_UID";
+
+/// The crashes since `since_usec`, newest first, `max` 0 means no limit
+pub(super) fn list_coredumps(since_usec: u64, max: usize) -> Result<Vec<Coredump>, SystemdErrors> {
+    let mut journal_reader = open_journal(&JournalSource::Local)?;
+    journal_reader.match_add(KEY_MESSAGE_ID, COREDUMP_MESSAGE_ID)?;
+    journal_reader.seek_tail()?;
+
+    let mut coredumps = Vec::new();
+    while (max == 0 || coredumps.len() < max) && journal_reader.previous()? != 0 {
+        let timestamp = journal_reader.timestamp_usec()?;
+        if timestamp < since_usec {
+            break;
+        }
+
+        let user_unit = get_data(&mut journal_reader, KEY_COREDUMP_USER_UNIT, "");
+        let (unit, level) = if user_unit.is_empty() {
+            (
+                get_data(&mut journal_reader, KEY_COREDUMP_UNIT, ""),
+                UnitDBusLevel::System,
+            )
+        } else {
+            (user_unit, UnitDBusLevel::UserSession)
+        };
+
+        let mut signal = get_data(&mut journal_reader, KEY_COREDUMP_SIGNAL_NAME, "");
+        if signal.is_empty() {
+            signal = get_data(&mut journal_reader, KEY_COREDUMP_SIGNAL, "");
+        }
+
+        coredumps.push(Coredump {
+            cursor: journal_reader.cursor()?,
+            timestamp,
+            unit,
+            level,
+            executable: get_data(&mut journal_reader, KEY_COREDUMP_EXE, ""),
+            command: get_data(&mut journal_reader, KEY_COREDUMP_COMM, ""),
+            signal,
+            pid: get_data(&mut journal_reader, KEY_COREDUMP_PID, "0")
+                .parse()
+                .unwrap_or_default(),
+            uid: get_data(&mut journal_reader, KEY_COREDUMP_UID, "0")
+                .parse()
+                .unwrap_or_default(),
+        });
+    }
+
+    Ok(coredumps)
+}
+
+/// The message of the coredump entry, it holds the stack trace of the threads
+pub(super) fn fetch_coredump_stack_trace(cursor: &str) -> Result<String, SystemdErrors> {
+    let mut journal_reader = open_journal(&JournalSource::Local)?;
+    journal_reader.seek_cursor(cursor)?;
+
+    if journal_reader.next()? == 0 || !journal_reader.test_cursor(cursor)? {
+        return Err(SystemdErrors::JournalError(format!(
+            "No coredump entry at cursor {cursor}"
+        )));
+    }
+
+    Ok(get_data(&mut journal_reader, KEY_MESSAGE, ""))
+}
+
+/// The calls recorded by the proxy, newest first, `max` 0 means no limit
+pub(super) fn list_audit_entries(max: usize) -> Result<Vec<AuditEntry>, SystemdErrors> {
+    let mut journal_reader = open_journal(&JournalSource::Local)?;
+    journal_reader.match_add(KEY_MESSAGE_ID, AUDIT_MESSAGE_ID)?;
+    journal_reader.seek_tail()?;
+
+    let mut entries = Vec::new();
+    while (max == 0 || entries.len() < max) && journal_reader.previous()? != 0 {
+        entries.push(AuditEntry {
+            timestamp: journal_reader.timestamp_usec()?,
+            caller_uid: get_data(&mut journal_reader, AUDIT_KEY_CALLER_UID, "")
+                .parse()
+                .ok(),
+            caller_pid: get_data(&mut journal_reader, AUDIT_KEY_CALLER_PID, "")
+                .parse()
+                .ok(),
+            method: get_data(&mut journal_reader, AUDIT_KEY_METHOD, ""),
+            action: get_data(&mut journal_reader, AUDIT_KEY_ACTION, ""),
+            unit: get_data(&mut journal_reader, AUDIT_KEY_UNIT, ""),
+            file_path: get_data(&mut journal_reader, AUDIT_KEY_FILE, ""),
+            content_sha256: get_data(&mut journal_reader, AUDIT_KEY_CONTENT_SHA256, ""),
+            previous_sha256: get_data(&mut journal_reader, AUDIT_KEY_PREVIOUS_SHA256, ""),
+            authorized: get_data(&mut journal_reader, AUDIT_KEY_AUTHORIZED, "") == "yes",
+            result: get_data(&mut journal_reader, AUDIT_KEY_RESULT, ""),
+        });
+    }
+
+    Ok(entries)
+}
+
 pub(super) fn fetch_last_time() -> Result<u64, SystemdErrors> {
     info!("Starting journal-logger list boot");
     let mut journal_reader = OpenOptions::default()
@@ -338,40 +916,116 @@ pub(super) fn fetch_last_time() -> Result<u64, SystemdErrors> {
     Ok(last_time)
 }
 
+fn open_journal(source: &JournalSource) -> Result<Journal, SystemdErrors> {
+    let journal_reader = match source {
+        JournalSource::Local => OpenOptions::default()
+            .open()
+            .expect("Could not open journal"),
+        JournalSource::Directory(dir) => OpenDirectoryOptions::default()
+            .open_directory(dir.to_string_lossy().to_string())
+            .map_err(|err| {
+                SystemdErrors::JournalError(format!("Can't open journal directory {dir:?}: {err}"))
+            })?,
+        JournalSource::Files(files) => OpenFilesOptions::default()
+            .open_files(files.iter().map(|file| file.to_string_lossy().to_string()))
+            .map_err(|err| {
+                SystemdErrors::JournalError(format!("Can't open journal files {files:?}: {err}"))
+            })?,
+    };
+
+    Ok(journal_reader)
+}
+
+/// The fields an entry of a unit can be matched on
+fn unit_keys(level: UnitDBusLevel) -> [&'static str; 5] {
+    match level {
+        UnitDBusLevel::UserSession | UnitDBusLevel::OtherUser => [
+            KEY_SYSTEMS_USER_UNIT,
+            KEY_USER_UNIT,
+            KEY_COREDUMP_USER_UNIT,
+            KEY_OBJECT_SYSTEMD_USER_UNIT,
+            KEY_SYSTEMD_USER_SLICE,
+        ],
+        _ => [
+            KEY_SYSTEMS_UNIT,
+            KEY_UNIT,
+            KEY_COREDUMP_UNIT,
+            KEY_OBJECT_SYSTEMD_UNIT,
+            KEY_SYSTEMD_SLICE,
+        ],
+    }
+}
+
+/// The units of a custom bus log on the machine of the bus, never in the local journal, their
+/// journal can only be read from an opened directory or files
+fn check_journal_source(source: &JournalSource, level: UnitDBusLevel) -> Result<(), SystemdErrors> {
+    if level.is_custom() && source.is_local() {
+        return Err(SystemdErrors::JournalError(
+            "The journal of a custom bus unit is on the machine of its bus".to_owned(),
+        ));
+    }
+    Ok(())
+}
+
+/// The units of another user have the same names as ours, only their uid tells them apart
+fn match_attached_user(
+    journal_reader: &mut Journal,
+    level: UnitDBusLevel,
+) -> Result<(), SystemdErrors> {
+    if level.is_other_user() {
+        let uid = crate::other_user::attached_uid()?;
+        journal_reader.match_and()?;
+        journal_reader.match_add(KEY_UID, uid.to_string())?;
+    }
+    Ok(())
+}
+
+/// When several units are merged, the index of the one the current entry belongs to
+fn entry_unit_idx(
+    journal_reader: &mut Journal,
+    unit_names: &[String],
+    level: UnitDBusLevel,
+) -> Option<u8> {
+    if unit_names.len() < 2 {
+        return None;
+    }
+
+    for key in unit_keys(level) {
+        let value = get_data(journal_reader, key, "");
+        if value.is_empty() {
+            continue;
+        }
+
+        if let Some(idx) = unit_names.iter().position(|unit_name| *unit_name == value) {
+            return Some(idx as u8);
+        }
+    }
+
+    None
+}
+
 fn create_journal_reader(
-    unit_name: &str,
+    source: &JournalSource,
+    unit_names: &[String],
     level: UnitDBusLevel,
     boot_filter: BootFilter,
+    field_matches: &FieldMatches,
 ) -> Result<Journal, SystemdErrors> {
-    let mut journal_reader = OpenOptions::default()
-        .open()
-        .expect("Could not open journal");
-
-    info!("JOURNAL UNIT {unit_name:?} LEVEL {level:?} BOOT {boot_filter:?}");
-    match level {
-        UnitDBusLevel::UserSession => {
-            journal_reader.match_add(KEY_SYSTEMS_USER_UNIT, unit_name)?;
-            journal_reader.match_or()?;
-            journal_reader.match_add(KEY_USER_UNIT, unit_name)?;
-            journal_reader.match_or()?;
-            journal_reader.match_add(KEY_COREDUMP_USER_UNIT, unit_name)?;
-            journal_reader.match_or()?;
-            journal_reader.match_add(KEY_OBJECT_SYSTEMD_USER_UNIT, unit_name)?;
-            journal_reader.match_or()?;
-            journal_reader.match_add(KEY_SYSTEMD_USER_SLICE, unit_name)?;
-        }
-        _ => {
-            journal_reader.match_add(KEY_SYSTEMS_UNIT, unit_name)?;
-            journal_reader.match_or()?;
-            journal_reader.match_add(KEY_UNIT, unit_name)?;
-            journal_reader.match_or()?;
-            journal_reader.match_add(KEY_COREDUMP_UNIT, unit_name)?;
-            journal_reader.match_or()?;
-            journal_reader.match_add(KEY_OBJECT_SYSTEMD_UNIT, unit_name)?;
-            journal_reader.match_or()?;
-            journal_reader.match_add(KEY_SYSTEMD_SLICE, unit_name)?;
+    check_journal_source(source, level)?;
+    let mut journal_reader = open_journal(source)?;
+
+    info!("JOURNAL UNITS {unit_names:?} LEVEL {level:?} BOOT {boot_filter:?}");
+    let mut first = true;
+    for unit_name in unit_names {
+        for key in unit_keys(level) {
+            if !first {
+                journal_reader.match_or()?;
+            }
+            first = false;
+            journal_reader.match_add(key, unit_name.as_str())?;
         }
-    };
+    }
+    match_attached_user(&mut journal_reader, level)?;
 
     match boot_filter {
         BootFilter::Current => {
@@ -388,10 +1042,69 @@ fn create_journal_reader(
             journal_reader.match_and()?;
             journal_reader.match_add(KEY_BOOT_ID, boot_id)?;
         }
+        BootFilter::Invocation(invocation_id) => {
+            let key_invocation_id = match level {
+                UnitDBusLevel::UserSession | UnitDBusLevel::OtherUser => KEY_USER_INVOCATION_ID,
+                _ => KEY_INVOCATION_ID,
+            };
+
+            journal_reader.match_and()?;
+            journal_reader.match_add(KEY_SYSTEMD_INVOCATION_ID, invocation_id.clone())?;
+            journal_reader.match_or()?;
+            journal_reader.match_add(key_invocation_id, invocation_id)?;
+        }
+    }
+
+    for (field, values) in field_matches.by_field() {
+        journal_reader.match_and()?;
+        for value in values {
+            journal_reader.match_add(field, value)?;
+        }
     }
+
     Ok(journal_reader)
 }
 
+/// The event at the reader position and its boot id
+fn read_event(
+    journal_reader: &mut Journal,
+    unit_names: &[String],
+    level: UnitDBusLevel,
+    time_in_usec: u64,
+    message_max_char: usize,
+    timestamp_style: TimestampStyle,
+) -> (JournalEvent, String) {
+    let default = "NONE";
+    let default_priority = "7";
+
+    let mut message = get_data(journal_reader, KEY_MESSAGE, default);
+
+    if message_max_char > 0 {
+        message = truncate(message, message_max_char);
+    }
+
+    let pid = get_data(journal_reader, KEY_PID, default);
+    let priority_str = get_data(journal_reader, KEY_PRIORITY, default_priority);
+    let priority = priority_str.parse::<u8>().map_or(7, |u| u);
+
+    let name = get_data(journal_reader, KEY_COMM, default);
+
+    let boot_id = get_data(journal_reader, KEY_BOOT_ID, default);
+
+    let unit_idx = entry_unit_idx(journal_reader, unit_names, level);
+    let unit_tag = unit_idx.map(|idx| unit_names[idx as usize].as_str());
+
+    let message_id = get_data(journal_reader, KEY_MESSAGE_ID, "");
+
+    let prefix = make_prefix(time_in_usec, name, pid, timestamp_style, unit_tag);
+
+    let journal_event = JournalEvent::new_param(priority, time_in_usec, prefix, message)
+        .with_unit_tag(unit_idx)
+        .with_catalog(journal_catalog::has_catalog(&message_id));
+
+    (journal_event, boot_id)
+}
+
 fn next(journal_reader: &mut Journal, grab_direction: WhatGrab) -> Result<u64, sysd::Error> {
     match grab_direction {
         WhatGrab::Newer => journal_reader.next(),
@@ -460,11 +1173,12 @@ macro_rules! formated_time {
     };
 }
 
-fn make_prefix(
+pub(crate) fn make_prefix(
     timestamp_usec: u64,
     name: String,
     pid: String,
     timestamp_style: TimestampStyle,
+    unit_tag: Option<&str>,
 ) -> String {
     let date = match timestamp_style {
         TimestampStyle::Pretty => {
@@ -490,7 +1204,10 @@ fn make_prefix(
         TimestampStyle::UnixUsec => format!("@{timestamp_usec}"),
     };
 
-    format!("{date} {name}[{pid}]: ")
+    match unit_tag {
+        Some(unit_tag) => format!("{date} [{unit_tag}] {name}[{pid}]: "),
+        None => format!("{date} {name}[{pid}]: "),
+    }
 }
 
 #[cfg(test)]
@@ -504,6 +1221,20 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn test_check_journal_source() {
+        let custom = UnitDBusLevel::Custom(1);
+        assert!(check_journal_source(&JournalSource::Local, custom).is_err());
+        assert!(check_journal_source(&JournalSource::Local, UnitDBusLevel::System).is_ok());
+        assert!(
+            check_journal_source(
+                &JournalSource::Directory("/var/lib/machines/web/var/log/journal".into()),
+                custom
+            )
+            .is_ok()
+        );
+    }
+
     #[test]
     fn test_truncate() {
         let s = "12345678901234567890".to_string();
@@ -534,6 +1265,27 @@ mod tests {
         info!("{s} {new_s}");
     }
 
+    #[test]
+    fn test_make_prefix_unit_tag() {
+        let prefix = make_prefix(
+            5 * USEC_PER_SEC,
+            "comm".to_owned(),
+            "12".to_owned(),
+            TimestampStyle::Unix,
+            None,
+        );
+        assert_eq!(prefix, "@5 comm[12]: ");
+
+        let prefix = make_prefix(
+            5 * USEC_PER_SEC,
+            "comm".to_owned(),
+            "12".to_owned(),
+            TimestampStyle::Unix,
+            Some("a.service"),
+        );
+        assert_eq!(prefix, "@5 [a.service] comm[12]: ");
+    }
+
     fn have_journal() -> bool {
         if !Path::new("/run/systemd/journal/").exists() {
             println!("missing journal files");
@@ -0,0 +1,159 @@
+//! Message catalog lookup, the explanations `journalctl -x` adds to the entries
+//! with a `MESSAGE_ID`.
+//!
+//! See <https://www.freedesktop.org/software/systemd/man/latest/sd_journal_get_catalog.html>
+
+use std::{
+    collections::HashMap,
+    ffi::CStr,
+    sync::{LazyLock, Mutex},
+};
+use tracing::debug;
+
+#[repr(C, align(8))]
+#[derive(Clone, Copy)]
+struct SdId128 {
+    bytes: [u8; 16],
+}
+
+#[link(name = "systemd")]
+unsafe extern "C" {
+    fn sd_journal_get_catalog_for_message_id(
+        id: SdId128,
+        ret: *mut *mut libc::c_char,
+    ) -> libc::c_int;
+}
+
+/// Message ids already looked up, if they have a catalog entry
+static HAS_CATALOG: LazyLock<Mutex<HashMap<String, bool>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Tells if the message id has a catalog entry, the result is cached
+pub(crate) fn has_catalog(message_id: &str) -> bool {
+    if message_id.is_empty() {
+        return false;
+    }
+
+    let mut cache = HAS_CATALOG.lock().expect("catalog cache lock");
+    if let Some(has_catalog) = cache.get(message_id) {
+        return *has_catalog;
+    }
+
+    let has_catalog = catalog_for_message_id(message_id).is_some();
+    cache.insert(message_id.to_owned(), has_catalog);
+    has_catalog
+}
+
+/// The catalog text of the message id, with its `@FIELD@` placeholders
+pub(crate) fn catalog_for_message_id(message_id: &str) -> Option<String> {
+    let bytes = parse_id128(message_id)?;
+
+    let mut text: *mut libc::c_char = std::ptr::null_mut();
+    let ret = unsafe { sd_journal_get_catalog_for_message_id(SdId128 { bytes }, &mut text) };
+
+    if ret < 0 || text.is_null() {
+        debug!("No catalog for message id {message_id} ({ret})");
+        return None;
+    }
+
+    let catalog = unsafe { CStr::from_ptr(text) }
+        .to_string_lossy()
+        .into_owned();
+    unsafe { libc::free(text as *mut libc::c_void) };
+
+    Some(catalog)
+}
+
+/// Replace the `@FIELD@` placeholders by the entry field values, unknown ones are left as is
+pub(crate) fn substitute_fields(text: &str, fields: &[(String, String)]) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find('@') {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+
+        let value = after.find('@').and_then(|end| {
+            let name = &after[..end];
+            let valid = !name.is_empty()
+                && name
+                    .chars()
+                    .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_');
+
+            valid
+                .then(|| fields.iter().find(|(field, _)| field == name))
+                .flatten()
+                .map(|(_, value)| (value, end))
+        });
+
+        match value {
+            Some((value, end)) => {
+                out.push_str(value);
+                rest = &after[end + 1..];
+            }
+            None => {
+                out.push('@');
+                rest = after;
+            }
+        }
+    }
+
+    out.push_str(rest);
+    out
+}
+
+/// 32 hexadecimal digits, dashes allowed like in the UUID format
+fn parse_id128(id: &str) -> Option<[u8; 16]> {
+    let digits: Vec<u8> = id
+        .chars()
+        .filter(|c| *c != '-')
+        .map(|c| c.to_digit(16).map(|d| d as u8))
+        .collect::<Option<Vec<u8>>>()?;
+
+    if digits.len() != 32 {
+        return None;
+    }
+
+    let mut bytes = [0; 16];
+    for (idx, byte) in bytes.iter_mut().enumerate() {
+        *byte = (digits[idx * 2] << 4) | digits[idx * 2 + 1];
+    }
+
+    Some(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_id128() {
+        let bytes = parse_id128("39f53479d3a045ac8e11786248231fbf").unwrap();
+        assert_eq!(bytes[0], 0x39);
+        assert_eq!(bytes[15], 0xbf);
+
+        assert_eq!(
+            parse_id128("39f53479-d3a0-45ac-8e11-786248231fbf"),
+            Some(bytes)
+        );
+        assert_eq!(parse_id128("39f53479"), None);
+        assert_eq!(parse_id128("z9f53479d3a045ac8e11786248231fbf"), None);
+    }
+
+    #[test]
+    fn test_substitute_fields() {
+        let fields = vec![
+            ("UNIT".to_owned(), "foo.service".to_owned()),
+            ("JOB_RESULT".to_owned(), "failed".to_owned()),
+        ];
+
+        assert_eq!(
+            substitute_fields("Unit @UNIT@ has @JOB_RESULT@.", &fields),
+            "Unit foo.service has failed."
+        );
+        assert_eq!(
+            substitute_fields("Mail root@localhost, @MISSING@ @", &fields),
+            "Mail root@localhost, @MISSING@ @"
+        );
+    }
+}
@@ -1,3 +1,6 @@
+use base::{consts::AUDIT_RESULT_SUCCESS, enums::UnitDBusLevel};
+use std::path::PathBuf;
+
 pub const BOOT_IDX: u8 = 200;
 
 #[derive(Clone, Copy, Debug)]
@@ -108,6 +111,12 @@ pub struct EventRange {
     pub newest_events_time: Option<u64>,
     pub batch_size: usize,
     pub what_grab: WhatGrab,
+    /// Least important priority kept, from 0 (emerg) to 7 (debug)
+    pub min_priority: Option<u8>,
+    /// Realtime µs lower bound of the events
+    pub since: Option<u64>,
+    /// Realtime µs upper bound of the events
+    pub until: Option<u64>,
 }
 
 impl EventRange {
@@ -122,9 +131,32 @@ impl EventRange {
             newest_events_time,
             batch_size,
             what_grab,
+            min_priority: None,
+            since: None,
+            until: None,
         }
     }
 
+    pub fn with_filters(
+        mut self,
+        min_priority: Option<u8>,
+        since: Option<u64>,
+        until: Option<u64>,
+    ) -> Self {
+        self.min_priority = min_priority;
+        self.since = since;
+        self.until = until;
+        self
+    }
+
+    pub fn is_before_since(&self, time: u64) -> bool {
+        self.since.is_some_and(|since| time < since)
+    }
+
+    pub fn is_after_until(&self, time: u64) -> bool {
+        self.until.is_some_and(|until| time > until)
+    }
+
     /*     pub fn has_reached_end(&self, time: u64) -> bool {
         if let Some(end) = self.end {
             if self.oldest_first {
@@ -144,6 +176,10 @@ pub struct JournalEvent {
     pub message: String,
     pub timestamp: u64,
     pub priority: u8,
+    /// Index of the unit the event belongs to, when several units are merged
+    pub unit_tag: Option<u8>,
+    /// The event `MESSAGE_ID` has a catalog explanation
+    pub has_catalog: bool,
 }
 
 impl JournalEvent {
@@ -153,9 +189,123 @@ impl JournalEvent {
             message,
             timestamp,
             priority,
+            unit_tag: None,
+            has_catalog: false,
+        }
+    }
+
+    pub fn with_unit_tag(mut self, unit_tag: Option<u8>) -> Self {
+        self.unit_tag = unit_tag;
+        self
+    }
+
+    pub fn with_catalog(mut self, has_catalog: bool) -> Self {
+        self.has_catalog = has_catalog;
+        self
+    }
+}
+/// Where the journal entries are read from
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum JournalSource {
+    /// The journal of the running system
+    #[default]
+    Local,
+    /// A journal directory, like a copy of `/var/log/journal/<machine-id>`
+    Directory(PathBuf),
+    /// Individual `.journal` files
+    Files(Vec<PathBuf>),
+}
+
+impl JournalSource {
+    pub fn is_local(&self) -> bool {
+        matches!(self, JournalSource::Local)
+    }
+
+    /// Short description of the opened paths
+    pub fn paths_label(&self) -> String {
+        match self {
+            JournalSource::Local => String::new(),
+            JournalSource::Directory(dir) => dir.display().to_string(),
+            JournalSource::Files(files) => files
+                .iter()
+                .map(|file| {
+                    file.file_name()
+                        .map(|name| name.to_string_lossy().to_string())
+                        .unwrap_or_else(|| file.display().to_string())
+                })
+                .collect::<Vec<_>>()
+                .join(", "),
+        }
+    }
+}
+
+/// Extra `FIELD=value` matches applied on the journal query.
+///
+/// Values of the same field are combined with OR, different fields with AND.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FieldMatches(Vec<(String, String)>);
+
+impl FieldMatches {
+    pub fn contains(&self, field: &str, value: &str) -> bool {
+        self.0.iter().any(|(f, v)| f == field && v == value)
+    }
+
+    /// Add the match if absent, remove it otherwise
+    pub fn toggle(&mut self, field: &str, value: &str) {
+        if let Some(idx) = self.0.iter().position(|(f, v)| f == field && v == value) {
+            self.0.remove(idx);
+        } else {
+            self.0.push((field.to_owned(), value.to_owned()));
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.0.clear();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// The matches grouped by field, in insertion order
+    pub fn by_field(&self) -> Vec<(&str, Vec<&str>)> {
+        let mut grouped: Vec<(&str, Vec<&str>)> = Vec::new();
+        for (field, value) in &self.0 {
+            match grouped.iter_mut().find(|(f, _)| f == field) {
+                Some((_, values)) => values.push(value),
+                None => grouped.push((field, vec![value])),
+            }
         }
+        grouped
     }
 }
+
+/// The distinct values of a field with their number of entries, most frequent first
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct JournalFacet {
+    pub field: String,
+    pub values: Vec<(String, usize)>,
+}
+
+/// A journal entry reduced to the fields needed to follow the unit invocations
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UnitJournalRecord {
+    pub timestamp: u64,
+    pub boot_id: String,
+    /// The unit the manager is talking about, or the unit of the process that logged
+    pub unit: String,
+    /// `INVOCATION_ID`, `USER_INVOCATION_ID` or `_SYSTEMD_INVOCATION_ID`
+    pub invocation_id: String,
+    pub message_id: String,
+    /// `UNIT_RESULT` or `JOB_RESULT`
+    pub result: String,
+}
+
+#[derive(Debug, Clone)]
 pub struct Boot {
     pub index: i32,
     pub boot_id: String,
@@ -177,3 +327,60 @@ impl Boot {
         self.last - self.first
     }
 }
+
+/// A crash recorded by `systemd-coredump`
+#[derive(Debug, Clone)]
+pub struct Coredump {
+    pub cursor: String,
+    /// Realtime µs of the journal entry
+    pub timestamp: u64,
+    /// `COREDUMP_USER_UNIT` on the user session level, `COREDUMP_UNIT` otherwise
+    pub unit: String,
+    pub level: UnitDBusLevel,
+    pub executable: String,
+    pub command: String,
+    pub signal: String,
+    pub pid: u32,
+    pub uid: u32,
+}
+
+impl Coredump {
+    /// Unit name or executable contains `text`, ignoring the case
+    pub fn matches(&self, text: &str) -> bool {
+        let text = text.to_lowercase();
+        self.unit.to_lowercase().contains(&text) || self.executable.to_lowercase().contains(&text)
+    }
+}
+
+/// A privileged call made through the proxy, recorded in the journal by the proxy
+#[derive(Debug, Clone, Default)]
+pub struct AuditEntry {
+    /// Realtime µs of the journal entry
+    pub timestamp: u64,
+    pub caller_uid: Option<u32>,
+    pub caller_pid: Option<u32>,
+    pub method: String,
+    /// The polkit action checked
+    pub action: String,
+    pub unit: String,
+    pub file_path: String,
+    pub content_sha256: String,
+    /// Empty if the file did not exist
+    pub previous_sha256: String,
+    pub authorized: bool,
+    pub result: String,
+}
+
+impl AuditEntry {
+    pub fn is_success(&self) -> bool {
+        self.authorized && self.result == AUDIT_RESULT_SUCCESS
+    }
+
+    /// Method, unit or file contains `text`, ignoring the case
+    pub fn matches(&self, text: &str) -> bool {
+        let text = text.to_lowercase();
+        self.method.to_lowercase().contains(&text)
+            || self.unit.to_lowercase().contains(&text)
+            || self.file_path.to_lowercase().contains(&text)
+    }
+}
@@ -0,0 +1,346 @@
+//! Journal files disk usage and journald storage limits.
+//!
+//! Vacuuming and rotating need privileges and go through the proxy.
+
+use crate::{errors::SystemdErrors, journal_data::Boot};
+use std::{
+    collections::BTreeMap,
+    fs,
+    os::unix::fs::MetadataExt,
+    path::{Path, PathBuf},
+    time::UNIX_EPOCH,
+};
+use tracing::{info, warn};
+
+const PERSISTENT_JOURNAL_DIR: &str = "/var/log/journal";
+const RUNTIME_JOURNAL_DIR: &str = "/run/log/journal";
+
+/// Lowest to highest precedence, the drop-ins are read in file name order
+const JOURNALD_CONF: &str = "/etc/systemd/journald.conf";
+const JOURNALD_CONF_DIRS: [&str; 3] = [
+    "/usr/lib/systemd/journald.conf.d",
+    "/run/systemd/journald.conf.d",
+    "/etc/systemd/journald.conf.d",
+];
+
+/// The `[Journal]` settings about storage and retention
+pub const JOURNALD_LIMIT_KEYS: [&str; 12] = [
+    "Storage",
+    "Compress",
+    "SystemMaxUse",
+    "SystemKeepFree",
+    "SystemMaxFileSize",
+    "SystemMaxFiles",
+    "RuntimeMaxUse",
+    "RuntimeKeepFree",
+    "RuntimeMaxFileSize",
+    "RuntimeMaxFiles",
+    "MaxRetentionSec",
+    "MaxFileSec",
+];
+
+#[derive(Debug, Clone)]
+pub struct JournalFile {
+    pub path: PathBuf,
+    /// Allocated bytes, like `journalctl --disk-usage`
+    pub disk_usage: u64,
+    /// Realtime µs of the first entry, known from the name of archived files
+    pub head: Option<u64>,
+    /// Realtime µs of the last modification
+    pub modified: u64,
+    pub archived: bool,
+    /// In `/run/log/journal`, lost on reboot
+    pub runtime: bool,
+}
+
+impl JournalFile {
+    pub fn file_name(&self) -> String {
+        self.path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default()
+    }
+
+    fn overlaps(&self, first: u64, last: u64) -> bool {
+        self.head.unwrap_or(0) <= last && self.modified >= first
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct BootUsage {
+    pub boot: Boot,
+    /// Disk usage of the files holding the boot entries, files can be shared with other boots
+    pub disk_usage: u64,
+    pub files: usize,
+}
+
+/// Vacuum thresholds, `0` means not applied
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct VacuumRequest {
+    /// Bytes
+    pub max_size: u64,
+    /// Seconds
+    pub max_age: u64,
+    pub max_files: u64,
+}
+
+impl VacuumRequest {
+    pub fn is_empty(&self) -> bool {
+        self.max_size == 0 && self.max_age == 0 && self.max_files == 0
+    }
+}
+
+/// The journal files of the persistent and the volatile storages
+pub fn list_journal_files() -> Result<Vec<JournalFile>, SystemdErrors> {
+    crate::backend::ensure_live()?;
+
+    let mut files = Vec::new();
+    list_journal_dir(Path::new(PERSISTENT_JOURNAL_DIR), false, &mut files)?;
+    list_journal_dir(Path::new(RUNTIME_JOURNAL_DIR), true, &mut files)?;
+
+    fill_active_heads(&mut files);
+
+    files.sort_by_key(|file| (file.runtime, file.head.unwrap_or(file.modified)));
+    Ok(files)
+}
+
+/// An active file starts about when the last archived file of the same prefix was closed
+fn fill_active_heads(files: &mut [JournalFile]) {
+    let prefix = |file: &JournalFile| {
+        let file_name = file.file_name();
+        let prefix = file_name
+            .split(['@', '.'])
+            .next()
+            .unwrap_or_default()
+            .to_owned();
+        (file.path.parent().map(Path::to_path_buf), prefix)
+    };
+
+    let mut last_archived = BTreeMap::new();
+    for file in files.iter().filter(|file| file.archived) {
+        let modified = last_archived.entry(prefix(file)).or_insert(0);
+        *modified = file.modified.max(*modified);
+    }
+
+    for file in files.iter_mut().filter(|file| file.head.is_none()) {
+        file.head = last_archived.get(&prefix(file)).copied();
+    }
+}
+
+/// Files are in the machine id sub directories
+fn list_journal_dir(
+    dir: &Path,
+    runtime: bool,
+    files: &mut Vec<JournalFile>,
+) -> Result<(), SystemdErrors> {
+    if !dir.exists() {
+        info!("No journal directory {dir:?}");
+        return Ok(());
+    }
+
+    for machine_dir in fs::read_dir(dir)? {
+        let machine_dir = machine_dir?.path();
+        if !machine_dir.is_dir() {
+            continue;
+        }
+
+        let entries = match fs::read_dir(&machine_dir) {
+            Ok(entries) => entries,
+            Err(err) => {
+                warn!("Can't list journal directory {machine_dir:?}: {err}");
+                continue;
+            }
+        };
+
+        for entry in entries {
+            let path = entry?.path();
+            let Some(file_name) = path.file_name().map(|name| name.to_string_lossy()) else {
+                continue;
+            };
+
+            let archived = file_name.ends_with(".journal~") || file_name.contains('@');
+            if !file_name.ends_with(".journal") && !file_name.ends_with(".journal~") {
+                continue;
+            }
+
+            let metadata = fs::metadata(&path)?;
+            let modified = metadata
+                .modified()
+                .ok()
+                .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+                .map_or(0, |duration| duration.as_micros() as u64);
+
+            files.push(JournalFile {
+                head: archived_head(&file_name),
+                path,
+                disk_usage: metadata.blocks() * 512,
+                modified,
+                archived,
+                runtime,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// The head realtime of an archived file named `<prefix>@<seqnum id>-<head seqnum>-<head realtime>.journal`
+fn archived_head(file_name: &str) -> Option<u64> {
+    let stem = file_name.strip_suffix(".journal")?;
+    let (_prefix, id) = stem.rsplit_once('@')?;
+    let head_realtime = id.rsplit('-').next()?;
+    u64::from_str_radix(head_realtime, 16).ok()
+}
+
+/// Disk usage of the files holding the entries of each boot
+pub fn boots_usage(boots: &[Boot], files: &[JournalFile]) -> Vec<BootUsage> {
+    boots
+        .iter()
+        .map(|boot| {
+            let boot_files: Vec<&JournalFile> = files
+                .iter()
+                .filter(|file| file.overlaps(boot.first, boot.last))
+                .collect();
+
+            BootUsage {
+                boot: boot.clone(),
+                disk_usage: boot_files.iter().map(|file| file.disk_usage).sum(),
+                files: boot_files.len(),
+            }
+        })
+        .collect()
+}
+
+/// The storage and retention settings set in the journald configuration files,
+/// the unset ones have their default value
+pub fn journald_limits() -> BTreeMap<String, String> {
+    let mut settings = BTreeMap::new();
+
+    let mut conf_files = vec![PathBuf::from(JOURNALD_CONF)];
+    for conf_dir in JOURNALD_CONF_DIRS {
+        let Ok(entries) = fs::read_dir(conf_dir) else {
+            continue;
+        };
+
+        let mut drop_ins: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "conf"))
+            .collect();
+        drop_ins.sort();
+        conf_files.extend(drop_ins);
+    }
+
+    for conf_file in conf_files {
+        match fs::read_to_string(&conf_file) {
+            Ok(content) => parse_journald_conf(&content, &mut settings),
+            Err(err) => info!("Journald configuration {conf_file:?}: {err}"),
+        }
+    }
+
+    settings
+}
+
+fn parse_journald_conf(content: &str, settings: &mut BTreeMap<String, String>) {
+    let mut in_journal_section = false;
+
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+            continue;
+        }
+
+        if line.starts_with('[') {
+            in_journal_section = line == "[Journal]";
+            continue;
+        }
+
+        if !in_journal_section {
+            continue;
+        }
+
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+
+        let key = key.trim();
+        if !JOURNALD_LIMIT_KEYS.contains(&key) {
+            continue;
+        }
+
+        let value = value.trim();
+        if value.is_empty() {
+            // An empty assignment resets to the default
+            settings.remove(key);
+        } else {
+            settings.insert(key.to_owned(), value.to_owned());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_archived_head() {
+        assert_eq!(
+            archived_head(
+                "system@f3a6c3fcab6f4be7b4d5e5aeb2f1e6b7-0000000000000001-0005f3c2e4d1a2b3.journal"
+            ),
+            Some(0x0005f3c2e4d1a2b3)
+        );
+        assert_eq!(archived_head("system.journal"), None);
+        assert_eq!(
+            archived_head("user-1000@0005f3c2e4d1a2b3-5ab4c3d2e1f00a1b.journal~"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_parse_journald_conf() {
+        let mut settings = BTreeMap::new();
+        parse_journald_conf(
+            "[Journal]\n#SystemMaxUse=\nSystemMaxUse=500M\nStorage = persistent\nForwardToSyslog=no\n\
+             [Other]\nSystemMaxFiles=3\n",
+            &mut settings,
+        );
+
+        assert_eq!(settings.get("SystemMaxUse").unwrap(), "500M");
+        assert_eq!(settings.get("Storage").unwrap(), "persistent");
+        assert!(!settings.contains_key("ForwardToSyslog"));
+        assert!(!settings.contains_key("SystemMaxFiles"));
+
+        parse_journald_conf("[Journal]\nSystemMaxUse=\n", &mut settings);
+        assert!(!settings.contains_key("SystemMaxUse"));
+    }
+
+    #[test]
+    fn test_boots_usage() {
+        let file = |head, modified, disk_usage| JournalFile {
+            path: PathBuf::new(),
+            disk_usage,
+            head,
+            modified,
+            archived: head.is_some(),
+            runtime: false,
+        };
+
+        let files = vec![
+            file(Some(0), 100, 10),
+            file(Some(100), 200, 20),
+            file(None, 300, 30),
+        ];
+        let boot = |index, first, last| Boot {
+            index,
+            boot_id: String::new(),
+            first,
+            last,
+            total: 2,
+        };
+
+        let usage = boots_usage(&[boot(1, 50, 90), boot(2, 150, 250)], &files);
+        assert_eq!(usage[0].disk_usage, 10 + 30);
+        assert_eq!(usage[1].disk_usage, 20 + 30);
+        assert_eq!(usage[1].files, 2);
+    }
+}
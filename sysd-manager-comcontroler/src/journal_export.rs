@@ -0,0 +1,371 @@
+//! Journal entries serialization for the export to file.
+//!
+//! The formats follow the `journalctl --output` ones: `short`, `short-iso`,
+//! `json` (one object per line) and `export` (the Journal Export Format).
+
+use chrono::{DateTime, Local};
+use gettextrs::pgettext;
+use std::{
+    borrow::Cow,
+    io::{self, Write},
+};
+use strum::EnumIter;
+
+pub const KEY_CURSOR: &str = "__CURSOR";
+pub const KEY_REALTIME_TIMESTAMP: &str = "__REALTIME_TIMESTAMP";
+pub const KEY_MONOTONIC_TIMESTAMP: &str = "__MONOTONIC_TIMESTAMP";
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, EnumIter)]
+pub enum JournalExportFormat {
+    Short,
+    ShortIso,
+    Json,
+    Export,
+}
+
+impl JournalExportFormat {
+    pub fn code(&self) -> &str {
+        match self {
+            JournalExportFormat::Short => "short",
+            JournalExportFormat::ShortIso => "short-iso",
+            JournalExportFormat::Json => "json",
+            JournalExportFormat::Export => "export",
+        }
+    }
+
+    pub fn label(&self) -> String {
+        match self {
+            //journal export format option
+            JournalExportFormat::Short => pgettext("journal export", "Short text"),
+            //journal export format option
+            JournalExportFormat::ShortIso => pgettext("journal export", "Short text ISO 8601"),
+            //journal export format option
+            JournalExportFormat::Json => pgettext("journal export", "JSON lines"),
+            //journal export format option
+            JournalExportFormat::Export => pgettext("journal export", "Journal Export Format"),
+        }
+    }
+
+    pub fn extension(&self) -> &str {
+        match self {
+            JournalExportFormat::Short | JournalExportFormat::ShortIso => "log",
+            JournalExportFormat::Json => "json",
+            JournalExportFormat::Export => "export",
+        }
+    }
+}
+
+/// One journal entry with its address fields
+///
+/// The fields are kept in journal order with their raw values, a field can repeat
+/// and its value isn't necessarily UTF-8.
+#[derive(Debug, Default)]
+pub struct ExportEntry {
+    pub cursor: String,
+    pub realtime: u64,
+    pub monotonic: u64,
+    pub fields: Vec<(String, Vec<u8>)>,
+}
+
+impl ExportEntry {
+    /// First textual value of `key`
+    fn field(&self, key: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .filter(|(name, _)| name == key)
+            .find_map(|(_, value)| std::str::from_utf8(value).ok())
+            .filter(|value| !value.is_empty())
+    }
+
+    /// The message like `journalctl -o short` shows it, binary ones are summarized
+    fn message(&self) -> Cow<'_, str> {
+        let Some((_, value)) = self.fields.iter().find(|(name, _)| name == "MESSAGE") else {
+            return Cow::Borrowed("");
+        };
+
+        match std::str::from_utf8(value) {
+            Ok(message) => Cow::Borrowed(message),
+            Err(_) => Cow::Owned(format!("[{}B blob data]", value.len())),
+        }
+    }
+
+    /// `journalctl -o short` like line prefix source, `SYSLOG_IDENTIFIER` or `_COMM`
+    fn identifier(&self) -> &str {
+        self.field("SYSLOG_IDENTIFIER")
+            .or_else(|| self.field("_COMM"))
+            .unwrap_or("unknown")
+    }
+
+    fn pid(&self) -> Option<&str> {
+        self.field("_PID").or_else(|| self.field("SYSLOG_PID"))
+    }
+
+    fn address_fields(&self) -> [(&str, String); 3] {
+        [
+            (KEY_CURSOR, self.cursor.clone()),
+            (KEY_REALTIME_TIMESTAMP, self.realtime.to_string()),
+            (KEY_MONOTONIC_TIMESTAMP, self.monotonic.to_string()),
+        ]
+    }
+}
+
+pub fn write_entry(
+    writer: &mut dyn Write,
+    format: JournalExportFormat,
+    entry: &ExportEntry,
+) -> io::Result<()> {
+    match format {
+        JournalExportFormat::Short => write_short(writer, entry, "%b %d %H:%M:%S"),
+        JournalExportFormat::ShortIso => write_short(writer, entry, "%Y-%m-%dT%H:%M:%S%z"),
+        JournalExportFormat::Json => write_json(writer, entry),
+        JournalExportFormat::Export => write_export(writer, entry),
+    }
+}
+
+fn write_short(writer: &mut dyn Write, entry: &ExportEntry, time_format: &str) -> io::Result<()> {
+    let time = DateTime::from_timestamp_micros(entry.realtime as i64)
+        .unwrap_or_default()
+        .with_timezone(&Local);
+
+    write!(writer, "{}", time.format(time_format))?;
+
+    if let Some(hostname) = entry.field("_HOSTNAME") {
+        write!(writer, " {hostname}")?;
+    }
+
+    write!(writer, " {}", entry.identifier())?;
+
+    if let Some(pid) = entry.pid() {
+        write!(writer, "[{pid}]")?;
+    }
+
+    writeln!(writer, ": {}", entry.message())
+}
+
+fn write_json(writer: &mut dyn Write, entry: &ExportEntry) -> io::Result<()> {
+    let mut line = String::from("{");
+
+    for (idx, (key, value)) in entry.address_fields().iter().enumerate() {
+        if idx != 0 {
+            line.push(',');
+        }
+        push_json_string(&mut line, key);
+        line.push(':');
+        push_json_string(&mut line, value);
+    }
+
+    // Like journalctl, a repeated field is written once with the array of its values
+    let mut keys: Vec<&str> = Vec::new();
+    for (key, _) in entry.fields.iter() {
+        if !keys.contains(&key.as_str()) {
+            keys.push(key);
+        }
+    }
+
+    for key in keys {
+        let values: Vec<&[u8]> = entry
+            .fields
+            .iter()
+            .filter(|(name, _)| name == key)
+            .map(|(_, value)| value.as_slice())
+            .collect();
+
+        line.push(',');
+        push_json_string(&mut line, key);
+        line.push(':');
+
+        if let [value] = values.as_slice() {
+            push_json_value(&mut line, value);
+        } else {
+            line.push('[');
+            for (idx, value) in values.iter().enumerate() {
+                if idx != 0 {
+                    line.push(',');
+                }
+                push_json_value(&mut line, value);
+            }
+            line.push(']');
+        }
+    }
+
+    line.push('}');
+    writeln!(writer, "{line}")
+}
+
+/// See <https://systemd.io/JOURNAL_EXPORT_FORMATS/>
+fn write_export(writer: &mut dyn Write, entry: &ExportEntry) -> io::Result<()> {
+    for (key, value) in entry.address_fields() {
+        writeln!(writer, "{key}={value}")?;
+    }
+
+    for (key, value) in entry.fields.iter() {
+        if is_printable(value) {
+            write!(writer, "{key}=")?;
+            writer.write_all(value)?;
+            writeln!(writer)?;
+        } else {
+            // Binary safe serialization
+            writeln!(writer, "{key}")?;
+            writer.write_all(&(value.len() as u64).to_le_bytes())?;
+            writer.write_all(value)?;
+            writeln!(writer)?;
+        }
+    }
+
+    writeln!(writer)
+}
+
+/// Valid UTF-8 without control characters other than tab, the condition for
+/// journalctl to write a field as text
+fn is_printable(value: &[u8]) -> bool {
+    std::str::from_utf8(value).is_ok_and(|value| {
+        !value
+            .chars()
+            .any(|c| c != '\t' && (c < ' ' || ('\u{7f}'..='\u{9f}').contains(&c)))
+    })
+}
+
+/// A JSON string for a UTF-8 value, otherwise the array of its bytes as journalctl does
+fn push_json_value(out: &mut String, value: &[u8]) {
+    match std::str::from_utf8(value) {
+        Ok(value) => push_json_string(out, value),
+        Err(_) => {
+            out.push('[');
+            for (idx, byte) in value.iter().enumerate() {
+                if idx != 0 {
+                    out.push(',');
+                }
+                out.push_str(&byte.to_string());
+            }
+            out.push(']');
+        }
+    }
+}
+
+fn push_json_string(out: &mut String, value: &str) {
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry() -> ExportEntry {
+        let fields = [
+            ("MESSAGE", "Started \"a\"\tnow"),
+            ("_COMM", "systemd"),
+            ("_HOSTNAME", "host"),
+            ("_PID", "1"),
+        ]
+        .into_iter()
+        .map(|(key, value)| (key.to_owned(), value.as_bytes().to_vec()))
+        .collect();
+
+        ExportEntry {
+            cursor: "s=1;i=2".to_owned(),
+            realtime: 1_700_000_000_000_000,
+            monotonic: 42,
+            fields,
+        }
+    }
+
+    #[test]
+    fn test_write_json() {
+        let mut out = Vec::new();
+        write_entry(&mut out, JournalExportFormat::Json, &entry()).unwrap();
+
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "{\"__CURSOR\":\"s=1;i=2\",\"__REALTIME_TIMESTAMP\":\"1700000000000000\",\
+             \"__MONOTONIC_TIMESTAMP\":\"42\",\"MESSAGE\":\"Started \\\"a\\\"\\tnow\",\
+             \"_COMM\":\"systemd\",\"_HOSTNAME\":\"host\",\"_PID\":\"1\"}\n"
+        );
+    }
+
+    #[test]
+    fn test_write_export() {
+        let mut entry = entry();
+        entry.fields[0].1 = b"two\nlines".to_vec();
+
+        let mut out = Vec::new();
+        write_entry(&mut out, JournalExportFormat::Export, &entry).unwrap();
+
+        let mut expected = b"__CURSOR=s=1;i=2\n__REALTIME_TIMESTAMP=1700000000000000\n\
+            __MONOTONIC_TIMESTAMP=42\nMESSAGE\n"
+            .to_vec();
+        expected.extend_from_slice(&9u64.to_le_bytes());
+        expected.extend_from_slice(b"two\nlines\n_COMM=systemd\n_HOSTNAME=host\n_PID=1\n\n");
+
+        assert_eq!(out, expected);
+    }
+
+    #[test]
+    fn test_write_short() {
+        let mut out = Vec::new();
+        write_entry(&mut out, JournalExportFormat::Short, &entry()).unwrap();
+
+        let line = String::from_utf8(out).unwrap();
+        assert!(line.ends_with(" host systemd[1]: Started \"a\"\tnow\n"));
+    }
+
+    #[test]
+    fn test_write_export_repeated_and_binary() {
+        let mut entry = entry();
+        entry.fields = vec![
+            ("MESSAGE".to_owned(), vec![0xff, 0x00, b'a']),
+            ("TAG".to_owned(), b"one".to_vec()),
+            ("TAG".to_owned(), b"two".to_vec()),
+        ];
+
+        let mut out = Vec::new();
+        write_entry(&mut out, JournalExportFormat::Export, &entry).unwrap();
+
+        let mut expected = b"__CURSOR=s=1;i=2\n__REALTIME_TIMESTAMP=1700000000000000\n\
+            __MONOTONIC_TIMESTAMP=42\nMESSAGE\n"
+            .to_vec();
+        expected.extend_from_slice(&3u64.to_le_bytes());
+        expected.extend_from_slice(&[0xff, 0x00, b'a']);
+        expected.extend_from_slice(b"\nTAG=one\nTAG=two\n\n");
+
+        assert_eq!(out, expected);
+    }
+
+    #[test]
+    fn test_write_json_repeated_and_binary() {
+        let mut entry = entry();
+        entry.fields = vec![
+            ("MESSAGE".to_owned(), vec![0xff, 0x00, b'a']),
+            ("TAG".to_owned(), b"one".to_vec()),
+            ("TAG".to_owned(), b"two".to_vec()),
+        ];
+
+        let mut out = Vec::new();
+        write_entry(&mut out, JournalExportFormat::Json, &entry).unwrap();
+
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "{\"__CURSOR\":\"s=1;i=2\",\"__REALTIME_TIMESTAMP\":\"1700000000000000\",\
+             \"__MONOTONIC_TIMESTAMP\":\"42\",\"MESSAGE\":[255,0,97],\"TAG\":[\"one\",\"two\"]}\n"
+        );
+
+        let mut out = Vec::new();
+        write_entry(&mut out, JournalExportFormat::Short, &entry).unwrap();
+        assert!(
+            String::from_utf8(out)
+                .unwrap()
+                .ends_with(": [3B blob data]\n")
+        );
+    }
+}
@@ -1,18 +1,33 @@
 #![allow(unused_must_use)]
 pub mod analyze;
+pub mod backend;
+pub mod calendar_spec;
+pub mod custom_bus;
 pub mod data;
 pub mod enums;
 pub mod errors;
 mod file;
+pub mod invocation;
 mod journal;
+mod journal_catalog;
 pub mod journal_data;
+pub mod journal_disk;
+pub mod journal_export;
+pub mod logind;
+pub mod manager_environment;
+pub mod other_user;
 #[cfg(not(any(feature = "flatpak", feature = "appimage")))]
 pub mod proxy_switcher;
 pub mod socket_unit;
 pub(crate) mod sysdbus;
+pub mod targets;
+pub mod template_unit;
 pub mod time_handling;
+pub mod timer_history;
+pub mod timer_schedule;
 
 use crate::{
+    backend::backend,
     data::{ListedLoadedUnit, UnitInfo, UnitProcess, UnitPropertySetter},
     enums::{
         ActiveState, CleanOption, DependencyType, DisEnableFlags, KillWho, LoadState,
@@ -22,7 +37,7 @@ use crate::{
     journal_data::Boot,
     sysdbus::{
         ListedUnitFile,
-        dbus_proxies::{Systemd1ManagerProxy, systemd_manager, systemd_manager_async},
+        dbus_proxies::{Systemd1ManagerProxy, systemd_manager_async},
     },
     time_handling::TimestampStyle,
 };
@@ -35,24 +50,30 @@ use enumflags2::{BitFlag, BitFlags};
 use errors::SystemdErrors;
 use flagset::{FlagSet, flags};
 use glib::Quark;
-use journal_data::{EventRange, JournalEventChunk};
+use journal_data::{
+    AuditEntry, Coredump, EventRange, FieldMatches, JournalEventChunk, JournalFacet, JournalSource,
+};
+use journal_export::JournalExportFormat;
 use std::{
     any::Any,
     collections::{BTreeMap, BTreeSet, HashMap},
     fs::File,
-    io::Read,
-    sync::OnceLock,
-    time::Duration,
+    io::{BufWriter, Read},
+    path::Path,
+    sync::{
+        OnceLock,
+        atomic::{AtomicBool, AtomicUsize},
+    },
 };
 pub use sysdbus::{
-    get_unit_file_state, list_units_description_and_state_async, sysd_proxy_service_name,
-    watcher::{SystemdSignal, SystemdSignalRow, init_signal_watcher},
-};
-use tokio::{
-    runtime::Runtime,
-    sync::broadcast::{self, error::RecvError},
-    time::timeout,
+    get_unit_file_state, list_units_description_and_state_async, set_bus_address,
+    sysd_proxy_service_name,
+    watcher::{
+        SystemdSignal, SystemdSignalRow, UnitStateChange, init_signal_watcher,
+        watch_unit_state_changes,
+    },
 };
+use tokio::runtime::Runtime;
 use tracing::{error, info, warn};
 use zvariant::OwnedValue;
 
@@ -62,15 +83,14 @@ pub use sysdbus::shut_down_sysd_proxy;
 #[cfg(not(any(feature = "flatpak", feature = "appimage")))]
 use crate::sysdbus::to_proxy::{self, SysDManagerComLinkProxy};
 
-#[cfg(not(any(feature = "flatpak", feature = "appimage")))]
-use base::consts::PROXY_SERVICE;
-
 #[derive(Default, Clone, PartialEq, Debug)]
 pub enum BootFilter {
     #[default]
     Current,
     All,
     Id(String),
+    /// Only the entries of one unit run, whatever the boot
+    Invocation(String),
 }
 
 #[derive(Clone, Debug)]
@@ -226,19 +246,16 @@ impl ListUnitResponse {
 }
 
 pub async fn list_loaded_units(level: UnitDBusLevel) -> Result<ListUnitResponse, SystemdErrors> {
-    let v = systemd_manager_async(level).await?.list_units().await?;
-    Ok(ListUnitResponse::Loaded(level, v))
+    backend().list_loaded_units(level).await
 }
 
 pub async fn list_loaded_units_by_patterns(
     level: UnitDBusLevel,
     patterns: &[&str],
 ) -> Result<ListUnitResponse, SystemdErrors> {
-    let v = systemd_manager_async(level)
-        .await?
-        .list_units_by_patterns(&[], patterns)
-        .await?;
-    Ok(ListUnitResponse::Loaded(level, v))
+    backend()
+        .list_loaded_units_by_patterns(level, patterns)
+        .await
 }
 
 pub async fn list_loaded_units_timers(
@@ -279,23 +296,67 @@ pub async fn list_loaded_units_list(
     list_loaded_units_by_patterns(level, &unit_list).await
 }
 
-pub async fn list_unit_files(level: UnitDBusLevel) -> Result<ListUnitResponse, SystemdErrors> {
-    let v = systemd_manager_async(level)
+/// List the loaded instances of a template unit, i.e. `foo@.service` gives all the loaded `foo@*.service`
+pub async fn list_template_instances(
+    level: UnitDBusLevel,
+    template: &str,
+) -> Result<Vec<ListedLoadedUnit>, SystemdErrors> {
+    let Some(pattern) = template_unit::instances_pattern(template) else {
+        return Err(SystemdErrors::Custom(format!(
+            "{template:?} is not a template unit"
+        )));
+    };
+
+    match backend()
+        .list_loaded_units_by_patterns(level, &[&pattern])
         .await?
-        .list_unit_files()
-        .await?;
-    Ok(ListUnitResponse::File(level, v))
+    {
+        ListUnitResponse::Loaded(_, instances) => Ok(instances),
+        ListUnitResponse::File(_, _) => Ok(Vec::new()),
+    }
+}
+
+/// Returns the path of the template unit file, if the template is installed
+pub async fn fetch_template_file_path(
+    level: UnitDBusLevel,
+    template: &str,
+) -> Result<Option<String>, SystemdErrors> {
+    let ListUnitResponse::File(_, unit_files) = backend()
+        .list_unit_files_by_patterns(level, &[template])
+        .await?
+    else {
+        return Ok(None);
+    };
+
+    Ok(unit_files
+        .into_iter()
+        .find(|unit_file| unit_file.unit_primary_name() == template)
+        .map(|unit_file| unit_file.unit_file_path))
+}
+
+/// Stop all the given instances, returning the outcome for each one
+pub async fn stop_template_instances(
+    level: UnitDBusLevel,
+    instances: &[String],
+    mode: StartStopMode,
+) -> Vec<(String, Result<String, SystemdErrors>)> {
+    let mut results = Vec::with_capacity(instances.len());
+    for instance in instances {
+        let result = restartstop_unit(level, instance, mode, ReStartStop::Stop).await;
+        results.push((instance.clone(), result));
+    }
+    results
+}
+
+pub async fn list_unit_files(level: UnitDBusLevel) -> Result<ListUnitResponse, SystemdErrors> {
+    backend().list_unit_files(level).await
 }
 
 pub async fn list_unit_files_by_patterns(
     level: UnitDBusLevel,
     patterns: &[&str],
 ) -> Result<ListUnitResponse, SystemdErrors> {
-    let v = systemd_manager_async(level)
-        .await?
-        .list_unit_files_by_patterns(&[], patterns)
-        .await?;
-    Ok(ListUnitResponse::File(level, v))
+    backend().list_unit_files_by_patterns(level, patterns).await
 }
 
 pub async fn list_unit_files_timers(
@@ -338,7 +399,7 @@ pub async fn list_unit_files_list(
 pub async fn complete_unit_information(
     units: &[CompleteUnitPropertiesCallParams],
 ) -> Result<Vec<UpdatedUnitInfo>, SystemdErrors> {
-    sysdbus::complete_unit_information(units).await
+    backend().complete_unit_information(units).await
 }
 
 pub async fn complete_single_unit_information(
@@ -353,7 +414,7 @@ pub async fn complete_single_unit_information(
         object_path,
         status,
     )];
-    sysdbus::complete_unit_information(&units).await
+    backend().complete_unit_information(&units).await
 }
 
 /// Takes a unit name as input and attempts to start it
@@ -439,116 +500,16 @@ pub async fn restartstop_unit(
     mode: StartStopMode,
     action: ReStartStop,
 ) -> Result<String, SystemdErrors> {
-    let watcher = init_signal_watcher(level).await;
-    let job = restartstop_unit_call(level, unit_name, mode, &action).await?;
-    let job_id = job_number(&job).ok_or("Invalid Job Id for job: {job}")?;
-
-    let duration = Duration::from_secs(10);
-    timeout(duration, wait_job_removed(job_id, watcher))
-        .await
-        .map_err(|_err| SystemdErrors::Timeout(duration))
-        .and_then(|res| res.map(|_| job))
-        .inspect(|_job| {
-            #[cfg(not(any(feature = "flatpak", feature = "appimage")))]
-            if matches!(action, ReStartStop::Start | ReStartStop::Restart)
-                && unit_name.starts_with(PROXY_SERVICE)
-            {
-                to_proxy::start_heart_beat()
-            }
-        })
-}
-
-const DONE: &str = "done";
-const SKIPPED: &str = "skipped";
-const CANCELED: &str = "canceled";
-const TIMEOUT: &str = "timeout";
-const FAILED: &str = "failed";
-const DEPENDENCY: &str = "dependency";
-const INVALID: &str = "invalid";
-
-async fn wait_job_removed(
-    job_id: u32,
-    mut watcher: broadcast::Receiver<SystemdSignal>,
-) -> Result<(), SystemdErrors> {
-    loop {
-        match watcher.recv().await {
-            Ok(SystemdSignal::JobRemoved(_level, id, _, _unit, result)) if id == job_id => {
-                match result.as_str() {
-                    DONE => {
-                        break;
-                    }
-                    CANCELED => return Err(SystemdErrors::JobRemoved(CANCELED.to_owned())),
-                    TIMEOUT => return Err(SystemdErrors::JobRemoved(TIMEOUT.to_owned())),
-                    FAILED => return Err(SystemdErrors::JobRemoved(FAILED.to_owned())),
-                    DEPENDENCY => return Err(SystemdErrors::JobRemoved(DEPENDENCY.to_owned())),
-                    SKIPPED => return Err(SystemdErrors::JobRemoved(SKIPPED.to_owned())),
-                    INVALID => return Err(SystemdErrors::JobRemoved(INVALID.to_owned())),
-                    unkown_result => {
-                        warn!("Unknown JobRemoved result {unkown_result}");
-                    }
-                }
-            }
-            Ok(_) => {}
-            Err(RecvError::Lagged(lag)) => info!("Lagged {lag:?}"),
-            Err(err) => {
-                warn!("Recev Err {err:?}");
-                return Err(SystemdErrors::JobRemoved(format!("{err:?}")));
-            }
-        }
-    }
-    Ok(())
-}
-
-fn job_number(job: &str) -> Option<u32> {
-    job.rsplit_once('/').and_then(|(_, job_id)| {
-        job_id
-            .parse::<u32>()
-            .inspect_err(|err| warn!("Job {err:?}"))
-            .ok()
-    })
-}
-
-async fn restartstop_unit_call(
-    level: UnitDBusLevel,
-    unit_name: &str,
-    mode: StartStopMode,
-    action: &ReStartStop,
-) -> Result<String, SystemdErrors> {
-    #[cfg(not(any(feature = "flatpak", feature = "appimage")))]
-    match level {
-        UnitDBusLevel::System | UnitDBusLevel::Both => {
-            use crate::sysdbus::to_proxy::get_proxy_async;
-
-            let proxy = get_proxy_async().await?;
-            if action.use_proxy() && !unit_name.starts_with(PROXY_SERVICE) {
-                // proxy_call_blocking!(restart_unit, unit_name, mode.as_str())
-
-                match action.action(&proxy, unit_name, mode).await {
-                    Ok(ok) => Ok(ok),
-                    Err(SystemdErrors::ZFdoServiceUnknowm(msg)) => {
-                        warn!("Async ServiceUnkown: {:?} Function: {:?}", msg, action);
-                        to_proxy::lazy_start_proxy_async().await;
-                        action.action(&proxy, unit_name, mode).await
-                    }
-                    Err(err) => Err(err),
-                }
-            } else {
-                let manager = sysdbus::dbus_proxies::system_manager_system_async().await?;
-                action.systemd_action(manager, unit_name, mode).await
-            }
-        }
-
-        UnitDBusLevel::UserSession => {
-            let manager = sysdbus::dbus_proxies::system_manager_user_session_async().await?;
-            action.systemd_action(manager, unit_name, mode).await
-        }
+    if matches!(action, ReStartStop::Stop) && !mode.allowed_to_stop() {
+        return Err(SystemdErrors::Custom(format!(
+            "Mode {:?} is invalid to stop a unit",
+            mode.as_str()
+        )));
     }
 
-    #[cfg(any(feature = "flatpak", feature = "appimage"))]
-    {
-        let manager = sysdbus::dbus_proxies::systemd_manager_async(level).await?;
-        action.systemd_action(manager, unit_name, mode).await
-    }
+    backend()
+        .restartstop_unit(level, unit_name, mode, action)
+        .await
 }
 
 pub fn disenable_unit_file(
@@ -580,33 +541,7 @@ pub fn enable_unit_file(
     unit_file: &str,
     flags: BitFlags<DisEnableFlags>,
 ) -> Result<DisEnAbleUnitFilesResponse, SystemdErrors> {
-    #[cfg(not(any(feature = "flatpak", feature = "appimage")))]
-    match level {
-        UnitDBusLevel::System | UnitDBusLevel::Both => {
-            if proxy_switcher::PROXY_SWITCHER.enable_unit_file() {
-                proxy_call_blocking!(
-                    enable_unit_files_with_flags,
-                    &[unit_file],
-                    flags.bits_c() as u64
-                )
-            } else {
-                systemd_manager()
-                    .enable_unit_files_with_flags(&[unit_file], flags.bits_c() as u64)
-                    .map_err(|err| err.into())
-            }
-        }
-        UnitDBusLevel::UserSession => sysdbus::dbus_proxies::systemd_manager_session()
-            .enable_unit_files_with_flags(&[unit_file], flags.bits_c() as u64)
-            .map_err(|err| err.into()),
-    }
-
-    #[cfg(any(feature = "flatpak", feature = "appimage"))]
-    {
-        use crate::sysdbus::dbus_proxies::systemd_manager_blocking;
-        systemd_manager_blocking(level)
-            .enable_unit_files_with_flags(&[unit_file], flags.bits_c() as u64)
-            .map_err(|err| err.into())
-    }
+    backend().enable_unit_file(level, unit_file, flags)
 }
 
 pub fn disable_unit_file(
@@ -614,56 +549,21 @@ pub fn disable_unit_file(
     unit_file: &str,
     flags: BitFlags<DisEnableFlags>,
 ) -> Result<DisEnAbleUnitFilesResponse, SystemdErrors> {
-    info!("{:?} {} {:?}", level, unit_file, flags.bits_c());
-    #[cfg(not(any(feature = "flatpak", feature = "appimage")))]
-    match level {
-        UnitDBusLevel::System | UnitDBusLevel::Both => {
-            if proxy_switcher::PROXY_SWITCHER.disable_unit_file() {
-                proxy_call_blocking!(
-                    disable_unit_files_with_flags,
-                    &[unit_file],
-                    flags.bits_c() as u64
-                )
-            } else {
-                systemd_manager()
-                    .disable_unit_files_with_flags_and_install_info(
-                        &[unit_file],
-                        flags.bits_c() as u64,
-                    )
-                    .map_err(|err| err.into())
-            }
-        }
-        UnitDBusLevel::UserSession => sysdbus::dbus_proxies::systemd_manager_session()
-            .disable_unit_files_with_flags_and_install_info(&[unit_file], flags.bits_c() as u64)
-            .map_err(|err| err.into()),
-    }
-
-    #[cfg(any(feature = "flatpak", feature = "appimage"))]
-    {
-        use crate::sysdbus::dbus_proxies::systemd_manager_blocking;
-        systemd_manager_blocking(level)
-            .disable_unit_files_with_flags_and_install_info(&[unit_file], flags.bits_c() as u64)
-            .map_err(|err| err.into())
-    }
+    backend().disable_unit_file(level, unit_file, flags)
 }
 
 pub async fn fetch_drop_in_paths(
     level: UnitDBusLevel,
     unit_name: &str,
 ) -> Result<Vec<String>, SystemdErrors> {
-    sysdbus::fetch_drop_in_paths(level, unit_name).await
+    backend().fetch_drop_in_paths(level, unit_name).await
 }
 /// Read the unit file and return it's contents so that we can display it
 pub fn fetch_unit_file_content(
     file_path: Option<&str>,
     unit_primary_name: &str,
 ) -> Result<String, SystemdErrors> {
-    let Some(file_path) = file_path else {
-        warn!("No file path for {:?}", unit_primary_name);
-        return Ok(String::new());
-    };
-
-    file_open_get_content(file_path, unit_primary_name)
+    backend().fetch_unit_file_content(file_path, unit_primary_name)
 }
 
 #[allow(unused)]
@@ -719,29 +619,59 @@ fn file_open_get_content(
     Ok(output)
 }
 
-/// Obtains the journal log for the given unit.
+/// Obtains the journal log for the given units, interleaved in time order.
+#[allow(clippy::too_many_arguments)]
 pub fn get_unit_journal(
-    primary_name: String,
+    source: &JournalSource,
+    unit_names: &[String],
     level: UnitDBusLevel,
     boot_filter: BootFilter,
+    field_matches: &FieldMatches,
     range: EventRange,
     message_max_char: usize,
     timestamp_style: TimestampStyle,
 ) -> Result<JournalEventChunk, SystemdErrors> {
-    journal::get_unit_journal_events(
-        primary_name,
+    backend().get_unit_journal(
+        source,
+        unit_names,
         level,
         boot_filter,
+        field_matches,
         range,
         message_max_char,
         timestamp_style,
     )
 }
 
+/// Obtains the journal events around the entry at `cursor`, `context` events before and after.
+#[allow(clippy::too_many_arguments)]
+pub fn get_unit_journal_around_cursor(
+    source: &JournalSource,
+    unit_names: &[String],
+    level: UnitDBusLevel,
+    field_matches: &FieldMatches,
+    cursor: &str,
+    context: usize,
+    message_max_char: usize,
+    timestamp_style: TimestampStyle,
+) -> Result<JournalEventChunk, SystemdErrors> {
+    backend().get_unit_journal_around_cursor(
+        source,
+        unit_names,
+        level,
+        field_matches,
+        cursor,
+        context,
+        message_max_char,
+        timestamp_style,
+    )
+}
+
 #[allow(clippy::too_many_arguments)]
 pub fn get_unit_journal_continuous(
-    unit_name: String,
+    unit_names: Vec<String>,
     level: UnitDBusLevel,
+    field_matches: FieldMatches,
     range: EventRange,
     journal_continuous_receiver: std::sync::mpsc::Receiver<()>,
     sender: std::sync::mpsc::Sender<JournalEventChunk>,
@@ -749,9 +679,10 @@ pub fn get_unit_journal_continuous(
     timestamp_style: TimestampStyle,
     check_for_new_journal_entry: fn(),
 ) {
-    if let Err(err) = journal::get_unit_journal_events_continuous(
-        unit_name,
+    if let Err(err) = backend().get_unit_journal_continuous(
+        unit_names,
         level,
+        field_matches,
         range,
         journal_continuous_receiver,
         sender,
@@ -769,14 +700,182 @@ pub fn get_unit_journal_continuous(
     }
 }
 
+/// Count the values of `fields` on the most recent entries of the unit journal query.
+pub fn list_unit_journal_facets(
+    source: &JournalSource,
+    unit_names: &[String],
+    level: UnitDBusLevel,
+    boot_filter: BootFilter,
+    field_matches: &FieldMatches,
+    fields: &[&str],
+    max_entries: usize,
+) -> Result<Vec<JournalFacet>, SystemdErrors> {
+    backend().list_unit_journal_facets(
+        source,
+        unit_names,
+        level,
+        boot_filter,
+        field_matches,
+        fields,
+        max_entries,
+    )
+}
+
+/// The cursor and every field of the unit journal entry logged at `timestamp_usec`.
+pub fn fetch_journal_event_fields(
+    source: &JournalSource,
+    unit_names: &[String],
+    level: UnitDBusLevel,
+    boot_filter: BootFilter,
+    field_matches: &FieldMatches,
+    timestamp_usec: u64,
+) -> Result<Vec<(String, String)>, SystemdErrors> {
+    crate::backend::ensure_live()?;
+
+    journal::fetch_journal_event_fields(
+        source,
+        unit_names,
+        level,
+        boot_filter,
+        field_matches,
+        timestamp_usec,
+    )
+}
+
+/// The catalog explanation of the unit journal entry logged at `timestamp_usec`, if its
+/// `MESSAGE_ID` has one.
+pub fn fetch_journal_event_catalog(
+    source: &JournalSource,
+    unit_names: &[String],
+    level: UnitDBusLevel,
+    boot_filter: BootFilter,
+    field_matches: &FieldMatches,
+    timestamp_usec: u64,
+) -> Result<Option<String>, SystemdErrors> {
+    crate::backend::ensure_live()?;
+
+    journal::fetch_journal_event_catalog(
+        source,
+        unit_names,
+        level,
+        boot_filter,
+        field_matches,
+        timestamp_usec,
+    )
+}
+
+/// Export the unit journal query to the file at `path`, returns the number of written entries.
+#[allow(clippy::too_many_arguments)]
+pub fn export_unit_journal(
+    source: &JournalSource,
+    unit_names: &[String],
+    level: UnitDBusLevel,
+    boot_filter: BootFilter,
+    field_matches: &FieldMatches,
+    range: &EventRange,
+    format: JournalExportFormat,
+    path: &Path,
+    progress: &AtomicUsize,
+    cancel: &AtomicBool,
+) -> Result<usize, SystemdErrors> {
+    crate::backend::ensure_live()?;
+
+    let file = File::create(path)?;
+    let mut writer = BufWriter::new(file);
+
+    journal::export_unit_journal(
+        source,
+        unit_names,
+        level,
+        boot_filter,
+        field_matches,
+        range,
+        format,
+        &mut writer,
+        progress,
+        cancel,
+    )
+}
+
+/// The system and user units logging in the journal `source`.
+pub fn list_journal_units(
+    source: &JournalSource,
+) -> Result<Vec<(String, UnitDBusLevel)>, SystemdErrors> {
+    crate::backend::ensure_live()?;
+    journal::list_journal_units(source)
+}
+
 pub fn list_boots() -> Result<Vec<Boot>, SystemdErrors> {
+    crate::backend::ensure_live()?;
     journal::list_boots()
 }
 
 pub fn fetch_last_time() -> Result<u64, SystemdErrors> {
+    crate::backend::ensure_live()?;
     journal::fetch_last_time()
 }
 
+/// The `systemd-coredump` crashes since `since_usec`, newest first, `max` 0 means no limit.
+pub fn list_coredumps(since_usec: u64, max: usize) -> Result<Vec<Coredump>, SystemdErrors> {
+    crate::backend::ensure_live()?;
+    journal::list_coredumps(since_usec, max)
+}
+
+/// The stack trace stored in the coredump journal entry.
+pub fn fetch_coredump_stack_trace(cursor: &str) -> Result<String, SystemdErrors> {
+    crate::backend::ensure_live()?;
+    journal::fetch_coredump_stack_trace(cursor)
+}
+
+/// The privileged calls audited by the proxy, newest first, `max` 0 means no limit.
+pub fn list_audit_entries(max: usize) -> Result<Vec<AuditEntry>, SystemdErrors> {
+    crate::backend::ensure_live()?;
+    journal::list_audit_entries(max)
+}
+
+/// The latest journald reports of the journals disk usage and limits, newest first.
+pub fn list_journald_usage_reports() -> Result<Vec<(u64, String)>, SystemdErrors> {
+    crate::backend::ensure_live()?;
+    journal::list_journald_usage_reports()
+}
+
+/// Archive the active journal files, through the proxy.
+pub async fn journal_rotate() -> Result<(), SystemdErrors> {
+    crate::backend::ensure_live()?;
+
+    info!("Rotating the journal");
+
+    #[cfg(not(any(feature = "flatpak", feature = "appimage")))]
+    {
+        proxy_call_async!(journal_rotate)
+    }
+
+    #[cfg(any(feature = "flatpak", feature = "appimage"))]
+    Err(SystemdErrors::Custom(
+        "Journal rotation needs the proxy".to_owned(),
+    ))
+}
+
+/// Remove the oldest archived journal files, through the proxy. Returns the vacuum report.
+pub async fn journal_vacuum(
+    rotate: bool,
+    request: journal_disk::VacuumRequest,
+) -> Result<String, SystemdErrors> {
+    crate::backend::ensure_live()?;
+
+    info!("Vacuuming the journal {request:?} rotate {rotate}");
+
+    #[cfg(not(any(feature = "flatpak", feature = "appimage")))]
+    {
+        proxy_call_async!(journal_vacuum, rotate, request)
+    }
+
+    #[cfg(any(feature = "flatpak", feature = "appimage"))]
+    Err(SystemdErrors::Custom(
+        "Journal vacuum needs the proxy".to_owned(),
+    ))
+}
+
 pub fn commander_output(
     prog_n_args: &[&str],
     environment_variables: Option<&[(&str, &str)]>,
@@ -872,6 +971,8 @@ pub fn generate_file_uri(file_path: &str) -> String {
 }
 
 pub fn fetch_system_info() -> Result<Vec<(UnitType, String, String)>, SystemdErrors> {
+    crate::backend::ensure_live()?;
+
     //TODO check with Session (user)
     sysdbus::fetch_system_info(UnitDBusLevel::System)
 }
@@ -883,7 +984,7 @@ pub fn fetch_system_unit_info_native(
     let unit_type: UnitType = unit.unit_type();
     let object_path = unit.object_path();
 
-    sysdbus::fetch_system_unit_info_native(level, &object_path, unit_type)
+    backend().fetch_system_unit_info_native(level, &object_path, unit_type)
 }
 
 pub fn fetch_system_unit_info_native_map(
@@ -893,7 +994,7 @@ pub fn fetch_system_unit_info_native_map(
     let unit_type: UnitType = unit.unit_type();
     let object_path = unit.object_path();
 
-    sysdbus::fetch_system_unit_info_native_map(level, &object_path, unit_type)
+    backend().fetch_system_unit_info_native_map(level, &object_path, unit_type)
 }
 
 /* fn get_unit_path(unit: &UnitInfo) -> String {
@@ -920,36 +1021,15 @@ pub fn kill_unit(
     who: KillWho,
     signal: i32,
 ) -> Result<(), SystemdErrors> {
-    sysdbus::kill_unit(level, primary_name, who, signal)
+    backend().kill_unit(level, primary_name, who, signal)
 }
 
 pub fn freeze_unit(params: Option<(UnitDBusLevel, String)>) -> Result<(), SystemdErrors> {
-    if let Some((_level, primary_name)) = params {
-        #[cfg(not(any(feature = "flatpak", feature = "appimage")))]
-        match _level {
-            UnitDBusLevel::System | UnitDBusLevel::Both => {
-                if proxy_switcher::PROXY_SWITCHER.freeze() {
-                    proxy_call_blocking!(freeze_unit, &primary_name)
-                } else {
-                    let proxy = systemd_manager();
-                    proxy.freeze_unit(&primary_name)?;
-                    Ok(())
-                }
-            }
-            UnitDBusLevel::UserSession => sysdbus::dbus_proxies::systemd_manager_session()
-                .freeze_unit(&primary_name)
-                .map_err(|err| err.into()),
-        }
+    let Some((level, primary_name)) = params else {
+        return Err(SystemdErrors::NoUnit);
+    };
 
-        #[cfg(any(feature = "flatpak", feature = "appimage"))]
-        {
-            let proxy = systemd_manager();
-            proxy.freeze_unit(&primary_name)?;
-            Ok(())
-        }
-    } else {
-        Err(SystemdErrors::NoUnit)
-    }
+    backend().freeze_unit(level, &primary_name)
 }
 
 pub fn thaw_unit(params: Option<(UnitDBusLevel, String)>) -> Result<(), SystemdErrors> {
@@ -957,29 +1037,7 @@ pub fn thaw_unit(params: Option<(UnitDBusLevel, String)>) -> Result<(), SystemdE
         return Err(SystemdErrors::NoUnit);
     };
 
-    #[cfg(not(any(feature = "flatpak", feature = "appimage")))]
-    match level {
-        UnitDBusLevel::System | UnitDBusLevel::Both => {
-            if proxy_switcher::PROXY_SWITCHER.thaw() {
-                proxy_call_blocking!(thaw_unit, &primary_name)
-            } else {
-                let proxy = systemd_manager();
-                proxy.thaw_unit(&primary_name)?;
-                Ok(())
-            }
-        }
-        UnitDBusLevel::UserSession => sysdbus::dbus_proxies::systemd_manager_session()
-            .thaw_unit(&primary_name)
-            .map_err(|err| err.into()),
-    }
-
-    #[cfg(any(feature = "flatpak", feature = "appimage"))]
-    {
-        use crate::sysdbus::dbus_proxies::systemd_manager_blocking;
-        let proxy = systemd_manager_blocking(level);
-        proxy.thaw_unit(&primary_name)?;
-        Ok(())
-    }
+    backend().thaw_unit(level, &primary_name)
 }
 
 pub fn reload_unit(
@@ -987,7 +1045,7 @@ pub fn reload_unit(
     primary_name: &str,
     mode: StartStopMode,
 ) -> Result<String, SystemdErrors> {
-    sysdbus::reload_unit(level, primary_name, mode.as_str())
+    backend().reload_unit(level, primary_name, mode)
 }
 
 pub fn queue_signal_unit(
@@ -997,7 +1055,7 @@ pub fn queue_signal_unit(
     signal: i32,
     value: i32,
 ) -> Result<(), SystemdErrors> {
-    sysdbus::queue_signal_unit(level, primary_name, who, signal, value)
+    backend().queue_signal_unit(level, primary_name, who, signal, value)
 }
 
 pub fn clean_unit(
@@ -1017,31 +1075,7 @@ pub fn clean_unit(
         what.iter().map(|s| s.as_str()).collect()
     };
 
-    #[cfg(not(any(feature = "flatpak", feature = "appimage")))]
-    match level {
-        UnitDBusLevel::System | UnitDBusLevel::Both => {
-            if proxy_switcher::PROXY_SWITCHER.clean() {
-                proxy_call_blocking!(clean_unit, unit_name, &clean_what)
-            } else {
-                let proxy = systemd_manager();
-                proxy
-                    .clean_unit(unit_name, &clean_what)
-                    .map_err(|err| err.into())
-            }
-        }
-        UnitDBusLevel::UserSession => sysdbus::dbus_proxies::systemd_manager_session()
-            .clean_unit(unit_name, &clean_what)
-            .map_err(|err| err.into()),
-    }
-
-    #[cfg(any(feature = "flatpak", feature = "appimage"))]
-    {
-        use crate::sysdbus::dbus_proxies::systemd_manager_blocking;
-
-        systemd_manager_blocking(level)
-            .clean_unit(unit_name, &clean_what)
-            .map_err(|err| err.into())
-    }
+    backend().clean_unit(level, unit_name, &clean_what)
 }
 
 pub fn mask_unit_files(
@@ -1050,7 +1084,7 @@ pub fn mask_unit_files(
     runtime: bool,
     force: bool,
 ) -> Result<Vec<DisEnAbleUnitFiles>, SystemdErrors> {
-    sysdbus::mask_unit_files(level, &[primary_name], runtime, force)
+    backend().mask_unit_file(level, primary_name, runtime, force)
 }
 
 pub fn preset_unit_files(
@@ -1059,7 +1093,7 @@ pub fn preset_unit_files(
     runtime: bool,
     force: bool,
 ) -> Result<DisEnAbleUnitFilesResponse, SystemdErrors> {
-    sysdbus::preset_unit_file(level, &[primary_name], runtime, force)
+    backend().preset_unit_file(level, primary_name, runtime, force)
 }
 
 pub fn reenable_unit_file(
@@ -1068,7 +1102,7 @@ pub fn reenable_unit_file(
     runtime: bool,
     force: bool,
 ) -> Result<DisEnAbleUnitFilesResponse, SystemdErrors> {
-    sysdbus::reenable_unit_file(level, &[primary_name], runtime, force)
+    backend().reenable_unit_file(level, primary_name, runtime, force)
 }
 
 pub fn unmask_unit_files(
@@ -1076,7 +1110,7 @@ pub fn unmask_unit_files(
     primary_name: &str,
     runtime: bool,
 ) -> Result<Vec<DisEnAbleUnitFiles>, SystemdErrors> {
-    sysdbus::unmask_unit_files(level, &[primary_name], runtime)
+    backend().unmask_unit_file(level, primary_name, runtime)
 }
 
 pub fn link_unit_files(
@@ -1085,64 +1119,11 @@ pub fn link_unit_files(
     runtime: bool,
     force: bool,
 ) -> Result<Vec<DisEnAbleUnitFiles>, SystemdErrors> {
-    sysdbus::link_unit_files(dbus_level, &[unit_file], runtime, force)
+    backend().link_unit_file(dbus_level, unit_file, runtime, force)
 }
 
 pub async fn daemon_reload(level: UnitDBusLevel) -> Result<(), SystemdErrors> {
-    let mut watcher = init_signal_watcher(level).await;
-    daemon_reload_core(level).await?;
-
-    let mut wait_reload = async || {
-        loop {
-            match watcher.recv().await {
-                Ok(SystemdSignal::Reloading(_, active)) => {
-                    if active {
-                        info!("Reloading!");
-                    } else {
-                        info!("Reload Finised");
-                        break;
-                    }
-                }
-                Ok(_) => {}
-                Err(RecvError::Lagged(lag)) => info!("Lagged {lag:?}"),
-                Err(err) => {
-                    warn!("Recev Err {err:?}");
-                    break;
-                }
-            }
-        }
-    };
-
-    let duration = Duration::from_secs(10);
-    match timeout(duration, wait_reload()).await {
-        Ok(_) => Ok(()),
-        Err(_err) => Err(SystemdErrors::Timeout(duration)),
-    }
-}
-
-async fn daemon_reload_core(level: UnitDBusLevel) -> Result<(), SystemdErrors> {
-    #[cfg(not(any(feature = "flatpak", feature = "appimage")))]
-    if level.user_session() || !proxy_switcher::PROXY_SWITCHER.reload() {
-        info!("Reloading Daemon - Direct");
-        systemd_manager_async(level)
-            .await?
-            .reload()
-            .await
-            .map_err(|err| err.into())
-    } else {
-        info!("Reloading Daemon - Proxy");
-        proxy_call_async!(reload)
-    }
-
-    #[cfg(any(feature = "flatpak", feature = "appimage"))]
-    {
-        info!("Reloading Daemon - Direct");
-        systemd_manager_async(level)
-            .await?
-            .reload()
-            .await
-            .map_err(|err| err.into())
-    }
+    backend().daemon_reload(level).await
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -1189,16 +1170,22 @@ pub fn fetch_unit_dependencies(
     dependency_type: DependencyType,
     plain: bool,
 ) -> Result<Dependency, SystemdErrors> {
-    sysdbus::unit_get_dependencies(level, primary_name, object_path, dependency_type, plain)
+    backend().fetch_unit_dependencies(level, primary_name, object_path, dependency_type, plain)
+}
+
+/// The units grouped by a slice or a target.
+pub fn fetch_unit_members(
+    level: UnitDBusLevel,
+    unit_name: &str,
+) -> Result<Vec<String>, SystemdErrors> {
+    backend().fetch_unit_members(level, unit_name)
 }
 
 pub fn get_unit_active_state(
     level: UnitDBusLevel,
     primary_name: &str,
 ) -> Result<ActiveState, SystemdErrors> {
-    let object_path = sysdbus::unit_dbus_path_from_name(primary_name);
-
-    sysdbus::get_unit_active_state(level, &object_path)
+    backend().get_unit_active_state(level, primary_name)
 }
 
 pub fn retreive_unit_processes(
@@ -1206,27 +1193,10 @@ pub fn retreive_unit_processes(
 ) -> Result<BTreeMap<String, BTreeSet<UnitProcess>>, SystemdErrors> {
     let level = unit.dbus_level();
 
-    let unit_processes = sysdbus::retreive_unit_processes(level, &unit.primary())?;
+    let unit_processes = backend().retreive_unit_processes(level, &unit.primary())?;
 
-    // let mut unit_processes_out = Vec::with_capacity(unit_processes.len());
     let mut unit_processes_map: BTreeMap<String, BTreeSet<UnitProcess>> = BTreeMap::new();
     for unit_process in unit_processes {
-        let unit_process = {
-            let Some(unit_name) = unit_process.path.rsplit_once('/').map(|a| a.1) else {
-                warn!("No unit name for path {:?}", unit_process.path);
-                continue;
-            };
-
-            let unit_name_idx = unit_process.path.len() - unit_name.len();
-
-            UnitProcess {
-                path: unit_process.path,
-                pid: unit_process.pid,
-                name: unit_process.name,
-                unit_name: unit_name_idx,
-            }
-        };
-
         if let Some(set) = unit_processes_map.get_mut(unit_process.unit_name()) {
             set.insert(unit_process);
         } else {
@@ -1283,7 +1253,8 @@ pub async fn fetch_unit_properties(
     unit_properties: UnitProperties,
     properties: Vec<(UnitType, &str, Quark)>,
 ) -> Result<Vec<UnitPropertySetter>, SystemdErrors> {
-    sysdbus::fetch_unit_properties(level, unit_primary_name, path, unit_properties, properties)
+    backend()
+        .fetch_unit_properties(level, unit_primary_name, path, unit_properties, properties)
         .await
 }
 
@@ -1293,7 +1264,7 @@ pub fn fetch_unit_property_blocking(
     unit_type: UnitType,
     unit_property: &str,
 ) -> Result<OwnedValue, SystemdErrors> {
-    sysdbus::fetch_unit_property_blocking(level, unit_primary_name, unit_type, unit_property)
+    backend().fetch_unit_property_blocking(level, unit_primary_name, unit_type, unit_property)
 }
 
 pub async fn create_drop_in(
@@ -1325,6 +1296,12 @@ pub async fn save_file(
 ) -> Result<u64, SystemdErrors> {
     info!("Saving file {file_path:?}");
 
+    if level.is_custom() {
+        return Err(SystemdErrors::Custom(
+            "The unit files of a custom bus are not local files".to_owned(),
+        ));
+    }
+
     let user_session = level.user_session();
     //TODO check the case of /run
 
@@ -1364,7 +1341,7 @@ pub async fn revert_unit_file_full(
     info!("Reverting unit file {unit_name:?}");
 
     #[cfg(not(any(feature = "flatpak", feature = "appimage")))]
-    if level.user_session() || !proxy_switcher::PROXY_SWITCHER.revert_unit_file() {
+    if level.bypasses_proxy() || !proxy_switcher::PROXY_SWITCHER.revert_unit_file() {
         systemd_manager_async(level)
             .await?
             .revert_unit_files(&[unit_name])
@@ -0,0 +1,113 @@
+//! The sessions known by logind and the lingering of the users.
+//!
+//! Without lingering the user manager, and all the user services with it, stops when the
+//! last session of the user closes.
+
+use crate::{
+    errors::SystemdErrors,
+    sysdbus::{
+        dbus_proxies::{Login1ManagerProxy, Login1SessionProxy},
+        get_connection,
+    },
+};
+use base::enums::UnitDBusLevel;
+use tracing::{info, warn};
+
+/// A session known by logind
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct LoginSession {
+    pub id: String,
+    pub uid: u32,
+    pub user: String,
+    /// Empty for the sessions without seat, like the ssh ones
+    pub seat: String,
+    pub tty: String,
+    /// `online`, `active` or `closing`
+    pub state: String,
+    /// `user`, `greeter`, `lock-screen`, ...
+    pub class: String,
+    pub remote: bool,
+    pub idle: bool,
+    /// Realtime in microseconds, 0 when not idle
+    pub idle_since: u64,
+}
+
+pub async fn list_sessions() -> Result<Vec<LoginSession>, SystemdErrors> {
+    crate::backend::ensure_live()?;
+
+    let connection = get_connection(UnitDBusLevel::System).await?;
+    let manager = Login1ManagerProxy::new(&connection).await?;
+
+    let mut sessions = Vec::new();
+    for (id, uid, user, seat, path) in manager.list_sessions().await? {
+        let proxy = Login1SessionProxy::builder(&connection)
+            .path(path)?
+            .build()
+            .await?;
+
+        let idle = proxy.idle_hint().await.unwrap_or_default();
+        let session = LoginSession {
+            tty: proxy.tty().await.unwrap_or_default(),
+            state: proxy
+                .state()
+                .await
+                .inspect_err(|err| warn!("Session {id:?} state {err:?}"))
+                .unwrap_or_default(),
+            class: proxy.class().await.unwrap_or_default(),
+            remote: proxy.remote().await.unwrap_or_default(),
+            idle,
+            idle_since: if idle {
+                proxy.idle_since_hint().await.unwrap_or_default()
+            } else {
+                0
+            },
+            id,
+            uid,
+            user,
+            seat,
+        };
+
+        sessions.push(session);
+    }
+
+    sessions.sort_by(|a, b| a.uid.cmp(&b.uid).then_with(|| a.id.cmp(&b.id)));
+    Ok(sessions)
+}
+
+/// The unit running the user manager of `uid`, on the system bus
+pub fn user_manager_unit(uid: u32) -> String {
+    format!("user@{uid}.service")
+}
+
+/// Keep the user manager of `uid` running without session, or not
+pub async fn set_user_linger(uid: u32, enable: bool) -> Result<(), SystemdErrors> {
+    crate::backend::ensure_live()?;
+
+    info!("Set linger of {uid} to {enable}");
+
+    #[cfg(not(any(feature = "flatpak", feature = "appimage")))]
+    {
+        crate::proxy_call_async!(set_user_linger, uid, enable)
+    }
+
+    #[cfg(any(feature = "flatpak", feature = "appimage"))]
+    {
+        // logind asks the authorization itself
+        let connection = get_connection(UnitDBusLevel::System).await?;
+        Login1ManagerProxy::new(&connection)
+            .await?
+            .set_user_linger(uid, enable, true)
+            .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_user_manager_unit() {
+        assert_eq!(user_manager_unit(1000), "user@1000.service");
+    }
+}
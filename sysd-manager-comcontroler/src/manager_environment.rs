@@ -0,0 +1,384 @@
+//! The environment block of a service manager, passed to every process it spawns, and the
+//! environment a unit gets on top of it.
+//!
+//! A unit process sees the manager environment, overridden by the unit `Environment=`, itself
+//! overridden by the `EnvironmentFile=` files.
+
+use crate::{
+    enums::UnitType,
+    errors::SystemdErrors,
+    sysdbus::{
+        dbus_proxies::{ZPropertiesProxyBlocking, systemd_manager_async},
+        get_blocking_connection, unit_dbus_path_from_name,
+    },
+};
+use base::enums::UnitDBusLevel;
+use std::collections::BTreeMap;
+use tracing::{info, warn};
+use zvariant::OwnedValue;
+
+pub type Variable = (String, String);
+
+/// A variable name, letters, digits and underscores not starting with a digit
+pub fn is_valid_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Split a `NAME=value` assignment
+pub fn parse_assignment(assignment: &str) -> Option<Variable> {
+    let (name, value) = assignment.split_once('=')?;
+    is_valid_name(name).then(|| (name.to_owned(), value.to_owned()))
+}
+
+/// Parse a dotenv file, as `EnvironmentFile=` does.
+///
+/// Blank lines and `#` or `;` comments are skipped, an `export ` prefix is allowed and the
+/// values can be single or double quoted. Like systemd, the lines that are not a valid
+/// assignment are ignored.
+pub fn parse_dotenv(content: &str) -> Vec<Variable> {
+    let mut variables = Vec::new();
+
+    for (index, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+            continue;
+        }
+
+        let line = line.strip_prefix("export ").unwrap_or(line).trim_start();
+
+        let Some((name, value)) = line.split_once('=') else {
+            warn!("Line {}: ignoring {line:?}, missing '='", index + 1);
+            continue;
+        };
+
+        let name = name.trim_end();
+        if !is_valid_name(name) {
+            warn!(
+                "Line {}: ignoring invalid variable name {name:?}",
+                index + 1
+            );
+            continue;
+        }
+
+        variables.push((name.to_owned(), unquote(value.trim_start())));
+    }
+
+    variables
+}
+
+fn unquote(value: &str) -> String {
+    for quote in ['"', '\''] {
+        if value.len() >= 2 && value.starts_with(quote) && value.ends_with(quote) {
+            let inner = &value[1..value.len() - 1];
+            return if quote == '"' {
+                inner.replace("\\\"", "\"").replace("\\\\", "\\")
+            } else {
+                inner.to_owned()
+            };
+        }
+    }
+
+    value.trim_end().to_owned()
+}
+
+pub async fn manager_environment(level: UnitDBusLevel) -> Result<Vec<Variable>, SystemdErrors> {
+    crate::backend::ensure_live()?;
+
+    let environment = systemd_manager_async(level).await?.environment().await?;
+    Ok(environment
+        .iter()
+        .filter_map(|assignment| parse_assignment(assignment))
+        .collect())
+}
+
+/// The `SetEnvironment` and `UnsetEnvironment` calls turning a manager environment into another
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct EnvironmentChanges {
+    pub set: Vec<String>,
+    pub unset: Vec<String>,
+}
+
+impl EnvironmentChanges {
+    pub fn new(current: &[Variable], edited: &[Variable]) -> Self {
+        let current: BTreeMap<&str, &str> = current
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+            .collect();
+        let edited: BTreeMap<&str, &str> = edited
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+            .collect();
+
+        let set = edited
+            .iter()
+            .filter(|(name, value)| current.get(*name) != Some(*value))
+            .map(|(name, value)| format!("{name}={value}"))
+            .collect();
+
+        let unset = current
+            .keys()
+            .filter(|name| !edited.contains_key(*name))
+            .map(|name| name.to_string())
+            .collect();
+
+        EnvironmentChanges { set, unset }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.set.is_empty() && self.unset.is_empty()
+    }
+}
+
+pub async fn apply_environment_changes(
+    level: UnitDBusLevel,
+    changes: &EnvironmentChanges,
+) -> Result<(), SystemdErrors> {
+    crate::backend::ensure_live()?;
+
+    info!("Manager environment on {level:?} {changes:?}");
+
+    if !changes.unset.is_empty() {
+        let names: Vec<&str> = changes.unset.iter().map(|s| s.as_str()).collect();
+        unset_environment(level, &names).await?;
+    }
+
+    if !changes.set.is_empty() {
+        let assignments: Vec<&str> = changes.set.iter().map(|s| s.as_str()).collect();
+        set_environment(level, &assignments).await?;
+    }
+
+    Ok(())
+}
+
+async fn set_environment(level: UnitDBusLevel, assignments: &[&str]) -> Result<(), SystemdErrors> {
+    #[cfg(not(any(feature = "flatpak", feature = "appimage")))]
+    if !level.bypasses_proxy() && !level.is_other_user() {
+        return crate::proxy_call_async!(set_environment, assignments);
+    }
+
+    systemd_manager_async(level)
+        .await?
+        .set_environment(assignments)
+        .await?;
+    Ok(())
+}
+
+async fn unset_environment(level: UnitDBusLevel, names: &[&str]) -> Result<(), SystemdErrors> {
+    #[cfg(not(any(feature = "flatpak", feature = "appimage")))]
+    if !level.bypasses_proxy() && !level.is_other_user() {
+        return crate::proxy_call_async!(unset_environment, names);
+    }
+
+    systemd_manager_async(level)
+        .await?
+        .unset_environment(names)
+        .await?;
+    Ok(())
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EnvironmentSource {
+    Manager,
+    Unit,
+    File(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EffectiveVariable {
+    pub name: String,
+    pub value: String,
+    pub source: EnvironmentSource,
+    /// The value replaced by this one from a lower source
+    pub overridden: Option<String>,
+}
+
+/// The `Environment=` and `EnvironmentFile=` of a unit
+#[derive(Debug, Default)]
+pub struct UnitEnvironment {
+    pub environment: Vec<Variable>,
+    pub files: Vec<(String, Vec<Variable>)>,
+    /// Files that could not be read, with the reason
+    pub unread_files: Vec<(String, String)>,
+}
+
+impl UnitEnvironment {
+    /// The variables the unit processes get, sorted by name
+    pub fn effective(&self, manager: &[Variable]) -> Vec<EffectiveVariable> {
+        let mut variables: BTreeMap<String, EffectiveVariable> = BTreeMap::new();
+
+        let layers = std::iter::once((EnvironmentSource::Manager, manager))
+            .chain(std::iter::once((
+                EnvironmentSource::Unit,
+                self.environment.as_slice(),
+            )))
+            .chain(self.files.iter().map(|(path, file_variables)| {
+                (
+                    EnvironmentSource::File(path.clone()),
+                    file_variables.as_slice(),
+                )
+            }));
+
+        for (source, layer) in layers {
+            for (name, value) in layer {
+                let overridden = variables.remove(name).map(|previous| previous.value);
+                variables.insert(
+                    name.clone(),
+                    EffectiveVariable {
+                        name: name.clone(),
+                        value: value.clone(),
+                        source: source.clone(),
+                        overridden,
+                    },
+                );
+            }
+        }
+
+        variables.into_values().collect()
+    }
+}
+
+/// Read the environment settings of a unit, the files are read with the user permissions
+pub fn unit_environment(
+    level: UnitDBusLevel,
+    unit_name: &str,
+) -> Result<UnitEnvironment, SystemdErrors> {
+    crate::backend::ensure_live()?;
+
+    let unit_type = unit_name
+        .rsplit_once('.')
+        .map(|(_, suffix)| UnitType::new(suffix))
+        .unwrap_or(UnitType::Unknown);
+
+    if !has_exec_context(&unit_type) {
+        return Ok(UnitEnvironment::default());
+    }
+
+    let connection = get_blocking_connection(level)?;
+    let properties = ZPropertiesProxyBlocking::builder(&connection)
+        .path(unit_dbus_path_from_name(unit_name))?
+        .build()?;
+
+    let interface = unit_type.interface();
+    let environment: OwnedValue = properties.get(interface, "Environment")?;
+    let environment: Vec<String> = environment.try_into()?;
+    let environment_files: OwnedValue = properties.get(interface, "EnvironmentFiles")?;
+    let environment_files: Vec<(String, bool)> = environment_files.try_into()?;
+
+    let mut unit_environment = UnitEnvironment {
+        environment: environment
+            .iter()
+            .filter_map(|assignment| parse_assignment(assignment))
+            .collect(),
+        ..Default::default()
+    };
+
+    for (path, optional) in environment_files {
+        match std::fs::read_to_string(&path).map(|content| parse_dotenv(&content)) {
+            Ok(variables) => unit_environment.files.push((path, variables)),
+            Err(err) if optional && err.kind() == std::io::ErrorKind::NotFound => {}
+            Err(err) => {
+                warn!("Environment file {path:?} {err:?}");
+                unit_environment.unread_files.push((path, err.to_string()));
+            }
+        }
+    }
+
+    Ok(unit_environment)
+}
+
+/// The unit types spawning processes
+fn has_exec_context(unit_type: &UnitType) -> bool {
+    matches!(
+        unit_type,
+        UnitType::Service | UnitType::Socket | UnitType::Mount | UnitType::Swap
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn var(name: &str, value: &str) -> Variable {
+        (name.to_owned(), value.to_owned())
+    }
+
+    #[test]
+    fn test_parse_dotenv() {
+        let content = r#"
+# proxy settings
+export HTTP_PROXY=http://proxy:3128
+NO_PROXY = "localhost,127.0.0.1"
+; other comment
+GREETING='hello world'
+NOT AN ASSIGNMENT
+1ABC=invalid name
+EMPTY=
+"#;
+
+        let variables = parse_dotenv(content);
+        assert_eq!(
+            variables,
+            vec![
+                var("HTTP_PROXY", "http://proxy:3128"),
+                var("NO_PROXY", "localhost,127.0.0.1"),
+                var("GREETING", "hello world"),
+                var("EMPTY", ""),
+            ]
+        );
+
+        assert!(parse_dotenv("NOVALUE").is_empty());
+    }
+
+    #[test]
+    fn test_environment_changes() {
+        let current = vec![var("LANG", "C"), var("PATH", "/usr/bin"), var("OLD", "1")];
+        let edited = vec![
+            var("LANG", "fr_CA.UTF-8"),
+            var("PATH", "/usr/bin"),
+            var("DISPLAY", ":0"),
+        ];
+
+        let changes = EnvironmentChanges::new(&current, &edited);
+        assert_eq!(
+            changes,
+            EnvironmentChanges {
+                set: vec!["DISPLAY=:0".to_owned(), "LANG=fr_CA.UTF-8".to_owned()],
+                unset: vec!["OLD".to_owned()],
+            }
+        );
+
+        assert!(EnvironmentChanges::new(&current, &current).is_empty());
+    }
+
+    #[test]
+    fn test_effective_environment() {
+        let manager = vec![var("LANG", "C"), var("PATH", "/usr/bin")];
+        let unit = UnitEnvironment {
+            environment: vec![var("LANG", "fr_CA.UTF-8"), var("PORT", "80")],
+            files: vec![("/etc/default/app".to_owned(), vec![var("PORT", "8080")])],
+            unread_files: vec![],
+        };
+
+        let effective = unit.effective(&manager);
+        assert_eq!(effective.len(), 3);
+
+        assert_eq!(effective[0].name, "LANG");
+        assert_eq!(effective[0].source, EnvironmentSource::Unit);
+        assert_eq!(effective[0].overridden.as_deref(), Some("C"));
+
+        assert_eq!(effective[1].name, "PATH");
+        assert_eq!(effective[1].source, EnvironmentSource::Manager);
+        assert_eq!(effective[1].overridden, None);
+
+        assert_eq!(effective[2].name, "PORT");
+        assert_eq!(effective[2].value, "8080");
+        assert_eq!(
+            effective[2].source,
+            EnvironmentSource::File("/etc/default/app".to_owned())
+        );
+    }
+}
@@ -0,0 +1,389 @@
+//! The user session manager of another user, for an administrator helping that user.
+//!
+//! The bus of a user only accepts its owner, so every call goes through the privileged proxy
+//! which connects to `/run/user/<uid>/bus` for the attached user. The units take the
+//! [`UnitDBusLevel::OtherUser`] level, one user being attached at a time.
+
+use crate::{
+    CompleteUnitPropertiesCallParams, ListUnitResponse, ReStartStop, UpdatedUnitInfo,
+    enums::{ActiveState, DisEnableFlags, LoadState, StartStopMode, UnitFileStatus},
+    errors::SystemdErrors,
+    sysdbus::{
+        dbus_proxies::{Login1ManagerProxy, Login1UserProxy},
+        get_connection,
+    },
+};
+use base::{enums::UnitDBusLevel, proxy::DisEnAbleUnitFilesResponse};
+use enumflags2::BitFlags;
+use std::{collections::HashMap, str::FromStr, sync::RwLock};
+use tracing::{info, warn};
+use zvariant::OwnedValue;
+
+const INTERFACE_SYSTEMD_UNIT: &str = "org.freedesktop.systemd1.Unit";
+
+/// A user known by logind
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoginUser {
+    pub uid: u32,
+    pub name: String,
+    /// `active`, `online`, `lingering`, ...
+    pub state: String,
+    pub linger: bool,
+}
+
+/// The user whose session manager is listed as [`UnitDBusLevel::OtherUser`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AttachedUser {
+    pub uid: u32,
+    pub name: String,
+}
+
+static ATTACHED_USER: RwLock<Option<AttachedUser>> = RwLock::new(None);
+
+pub fn attach_user(uid: u32, name: &str) {
+    info!("Attach the user manager of {name:?} ({uid})");
+    *ATTACHED_USER.write().unwrap() = Some(AttachedUser {
+        uid,
+        name: name.to_owned(),
+    });
+}
+
+pub fn detach_user() -> Option<AttachedUser> {
+    let detached = ATTACHED_USER.write().unwrap().take();
+    if let Some(user) = &detached {
+        info!("Detach the user manager of {:?} ({})", user.name, user.uid);
+    }
+    detached
+}
+
+pub fn attached_user() -> Option<AttachedUser> {
+    ATTACHED_USER.read().unwrap().clone()
+}
+
+pub(crate) fn attached_uid() -> Result<u32, SystemdErrors> {
+    attached_user()
+        .map(|user| user.uid)
+        .ok_or_else(|| SystemdErrors::Custom("No user attached".to_owned()))
+}
+
+/// Never connect directly, the bus of the other users is only reached by the proxy
+pub(crate) fn no_direct_connection() -> SystemdErrors {
+    SystemdErrors::Custom(
+        "The units of other users are only reachable through the proxy".to_owned(),
+    )
+}
+
+/// The logged in and lingering users
+pub async fn list_login_users() -> Result<Vec<LoginUser>, SystemdErrors> {
+    crate::backend::ensure_live()?;
+
+    let connection = get_connection(UnitDBusLevel::System).await?;
+    let manager = Login1ManagerProxy::new(&connection).await?;
+
+    let mut users = Vec::new();
+    for (uid, name, path) in manager.list_users().await? {
+        let user = Login1UserProxy::builder(&connection)
+            .path(path)?
+            .build()
+            .await?;
+
+        let state = user
+            .state()
+            .await
+            .inspect_err(|err| warn!("User {name:?} state {err:?}"))
+            .unwrap_or_default();
+        let linger = user.linger().await.unwrap_or_default();
+
+        users.push(LoginUser {
+            uid,
+            name,
+            state,
+            linger,
+        });
+    }
+
+    users.sort_by_key(|user| user.uid);
+    Ok(users)
+}
+
+#[cfg(not(any(feature = "flatpak", feature = "appimage")))]
+mod routed {
+    use super::*;
+
+    pub(super) async fn list_loaded_units() -> Result<ListUnitResponse, SystemdErrors> {
+        let uid = attached_uid()?;
+        let units = crate::proxy_call_async!(user_list_units, uid)?;
+        Ok(ListUnitResponse::Loaded(UnitDBusLevel::OtherUser, units))
+    }
+
+    pub(super) async fn list_unit_files() -> Result<ListUnitResponse, SystemdErrors> {
+        let uid = attached_uid()?;
+        let files = crate::proxy_call_async!(user_list_unit_files, uid)?;
+        Ok(ListUnitResponse::File(UnitDBusLevel::OtherUser, files))
+    }
+
+    pub(super) async fn unit_properties(
+        unit_name: &str,
+    ) -> Result<HashMap<String, OwnedValue>, SystemdErrors> {
+        let uid = attached_uid()?;
+        crate::proxy_call_async!(user_unit_properties, uid, unit_name, INTERFACE_SYSTEMD_UNIT)
+    }
+
+    pub(super) async fn restartstop_unit(
+        unit_name: &str,
+        mode: StartStopMode,
+        action: &ReStartStop,
+    ) -> Result<String, SystemdErrors> {
+        let uid = attached_uid()?;
+        crate::proxy_call_async!(user_restartstop_unit, uid, unit_name, mode.as_str(), action)
+    }
+
+    pub(super) async fn daemon_reload() -> Result<(), SystemdErrors> {
+        let uid = attached_uid()?;
+        crate::proxy_call_async!(user_reload, uid)
+    }
+
+    pub(super) fn unit_properties_blocking(
+        unit_name: &str,
+    ) -> Result<HashMap<String, OwnedValue>, SystemdErrors> {
+        let uid = attached_uid()?;
+        crate::proxy_call_blocking!(
+            user_unit_properties_blocking,
+            uid,
+            unit_name,
+            INTERFACE_SYSTEMD_UNIT
+        )
+    }
+
+    pub(super) fn enable_unit_file(
+        unit_file: &str,
+        flags: u64,
+    ) -> Result<DisEnAbleUnitFilesResponse, SystemdErrors> {
+        let uid = attached_uid()?;
+        crate::proxy_call_blocking!(user_enable_unit_files_with_flags, uid, &[unit_file], flags)
+    }
+
+    pub(super) fn disable_unit_file(
+        unit_file: &str,
+        flags: u64,
+    ) -> Result<DisEnAbleUnitFilesResponse, SystemdErrors> {
+        let uid = attached_uid()?;
+        crate::proxy_call_blocking!(user_disable_unit_files_with_flags, uid, &[unit_file], flags)
+    }
+}
+
+#[cfg(any(feature = "flatpak", feature = "appimage"))]
+mod routed {
+    use super::*;
+
+    fn no_proxy() -> SystemdErrors {
+        SystemdErrors::Custom("The units of other users need the proxy".to_owned())
+    }
+
+    pub(super) async fn list_loaded_units() -> Result<ListUnitResponse, SystemdErrors> {
+        Err(no_proxy())
+    }
+
+    pub(super) async fn list_unit_files() -> Result<ListUnitResponse, SystemdErrors> {
+        Err(no_proxy())
+    }
+
+    pub(super) async fn unit_properties(
+        _unit_name: &str,
+    ) -> Result<HashMap<String, OwnedValue>, SystemdErrors> {
+        Err(no_proxy())
+    }
+
+    pub(super) async fn restartstop_unit(
+        _unit_name: &str,
+        _mode: StartStopMode,
+        _action: &ReStartStop,
+    ) -> Result<String, SystemdErrors> {
+        Err(no_proxy())
+    }
+
+    pub(super) async fn daemon_reload() -> Result<(), SystemdErrors> {
+        Err(no_proxy())
+    }
+
+    pub(super) fn unit_properties_blocking(
+        _unit_name: &str,
+    ) -> Result<HashMap<String, OwnedValue>, SystemdErrors> {
+        Err(no_proxy())
+    }
+
+    pub(super) fn enable_unit_file(
+        _unit_file: &str,
+        _flags: u64,
+    ) -> Result<DisEnAbleUnitFilesResponse, SystemdErrors> {
+        Err(no_proxy())
+    }
+
+    pub(super) fn disable_unit_file(
+        _unit_file: &str,
+        _flags: u64,
+    ) -> Result<DisEnAbleUnitFilesResponse, SystemdErrors> {
+        Err(no_proxy())
+    }
+}
+
+pub(crate) use routed::{daemon_reload, list_loaded_units, list_unit_files, restartstop_unit};
+
+/// `ListUnitsByPatterns` done on the complete list, the proxy only lists everything
+pub(crate) async fn list_loaded_units_by_patterns(
+    patterns: &[&str],
+) -> Result<ListUnitResponse, SystemdErrors> {
+    let mut response = list_loaded_units().await?;
+    if let ListUnitResponse::Loaded(_, units) = &mut response {
+        units.retain(|unit| matches_any(patterns, &unit.primary_unit_name));
+    }
+    Ok(response)
+}
+
+pub(crate) async fn list_unit_files_by_patterns(
+    patterns: &[&str],
+) -> Result<ListUnitResponse, SystemdErrors> {
+    let mut response = list_unit_files().await?;
+    if let ListUnitResponse::File(_, files) = &mut response {
+        files.retain(|file| matches_any(patterns, file.unit_primary_name()));
+    }
+    Ok(response)
+}
+
+fn matches_any(patterns: &[&str], unit_name: &str) -> bool {
+    patterns.is_empty()
+        || patterns
+            .iter()
+            .any(|pattern| pattern_matches(pattern, unit_name))
+}
+
+/// Shell like match supporting only `*`, enough for the patterns the GUI sends
+fn pattern_matches(pattern: &str, unit_name: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = unit_name.strip_prefix(first) else {
+        return false;
+    };
+
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        return rest.is_empty();
+    };
+
+    for part in middle {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+
+    rest.ends_with(last)
+}
+
+pub(crate) fn enable_unit_file(
+    unit_file: &str,
+    flags: BitFlags<DisEnableFlags>,
+) -> Result<DisEnAbleUnitFilesResponse, SystemdErrors> {
+    routed::enable_unit_file(unit_file, flags.bits_c() as u64)
+}
+
+pub(crate) fn disable_unit_file(
+    unit_file: &str,
+    flags: BitFlags<DisEnableFlags>,
+) -> Result<DisEnAbleUnitFilesResponse, SystemdErrors> {
+    routed::disable_unit_file(unit_file, flags.bits_c() as u64)
+}
+
+pub(crate) fn get_unit_active_state(unit_name: &str) -> Result<ActiveState, SystemdErrors> {
+    let properties = routed::unit_properties_blocking(unit_name)?;
+    let active_state = string_property(&properties, "ActiveState").unwrap_or_default();
+    Ok(ActiveState::from(active_state.as_str()))
+}
+
+/// Same completion as the direct bus, from the `Unit` properties the proxy returns
+pub(crate) async fn complete_unit_information(
+    units: &[&CompleteUnitPropertiesCallParams],
+) -> Vec<UpdatedUnitInfo> {
+    let mut output = Vec::with_capacity(units.len());
+    for params in units {
+        match routed::unit_properties(&params.unit_name).await {
+            Ok(properties) => output.push(updated_unit_info(&params.unit_name, &properties)),
+            Err(err) => warn!("Complete unit {:?} error {err:?}", params.unit_name),
+        }
+    }
+    output
+}
+
+fn updated_unit_info(unit_name: &str, properties: &HashMap<String, OwnedValue>) -> UpdatedUnitInfo {
+    let mut update = UpdatedUnitInfo::new(unit_name.to_owned(), UnitDBusLevel::OtherUser);
+
+    update.active_state =
+        string_property(properties, "ActiveState").map(|state| ActiveState::from(state.as_str()));
+    update.description = string_property(properties, "Description");
+    update.load_state =
+        string_property(properties, "LoadState").map(|state| LoadState::from(state.as_str()));
+    update.sub_state = string_property(properties, "SubState");
+    update.unit_file_preset = string_property(properties, "UnitFilePreset");
+    update.fragment_path = string_property(properties, "FragmentPath").filter(|p| !p.is_empty());
+    update.enablement_status = string_property(properties, "UnitFileState")
+        .and_then(|state| UnitFileStatus::from_str(&state).ok());
+    update.valid_unit_name = true;
+
+    update
+}
+
+fn string_property(properties: &HashMap<String, OwnedValue>, key: &str) -> Option<String> {
+    let value = properties.get(key)?;
+    value
+        .try_clone()
+        .ok()
+        .and_then(|value| String::try_from(value).ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zvariant::Str;
+
+    #[test]
+    fn test_attach_user() {
+        attach_user(1001, "alice");
+        assert_eq!(attached_uid().unwrap(), 1001);
+        assert_eq!(detach_user().unwrap().name, "alice");
+        assert!(attached_user().is_none());
+        assert!(attached_uid().is_err());
+    }
+
+    #[test]
+    fn test_pattern_matches() {
+        assert!(pattern_matches("*.timer", "backup.timer"));
+        assert!(!pattern_matches("*.timer", "backup.service"));
+        assert!(pattern_matches("mpd.service", "mpd.service"));
+        assert!(!pattern_matches("mpd.service", "mpd.service.d"));
+        assert!(pattern_matches("foo@*.service", "foo@bar.service"));
+        assert!(!pattern_matches("foo@*.service", "foo.service"));
+        assert!(pattern_matches("*", "anything.socket"));
+        assert!(matches_any(&[], "anything.socket"));
+    }
+
+    #[test]
+    fn test_updated_unit_info() {
+        let mut properties = HashMap::new();
+        for (key, value) in [
+            ("ActiveState", "active"),
+            ("Description", "Music Player Daemon"),
+            ("SubState", "running"),
+            ("FragmentPath", ""),
+        ] {
+            properties.insert(key.to_owned(), OwnedValue::from(Str::from(value)));
+        }
+
+        let update = updated_unit_info("mpd.service", &properties);
+        assert_eq!(update.level, UnitDBusLevel::OtherUser);
+        assert_eq!(update.active_state, Some(ActiveState::Active));
+        assert_eq!(update.description.as_deref(), Some("Music Player Daemon"));
+        assert_eq!(update.sub_state.as_deref(), Some("running"));
+        assert_eq!(update.fragment_path, None);
+        assert_eq!(update.enablement_status, None);
+    }
+}
@@ -72,7 +72,7 @@ mod imp {
 
         #[property(get)]
         socket_listen_idx: Cell<u32>,
-        #[property(get, set, default)]
+        #[property(get, set)]
         dbus_level: Cell<UnitDBusLevel>,
 
         // socket_listen_idx: Cell<usize>,
@@ -82,6 +82,18 @@ pub static BLK_CON_SYST: RwLock<Option<Connection>> = RwLock::new(None);
 pub static BLK_CON_USER: RwLock<Option<Connection>> = RwLock::new(None);
 pub static CON_ASYNC_SYST: RwLock<Option<zbus::Connection>> = RwLock::new(None);
 pub static CON_ASYNC_USER: RwLock<Option<zbus::Connection>> = RwLock::new(None);
+static BLK_CON_CUSTOM: RwLock<BTreeMap<UnitDBusLevel, Connection>> = RwLock::new(BTreeMap::new());
+static CON_ASYNC_CUSTOM: RwLock<BTreeMap<UnitDBusLevel, zbus::Connection>> =
+    RwLock::new(BTreeMap::new());
+
+/// Bus used for the system and the user session levels instead of the standard ones
+static BUS_ADDRESS: OnceLock<String> = OnceLock::new();
+
+/// Connect every level to the bus at `address`, like a private test bus. To call before the
+/// first connection, returns false if an other address was already set.
+pub fn set_bus_address(address: &str) -> bool {
+    BUS_ADDRESS.get_or_init(|| address.to_owned()) == address
+}
 
 struct RunContext {
     run_mode: RunMode,
@@ -239,6 +251,23 @@ pub fn shut_down_sysd_proxy() -> Result<(), SystemdErrors> {
 }
 
 pub(crate) fn get_blocking_connection(level: UnitDBusLevel) -> Result<Connection, SystemdErrors> {
+    if level.is_other_user() {
+        return Err(crate::other_user::no_direct_connection());
+    }
+
+    if level.is_custom() {
+        if let Some(conn) = BLK_CON_CUSTOM.read().unwrap().get(&level) {
+            return Ok(conn.clone());
+        }
+
+        let connection = build_blocking_connection(level)?;
+        BLK_CON_CUSTOM
+            .write()
+            .unwrap()
+            .insert(level, connection.clone());
+        return Ok(connection);
+    }
+
     let lock = match level {
         UnitDBusLevel::UserSession => &BLK_CON_USER,
         _ => &BLK_CON_SYST,
@@ -256,10 +285,14 @@ pub(crate) fn get_blocking_connection(level: UnitDBusLevel) -> Result<Connection
 }
 
 fn build_blocking_connection(level: UnitDBusLevel) -> Result<Connection, SystemdErrors> {
-    debug!("Getting connection Level {:?}, id {}", level, level as u32);
-    let connection_builder = match level {
-        UnitDBusLevel::UserSession => zbus::blocking::connection::Builder::session()?,
-        _ => zbus::blocking::connection::Builder::system()?,
+    debug!("Getting connection Level {:?}, id {}", level, level.value());
+    let connection_builder = match (BUS_ADDRESS.get(), level) {
+        (_, level) if level.is_custom() => {
+            zbus::blocking::connection::Builder::address(custom_bus_address(level)?.as_str())?
+        }
+        (Some(address), _) => zbus::blocking::connection::Builder::address(address.as_str())?,
+        (None, UnitDBusLevel::UserSession) => zbus::blocking::connection::Builder::session()?,
+        (None, _) => zbus::blocking::connection::Builder::system()?,
     };
 
     let connection = connection_builder
@@ -272,6 +305,23 @@ fn build_blocking_connection(level: UnitDBusLevel) -> Result<Connection, Systemd
 }
 
 pub async fn get_connection(level: UnitDBusLevel) -> Result<zbus::Connection, SystemdErrors> {
+    if level.is_other_user() {
+        return Err(crate::other_user::no_direct_connection());
+    }
+
+    if level.is_custom() {
+        if let Some(conn) = CON_ASYNC_CUSTOM.read().unwrap().get(&level) {
+            return Ok(conn.clone());
+        }
+
+        let connection = build_connection(level).await?;
+        CON_ASYNC_CUSTOM
+            .write()
+            .unwrap()
+            .insert(level, connection.clone());
+        return Ok(connection);
+    }
+
     let lock: &RwLock<Option<zbus::Connection>> = match level {
         UnitDBusLevel::UserSession => &CON_ASYNC_USER,
         _ => &CON_ASYNC_SYST,
@@ -289,10 +339,14 @@ pub async fn get_connection(level: UnitDBusLevel) -> Result<zbus::Connection, Sy
 }
 
 async fn build_connection(level: UnitDBusLevel) -> Result<zbus::Connection, SystemdErrors> {
-    debug!("Level {:?}, id {}", level, level as u32);
-    let connection_builder = match level {
-        UnitDBusLevel::UserSession => zbus::connection::Builder::session()?,
-        _ => zbus::connection::Builder::system()?,
+    debug!("Level {:?}, id {}", level, level.value());
+    let connection_builder = match (BUS_ADDRESS.get(), level) {
+        (_, level) if level.is_custom() => {
+            zbus::connection::Builder::address(custom_bus_address(level)?.as_str())?
+        }
+        (Some(address), _) => zbus::connection::Builder::address(address.as_str())?,
+        (None, UnitDBusLevel::UserSession) => zbus::connection::Builder::session()?,
+        (None, _) => zbus::connection::Builder::system()?,
     };
 
     let connection = connection_builder
@@ -305,6 +359,18 @@ async fn build_connection(level: UnitDBusLevel) -> Result<zbus::Connection, Syst
     Ok(connection)
 }
 
+fn custom_bus_address(level: UnitDBusLevel) -> Result<String, SystemdErrors> {
+    crate::custom_bus::custom_bus(level)
+        .map(|bus| bus.address)
+        .ok_or_else(|| SystemdErrors::Custom(format!("No bus connection registered for {level:?}")))
+}
+
+/// Drop the cached connections of a custom bus level, the next call connects again
+pub(crate) fn forget_custom_connection(level: UnitDBusLevel) {
+    BLK_CON_CUSTOM.write().unwrap().remove(&level);
+    CON_ASYNC_CUSTOM.write().unwrap().remove(&level);
+}
+
 async fn list_units_list_async(
     connection: zbus::Connection,
 ) -> Result<Vec<ListedLoadedUnit>, SystemdErrors> {
@@ -330,7 +396,7 @@ pub fn get_unit_file_state(
     level: UnitDBusLevel,
     unit_file: &str,
 ) -> Result<UnitFileStatus, SystemdErrors> {
-    let manager_proxy = systemd_manager_blocking(level);
+    let manager_proxy = systemd_manager_blocking(level)?;
     let status: UnitFileStatus = manager_proxy.get_unit_file_state(unit_file)?.into();
     Ok(status)
 }
@@ -425,7 +491,8 @@ pub async fn get_unit_file_state_async(
 pub async fn list_units_description_and_state_async(
     level: UnitDBusLevel,
 ) -> Result<(Vec<ListedLoadedUnit>, Vec<SystemdUnitFile>), SystemdErrors> {
-    let t1 = tokio::spawn(systemd_manager_async(level).await?.list_units());
+    let manager = systemd_manager_async(level).await?;
+    let t1 = tokio::spawn(async move { manager.list_units().await });
     let t2 = tokio::spawn(fill_list_unit_files(level));
 
     let joined = tokio::join!(t1, t2);
@@ -441,6 +508,11 @@ pub async fn complete_unit_information(
 ) -> Result<Vec<UpdatedUnitInfo>, SystemdErrors> {
     let mut ouput = Vec::with_capacity(units.len());
     for params in units.iter() {
+        // completed through the proxy by the caller
+        if params.level.is_other_user() {
+            continue;
+        }
+
         let connection = get_connection(params.level).await?;
 
         let f2 = get_unit_file_state_async(params.level, &params.unit_name);
@@ -1240,6 +1312,39 @@ pub(super) fn unit_get_dependencies(
     Ok(dependency)
 }
 
+/// The units grouped by a slice or a target
+pub(super) fn unit_members(
+    dbus_level: UnitDBusLevel,
+    unit_name: &str,
+) -> Result<Vec<String>, SystemdErrors> {
+    let connection = get_blocking_connection(dbus_level)?;
+    let object_path = unit_dbus_path_from_name(unit_name);
+    let map = fetch_unit_all_properties(&connection, &object_path)?;
+
+    // The units of a slice implicitly require it
+    let properties: &[&str] = if unit_name.ends_with(".slice") {
+        &["RequiredBy"]
+    } else {
+        &["Wants", "Requires", "ConsistsOf"]
+    };
+
+    let mut members = BTreeSet::new();
+    for property_key in properties {
+        let Some(value) = map.get(*property_key) else {
+            warn!("property key {property_key:?} does't exist");
+            continue;
+        };
+
+        let array: &Array = value.try_into()?;
+        for sv in array.iter() {
+            let member: &str = sv.try_into()?;
+            members.insert(member.to_owned());
+        }
+    }
+
+    Ok(members.into_iter().collect())
+}
+
 fn flatit(parent: &Dependency, all_children: &mut BTreeSet<Dependency>) {
     for child in parent.children.iter() {
         flatit(child, all_children);
@@ -1343,6 +1448,42 @@ fn hexchar(x: u8) -> char {
     TABLE[(x & 15) as usize]
 }
 
+/// The unit name of a unit object path, the reverse of [`unit_dbus_path_from_name`]
+pub fn unit_name_from_dbus_path(path: &str) -> Option<String> {
+    const PREFIX: &str = "/org/freedesktop/systemd1/unit/";
+    path.strip_prefix(PREFIX).map(bus_label_unescape)
+}
+
+fn bus_label_unescape(label: &str) -> String {
+    if label == "_" {
+        return String::new();
+    }
+
+    let bytes = label.as_bytes();
+    let mut r = Vec::with_capacity(bytes.len());
+
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = (bytes[i] == b'_')
+            .then(|| label.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+
+        match escaped {
+            Some(c) => {
+                r.push(c);
+                i += 3;
+            }
+            None => {
+                r.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+
+    String::from_utf8_lossy(&r).into_owned()
+}
+
 pub fn get_unit_active_state(
     dbus_level: UnitDBusLevel,
     unit_path: &str,
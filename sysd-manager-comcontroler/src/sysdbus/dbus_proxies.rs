@@ -6,7 +6,7 @@ use base::{
 };
 use tokio::sync::OnceCell;
 use tracing::error;
-use zbus::proxy;
+use zbus::{CacheProperties, proxy};
 use zvariant::{OwnedObjectPath, OwnedValue};
 
 use crate::{
@@ -15,6 +15,62 @@ use crate::{
     sysdbus::{ListedUnitFile, get_blocking_connection, get_connection},
 };
 
+/// `ListSessions` entry, `(session id, uid, user name, seat id, session object path)`
+pub(crate) type ListedSession = (String, u32, String, String, OwnedObjectPath);
+
+#[proxy(
+    interface = "org.freedesktop.login1.Manager",
+    default_service = "org.freedesktop.login1",
+    default_path = "/org/freedesktop/login1"
+)]
+pub(crate) trait Login1Manager {
+    /// `(uid, name, user object path)` of the logged in and lingering users
+    fn list_users(&self) -> Result<Vec<(u32, String, OwnedObjectPath)>, zbus::Error>;
+
+    fn list_sessions(&self) -> Result<Vec<ListedSession>, zbus::Error>;
+
+    #[zbus(allow_interactive_auth)]
+    fn set_user_linger(&self, uid: u32, enable: bool, interactive: bool)
+    -> Result<(), zbus::Error>;
+}
+
+#[proxy(
+    interface = "org.freedesktop.login1.User",
+    default_service = "org.freedesktop.login1"
+)]
+pub(crate) trait Login1User {
+    #[zbus(property)]
+    fn state(&self) -> Result<String, zbus::Error>;
+
+    #[zbus(property)]
+    fn linger(&self) -> Result<bool, zbus::Error>;
+}
+
+#[proxy(
+    interface = "org.freedesktop.login1.Session",
+    default_service = "org.freedesktop.login1"
+)]
+pub(crate) trait Login1Session {
+    #[zbus(property, name = "TTY")]
+    fn tty(&self) -> Result<String, zbus::Error>;
+
+    #[zbus(property)]
+    fn state(&self) -> Result<String, zbus::Error>;
+
+    #[zbus(property)]
+    fn class(&self) -> Result<String, zbus::Error>;
+
+    #[zbus(property)]
+    fn remote(&self) -> Result<bool, zbus::Error>;
+
+    #[zbus(property)]
+    fn idle_hint(&self) -> Result<bool, zbus::Error>;
+
+    /// Realtime in microseconds
+    #[zbus(property)]
+    fn idle_since_hint(&self) -> Result<u64, zbus::Error>;
+}
+
 #[proxy(
     interface = "org.freedesktop.DBus.Properties",
     default_service = "org.freedesktop.systemd1"
@@ -60,6 +116,12 @@ pub(crate) trait ZUnitInfo {
     #[zbus(property)]
     fn drop_in_paths(&self) -> Result<Vec<String>, zbus::Error>;
 
+    #[zbus(property)]
+    fn allow_isolate(&self) -> Result<bool, zbus::Error>;
+
+    #[zbus(property)]
+    fn ignore_on_isolate(&self) -> Result<bool, zbus::Error>;
+
     fn stop(&self, mode: &str) -> Result<OwnedObjectPath, zbus::Error>;
 }
 
@@ -171,6 +233,24 @@ pub(crate) trait Systemd1Manager {
 
     fn get_unit_file_state(&self, file: &str) -> Result<String, zbus::Error>;
 
+    fn get_default_target(&self) -> zbus::fdo::Result<String>;
+
+    #[zbus(allow_interactive_auth)]
+    fn set_default_target(
+        &self,
+        name: &str,
+        force: bool,
+    ) -> zbus::fdo::Result<Vec<DisEnAbleUnitFiles>>;
+
+    #[zbus(property)]
+    fn environment(&self) -> Result<Vec<String>, zbus::Error>;
+
+    #[zbus(allow_interactive_auth)]
+    fn set_environment(&self, assignments: &[&str]) -> zbus::fdo::Result<()>;
+
+    #[zbus(allow_interactive_auth)]
+    fn unset_environment(&self, names: &[&str]) -> zbus::fdo::Result<()>;
+
     fn subscribe(&self) -> Result<(), zbus::Error>;
     fn unsubscribe(&self) -> Result<(), zbus::Error>;
 }
@@ -212,18 +292,37 @@ pub fn systemd_manager_session<'a>() -> &'a Systemd1ManagerProxyBlocking<'a> {
     (&*SYSTEM_MANAGER_SESSION_BLOCKING) as _
 }
 
-pub fn systemd_manager_blocking<'a>(level: UnitDBusLevel) -> &'a Systemd1ManagerProxyBlocking<'a> {
+pub fn systemd_manager_blocking(
+    level: UnitDBusLevel,
+) -> Result<Systemd1ManagerProxyBlocking<'static>, SystemdErrors> {
     match level {
-        UnitDBusLevel::System | UnitDBusLevel::Both => systemd_manager(),
-        UnitDBusLevel::UserSession => systemd_manager_session(),
+        UnitDBusLevel::System | UnitDBusLevel::Both => Ok(systemd_manager().clone()),
+        UnitDBusLevel::UserSession => Ok(systemd_manager_session().clone()),
+        _ => {
+            // not cached, the bus can be removed at any time
+            let conn = get_blocking_connection(level)?;
+            let proxy = Systemd1ManagerProxyBlocking::builder(&conn)
+                .cache_properties(CacheProperties::No)
+                .build()?;
+            Ok(proxy)
+        }
     }
 }
+
 pub async fn systemd_manager_async(
     level: UnitDBusLevel,
-) -> Result<&'static Systemd1ManagerProxy<'static>, SystemdErrors> {
+) -> Result<Systemd1ManagerProxy<'static>, SystemdErrors> {
     match level {
-        UnitDBusLevel::System | UnitDBusLevel::Both => system_manager_system_async().await,
-        UnitDBusLevel::UserSession => system_manager_user_session_async().await,
+        UnitDBusLevel::System | UnitDBusLevel::Both => system_manager_system_async().await.cloned(),
+        UnitDBusLevel::UserSession => system_manager_user_session_async().await.cloned(),
+        _ => {
+            let conn = get_connection(level).await?;
+            let proxy = Systemd1ManagerProxy::builder(&conn)
+                .cache_properties(CacheProperties::No)
+                .build()
+                .await?;
+            Ok(proxy)
+        }
     }
 }
 
@@ -4,33 +4,53 @@ use crate::{
     SystemdUnitFile,
     enums::{DependencyType, StartStopMode},
 };
-use test_base::{TEST_SERVICE, init_logs};
+use std::sync::{Mutex, MutexGuard};
+use test_base::{
+    TEST_SERVICE, TestHarness,
+    harness::{
+        ACTIVE_SERVICE, ACTIVE_SERVICE_PID, FAILING_SERVICE, MASK_SERVICE, TEST_TIMER,
+        TIMER_SERVICE, default_units,
+    },
+    init_logs,
+};
 use zvariant::Value;
 
-#[ignore = "need a connection to a service"]
+/// Stop through the library on the private bus, the system level would go through the proxy
 #[test]
 fn stop_service_test() -> Result<(), SystemdErrors> {
-    crate::stop_unit(UnitDBusLevel::System, TEST_SERVICE, StartStopMode::Fail)?;
+    let Some((_harness, _serial)) = fake_bus() else {
+        return Ok(());
+    };
+
+    let level = UnitDBusLevel::UserSession;
+    crate::stop_unit(level, ACTIVE_SERVICE, StartStopMode::Fail)?;
+    assert_eq!(
+        crate::get_unit_active_state(level, ACTIVE_SERVICE)?,
+        ActiveState::Inactive
+    );
     Ok(())
 }
 
-#[ignore = "need a connection to a service"]
 #[test]
-fn test_get_unit_file_state() {
-    init_logs();
-    let file1: &str = TEST_SERVICE;
+fn test_get_unit_file_state() -> Result<(), SystemdErrors> {
+    let Some((_harness, _serial)) = fake_bus() else {
+        return Ok(());
+    };
 
-    let status = get_unit_file_state(UnitDBusLevel::System, file1);
+    let status = get_unit_file_state(UnitDBusLevel::System, TIMER_SERVICE)?;
     debug!("Status: {status:?}");
+    assert_eq!(status, UnitFileStatus::Static);
+    Ok(())
 }
 
-#[ignore = "need a connection to a service"]
-#[tokio::test]
-async fn test_list_unit_files_system() -> Result<(), SystemdErrors> {
-    init_logs();
+#[test]
+fn test_list_unit_files_system() -> Result<(), SystemdErrors> {
+    let Some((_harness, _serial)) = fake_bus() else {
+        return Ok(());
+    };
 
     let level = UnitDBusLevel::System;
-    let unit_files = fill_list_unit_files(level).await?;
+    let unit_files = crate::runtime().block_on(fill_list_unit_files(level))?;
 
     info!("Unit file returned {}", unit_files.len());
 
@@ -39,17 +59,20 @@ async fn test_list_unit_files_system() -> Result<(), SystemdErrors> {
         debug!("{}", unit_file.file_path);
     }
 
+    assert_eq!(unit_files.len(), default_units().len());
+    assert!(unit_files.iter().all(|unit_file| unit_file.level == level));
+
     Ok(())
 }
 
-#[ignore = "need a connection to a service"]
-#[tokio::test]
-async fn test_list_unit_files_remote() -> Result<(), SystemdErrors> {
-    init_logs();
+#[test]
+fn test_list_unit_files_paths() -> Result<(), SystemdErrors> {
+    let Some((_harness, _serial)) = fake_bus() else {
+        return Ok(());
+    };
 
-    // let stream = UnixStream::connect("plr@192.168.0.114").await?;
     let level = UnitDBusLevel::System;
-    let unit_files = fill_list_unit_files(level).await?;
+    let unit_files = crate::runtime().block_on(fill_list_unit_files(level))?;
 
     info!("Unit file returned {}", unit_files.len());
 
@@ -58,20 +81,33 @@ async fn test_list_unit_files_remote() -> Result<(), SystemdErrors> {
         debug!("{}", unit_file.file_path);
     }
 
+    let unit_file = unit_files
+        .iter()
+        .find(|unit_file| unit_file.full_name == TEST_SERVICE)
+        .expect("test service file listed");
+    assert_eq!(
+        unit_file.file_path,
+        format!("/etc/systemd/system/{TEST_SERVICE}")
+    );
+
     Ok(())
 }
 
-#[ignore = "need a connection to a service"]
-#[tokio::test]
-async fn test_list_unit_files_system_raw() -> Result<(), SystemdErrors> {
-    init_logs();
+#[test]
+fn test_list_unit_files_system_raw() -> Result<(), SystemdErrors> {
+    let Some((_harness, _serial)) = fake_bus() else {
+        return Ok(());
+    };
 
     let level = UnitDBusLevel::System;
 
-    let array: Vec<ListedUnitFile> = systemd_manager_async(level)
-        .await?
-        .list_unit_files()
-        .await?;
+    let array: Vec<ListedUnitFile> = crate::runtime().block_on(async {
+        systemd_manager_async(level)
+            .await?
+            .list_unit_files()
+            .await
+            .map_err(SystemdErrors::from)
+    })?;
 
     for (idx, unit_file) in array.iter().enumerate() {
         debug!(
@@ -80,18 +116,28 @@ async fn test_list_unit_files_system_raw() -> Result<(), SystemdErrors> {
         );
     }
 
+    let unit_file = array
+        .iter()
+        .find(|unit_file| unit_file.unit_file_path.ends_with(TEST_SERVICE))
+        .expect("test service file listed");
+    assert_eq!(unit_file.enablement_status, "enabled");
+
     Ok(())
 }
 
-#[ignore = "need a connection to a service"]
-#[tokio::test]
-async fn test_list_units_by_patterns() -> Result<(), SystemdErrors> {
-    init_logs();
+#[test]
+fn test_list_units_by_patterns() -> Result<(), SystemdErrors> {
+    let Some((_harness, _serial)) = fake_bus() else {
+        return Ok(());
+    };
 
-    let array: Vec<ListedLoadedUnit> = systemd_manager_async(UnitDBusLevel::System)
-        .await?
-        .list_units_by_patterns(&["active", "inactive"], &["*.timer"])
-        .await?;
+    let array: Vec<ListedLoadedUnit> = crate::runtime().block_on(async {
+        systemd_manager_async(UnitDBusLevel::System)
+            .await?
+            .list_units_by_patterns(&["active", "inactive"], &["*.timer"])
+            .await
+            .map_err(SystemdErrors::from)
+    })?;
 
     for (idx, loaded_unit) in array.iter().enumerate() {
         debug!(
@@ -100,30 +146,45 @@ async fn test_list_units_by_patterns() -> Result<(), SystemdErrors> {
         );
     }
 
+    let names: Vec<_> = array
+        .iter()
+        .map(|unit| unit.primary_unit_name.as_str())
+        .collect();
+    assert_eq!(names, vec![TEST_TIMER]);
+
     Ok(())
 }
 
-#[ignore = "need a connection to a service"]
-#[tokio::test]
-async fn test_list_units_late_filter_timer() -> Result<(), SystemdErrors> {
-    init_logs();
+#[test]
+fn test_list_units_late_filter_timer() -> Result<(), SystemdErrors> {
+    let Some((_harness, _serial)) = fake_bus() else {
+        return Ok(());
+    };
 
-    let array: Vec<ListedLoadedUnit> = systemd_manager_async(UnitDBusLevel::System)
-        .await?
-        .list_units()
-        .await?;
+    let array: Vec<ListedLoadedUnit> = crate::runtime().block_on(async {
+        systemd_manager_async(UnitDBusLevel::System)
+            .await?
+            .list_units()
+            .await
+            .map_err(SystemdErrors::from)
+    })?;
 
-    for (idx, loaded_unit) in array
+    let timers: Vec<_> = array
         .iter()
         .filter(|lunit| lunit.primary_unit_name.ends_with("timer"))
-        .enumerate()
-    {
+        .collect();
+
+    for (idx, loaded_unit) in timers.iter().enumerate() {
         debug!(
             "{idx} - {} - {}",
             loaded_unit.primary_unit_name, loaded_unit.active_state
         );
     }
 
+    assert_eq!(timers.len(), 1);
+    assert_eq!(timers[0].primary_unit_name, TEST_TIMER);
+    assert_eq!(timers[0].active_state, "active");
+
     Ok(())
 }
 
@@ -154,11 +215,11 @@ fn test_list_units() -> Result<(), SystemdErrors> {
     Ok(())
 } */
 
-#[ignore = "need a connection to a service"]
 #[test]
 pub fn test_get_unit_path() -> Result<(), SystemdErrors> {
-    init_logs();
-    let unit_file: &str = "tiny_daemon.service";
+    let Some((_harness, _serial)) = fake_bus() else {
+        return Ok(());
+    };
 
     let connection = get_blocking_connection(UnitDBusLevel::System)?;
 
@@ -167,7 +228,7 @@ pub fn test_get_unit_path() -> Result<(), SystemdErrors> {
         PATH_SYSTEMD,
         Some(INTERFACE_SYSTEMD_MANAGER),
         "GetUnit",
-        &(unit_file),
+        &(TEST_SERVICE),
     )?;
 
     info!("message {message:?}");
@@ -175,142 +236,240 @@ pub fn test_get_unit_path() -> Result<(), SystemdErrors> {
     let body = message.body();
 
     let z: zvariant::ObjectPath = body.deserialize()?;
-    //let z :String = body.deserialize()?;
 
     info!("obj {:?}", z.as_str());
+    assert_eq!(z.as_str(), unit_dbus_path_from_name(TEST_SERVICE));
 
-    /*         let body = message.body();
-
-    let des = body.deserialize();
-
-    println!("{:#?}", des); */
     Ok(())
 }
 
-#[ignore = "need a connection to a service"]
 #[test]
 pub fn test_fetch_system_unit_info() -> Result<(), SystemdErrors> {
-    init_logs();
+    let Some((_harness, _serial)) = fake_bus() else {
+        return Ok(());
+    };
 
-    let btree_map = fetch_system_unit_info(
+    let properties = fetch_system_unit_info(
         UnitDBusLevel::System,
-        "/org/freedesktop/systemd1/unit/tiny_5fdaemon_2eservice",
+        &unit_dbus_path_from_name(ACTIVE_SERVICE),
         UnitType::Service,
     )?;
 
-    debug!("ALL PARAM: {btree_map:#?}");
+    debug!("ALL PARAM: {properties:#?}");
+
+    let property = |unit_type: UnitType, key: &str| {
+        properties
+            .iter()
+            .find(|(property_type, property_key, _)| {
+                *property_type == unit_type && property_key == key
+            })
+            .map(|(_, _, value)| value.as_str())
+    };
+
+    let main_pid = ACTIVE_SERVICE_PID.to_string();
+    assert_eq!(
+        property(UnitType::Service, "MainPID"),
+        Some(main_pid.as_str())
+    );
+    assert_eq!(property(UnitType::Unit, "Id"), Some(ACTIVE_SERVICE));
     Ok(())
 }
 
-#[ignore = "need a connection to a service"]
 #[test]
 fn test_fetch_info() -> Result<(), SystemdErrors> {
-    init_logs();
+    let Some((_harness, _serial)) = fake_bus() else {
+        return Ok(());
+    };
 
     let path = unit_dbus_path_from_name(TEST_SERVICE);
 
     println!("unit {TEST_SERVICE} Path {path}");
-    let map = fetch_system_unit_info(UnitDBusLevel::System, &path, UnitType::Service)?;
+    let map = fetch_system_unit_info_native_map(UnitDBusLevel::System, &path, UnitType::Service)?;
 
     println!("{map:#?}");
+
+    let service_type: &str = map.get("Type").expect("Type property").try_into()?;
+    assert_eq!(service_type, "simple");
+    let fragment_path: &str = map
+        .get("FragmentPath")
+        .expect("FragmentPath property")
+        .try_into()?;
+    assert_eq!(fragment_path, format!("/etc/systemd/system/{TEST_SERVICE}"));
     Ok(())
 }
 
-#[ignore = "need a connection to a service"]
 #[test]
 fn test_fetch_system_info() -> Result<(), SystemdErrors> {
-    init_logs();
+    let Some((_harness, _serial)) = fake_bus() else {
+        return Ok(());
+    };
 
     let map = fetch_system_info(UnitDBusLevel::System)?;
 
     info!("{map:#?}");
+
+    let property = |key: &str| {
+        map.iter()
+            .find(|(unit_type, property_key, _)| {
+                *unit_type == UnitType::Manager && property_key == key
+            })
+            .map(|(_, _, value)| value.as_str())
+    };
+
+    assert_eq!(property("Version"), Some("fake"));
+    assert_eq!(property("LogLevel"), Some("info"));
     Ok(())
 }
 
-#[ignore = "need a connection to a service"]
 #[test]
 fn test_fetch_unit() -> Result<(), SystemdErrors> {
-    init_logs();
+    let Some((_harness, _serial)) = fake_bus() else {
+        return Ok(());
+    };
 
     let unit = fetch_unit(UnitDBusLevel::System, TEST_SERVICE)?;
 
     info!("{unit:#?}");
+    assert_eq!(unit.primary(), TEST_SERVICE);
+    assert_eq!(
+        unit.description().as_deref(),
+        Some("Fake tiny_daemon.service")
+    );
+    assert_eq!(
+        unit.file_path(),
+        Some(format!("/etc/systemd/system/{TEST_SERVICE}"))
+    );
+    assert_eq!(unit.dbus_level(), UnitDBusLevel::System);
     Ok(())
 }
 
-#[ignore = "need a connection to a service"]
 #[test]
 fn test_fetch_unit_user_session() -> Result<(), SystemdErrors> {
-    init_logs();
+    let Some((_harness, _serial)) = fake_bus() else {
+        return Ok(());
+    };
 
-    let unit = fetch_unit(UnitDBusLevel::UserSession, TEST_SERVICE)?;
+    let unit = fetch_unit(UnitDBusLevel::UserSession, TEST_TIMER)?;
 
     info!("{unit:#?}");
+    assert_eq!(unit.primary(), TEST_TIMER);
+    assert_eq!(unit.active_state(), ActiveState::Active);
+    assert_eq!(unit.dbus_level(), UnitDBusLevel::UserSession);
     Ok(())
 }
 
-#[ignore = "need a connection to a service"]
 #[test]
 fn test_fetch_unit_wrong_bus() -> Result<(), SystemdErrors> {
-    init_logs();
+    let Some((_harness, _serial)) = fake_bus() else {
+        return Ok(());
+    };
 
-    let unit = fetch_unit(UnitDBusLevel::UserSession, TEST_SERVICE)?;
+    let result = fetch_unit(UnitDBusLevel::OtherUser, TEST_SERVICE);
 
-    info!("{}", unit.debug());
+    let Err(err) = result else {
+        panic!("An other user's bus isn't reachable directly");
+    };
+    info!("{err:?}");
+    assert!(matches!(err, SystemdErrors::Custom(_)), "{err:?}");
     Ok(())
 }
 
-#[ignore = "need a connection to a service"]
 #[test]
 fn test_fetch_unit_dependencies() -> Result<(), SystemdErrors> {
-    init_logs();
+    let Some((_harness, _serial)) = fake_bus() else {
+        return Ok(());
+    };
 
     let path = unit_dbus_path_from_name(TEST_SERVICE);
-    let res = unit_get_dependencies(
+    let dependency = unit_get_dependencies(
         UnitDBusLevel::System,
         TEST_SERVICE,
         &path,
         DependencyType::Forward,
         false,
+    )?;
+
+    info!("{dependency:#?}");
+    assert_eq!(dependency_names(&dependency), vec![ACTIVE_SERVICE]);
+    let active = dependency.children.first().expect("one dependency");
+    assert_eq!(dependency_names(active), vec![TIMER_SERVICE]);
+
+    let plain = unit_get_dependencies(
+        UnitDBusLevel::System,
+        TEST_SERVICE,
+        &path,
+        DependencyType::Forward,
+        true,
+    )?;
+    assert_eq!(
+        dependency_names(&plain),
+        vec![ACTIVE_SERVICE, TIMER_SERVICE]
     );
 
-    info!("{:#?}", res.unwrap());
+    let after = unit_get_dependencies(
+        UnitDBusLevel::System,
+        TEST_SERVICE,
+        &path,
+        DependencyType::After,
+        false,
+    )?;
+    assert_eq!(dependency_names(&after), vec![ACTIVE_SERVICE]);
     Ok(())
 }
 
-#[ignore = "need a connection to a service"]
 #[test]
 fn test_fetch_unit_reverse_dependencies() -> Result<(), SystemdErrors> {
-    init_logs();
+    let Some((_harness, _serial)) = fake_bus() else {
+        return Ok(());
+    };
 
-    let path = unit_dbus_path_from_name(TEST_SERVICE);
-    let res = unit_get_dependencies(
+    let path = unit_dbus_path_from_name(TIMER_SERVICE);
+    let dependency = unit_get_dependencies(
         UnitDBusLevel::System,
-        TEST_SERVICE,
+        TIMER_SERVICE,
         &path,
         DependencyType::Reverse,
         false,
-    );
+    )?;
 
-    info!("{res:#?}");
+    info!("{dependency:#?}");
+    assert_eq!(dependency_names(&dependency), vec![ACTIVE_SERVICE]);
+    let active = dependency.children.first().expect("one reverse dependency");
+    assert_eq!(dependency_names(active), vec![TEST_SERVICE]);
+
+    let before = unit_get_dependencies(
+        UnitDBusLevel::System,
+        ACTIVE_SERVICE,
+        &unit_dbus_path_from_name(ACTIVE_SERVICE),
+        DependencyType::Before,
+        false,
+    )?;
+    assert_eq!(dependency_names(&before), vec![TEST_SERVICE]);
     Ok(())
 }
 
-#[ignore = "need a connection to a service"]
+fn dependency_names(dependency: &Dependency) -> Vec<&str> {
+    dependency
+        .children
+        .iter()
+        .map(|child| child.unit_name.as_str())
+        .collect()
+}
+
 #[test]
 fn test_fetch_unit_fail_wrong_name() -> Result<(), SystemdErrors> {
-    init_logs();
+    let Some((_harness, _serial)) = fake_bus() else {
+        return Ok(());
+    };
 
     let fake = format!("{TEST_SERVICE}_fake");
     match fetch_unit(UnitDBusLevel::System, &fake) {
-        Ok(_) => todo!(),
+        Ok(unit) => panic!("No unit {fake} on the bus, got {}", unit.primary()),
         Err(e) => {
             warn!("{e:?}");
-            if let SystemdErrors::ZNoSuchUnit(_method, _message) = e {
-                Ok(())
-            } else {
-                Err(SystemdErrors::Custom("Wrong expected Error".to_owned()))
-            }
+            // The fake manager only serves the objects of its units
+            assert!(format!("{e:?}").contains("UnknownObject"), "{e:?}");
+            Ok(())
         }
     }
 }
@@ -330,162 +489,220 @@ fn test_name_convertion() {
     }
 }
 
-#[ignore = "need a connection to a service"]
+#[test]
+fn test_name_from_path() {
+    let tests = [
+        "tiny_daemon.service",
+        "-.mount",
+        "1first",
+        "user@1000.service",
+    ];
+
+    for origin in tests {
+        let path = unit_dbus_path_from_name(origin);
+        assert_eq!(unit_name_from_dbus_path(&path).as_deref(), Some(origin));
+    }
+
+    // The way systemd escapes, digits only escaped at first
+    assert_eq!(
+        unit_name_from_dbus_path("/org/freedesktop/systemd1/unit/user_401000_2eservice").as_deref(),
+        Some("user@1000.service")
+    );
+    assert_eq!(unit_name_from_dbus_path("/org/freedesktop/systemd1"), None);
+}
+
 #[test]
 fn test_get_unit_processes() -> Result<(), SystemdErrors> {
-    let unit_file: &str = "system.slice";
+    let Some((_harness, _serial)) = fake_bus() else {
+        return Ok(());
+    };
 
-    let list = retreive_unit_processes(UnitDBusLevel::System, unit_file)?;
+    let list = retreive_unit_processes(UnitDBusLevel::System, ACTIVE_SERVICE)?;
 
-    for up in list {
+    for up in list.iter() {
         println!("{up:#?}")
     }
 
+    assert_eq!(
+        list,
+        vec![UnitProcessDeserialize {
+            path: format!("/system.slice/{ACTIVE_SERVICE}"),
+            pid: ACTIVE_SERVICE_PID,
+            name: "/usr/bin/fake_active --serve".to_owned(),
+        }]
+    );
+
+    assert!(retreive_unit_processes(UnitDBusLevel::System, TEST_SERVICE)?.is_empty());
     Ok(())
 }
 
-#[ignore = "need a connection to a service"]
 #[test]
 fn test_get_unit_active_state() -> Result<(), SystemdErrors> {
-    let unit_object = unit_dbus_path_from_name(TEST_SERVICE);
+    let Some((_harness, _serial)) = fake_bus() else {
+        return Ok(());
+    };
+
+    let unit_object = unit_dbus_path_from_name(TEST_TIMER);
 
     println!("path : {unit_object}");
     let state = get_unit_active_state(UnitDBusLevel::System, &unit_object)?;
 
-    println!("state of {TEST_SERVICE} is {state:?}");
+    println!("state of {TEST_TIMER} is {state:?}");
+    assert_eq!(state, ActiveState::Active);
 
     Ok(())
 }
 
-async fn get_unit_list_test(level: UnitDBusLevel) -> Result<Vec<ListedLoadedUnit>, SystemdErrors> {
-    let connection = get_connection(level).await?;
-
-    let r = list_units_list_async(connection).await?;
+fn get_unit_list_test(level: UnitDBusLevel) -> Result<Vec<ListedLoadedUnit>, SystemdErrors> {
+    let r = crate::runtime().block_on(async {
+        let connection = get_connection(level).await?;
+        list_units_list_async(connection).await
+    })?;
 
     info!("Returned units count: {}", r.len());
 
     Ok(r)
 }
 
-#[ignore = "need a connection to a service"]
-#[tokio::test]
-async fn test_get_unit_list_system() -> Result<(), SystemdErrors> {
-    init_logs();
-    let _map = get_unit_list_test(UnitDBusLevel::System).await?;
+#[test]
+fn test_get_unit_list_system() -> Result<(), SystemdErrors> {
+    let Some((_harness, _serial)) = fake_bus() else {
+        return Ok(());
+    };
+    let units = get_unit_list_test(UnitDBusLevel::System)?;
+    assert_eq!(units.len(), default_units().len());
     Ok(())
 }
 
-#[ignore = "need a connection to a service"]
-#[tokio::test]
-async fn test_get_unit_list_user() -> Result<(), SystemdErrors> {
-    init_logs();
-    let _map = get_unit_list_test(UnitDBusLevel::UserSession).await?;
+#[test]
+fn test_get_unit_list_user() -> Result<(), SystemdErrors> {
+    let Some((_harness, _serial)) = fake_bus() else {
+        return Ok(());
+    };
+    let units = get_unit_list_test(UnitDBusLevel::UserSession)?;
+    assert!(
+        units
+            .iter()
+            .any(|unit| unit.primary_unit_name == FAILING_SERVICE)
+    );
     Ok(())
 }
 
-async fn get_unit_file_list_test(
-    level: UnitDBusLevel,
-) -> Result<Vec<SystemdUnitFile>, SystemdErrors> {
-    let r = fill_list_unit_files(level).await?;
+fn get_unit_file_list_test(level: UnitDBusLevel) -> Result<Vec<SystemdUnitFile>, SystemdErrors> {
+    let r = crate::runtime().block_on(fill_list_unit_files(level))?;
 
     info!("Returned units count: {}", r.len());
 
     Ok(r)
 }
 
-#[ignore = "need a connection to a service"]
-#[tokio::test]
-async fn test_get_unit_file_list_system() -> Result<(), SystemdErrors> {
-    init_logs();
-    let _map = get_unit_file_list_test(UnitDBusLevel::System).await?;
+#[test]
+fn test_get_unit_file_list_system() -> Result<(), SystemdErrors> {
+    let Some((_harness, _serial)) = fake_bus() else {
+        return Ok(());
+    };
+    let unit_files = get_unit_file_list_test(UnitDBusLevel::System)?;
+    assert_eq!(unit_files.len(), default_units().len());
     Ok(())
 }
 
-#[ignore = "need a connection to a service"]
-#[tokio::test]
-async fn test_get_unit_file_list_user() -> Result<(), SystemdErrors> {
-    init_logs();
-    let _map = get_unit_list_test(UnitDBusLevel::UserSession).await?;
+#[test]
+fn test_get_unit_file_list_user() -> Result<(), SystemdErrors> {
+    let Some((_harness, _serial)) = fake_bus() else {
+        return Ok(());
+    };
+    let unit_files = get_unit_file_list_test(UnitDBusLevel::UserSession)?;
+    assert!(
+        unit_files
+            .iter()
+            .all(|unit_file| unit_file.level == UnitDBusLevel::UserSession)
+    );
     Ok(())
 }
 
-#[ignore = "need a connection to a service"]
-#[tokio::test]
-async fn test_get_list() -> Result<(), SystemdErrors> {
-    init_logs();
-    let connection = get_connection(UnitDBusLevel::System).await?;
+#[test]
+fn test_get_list() -> Result<(), SystemdErrors> {
+    let Some((_harness, _serial)) = fake_bus() else {
+        return Ok(());
+    };
+
+    let (units, unit_files, user_units, user_unit_files) = crate::runtime().block_on(async {
+        let connection = get_connection(UnitDBusLevel::System).await?;
+
+        let connection2 = get_connection(UnitDBusLevel::UserSession).await?;
 
-    let connection2 = get_connection(UnitDBusLevel::UserSession).await?;
+        use std::time::Instant;
+        let now = Instant::now();
+        let t1 = tokio::spawn(list_units_list_async(connection.clone()));
+        let t2 = tokio::spawn(fill_list_unit_files(UnitDBusLevel::System));
+        let t3 = tokio::spawn(list_units_list_async(connection2.clone()));
+        let t4 = tokio::spawn(fill_list_unit_files(UnitDBusLevel::UserSession));
 
-    use std::time::Instant;
-    let now = Instant::now();
-    let t1 = tokio::spawn(list_units_list_async(connection.clone()));
-    let t2 = tokio::spawn(fill_list_unit_files(UnitDBusLevel::System));
-    let t3 = tokio::spawn(list_units_list_async(connection2.clone()));
-    let t4 = tokio::spawn(fill_list_unit_files(UnitDBusLevel::UserSession));
+        let joined = tokio::join!(t1, t2, t3, t4);
 
-    let _asdf = tokio::join!(t1, t2, t3, t4);
+        let elapsed = now.elapsed();
+        println!("Elapsed: {elapsed:.2?}");
 
-    let elapsed = now.elapsed();
-    println!("Elapsed: {elapsed:.2?}");
-    /*        let a = asdf.0;
-    let b = asdf.1;
+        Ok::<_, SystemdErrors>((joined.0??, joined.1??, joined.2??, joined.3??))
+    })?;
 
-    println!("{:?}", a);
-    println!("{:?}", b); */
+    assert_eq!(units.len(), default_units().len());
+    assert_eq!(unit_files.len(), default_units().len());
+    assert_eq!(user_units.len(), default_units().len());
+    assert_eq!(user_unit_files.len(), default_units().len());
 
     Ok(())
 }
 
-#[ignore = "need a connection to a service"]
-#[tokio::test]
-async fn test_get_list2() -> Result<(), SystemdErrors> {
-    let connection = get_connection(UnitDBusLevel::System).await?;
+#[test]
+fn test_get_list2() -> Result<(), SystemdErrors> {
+    let Some((_harness, _serial)) = fake_bus() else {
+        return Ok(());
+    };
 
-    let connection2 = get_connection(UnitDBusLevel::UserSession).await?;
+    let (r1, r2, r3, r4) = crate::runtime().block_on(async {
+        let connection = get_connection(UnitDBusLevel::System).await?;
 
-    use std::time::Instant;
-    let now = Instant::now();
-    let t1 = list_units_list_async(connection.clone());
-    let t2 = fill_list_unit_files(UnitDBusLevel::System);
-    let t3 = list_units_list_async(connection2.clone());
-    let t4 = fill_list_unit_files(UnitDBusLevel::UserSession);
+        let connection2 = get_connection(UnitDBusLevel::UserSession).await?;
 
-    let joined_result = tokio::join!(t1, t2, t3, t4);
+        use std::time::Instant;
+        let now = Instant::now();
+        let t1 = list_units_list_async(connection.clone());
+        let t2 = fill_list_unit_files(UnitDBusLevel::System);
+        let t3 = list_units_list_async(connection2.clone());
+        let t4 = fill_list_unit_files(UnitDBusLevel::UserSession);
 
-    let elapsed = now.elapsed();
-    println!("Elapsed: {elapsed:.2?}");
+        let joined_result = tokio::join!(t1, t2, t3, t4);
 
-    let r1 = joined_result.0.unwrap();
-    let r2 = joined_result.1.unwrap();
-    let r3 = joined_result.2.unwrap();
-    let r4 = joined_result.3.unwrap();
+        let elapsed = now.elapsed();
+        println!("Elapsed: {elapsed:.2?}");
+
+        Ok::<_, SystemdErrors>((
+            joined_result.0?,
+            joined_result.1?,
+            joined_result.2?,
+            joined_result.3?,
+        ))
+    })?;
 
     println!("System unit description size {}", r1.len());
     println!("System unit file size {}", r2.len());
     println!("Session unit description size {}", r3.len());
     println!("Session unit file size {}", r4.len());
 
-    //check system collision
-    /*     for (key, _val) in r1 {
-        if r3.contains_key(&key) {
-            println!("collision description on key {key}");
-        }
-    } */
-
-    /*         let a = asdf.0;
-           let b = asdf.1;
+    assert_eq!(r1.len(), default_units().len());
+    assert_eq!(r2.len(), default_units().len());
+    assert_eq!(r3.len(), default_units().len());
+    assert_eq!(r4.len(), default_units().len());
 
-           println!("{:?}", a);
-           println!("{:?}", b);
-    */
     Ok(())
 }
 
-#[ignore = "need a connection to a service"]
 #[test]
 fn test_get_properties() -> Result<(), SystemdErrors> {
-    init_logs();
+    let Some((_harness, _serial)) = fake_bus() else {
+        return Ok(());
+    };
     let connection = get_blocking_connection(UnitDBusLevel::System)?;
 
     let object_path = unit_dbus_path_from_name(TEST_SERVICE);
@@ -494,14 +711,11 @@ fn test_get_properties() -> Result<(), SystemdErrors> {
         fdo::PropertiesProxy::builder(&connection)
             .destination(DESTINATION_SYSTEMD)?
             .path(object_path)?
-            // .interface(INTERFACE_SYSTEMD_UNIT)?
-            //  .interface(UnitType::Service.interface())?
             .build()?;
 
     let unit_type = UnitType::Service;
     let unit_interface = unit_type.interface();
 
-    //let unit_interface_name = InterfaceName::try_from(INTERFACE_SYSTEMD_UNIT).unwrap();
     let unit_interface_name = InterfaceName::try_from(INTERFACE_SYSTEMD_UNIT).unwrap();
 
     let mut unit_properties: HashMap<String, OwnedValue> =
@@ -515,45 +729,81 @@ fn test_get_properties() -> Result<(), SystemdErrors> {
 
     info!("Unit Properties size {}", unit_properties.len());
 
+    assert!(properties.contains_key("MainPID"));
+    assert!(unit_properties.contains_key("ActiveState"));
+
     for k in properties.into_keys() {
         unit_properties.remove(&k);
     }
 
     info!("Unit Properties size {}", unit_properties.len());
 
-    /*      for (k, v) in unit_properties {
-        info!("{k} {:?}", v);
-    } */
+    // The unit and the service interfaces don't share properties
+    assert!(unit_properties.contains_key("ActiveState"));
+    assert!(!unit_properties.contains_key("MainPID"));
 
     Ok(())
 }
 
-#[ignore = "need a connection to a service"]
 #[test]
 fn test_kill_unit() -> Result<(), SystemdErrors> {
-    init_logs();
+    let Some((harness, _serial)) = fake_bus() else {
+        return Ok(());
+    };
     let unit_name: &str = TEST_SERVICE;
 
-    kill_unit(UnitDBusLevel::System, unit_name, KillWho::Main, 1)
+    kill_unit(
+        UnitDBusLevel::System,
+        unit_name,
+        KillWho::Main,
+        libc::SIGHUP,
+    )?;
+
+    assert!(harness.systemd().signals().contains(&(
+        unit_name.to_owned(),
+        KillWho::Main.as_str().to_owned(),
+        libc::SIGHUP
+    )));
+
+    let result = kill_unit(
+        UnitDBusLevel::System,
+        "not_there.service",
+        KillWho::Main,
+        libc::SIGHUP,
+    );
+    assert!(
+        matches!(result, Err(SystemdErrors::ZNoSuchUnit(_, _))),
+        "{result:?}"
+    );
+    Ok(())
 }
 
-#[ignore = "need a connection to a service"]
 #[test]
 fn test_queue_signal_unit() -> Result<(), SystemdErrors> {
-    init_logs();
+    let Some((harness, _serial)) = fake_bus() else {
+        return Ok(());
+    };
     let unit_name: &str = TEST_SERVICE;
     let val: i32 = libc::SIGRTMIN();
     let val2 = libc::SIGRTMAX();
 
     println!("{val} {val2}");
 
-    queue_signal_unit(UnitDBusLevel::System, unit_name, KillWho::Main, 9, 0)
+    queue_signal_unit(UnitDBusLevel::System, unit_name, KillWho::All, val, 0)?;
+
+    assert!(harness.systemd().signals().contains(&(
+        unit_name.to_owned(),
+        KillWho::All.as_str().to_owned(),
+        val
+    )));
+    Ok(())
 }
 
-#[ignore = "need a connection to a service"]
 #[test]
 pub(super) fn test_unit_clean() -> Result<(), SystemdErrors> {
-    init_logs();
+    let Some((harness, _serial)) = fake_bus() else {
+        return Ok(());
+    };
     let handle_answer = |_method: &str, _return_message: &Message| {
         info!("Clean Unit SUCCESS");
 
@@ -573,7 +823,21 @@ pub(super) fn test_unit_clean() -> Result<(), SystemdErrors> {
     if let Err(ref e) = r {
         error!("{e:?}");
     }
-    r
+    r?;
+
+    call_systemd_manager_method(
+        UnitDBusLevel::System,
+        "CleanUnit",
+        &(ACTIVE_SERVICE, &["cache", "runtime"][..]),
+    )?;
+
+    let cleanings = harness.systemd().cleanings();
+    assert!(cleanings.contains(&(TEST_SERVICE.to_owned(), vec!["logs".to_owned()])));
+    assert!(cleanings.contains(&(
+        ACTIVE_SERVICE.to_owned(),
+        vec!["cache".to_owned(), "runtime".to_owned()]
+    )));
+    Ok(())
 }
 
 fn send_unit_message<T, U>(
@@ -649,216 +913,259 @@ where
     ))
 }
 
-#[ignore = "need a connection to a service"]
 #[test]
 fn test_mask_unit_file() -> Result<(), SystemdErrors> {
-    init_logs();
-    let unit_name: &str = TEST_SERVICE;
+    let Some((_harness, _serial)) = fake_bus() else {
+        return Ok(());
+    };
+    let unit_name: &str = MASK_SERVICE;
+
+    let changes = mask_unit_files(UnitDBusLevel::System, &[unit_name], false, false)?;
+
+    assert_eq!(changes.len(), 1);
+    assert_eq!(changes[0].change_type, "symlink");
+    assert_eq!(
+        changes[0].file_name,
+        format!("/etc/systemd/system/{MASK_SERVICE}")
+    );
+    assert_eq!(changes[0].destination, "/dev/null");
+    assert_eq!(
+        get_unit_file_state(UnitDBusLevel::System, unit_name)?,
+        UnitFileStatus::Masked
+    );
 
-    mask_unit_files(UnitDBusLevel::System, &[unit_name], false, false)?;
+    unmask_unit_files(UnitDBusLevel::System, &[unit_name], false)?;
+    assert_eq!(
+        get_unit_file_state(UnitDBusLevel::System, unit_name)?,
+        UnitFileStatus::Enabled
+    );
 
     Ok(())
 }
 
-#[ignore = "need a connection to a service"]
 #[test]
 fn test_mask_unit_file2() -> Result<(), SystemdErrors> {
-    init_logs();
-    let unit_file_name: &str = "/etc/systemd/system/tiny_daemon.service";
+    let Some((_harness, _serial)) = fake_bus() else {
+        return Ok(());
+    };
+    let unit_file_name = format!("/etc/systemd/system/{MASK_SERVICE}");
 
-    mask_unit_files(UnitDBusLevel::System, &[unit_file_name], false, false)?;
+    mask_unit_files(
+        UnitDBusLevel::System,
+        &[unit_file_name.as_str()],
+        false,
+        false,
+    )?;
+    assert_eq!(
+        get_unit_file_state(UnitDBusLevel::System, MASK_SERVICE)?,
+        UnitFileStatus::Masked
+    );
+
+    unmask_unit_files(UnitDBusLevel::System, &[unit_file_name.as_str()], false)?;
+    assert_eq!(
+        get_unit_file_state(UnitDBusLevel::System, MASK_SERVICE)?,
+        UnitFileStatus::Enabled
+    );
+
+    let result = mask_unit_files(
+        UnitDBusLevel::System,
+        &["/etc/systemd/system/not_there.service"],
+        false,
+        false,
+    );
+    assert!(
+        matches!(result, Err(SystemdErrors::ZNoSuchUnit(_, _))),
+        "{result:?}"
+    );
 
     Ok(())
 }
 
-#[ignore = "need a connection to a service"]
 #[test]
 fn test_unmask_unit_file() -> Result<(), SystemdErrors> {
-    init_logs();
-    let unit_name: &str = TEST_SERVICE;
+    let Some((_harness, _serial)) = fake_bus() else {
+        return Ok(());
+    };
+    let unit_name: &str = MASK_SERVICE;
 
-    unmask_unit_files(UnitDBusLevel::System, &[unit_name], false)?;
+    // Not masked, nothing to unmask
+    let changes = unmask_unit_files(UnitDBusLevel::System, &[unit_name], false)?;
+    assert!(changes.is_empty(), "{changes:?}");
+
+    mask_unit_files(UnitDBusLevel::System, &[unit_name], true, false)?;
+    assert_eq!(
+        get_unit_file_state(UnitDBusLevel::System, unit_name)?,
+        UnitFileStatus::MaskedRuntime
+    );
+
+    let changes = unmask_unit_files(UnitDBusLevel::System, &[unit_name], true)?;
+    assert_eq!(changes.len(), 1);
+    assert_eq!(changes[0].change_type, "unlink");
+    assert_eq!(
+        changes[0].file_name,
+        format!("/run/systemd/system/{MASK_SERVICE}")
+    );
+    assert_eq!(
+        get_unit_file_state(UnitDBusLevel::System, unit_name)?,
+        UnitFileStatus::Enabled
+    );
 
     Ok(())
 }
 
-#[ignore = "need a connection to a service"]
 #[test]
 fn test_introspect() -> Result<(), SystemdErrors> {
-    init_logs();
-
-    let result: Result<(), SystemdErrors> = {
-        let connection = get_blocking_connection(UnitDBusLevel::System)?;
-        info!("Connect");
-
-        let message = connection.call_method(
-            Some(DESTINATION_SYSTEMD),
-            //"/org/freedesktop/systemd1/unit/avahi_2ddaemon_2eservice",
-            //"/",
-            //"/org/freedesktop/systemd1/unit",
-            "/org/freedesktop/systemd1/archlinux_2dkeyring_2dwkd_2dsync_2etimer",
-            //  Some("org.freedesktop.DBus.Properties"),
-            Some("org.freedesktop.DBus.Introspectable"),
-            "Introspect",
-            // &(UnitType::Service.interface()),
-            &(),
-        )?;
-
-        info!("message {message:?}");
-
-        let body = message.body();
-
-        info!("signature {:?}", body.signature());
+    let Some((_harness, _serial)) = fake_bus() else {
+        return Ok(());
+    };
 
-        //let z: Vec<(String, OwnedValue)> = body.deserialize()?;
+    let connection = get_blocking_connection(UnitDBusLevel::System)?;
+    info!("Connect");
 
-        let z: String = body.deserialize()?;
-        //let z :String = body.deserialize()?;
+    let message = connection.call_method(
+        Some(DESTINATION_SYSTEMD),
+        unit_dbus_path_from_name(TEST_TIMER).as_str(),
+        Some("org.freedesktop.DBus.Introspectable"),
+        "Introspect",
+        &(),
+    )?;
 
-        info!("obj {:?}", z);
+    info!("message {message:?}");
 
-        /*         let body = message.body();
+    let body = message.body();
 
-        let des = body.deserialize();
+    info!("signature {:?}", body.signature());
 
-        println!("{:#?}", des); */
+    let z: String = body.deserialize()?;
 
-        /*     match get_unit_file_state(level, unit_primary_name) {
-            Ok(unit_file_status) => unit.set_enable_status(unit_file_status as u8),
-            Err(err) => warn!("Fail to get unit file state : {:?}", err),
-        } */
-        Ok(())
-    };
+    info!("obj {:?}", z);
+    assert!(z.contains(INTERFACE_SYSTEMD_UNIT), "{z}");
 
-    if let Err(ref e) = result {
-        warn!("ASTFGSDFGD {e:?}");
-    }
-    result
+    Ok(())
 }
 
-#[ignore = "need a connection to a service"]
 #[test]
 fn test_introspect2() -> Result<(), SystemdErrors> {
-    init_logs();
+    let Some((_harness, _serial)) = fake_bus() else {
+        return Ok(());
+    };
 
-    fn sub() -> Result<(), SystemdErrors> {
-        let connection = get_blocking_connection(UnitDBusLevel::System)?;
+    let connection = get_blocking_connection(UnitDBusLevel::System)?;
 
-        let proxy = Proxy::new(
-            &connection,
-            DESTINATION_SYSTEMD,
-            "/org/freedesktop/systemd1/unit/archlinux_2dkeyring_2dwkd_2dsync_2etimer",
-            "org.freedesktop.DBus.Introspectable",
-        )?;
+    let proxy = Proxy::new(
+        &connection,
+        DESTINATION_SYSTEMD,
+        unit_dbus_path_from_name(TEST_SERVICE),
+        "org.freedesktop.DBus.Introspectable",
+    )?;
 
-        info!("Proxy {proxy:?}");
+    info!("Proxy {proxy:?}");
 
-        let xml = proxy.introspect()?;
+    let xml = proxy.introspect()?;
 
-        let root_node = zbus_xml::Node::from_reader(xml.as_bytes())?;
+    let root_node = zbus_xml::Node::from_reader(xml.as_bytes())?;
 
-        for int in root_node.interfaces() {
-            info!("Interface {}", int.name());
+    let mut properties = Vec::new();
+    for int in root_node.interfaces() {
+        info!("Interface {}", int.name());
 
-            for prop in int.properties() {
-                info!("\tProp {} {:?}", prop.name(), prop.ty().to_string());
-            }
+        for prop in int.properties() {
+            info!("\tProp {} {:?}", prop.name(), prop.ty().to_string());
+            properties.push((int.name().to_string(), prop.name().to_string()));
         }
-
-        Ok(())
     }
 
-    sub().map_err(|e| {
-        warn!("ASTFGSDFGD {e:?}");
-        e
-    })
+    assert!(properties.contains(&(INTERFACE_SYSTEMD_UNIT.to_owned(), "ActiveState".to_owned())));
+    assert!(properties.contains(&(
+        UnitType::Service.interface().to_owned(),
+        "MainPID".to_owned()
+    )));
+
+    Ok(())
 }
 
-#[ignore = "need a connection to a service"]
-#[tokio::test]
-async fn test_introspect3() -> Result<(), SystemdErrors> {
-    init_logs();
+#[test]
+fn test_introspect3() -> Result<(), SystemdErrors> {
+    let Some((_harness, _serial)) = fake_bus() else {
+        return Ok(());
+    };
 
-    let map = fetch_unit_interface_properties().await?;
+    let map = crate::runtime().block_on(fetch_unit_interface_properties())?;
 
     for (k, v) in map.iter() {
         info!("{k}\t{}", v.len());
     }
 
+    let unit_properties = map.get(INTERFACE_SYSTEMD_UNIT).expect("unit interface");
+    assert!(
+        unit_properties
+            .iter()
+            .any(|property| property.name == "ActiveState")
+    );
+    assert!(map.contains_key(UnitType::Service.interface()));
+
     Ok(())
 }
 
-#[ignore = "need a connection to a service"]
-#[tokio::test]
-async fn test_introspect_types() -> Result<(), SystemdErrors> {
-    init_logs();
-
-    let map = fetch_unit_interface_properties().await?;
-    /*
-    let mut signatures = BTreeMap::new();
-
-    for unit_property in map.values().flat_map(|v| v) {
-        if let Some(v) = signatures.insert(&unit_property.signature, 1) {
-            signatures.insert(&unit_property.signature, v + 1);
-        }
-    }
+#[test]
+fn test_introspect_types() -> Result<(), SystemdErrors> {
+    let Some((_harness, _serial)) = fake_bus() else {
+        return Ok(());
+    };
 
-    println!("{signatures:#?}"); */
+    let map = crate::runtime().block_on(fetch_unit_interface_properties())?;
 
     let signatures = map
         .values()
         .flatten()
         .fold(BTreeMap::new(), |mut acc, unit_property| {
-            *acc.entry(&unit_property.signature).or_insert(1) += 1;
+            *acc.entry(unit_property.signature.as_str()).or_insert(0) += 1;
             acc
         });
 
     println!("{signatures:#?}");
 
+    // Like Id and Wants
+    assert!(signatures.contains_key("s"), "{signatures:?}");
+    assert!(signatures.contains_key("as"), "{signatures:?}");
+
     Ok(())
 }
 
-#[ignore = "need a connection to a service"]
-#[tokio::test]
-async fn test_get_properties2() -> Result<(), SystemdErrors> {
-    init_logs();
-
-    let connection = get_connection(UnitDBusLevel::System).await?;
+#[test]
+fn test_get_properties2() -> Result<(), SystemdErrors> {
+    let Some((_harness, _serial)) = fake_bus() else {
+        return Ok(());
+    };
 
     let object_path = unit_dbus_path_from_name(TEST_SERVICE);
-    let message = connection
-        .call_method(
-            Some(DESTINATION_SYSTEMD),
-            //"/org/freedesktop/systemd1/unit/avahi_2ddaemon_2eservice",
-            //"/",
-            //"/org/freedesktop/systemd1/unit",
-            // "/org/freedesktop/systemd1/archlinux_2dkeyring_2dwkd_2dsync_2etimer",
-            object_path,
-            //  Some("org.freedesktop.DBus.Properties"),
-            Some("org.freedesktop.DBus.Properties"),
-            "Get",
-            // &(UnitType::Service.interface()),
-            //      &("org.freedesktop.systemd1.Unit", "Id", "ConditionTimestamp"),
-            &("org.freedesktop.systemd1.Unit", "ConditionTimestamp"),
-        )
-        .await?;
+    let message = crate::runtime().block_on(async {
+        let connection = get_connection(UnitDBusLevel::System).await?;
+        connection
+            .call_method(
+                Some(DESTINATION_SYSTEMD),
+                object_path,
+                Some("org.freedesktop.DBus.Properties"),
+                "Get",
+                &(INTERFACE_SYSTEMD_UNIT, "Id"),
+            )
+            .await
+            .map_err(SystemdErrors::from)
+    })?;
 
     let body = message.body();
 
     info!("signature {:?}", body.signature().to_string());
 
-    //let z: Vec<(String, OwnedValue)> = body.deserialize()?;
-
-    //let z: OwnedValue = body.deserialize()?;
     let z: Value = body.deserialize()?;
-    //let z :String = body.deserialize()?;
 
     info!("obj {:?}", z);
+    assert_eq!(String::try_from(z)?, TEST_SERVICE);
 
     Ok(())
 }
 
-#[ignore = "need a connection to a service"]
+#[ignore = "reboots the host"]
 #[tokio::test]
 async fn test_reboot() -> Result<(), SystemdErrors> {
     let connection = get_connection(UnitDBusLevel::System).await?;
@@ -866,7 +1173,7 @@ async fn test_reboot() -> Result<(), SystemdErrors> {
     Ok(())
 }
 
-#[ignore = "need a connection to a service"]
+#[ignore = "powers off the host"]
 #[tokio::test]
 async fn test_power_off() -> Result<(), SystemdErrors> {
     let connection = get_connection(UnitDBusLevel::System).await?;
@@ -897,3 +1204,130 @@ fn test_bytes_msg2() {
 
     info!("String: {}", "ASDFASDFAF");
 }
+
+/// Point the library to the private test bus, `None` only when the bus tests are skipped.
+/// The calls go through the library runtime, the connections are kept by the library.
+/// The guard serializes the tests: they share the fake manager state, and the replies of the
+/// message iterators on the shared connections.
+fn fake_bus() -> Option<(&'static TestHarness, MutexGuard<'static, ()>)> {
+    static SERIAL: Mutex<()> = Mutex::new(());
+
+    let harness = test_base::harness()?;
+    let serial = SERIAL
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    assert!(set_bus_address(harness.address()));
+    Some((harness, serial))
+}
+
+#[test]
+fn test_fake_list_units() -> Result<(), SystemdErrors> {
+    let Some((_harness, _serial)) = fake_bus() else {
+        return Ok(());
+    };
+
+    let level = UnitDBusLevel::UserSession;
+    let units = crate::runtime().block_on(async {
+        systemd_manager_async(level)
+            .await?
+            .list_units()
+            .await
+            .map_err(SystemdErrors::from)
+    })?;
+
+    let unit = units
+        .iter()
+        .find(|unit| unit.primary_unit_name == TEST_SERVICE)
+        .expect("test service listed");
+    assert_eq!(unit.load_state, "loaded");
+    assert_eq!(
+        unit.unit_object_path.as_str(),
+        unit_dbus_path_from_name(TEST_SERVICE)
+    );
+
+    let timers = crate::runtime().block_on(async {
+        systemd_manager_async(level)
+            .await?
+            .list_units_by_patterns(&[], &["*.timer"])
+            .await
+            .map_err(SystemdErrors::from)
+    })?;
+    let names: Vec<_> = timers
+        .iter()
+        .map(|unit| unit.primary_unit_name.as_str())
+        .collect();
+    assert_eq!(names, vec![TEST_TIMER]);
+
+    Ok(())
+}
+
+#[test]
+fn test_fake_list_unit_files() -> Result<(), SystemdErrors> {
+    let Some((_harness, _serial)) = fake_bus() else {
+        return Ok(());
+    };
+
+    let unit_files = crate::runtime().block_on(fill_list_unit_files(UnitDBusLevel::UserSession))?;
+
+    let unit_file = unit_files
+        .iter()
+        .find(|unit_file| unit_file.full_name == TIMER_SERVICE)
+        .expect("timer service file listed");
+    assert_eq!(unit_file.status_code, UnitFileStatus::Static);
+
+    let status = get_unit_file_state(UnitDBusLevel::UserSession, TEST_SERVICE)?;
+    assert_eq!(status, UnitFileStatus::Enabled);
+
+    Ok(())
+}
+
+#[test]
+fn test_fake_start_stop_unit() -> Result<(), SystemdErrors> {
+    let Some((harness, _serial)) = fake_bus() else {
+        return Ok(());
+    };
+
+    let level = UnitDBusLevel::UserSession;
+    crate::start_unit(level, TEST_SERVICE, StartStopMode::Fail)?;
+    assert_eq!(
+        crate::get_unit_active_state(level, TEST_SERVICE)?,
+        ActiveState::Active
+    );
+
+    crate::stop_unit(level, TEST_SERVICE, StartStopMode::Fail)?;
+    assert_eq!(
+        crate::get_unit_active_state(level, TEST_SERVICE)?,
+        ActiveState::Inactive
+    );
+
+    let jobs: Vec<_> = harness
+        .systemd()
+        .jobs()
+        .into_iter()
+        .filter(|(_id, unit, _kind)| unit == TEST_SERVICE)
+        .map(|(_id, _unit, kind)| kind)
+        .collect();
+    assert_eq!(jobs, vec!["start", "stop"]);
+
+    Ok(())
+}
+
+#[test]
+fn test_fake_start_errors() {
+    let Some((_harness, _serial)) = fake_bus() else {
+        return;
+    };
+
+    let level = UnitDBusLevel::UserSession;
+    let result = crate::start_unit(level, FAILING_SERVICE, StartStopMode::Fail);
+    assert!(
+        matches!(result, Err(SystemdErrors::JobRemoved(ref reason)) if reason == "failed"),
+        "{result:?}"
+    );
+
+    let result = crate::start_unit(level, "not_there.service", StartStopMode::Fail);
+    assert!(
+        matches!(result, Err(SystemdErrors::ZNoSuchUnit(_, _))),
+        "{result:?}"
+    );
+}
@@ -1,6 +1,6 @@
 #![allow(dead_code)]
 
-use std::sync::OnceLock;
+use std::{collections::HashMap, sync::OnceLock};
 
 use base::{
     consts::{MAX_HEART_BEAT_ELAPSE, MIN_HEART_BEAT_ELAPSE},
@@ -14,11 +14,14 @@ use tokio::{
 };
 use tracing::{debug, info, warn};
 use zbus::proxy;
-use zvariant::OwnedObjectPath;
+use zvariant::{OwnedObjectPath, OwnedValue};
 
 use crate::{
+    ReStartStop,
+    data::ListedLoadedUnit,
     errors::SystemdErrors,
-    sysdbus::{get_blocking_connection, get_connection, run_context},
+    journal_disk::VacuumRequest,
+    sysdbus::{ListedUnitFile, get_blocking_connection, get_connection, run_context},
 };
 
 static HEART_BEAT_HANDLE: OnceLock<JoinHandle<Result<(), SystemdErrors>>> = OnceLock::new();
@@ -48,6 +51,15 @@ pub trait SysDManagerComLink {
     fn save_file(&mut self, file_name: &str, content: &str) -> zbus::fdo::Result<u64>;
     fn create_file(&mut self, file_name: &str, content: &str) -> zbus::fdo::Result<u64>;
 
+    fn journal_rotate(&mut self) -> zbus::fdo::Result<()>;
+    fn journal_vacuum(
+        &mut self,
+        rotate: bool,
+        max_size: u64,
+        max_age: u64,
+        max_files: u64,
+    ) -> zbus::fdo::Result<String>;
+
     fn revert_unit_files(&self, file_names: &[&str]) -> zbus::fdo::Result<Vec<DisEnAbleUnitFiles>>;
 
     fn enable_unit_files_with_flags(
@@ -62,6 +74,62 @@ pub trait SysDManagerComLink {
         flags: u64,
     ) -> zbus::fdo::Result<DisEnAbleUnitFilesResponse>;
 
+    fn set_default_target(
+        &self,
+        name: &str,
+        force: bool,
+    ) -> zbus::fdo::Result<Vec<DisEnAbleUnitFiles>>;
+
+    fn set_environment(&self, assignments: &[&str]) -> zbus::fdo::Result<()>;
+    fn unset_environment(&self, names: &[&str]) -> zbus::fdo::Result<()>;
+
+    fn user_list_units(&self, uid: u32) -> zbus::fdo::Result<Vec<ListedLoadedUnit>>;
+    fn user_list_unit_files(&self, uid: u32) -> zbus::fdo::Result<Vec<ListedUnitFile>>;
+    fn user_unit_properties(
+        &self,
+        uid: u32,
+        unit_name: &str,
+        interface: &str,
+    ) -> zbus::fdo::Result<HashMap<String, OwnedValue>>;
+    fn user_start_unit(
+        &self,
+        uid: u32,
+        unit_name: &str,
+        mode: &str,
+    ) -> zbus::fdo::Result<OwnedObjectPath>;
+    fn user_stop_unit(
+        &self,
+        uid: u32,
+        unit_name: &str,
+        mode: &str,
+    ) -> zbus::fdo::Result<OwnedObjectPath>;
+    fn user_restart_unit(
+        &self,
+        uid: u32,
+        unit_name: &str,
+        mode: &str,
+    ) -> zbus::fdo::Result<OwnedObjectPath>;
+    fn user_reload_unit(
+        &self,
+        uid: u32,
+        unit_name: &str,
+        mode: &str,
+    ) -> zbus::fdo::Result<OwnedObjectPath>;
+    fn user_enable_unit_files_with_flags(
+        &self,
+        uid: u32,
+        files: &[&str],
+        flags: u64,
+    ) -> zbus::fdo::Result<DisEnAbleUnitFilesResponse>;
+    fn user_disable_unit_files_with_flags(
+        &self,
+        uid: u32,
+        files: &[&str],
+        flags: u64,
+    ) -> zbus::fdo::Result<DisEnAbleUnitFilesResponse>;
+    fn user_reload(&self, uid: u32) -> zbus::fdo::Result<()>;
+    fn set_user_linger(&self, uid: u32, enable: bool) -> zbus::fdo::Result<()>;
+
     #[zbus(signal)]
     fn hello(msg: String) -> zbus::fdo::Result<()>;
 
@@ -271,6 +339,19 @@ pub async fn create_file(file_path: &str, content: &str) -> Result<u64, SystemdE
         .map_err(|e| e.into())
 }
 
+pub async fn journal_rotate() -> Result<(), SystemdErrors> {
+    let mut proxy = get_proxy_async().await?;
+    proxy.journal_rotate().await.map_err(|e| e.into())
+}
+
+pub async fn journal_vacuum(rotate: bool, request: VacuumRequest) -> Result<String, SystemdErrors> {
+    let mut proxy = get_proxy_async().await?;
+    proxy
+        .journal_vacuum(rotate, request.max_size, request.max_age, request.max_files)
+        .await
+        .map_err(|e| e.into())
+}
+
 pub async fn revert_unit_files(
     unit_names: &[&str],
 ) -> Result<Vec<DisEnAbleUnitFiles>, SystemdErrors> {
@@ -281,6 +362,30 @@ pub async fn revert_unit_files(
         .map_err(|e| e.into())
 }
 
+pub async fn set_default_target(
+    name: &str,
+    force: bool,
+) -> Result<Vec<DisEnAbleUnitFiles>, SystemdErrors> {
+    let proxy = get_proxy_async().await?;
+    proxy
+        .set_default_target(name, force)
+        .await
+        .map_err(|e| e.into())
+}
+
+pub async fn set_environment(assignments: &[&str]) -> Result<(), SystemdErrors> {
+    let proxy = get_proxy_async().await?;
+    proxy
+        .set_environment(assignments)
+        .await
+        .map_err(|e| e.into())
+}
+
+pub async fn unset_environment(names: &[&str]) -> Result<(), SystemdErrors> {
+    let proxy = get_proxy_async().await?;
+    proxy.unset_environment(names).await.map_err(|e| e.into())
+}
+
 pub fn enable_unit_files_with_flags(
     unit_files: &[&str],
     flags: u64,
@@ -300,3 +405,87 @@ pub fn disable_unit_files_with_flags(
         .disable_unit_files_with_flags(unit_files, flags)
         .map_err(|err| err.into())
 }
+
+pub async fn user_list_units(uid: u32) -> Result<Vec<ListedLoadedUnit>, SystemdErrors> {
+    let proxy = get_proxy_async().await?;
+    proxy.user_list_units(uid).await.map_err(|e| e.into())
+}
+
+pub async fn user_list_unit_files(uid: u32) -> Result<Vec<ListedUnitFile>, SystemdErrors> {
+    let proxy = get_proxy_async().await?;
+    proxy.user_list_unit_files(uid).await.map_err(|e| e.into())
+}
+
+pub async fn user_unit_properties(
+    uid: u32,
+    unit_name: &str,
+    interface: &str,
+) -> Result<HashMap<String, OwnedValue>, SystemdErrors> {
+    let proxy = get_proxy_async().await?;
+    proxy
+        .user_unit_properties(uid, unit_name, interface)
+        .await
+        .map_err(|e| e.into())
+}
+
+pub fn user_unit_properties_blocking(
+    uid: u32,
+    unit_name: &str,
+    interface: &str,
+) -> Result<HashMap<String, OwnedValue>, SystemdErrors> {
+    let proxy: SysDManagerComLinkProxyBlocking<'_> = get_proxy()?;
+    proxy
+        .user_unit_properties(uid, unit_name, interface)
+        .map_err(|err| err.into())
+}
+
+pub async fn user_restartstop_unit(
+    uid: u32,
+    unit_name: &str,
+    mode: &str,
+    action: &ReStartStop,
+) -> Result<String, SystemdErrors> {
+    let proxy = get_proxy_async().await?;
+    let path = match action {
+        ReStartStop::Start => proxy.user_start_unit(uid, unit_name, mode).await?,
+        ReStartStop::Stop => proxy.user_stop_unit(uid, unit_name, mode).await?,
+        ReStartStop::Restart => proxy.user_restart_unit(uid, unit_name, mode).await?,
+        ReStartStop::ReloadUnit => proxy.user_reload_unit(uid, unit_name, mode).await?,
+    };
+    Ok(path.to_string())
+}
+
+pub fn user_enable_unit_files_with_flags(
+    uid: u32,
+    unit_files: &[&str],
+    flags: u64,
+) -> Result<DisEnAbleUnitFilesResponse, SystemdErrors> {
+    let proxy: SysDManagerComLinkProxyBlocking<'_> = get_proxy()?;
+    proxy
+        .user_enable_unit_files_with_flags(uid, unit_files, flags)
+        .map_err(|err| err.into())
+}
+
+pub fn user_disable_unit_files_with_flags(
+    uid: u32,
+    unit_files: &[&str],
+    flags: u64,
+) -> Result<DisEnAbleUnitFilesResponse, SystemdErrors> {
+    let proxy: SysDManagerComLinkProxyBlocking<'_> = get_proxy()?;
+    proxy
+        .user_disable_unit_files_with_flags(uid, unit_files, flags)
+        .map_err(|err| err.into())
+}
+
+pub async fn user_reload(uid: u32) -> Result<(), SystemdErrors> {
+    let proxy = get_proxy_async().await?;
+    proxy.user_reload(uid).await.map_err(|e| e.into())
+}
+
+pub async fn set_user_linger(uid: u32, enable: bool) -> Result<(), SystemdErrors> {
+    let proxy = get_proxy_async().await?;
+    proxy
+        .set_user_linger(uid, enable)
+        .await
+        .map_err(|e| e.into())
+}
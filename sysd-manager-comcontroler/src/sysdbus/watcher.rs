@@ -1,19 +1,25 @@
 use crate::{
     data::UnitInfo,
+    enums::ActiveState,
     errors::SystemdErrors,
     runtime,
-    sysdbus::{dbus_proxies::Systemd1ManagerProxy, get_connection},
+    sysdbus::{dbus_proxies::Systemd1ManagerProxy, get_connection, unit_name_from_dbus_path},
 };
 use base::enums::UnitDBusLevel;
 use futures_util::stream::StreamExt;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, HashMap},
+    sync::Mutex,
+    time::{SystemTime, UNIX_EPOCH},
+};
 use tokio::{
-    sync::{OnceCell, broadcast, oneshot},
+    sync::{OnceCell, broadcast, mpsc, oneshot},
     task::JoinHandle,
 };
 use tracing::{debug, error, info, warn};
 use zbus::{MatchRule, MessageStream};
-use zvariant::OwnedObjectPath;
+use zvariant::{OwnedObjectPath, OwnedValue};
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
 pub enum SystemdSignal {
@@ -39,7 +45,7 @@ impl SystemdSignal {
         }
     }
 
-    pub fn bus_text(&self) -> &str {
+    pub fn bus_text(&self) -> Cow<'static, str> {
         let level = match self {
             SystemdSignal::UnitNew(level, _) => level,
             SystemdSignal::UnitRemoved(level, _) => level,
@@ -126,7 +132,7 @@ impl SystemdSignalRow {
         self.signal.type_text()
     }
 
-    pub fn bus_text(&self) -> &str {
+    pub fn bus_text(&self) -> Cow<'static, str> {
         self.signal.bus_text()
     }
 
@@ -138,6 +144,8 @@ impl SystemdSignalRow {
 static SENDER: OnceCell<broadcast::Sender<SystemdSignal>> = OnceCell::const_new();
 static WACHER_SYSTEM: OnceCell<JoinHandle<Result<(), SystemdErrors>>> = OnceCell::const_new();
 static WACHER_USER_SESSION: OnceCell<JoinHandle<Result<(), SystemdErrors>>> = OnceCell::const_new();
+static WACHER_CUSTOM: Mutex<BTreeMap<UnitDBusLevel, JoinHandle<Result<(), SystemdErrors>>>> =
+    Mutex::new(BTreeMap::new());
 
 pub async fn init_signal_watcher(level: UnitDBusLevel) -> broadcast::Receiver<SystemdSignal> {
     let sender = SENDER
@@ -167,11 +175,33 @@ pub async fn init_signal_watcher(level: UnitDBusLevel) -> broadcast::Receiver<Sy
                 .get_or_init(|| spawn_signal_watcher(UnitDBusLevel::UserSession, sender))
                 .await;
         }
+        custom => {
+            let running = WACHER_CUSTOM
+                .lock()
+                .unwrap()
+                .get(&custom)
+                .is_some_and(|handle| !handle.is_finished());
+
+            if !running {
+                let handle = spawn_signal_watcher(custom, sender).await;
+                if let Some(old) = WACHER_CUSTOM.lock().unwrap().insert(custom, handle) {
+                    old.abort();
+                }
+            }
+        }
     };
 
     sender.subscribe()
 }
 
+/// Stop watching the signals of a custom bus level
+pub(crate) fn stop_custom_signal_watcher(level: UnitDBusLevel) {
+    if let Some(handle) = WACHER_CUSTOM.lock().unwrap().remove(&level) {
+        info!("Stopping Watcher {:?}", level);
+        handle.abort();
+    }
+}
+
 async fn spawn_signal_watcher(
     level: UnitDBusLevel,
     sender: &broadcast::Sender<SystemdSignal>,
@@ -296,3 +326,118 @@ async fn signal_watcher(
 
     Ok(())
 }
+
+/// The unit properties followed by the unit state watcher, the other ones are ignored
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct UnitStateChange {
+    pub level: UnitDBusLevel,
+    pub unit_name: String,
+    pub active_state: Option<ActiveState>,
+    pub sub_state: Option<String>,
+    /// Service `NRestarts`
+    pub n_restarts: Option<u32>,
+    /// Service or timer `Result`
+    pub result: Option<String>,
+}
+
+impl UnitStateChange {
+    fn is_empty(&self) -> bool {
+        self.active_state.is_none()
+            && self.sub_state.is_none()
+            && self.n_restarts.is_none()
+            && self.result.is_none()
+    }
+}
+
+/// Send the state changes of the units of `level`, from their `PropertiesChanged` signals,
+/// until the receiver is dropped
+pub async fn watch_unit_state_changes(
+    level: UnitDBusLevel,
+    sender: mpsc::Sender<UnitStateChange>,
+) -> Result<(), SystemdErrors> {
+    info!("Starting unit state watcher {:?}", level);
+    let connection = get_connection(level).await?;
+
+    // systemd only emits the unit signals when there is a subscriber
+    let systemd_proxy = Systemd1ManagerProxy::new(&connection).await?;
+    if let Err(err) = systemd_proxy.subscribe().await {
+        warn!("Subscribe error {:?}", err);
+    };
+
+    let rule = MatchRule::builder()
+        .msg_type(zbus::message::Type::Signal)
+        .interface("org.freedesktop.DBus.Properties")?
+        .member("PropertiesChanged")?
+        .path_namespace("/org/freedesktop/systemd1/unit")?
+        .build();
+
+    let mut stream = MessageStream::for_match_rule(rule, &connection, Some(500)).await?;
+
+    while let Some(message) = stream.next().await {
+        let message = match message {
+            Ok(message) => message,
+            Err(err) => {
+                error!("{err}");
+                continue;
+            }
+        };
+
+        let Some(unit_name) = message
+            .header()
+            .path()
+            .and_then(|path| unit_name_from_dbus_path(path.as_str()))
+        else {
+            continue;
+        };
+
+        let (_interface, changed, _invalidated): (
+            String,
+            HashMap<String, OwnedValue>,
+            Vec<String>,
+        ) = match message.body().deserialize() {
+            Ok(body) => body,
+            Err(err) => {
+                warn!("PropertiesChanged body {err:?}");
+                continue;
+            }
+        };
+
+        let change = unit_state_change(level, unit_name, &changed);
+        if change.is_empty() {
+            continue;
+        }
+
+        if sender.send(change).await.is_err() {
+            info!("Unit state watcher {:?} stopped", level);
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+fn unit_state_change(
+    level: UnitDBusLevel,
+    unit_name: String,
+    changed: &HashMap<String, OwnedValue>,
+) -> UnitStateChange {
+    let string = |key: &str| {
+        changed
+            .get(key)
+            .and_then(|value| <&str>::try_from(value).ok())
+            .map(str::to_owned)
+    };
+
+    UnitStateChange {
+        level,
+        active_state: changed
+            .get("ActiveState")
+            .map(|value| ActiveState::from(Some(value))),
+        sub_state: string("SubState"),
+        n_restarts: changed
+            .get("NRestarts")
+            .and_then(|value| u32::try_from(value).ok()),
+        result: string("Result"),
+        unit_name,
+    }
+}
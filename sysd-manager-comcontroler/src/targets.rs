@@ -0,0 +1,177 @@
+//! The default target of a manager and the isolation of a target.
+//!
+//! Isolating is starting a target with [`StartStopMode::Isolate`], systemd then stops every
+//! unit that is not a dependency of the target, except the ones with `IgnoreOnIsolate=yes`.
+
+use crate::{
+    Dependency, ListUnitResponse, ReStartStop,
+    enums::{ActiveState, DependencyType, StartStopMode},
+    errors::SystemdErrors,
+    restartstop_unit,
+    sysdbus::{
+        dbus_proxies::{ZUnitInfoProxyBlocking, systemd_manager_async, systemd_manager_blocking},
+        get_blocking_connection, unit_dbus_path_from_name,
+    },
+};
+use base::{enums::UnitDBusLevel, proxy::DisEnAbleUnitFiles};
+use std::collections::BTreeSet;
+use tracing::{info, warn};
+
+pub const DEFAULT_TARGET: &str = "default.target";
+
+/// The targets offered to switch to
+pub const ISOLATE_TARGETS: [&str; 3] = ["rescue.target", "multi-user.target", "graphical.target"];
+
+pub async fn get_default_target(level: UnitDBusLevel) -> Result<String, SystemdErrors> {
+    crate::backend::ensure_live()?;
+
+    let target = systemd_manager_async(level)
+        .await?
+        .get_default_target()
+        .await?;
+    Ok(target)
+}
+
+/// The target unit files that can be chosen as default, templates and `default.target` excluded
+pub async fn list_target_files(level: UnitDBusLevel) -> Result<Vec<String>, SystemdErrors> {
+    crate::backend::ensure_live()?;
+
+    let ListUnitResponse::File(_, files) =
+        crate::list_unit_files_by_patterns(level, &["*.target"]).await?
+    else {
+        return Ok(Vec::new());
+    };
+
+    let mut targets: Vec<String> = files
+        .iter()
+        .map(|file| file.unit_primary_name())
+        .filter(|name| is_default_candidate(name))
+        .map(str::to_string)
+        .collect();
+    targets.sort();
+    targets.dedup();
+    Ok(targets)
+}
+
+fn is_default_candidate(name: &str) -> bool {
+    name != DEFAULT_TARGET && !name.ends_with("@.target")
+}
+
+/// Point `default.target` to `target`, replacing the current link
+pub async fn set_default_target(
+    level: UnitDBusLevel,
+    target: &str,
+) -> Result<Vec<DisEnAbleUnitFiles>, SystemdErrors> {
+    crate::backend::ensure_live()?;
+
+    info!("Set default target {target:?} on {level:?}");
+
+    #[cfg(not(any(feature = "flatpak", feature = "appimage")))]
+    if !level.bypasses_proxy() && !level.is_other_user() {
+        return crate::proxy_call_async!(set_default_target, target, true);
+    }
+
+    let changes = systemd_manager_async(level)
+        .await?
+        .set_default_target(target, true)
+        .await?;
+    Ok(changes)
+}
+
+/// Only the targets with `AllowIsolate=yes` can be isolated
+pub fn can_isolate(level: UnitDBusLevel, target: &str) -> Result<bool, SystemdErrors> {
+    crate::backend::ensure_live()?;
+
+    let connection = get_blocking_connection(level)?;
+    let unit_info_proxy = ZUnitInfoProxyBlocking::builder(&connection)
+        .path(unit_dbus_path_from_name(target))?
+        .build()?;
+
+    Ok(unit_info_proxy.allow_isolate()?)
+}
+
+/// The active units that systemd would stop by isolating `target`
+pub fn units_stopped_by_isolate(
+    level: UnitDBusLevel,
+    target: &str,
+) -> Result<Vec<String>, SystemdErrors> {
+    crate::backend::ensure_live()?;
+
+    let target_path = unit_dbus_path_from_name(target);
+    let dependencies =
+        crate::fetch_unit_dependencies(level, target, &target_path, DependencyType::Forward, true)?;
+
+    let mut kept = BTreeSet::new();
+    collect_dependencies(&dependencies, &mut kept);
+    kept.insert(target);
+
+    let connection = get_blocking_connection(level)?;
+    let mut stopped = Vec::new();
+    for unit in systemd_manager_blocking(level)?.list_units()? {
+        let active_state = ActiveState::from(unit.active_state.as_str());
+        if active_state.is_inactive() || kept.contains(unit.primary_unit_name.as_str()) {
+            continue;
+        }
+
+        // when unknown, list the unit rather than hiding one that may be stopped
+        let ignore_on_isolate = ZUnitInfoProxyBlocking::builder(&connection)
+            .path(unit.unit_object_path.clone())?
+            .build()?
+            .ignore_on_isolate()
+            .inspect_err(|err| warn!("{} IgnoreOnIsolate {err:?}", unit.primary_unit_name))
+            .unwrap_or(false);
+
+        if !ignore_on_isolate {
+            stopped.push(unit.primary_unit_name);
+        }
+    }
+
+    stopped.sort();
+    Ok(stopped)
+}
+
+fn collect_dependencies<'a>(dependency: &'a Dependency, names: &mut BTreeSet<&'a str>) {
+    for child in &dependency.children {
+        if names.insert(child.unit_name.as_str()) {
+            collect_dependencies(child, names);
+        }
+    }
+}
+
+/// Start `target` in isolate mode, returns the job path
+pub async fn isolate_target(level: UnitDBusLevel, target: &str) -> Result<String, SystemdErrors> {
+    crate::backend::ensure_live()?;
+
+    info!("Isolate {target:?} on {level:?}");
+    restartstop_unit(level, target, StartStopMode::Isolate, ReStartStop::Start).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_default_candidate() {
+        assert!(is_default_candidate("graphical.target"));
+        assert!(!is_default_candidate(DEFAULT_TARGET));
+        assert!(!is_default_candidate("container@.target"));
+    }
+
+    #[test]
+    fn test_collect_dependencies() {
+        let mut basic = Dependency::new("basic.target");
+        basic.children.insert(Dependency::new("sysinit.target"));
+
+        let mut multi_user = Dependency::new("multi-user.target");
+        multi_user.children.insert(basic);
+        multi_user.children.insert(Dependency::new("sshd.service"));
+
+        let mut names = BTreeSet::new();
+        collect_dependencies(&multi_user, &mut names);
+
+        assert_eq!(
+            names.into_iter().collect::<Vec<_>>(),
+            vec!["basic.target", "sshd.service", "sysinit.target"]
+        );
+    }
+}
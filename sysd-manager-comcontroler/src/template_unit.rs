@@ -0,0 +1,265 @@
+//! Helpers to work with template units (`foo@.service`) and their instances.
+//!
+//! Name escaping follows the rules of `systemd-escape`, and the specifier
+//! resolution covers the unit name specifiers documented in `systemd.unit(5)`.
+
+use std::fmt::Write;
+
+const TEMPLATE_SEPARATOR: char = '@';
+
+/// Escape a string the same way as `systemd-escape` does.
+pub fn escape(input: &str) -> String {
+    let mut out = String::with_capacity(input.len());
+
+    for (idx, byte) in input.bytes().enumerate() {
+        match byte {
+            b'/' => out.push('-'),
+            b'.' if idx == 0 => {
+                let _ = write!(out, "\\x{byte:02x}");
+            }
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b':' | b'_' | b'.' => out.push(byte as char),
+            _ => {
+                let _ = write!(out, "\\x{byte:02x}");
+            }
+        }
+    }
+
+    out
+}
+
+/// Escape a path the same way as `systemd-escape --path` does.
+pub fn escape_path(path: &str) -> String {
+    let trimmed: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+
+    if trimmed.is_empty() {
+        return "-".to_owned();
+    }
+
+    escape(&trimmed.join("/"))
+}
+
+/// Reverse the `systemd-escape` transformation.
+pub fn unescape(input: &str) -> String {
+    let bytes = input.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut idx = 0;
+
+    while idx < bytes.len() {
+        match bytes[idx] {
+            b'-' => {
+                out.push(b'/');
+                idx += 1;
+            }
+            b'\\' if idx + 3 < bytes.len() && bytes[idx + 1] == b'x' => {
+                let hex = std::str::from_utf8(&bytes[idx + 2..idx + 4]).unwrap_or_default();
+                match u8::from_str_radix(hex, 16) {
+                    Ok(value) => {
+                        out.push(value);
+                        idx += 4;
+                    }
+                    Err(_) => {
+                        out.push(b'\\');
+                        idx += 1;
+                    }
+                }
+            }
+            b => {
+                out.push(b);
+                idx += 1;
+            }
+        }
+    }
+
+    String::from_utf8_lossy(&out).to_string()
+}
+
+/// Split a unit name into its prefix, instance and type suffix.
+///
+/// `foo@bar.service` gives `("foo", Some("bar"), "service")`
+fn split_unit_name(unit_name: &str) -> Option<(&str, Option<&str>, &str)> {
+    let (name, suffix) = unit_name.rsplit_once('.')?;
+
+    match name.split_once(TEMPLATE_SEPARATOR) {
+        Some((prefix, instance)) => Some((prefix, Some(instance), suffix)),
+        None => Some((name, None, suffix)),
+    }
+}
+
+/// Returns the template name of a template or instance unit name.
+///
+/// `foo@bar.service` and `foo@.service` both give `foo@.service`
+pub fn template_name(unit_name: &str) -> Option<String> {
+    let (prefix, instance, suffix) = split_unit_name(unit_name)?;
+    instance.map(|_| format!("{prefix}{TEMPLATE_SEPARATOR}.{suffix}"))
+}
+
+/// Returns the instance part of an instance unit name, if any.
+pub fn instance_name(unit_name: &str) -> Option<&str> {
+    let (_prefix, instance, _suffix) = split_unit_name(unit_name)?;
+    instance.filter(|instance| !instance.is_empty())
+}
+
+/// Builds the instance unit name from a template name and a raw (not escaped) instance string.
+///
+/// If `is_path` is true, the instance is escaped as a path.
+pub fn instance_unit_name(template: &str, raw_instance: &str, is_path: bool) -> Option<String> {
+    let (prefix, _instance, suffix) = split_unit_name(template)?;
+
+    if raw_instance.is_empty() {
+        return None;
+    }
+
+    let instance = if is_path {
+        escape_path(raw_instance)
+    } else {
+        escape(raw_instance)
+    };
+    Some(format!("{prefix}{TEMPLATE_SEPARATOR}{instance}.{suffix}"))
+}
+
+/// Pattern matching all the instances of a template, to be used with `ListUnitsByPatterns`
+pub fn instances_pattern(template: &str) -> Option<String> {
+    let (prefix, _instance, suffix) = split_unit_name(template)?;
+    Some(format!("{prefix}{TEMPLATE_SEPARATOR}*.{suffix}"))
+}
+
+/// List the unit name specifiers with their resolved value for the given unit name.
+pub fn specifier_values(unit_name: &str) -> Vec<(char, String)> {
+    let Some((prefix, instance, _suffix)) = split_unit_name(unit_name) else {
+        return vec![];
+    };
+
+    let instance = instance.unwrap_or_default();
+    let name_no_suffix = unit_name
+        .rsplit_once('.')
+        .map(|(name, _)| name)
+        .unwrap_or(unit_name);
+    let prefix_final = prefix.rsplit_once('-').map(|(_, f)| f).unwrap_or(prefix);
+
+    let unescaped_instance = unescape(instance);
+    let file_path = if instance.is_empty() {
+        format!("/{}", unescape(prefix))
+    } else if unescaped_instance.starts_with('/') {
+        unescaped_instance.clone()
+    } else {
+        format!("/{unescaped_instance}")
+    };
+
+    vec![
+        ('n', unit_name.to_owned()),
+        ('N', name_no_suffix.to_owned()),
+        ('p', prefix.to_owned()),
+        ('P', unescape(prefix)),
+        ('i', instance.to_owned()),
+        ('I', unescaped_instance),
+        ('j', prefix_final.to_owned()),
+        ('J', unescape(prefix_final)),
+        ('f', file_path),
+    ]
+}
+
+/// Substitute the unit name specifiers (%n, %N, %p, %P, %i, %I, %j, %J, %f and %%) in `content`.
+///
+/// Unknown specifiers are left untouched.
+pub fn resolve_specifiers(content: &str, unit_name: &str) -> String {
+    let values = specifier_values(unit_name);
+
+    let mut out = String::with_capacity(content.len());
+    let mut chars = content.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            out.push(c);
+            continue;
+        }
+
+        match chars.peek().copied() {
+            Some('%') => {
+                chars.next();
+                out.push('%');
+            }
+            Some(spec) => {
+                if let Some((_, value)) = values.iter().find(|(s, _)| *s == spec) {
+                    chars.next();
+                    out.push_str(value);
+                } else {
+                    out.push('%');
+                }
+            }
+            None => out.push('%'),
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(escape("foo"), "foo");
+        assert_eq!(escape("foo bar"), "foo\\x20bar");
+        assert_eq!(escape("a-b"), "a\\x2db");
+        assert_eq!(escape("dev/sda1"), "dev-sda1");
+        assert_eq!(escape(".hidden"), "\\x2ehidden");
+        assert_eq!(escape("é"), "\\xc3\\xa9");
+    }
+
+    #[test]
+    fn test_escape_path() {
+        assert_eq!(escape_path("/"), "-");
+        assert_eq!(escape_path("/dev/sda1"), "dev-sda1");
+        assert_eq!(escape_path("/home//user/"), "home-user");
+    }
+
+    #[test]
+    fn test_unescape() {
+        for s in ["foo", "foo bar", "a-b", "dev/sda1", "é", ".hidden"] {
+            assert_eq!(unescape(&escape(s)), s);
+        }
+    }
+
+    #[test]
+    fn test_names() {
+        assert_eq!(
+            template_name("getty@tty1.service").as_deref(),
+            Some("getty@.service")
+        );
+        assert_eq!(
+            template_name("getty@.service").as_deref(),
+            Some("getty@.service")
+        );
+        assert_eq!(template_name("getty.service"), None);
+
+        assert_eq!(instance_name("getty@tty1.service"), Some("tty1"));
+        assert_eq!(instance_name("getty@.service"), None);
+
+        assert_eq!(
+            instance_unit_name("foo@.service", "my arg", false).as_deref(),
+            Some("foo@my\\x20arg.service")
+        );
+        assert_eq!(
+            instance_unit_name("fsck@.service", "/dev/sda1", true).as_deref(),
+            Some("fsck@dev-sda1.service")
+        );
+        assert_eq!(instance_unit_name("foo@.service", "", false), None);
+
+        assert_eq!(
+            instances_pattern("foo@.timer").as_deref(),
+            Some("foo@*.timer")
+        );
+    }
+
+    #[test]
+    fn test_resolve_specifiers() {
+        let content = "ExecStart=/bin/echo %i %I %n %N %p %j %f 100%% %Z";
+        let resolved = resolve_specifiers(content, "foo-bar@a\\x20b.service");
+
+        assert_eq!(
+            resolved,
+            "ExecStart=/bin/echo a\\x20b a b foo-bar@a\\x20b.service foo-bar@a\\x20b foo-bar bar /a b 100% %Z"
+        );
+    }
+}
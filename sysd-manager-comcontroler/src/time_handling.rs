@@ -1,4 +1,4 @@
-use chrono::{DateTime, Local, TimeDelta, TimeZone, Utc};
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, TimeDelta, TimeZone, Utc};
 use gettextrs::pgettext;
 use glib;
 use strum::EnumIter;
@@ -468,6 +468,26 @@ fn localtime_or_gmtime_usec(time_usec: i64, utc: bool) -> libc::tm {
     }
 }
 
+/// Parse a local date time like `2024-03-09 14:05:00`, `2024-03-09 14:05` or `2024-03-09`
+/// to realtime µs
+pub fn parse_local_timestamp(text: &str) -> Option<u64> {
+    let text = text.trim();
+
+    let date_time = ["%Y-%m-%d %H:%M:%S", "%Y-%m-%d %H:%M"]
+        .iter()
+        .find_map(|fmt| NaiveDateTime::parse_from_str(text, fmt).ok())
+        .or_else(|| {
+            NaiveDate::parse_from_str(text, "%Y-%m-%d")
+                .ok()
+                .and_then(|date| date.and_hms_opt(0, 0, 0))
+        })?;
+
+    Local
+        .from_local_datetime(&date_time)
+        .earliest()
+        .and_then(|date_time| u64::try_from(date_time.timestamp_micros()).ok())
+}
+
 ///from systemd
 pub fn calc_next_elapse(next_elapse_realtime: u64, next_elapse_monotonic: u64) -> u64 {
     let now_realtime = now_realtime();
@@ -511,6 +531,17 @@ mod tests {
         println!("since {since:?}");
     }
 
+    #[test]
+    fn test_parse_local_timestamp() {
+        let full = parse_local_timestamp("2024-03-09 14:05:30").unwrap();
+        let minutes = parse_local_timestamp(" 2024-03-09 14:05 ").unwrap();
+        let day = parse_local_timestamp("2024-03-09").unwrap();
+
+        assert_eq!(full - minutes, 30 * USEC_PER_SEC);
+        assert_eq!(minutes - day, (14 * 60 + 5) * USEC_PER_MINUTE);
+        assert_eq!(parse_local_timestamp("yesterday"), None);
+    }
+
     #[test]
     fn test_duration() {
         let now = Local::now();
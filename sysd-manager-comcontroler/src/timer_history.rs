@@ -0,0 +1,394 @@
+//! Timer history report rebuilt from the journal.
+//!
+//! The runs of the activated unit come from [`crate::invocation`].
+//! The timer activation and deactivation at each boot give the periods where the
+//! machine could run the timer, the calendar occurrences falling outside of them are
+//! reported as missed runs.
+
+use crate::{
+    calendar_spec::CalendarSpec,
+    errors::SystemdErrors,
+    invocation::{self, UnitRun},
+    journal,
+    journal_data::UnitJournalRecord,
+    time_handling::{SEC_PER_MINUTE, USEC_PER_SEC},
+    timer_schedule::TimerSchedule,
+};
+use sysd::id128::Id128;
+use tracing::warn;
+
+/// A catch-up run is expected shortly after the timer activation
+const CATCH_UP_WINDOW: u64 = 5 * SEC_PER_MINUTE * USEC_PER_SEC;
+
+/// Limit the number of missed occurrences reported per power off period
+const MAX_MISSED_PER_GAP: usize = 100;
+
+/// The period of a boot where the timer was active
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimerActivation {
+    pub boot_id: String,
+    pub from: u64,
+    pub until: u64,
+}
+
+/// A calendar occurrence that elapsed while the timer was not active
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MissedRun {
+    pub scheduled: u64,
+    /// The boot following the missed occurrence
+    pub boot_id: String,
+    pub caught_up: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DurationStats {
+    pub count: usize,
+    pub min: u64,
+    pub max: u64,
+    pub mean: u64,
+    pub median: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct TimerHistory {
+    pub timer: String,
+    pub unit: String,
+    /// Newest first
+    pub runs: Vec<UnitRun>,
+    /// Newest first
+    pub missed: Vec<MissedRun>,
+    pub stats: Option<DurationStats>,
+    pub failures: usize,
+}
+
+/// Build the timer history from the journal, going back at most `max_records` entries
+pub fn fetch_timer_history(
+    schedule: &TimerSchedule,
+    max_records: usize,
+) -> Result<TimerHistory, SystemdErrors> {
+    crate::backend::ensure_live()?;
+
+    let records = journal::list_unit_records(
+        &[schedule.timer.as_str(), schedule.unit.as_str()],
+        schedule.level,
+        max_records,
+    )?;
+
+    let current_boot = Id128::from_boot()?.to_string();
+
+    let mut runs = invocation::build_runs(&records, &schedule.unit, &current_boot);
+    let activations = build_activations(&records, &schedule.timer);
+
+    let calendar: Vec<CalendarSpec> = schedule
+        .calendar
+        .iter()
+        .filter_map(|spec| match CalendarSpec::parse(spec) {
+            Ok(calendar) => Some(calendar),
+            Err(err) => {
+                warn!("Timer {:?} calendar {spec:?}: {err}", schedule.timer);
+                None
+            }
+        })
+        .collect();
+
+    let missed = find_missed_runs(
+        &calendar,
+        &activations,
+        &mut runs,
+        schedule.persistent,
+        CATCH_UP_WINDOW + schedule.randomized_delay,
+    );
+
+    let stats = duration_stats(&runs);
+    let failures = runs.iter().filter(|run| run.result.is_failed()).count();
+
+    Ok(TimerHistory {
+        timer: schedule.timer.clone(),
+        unit: schedule.unit.clone(),
+        runs,
+        missed,
+        stats,
+        failures,
+    })
+}
+
+/// The periods where the timer was active, oldest first, one per boot.
+///
+/// A boot ends with its last record, from the timer or its unit, the timer seldom logs its
+/// own deactivation.
+pub fn build_activations(records: &[UnitJournalRecord], timer: &str) -> Vec<TimerActivation> {
+    let mut activations: Vec<TimerActivation> = Vec::new();
+
+    for record in records.iter().filter(|record| record.unit == timer) {
+        let activation = match activations
+            .iter_mut()
+            .find(|activation| activation.boot_id == record.boot_id)
+        {
+            Some(activation) => activation,
+            None => {
+                activations.push(TimerActivation {
+                    boot_id: record.boot_id.clone(),
+                    from: record.timestamp,
+                    until: record.timestamp,
+                });
+                activations.last_mut().expect("just pushed")
+            }
+        };
+
+        activation.from = activation.from.min(record.timestamp);
+        activation.until = activation.until.max(record.timestamp);
+    }
+
+    for record in records {
+        if let Some(activation) = activations
+            .iter_mut()
+            .find(|activation| activation.boot_id == record.boot_id)
+        {
+            activation.until = activation.until.max(record.timestamp);
+        }
+    }
+
+    activations.sort_by_key(|activation| activation.from);
+    activations
+}
+
+/// Find the calendar occurrences elapsed between two timer activations.
+///
+/// For persistent timers, the first run following the activation is flagged as a catch-up.
+pub fn find_missed_runs(
+    calendar: &[CalendarSpec],
+    activations: &[TimerActivation],
+    runs: &mut [UnitRun],
+    persistent: bool,
+    catch_up_window: u64,
+) -> Vec<MissedRun> {
+    let mut missed = Vec::new();
+
+    for pair in activations.windows(2) {
+        let (previous, current) = (&pair[0], &pair[1]);
+
+        let mut scheduled: Vec<u64> = calendar
+            .iter()
+            .flat_map(|spec| {
+                spec.next_elapses_until(previous.until, current.from, MAX_MISSED_PER_GAP)
+            })
+            .collect();
+
+        if scheduled.is_empty() {
+            continue;
+        }
+
+        scheduled.sort_unstable();
+        scheduled.dedup();
+
+        let catch_up_run = if persistent {
+            runs.iter_mut()
+                .filter(|run| {
+                    run.boot_id == current.boot_id
+                        && run.start >= current.from
+                        && run.start <= current.from + catch_up_window
+                })
+                .min_by_key(|run| run.start)
+        } else {
+            None
+        };
+
+        let caught_up = if let Some(run) = catch_up_run {
+            run.catch_up = true;
+            true
+        } else {
+            false
+        };
+
+        missed.extend(scheduled.into_iter().map(|scheduled| MissedRun {
+            scheduled,
+            boot_id: current.boot_id.clone(),
+            caught_up,
+        }));
+    }
+
+    missed.sort_by_key(|missed| std::cmp::Reverse(missed.scheduled));
+    missed
+}
+
+pub fn duration_stats(runs: &[UnitRun]) -> Option<DurationStats> {
+    let mut durations: Vec<u64> = runs.iter().filter_map(|run| run.duration()).collect();
+
+    if durations.is_empty() {
+        return None;
+    }
+
+    durations.sort_unstable();
+
+    let count = durations.len();
+    let sum: u64 = durations.iter().sum();
+    let median = if count.is_multiple_of(2) {
+        (durations[count / 2 - 1] + durations[count / 2]) / 2
+    } else {
+        durations[count / 2]
+    };
+
+    Some(DurationStats {
+        count,
+        min: durations[0],
+        max: durations[count - 1],
+        mean: sum / count as u64,
+        median,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::invocation::{
+        MESSAGE_ID_UNIT_FAILURE_RESULT, MESSAGE_ID_UNIT_STARTED, MESSAGE_ID_UNIT_STARTING,
+        MESSAGE_ID_UNIT_STOPPED, MESSAGE_ID_UNIT_SUCCESS, RunResult, build_runs,
+    };
+
+    const SEC: u64 = USEC_PER_SEC;
+
+    fn record(
+        timestamp: u64,
+        boot_id: &str,
+        unit: &str,
+        invocation_id: &str,
+        message_id: &str,
+        result: &str,
+    ) -> UnitJournalRecord {
+        UnitJournalRecord {
+            timestamp,
+            boot_id: boot_id.to_owned(),
+            unit: unit.to_owned(),
+            invocation_id: invocation_id.to_owned(),
+            message_id: message_id.to_owned(),
+            result: result.to_owned(),
+        }
+    }
+
+    fn records() -> Vec<UnitJournalRecord> {
+        vec![
+            record(
+                10 * SEC,
+                "b1",
+                "a.timer",
+                "",
+                MESSAGE_ID_UNIT_STARTED,
+                "done",
+            ),
+            record(
+                100 * SEC,
+                "b1",
+                "a.service",
+                "i1",
+                MESSAGE_ID_UNIT_STARTING,
+                "",
+            ),
+            record(101 * SEC, "b1", "a.service", "i1", "", ""),
+            record(
+                110 * SEC,
+                "b1",
+                "a.service",
+                "i1",
+                MESSAGE_ID_UNIT_SUCCESS,
+                "",
+            ),
+            record(
+                200 * SEC,
+                "b1",
+                "a.service",
+                "i2",
+                MESSAGE_ID_UNIT_STARTING,
+                "",
+            ),
+            record(
+                230 * SEC,
+                "b1",
+                "a.service",
+                "i2",
+                MESSAGE_ID_UNIT_FAILURE_RESULT,
+                "exit-code",
+            ),
+            record(300 * SEC, "b1", "a.timer", "", MESSAGE_ID_UNIT_STOPPED, ""),
+            record(
+                3_900 * SEC,
+                "b2",
+                "a.timer",
+                "",
+                MESSAGE_ID_UNIT_STARTED,
+                "done",
+            ),
+            record(
+                3_960 * SEC,
+                "b2",
+                "a.service",
+                "i3",
+                MESSAGE_ID_UNIT_STARTING,
+                "",
+            ),
+            record(
+                3_961 * SEC,
+                "b2",
+                "a.service",
+                "i3",
+                MESSAGE_ID_UNIT_STARTED,
+                "done",
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_build_runs() {
+        let runs = build_runs(&records(), "a.service", "b2");
+
+        assert_eq!(runs.len(), 3);
+        assert_eq!(runs[0].invocation_id, "i3");
+        assert_eq!(runs[0].result, RunResult::Running);
+        assert_eq!(runs[1].result, RunResult::Failed("exit-code".to_owned()));
+        assert_eq!(runs[1].duration(), Some(30 * SEC));
+        assert_eq!(runs[2].result, RunResult::Success);
+        assert_eq!(runs[2].entries, 3);
+
+        let stats = duration_stats(&runs).unwrap();
+        assert_eq!(stats.count, 2);
+        assert_eq!(stats.min, 10 * SEC);
+        assert_eq!(stats.max, 30 * SEC);
+        assert_eq!(stats.mean, 20 * SEC);
+    }
+
+    #[test]
+    fn test_find_missed_runs() {
+        let records = records();
+        let mut runs = build_runs(&records, "a.service", "b2");
+        let activations = build_activations(&records, "a.timer");
+
+        assert_eq!(
+            activations,
+            vec![
+                TimerActivation {
+                    boot_id: "b1".to_owned(),
+                    from: 10 * SEC,
+                    until: 300 * SEC,
+                },
+                TimerActivation {
+                    boot_id: "b2".to_owned(),
+                    from: 3_900 * SEC,
+                    until: 3_961 * SEC,
+                },
+            ]
+        );
+
+        let calendar = vec![CalendarSpec::parse("hourly UTC").unwrap()];
+        let missed = find_missed_runs(&calendar, &activations, &mut runs, true, CATCH_UP_WINDOW);
+
+        assert_eq!(
+            missed,
+            vec![MissedRun {
+                scheduled: 3_600 * SEC,
+                boot_id: "b2".to_owned(),
+                caught_up: true,
+            }]
+        );
+        assert!(runs[0].catch_up);
+        assert!(!runs[1].catch_up);
+    }
+}
@@ -0,0 +1,413 @@
+use crate::{
+    calendar_spec::CalendarSpec,
+    enums::UnitType,
+    errors::SystemdErrors,
+    sysdbus::{self, dbus_proxies::systemd_manager_blocking},
+    time_handling::calc_next_elapse,
+    timestamp_is_set,
+};
+use base::enums::UnitDBusLevel;
+use std::collections::{BTreeMap, HashMap};
+use tracing::warn;
+use zvariant::{OwnedValue, Value};
+
+const TIMERS_CALENDAR: &str = "TimersCalendar";
+const TIMERS_MONOTONIC: &str = "TimersMonotonic";
+const NEXT_ELAPSE_USEC_REALTIME: &str = "NextElapseUSecRealtime";
+const NEXT_ELAPSE_USEC_MONOTONIC: &str = "NextElapseUSecMonotonic";
+const LAST_TRIGGER_USEC: &str = "LastTriggerUSec";
+const RANDOMIZED_DELAY_USEC: &str = "RandomizedDelayUSec";
+const ACCURACY_USEC: &str = "AccuracyUSec";
+const PERSISTENT: &str = "Persistent";
+const UNIT: &str = "Unit";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MonotonicTrigger {
+    /// i.e. OnBootUSec, OnUnitActiveUSec, ...
+    pub base: String,
+    pub offset: u64,
+    pub next_elapse: u64,
+}
+
+impl MonotonicTrigger {
+    /// `OnUnitActiveSec=` and `OnUnitInactiveSec=` fire again after each activation of the unit
+    pub fn is_repeating(&self) -> bool {
+        matches!(
+            self.base.as_str(),
+            "OnUnitActiveUSec" | "OnUnitInactiveUSec"
+        ) && self.offset > 0
+    }
+}
+
+/// The schedule of one timer, as exposed by systemd
+#[derive(Debug, Clone, Default)]
+pub struct TimerSchedule {
+    pub timer: String,
+    /// The unit activated by the timer
+    pub unit: String,
+    pub level: UnitDBusLevel,
+    /// OnCalendar specifications
+    pub calendar: Vec<String>,
+    pub monotonic: Vec<MonotonicTrigger>,
+    pub next_elapse_realtime: u64,
+    pub next_elapse_monotonic: u64,
+    pub last_trigger: u64,
+    pub randomized_delay: u64,
+    pub accuracy: u64,
+    pub persistent: bool,
+}
+
+impl TimerSchedule {
+    /// Next elapse as calculated by systemd, converted in realtime
+    pub fn next_elapse(&self) -> u64 {
+        calc_next_elapse(self.next_elapse_realtime, self.next_elapse_monotonic)
+    }
+
+    /// Expand all the calendar specifications and the repeating monotonic triggers
+    /// between `from` and `until` (realtime µs).
+    ///
+    /// The systemd next elapse is always included when it falls in the range, so
+    /// one shot monotonic triggers show at least once.
+    pub fn occurrences(&self, from: u64, until: u64, max: usize) -> Vec<u64> {
+        let mut out: Vec<u64> = Vec::new();
+
+        for spec in &self.calendar {
+            match CalendarSpec::parse(spec) {
+                Ok(calendar) => out.extend(calendar.next_elapses_until(from, until, max)),
+                Err(err) => warn!("Timer {:?} calendar {spec:?}: {err}", self.timer),
+            }
+        }
+
+        for trigger in self
+            .monotonic
+            .iter()
+            .filter(|trigger| trigger.is_repeating())
+        {
+            //the trigger own next elapse is the most accurate anchor, the last trigger is the fallback
+            let first = if timestamp_is_set!(trigger.next_elapse) {
+                calc_next_elapse(0, trigger.next_elapse)
+            } else if timestamp_is_set!(self.last_trigger) {
+                self.last_trigger.saturating_add(trigger.offset)
+            } else {
+                continue;
+            };
+
+            out.extend(repeat_elapses(first, trigger.offset, from, until, max));
+        }
+
+        let next_elapse = self.next_elapse();
+        if timestamp_is_set!(next_elapse) && next_elapse > from && next_elapse <= until {
+            out.push(next_elapse);
+        }
+
+        out.sort_unstable();
+        //systemd own next elapse is often the same as the expanded one
+        out.dedup_by(|a, b| a.abs_diff(*b) < crate::time_handling::USEC_PER_SEC);
+        out.truncate(max);
+        out
+    }
+}
+
+/// Every `first + k * period` in `]from, until]`, at most `max` of them
+fn repeat_elapses(first: u64, period: u64, from: u64, until: u64, max: usize) -> Vec<u64> {
+    if period == 0 {
+        return vec![];
+    }
+
+    let mut next = if first > from {
+        first
+    } else {
+        let periods = (from - first) / period + 1;
+        first.saturating_add(periods.saturating_mul(period))
+    };
+
+    let mut out = Vec::new();
+    while next <= until && out.len() < max {
+        out.push(next);
+        next = next.saturating_add(period);
+    }
+    out
+}
+
+/// A moment where at least `threshold` timers fire in the same time window
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimerCollision {
+    pub window_start: u64,
+    pub timers: Vec<String>,
+}
+
+/// Group the occurrences in windows of `window_usec` and keep the ones with at least `threshold` distinct timers
+pub fn find_collisions(
+    occurrences: &[(String, u64)],
+    window_usec: u64,
+    threshold: usize,
+) -> Vec<TimerCollision> {
+    let window_usec = window_usec.max(1);
+    let mut buckets: BTreeMap<u64, Vec<String>> = BTreeMap::new();
+
+    for (timer, time) in occurrences {
+        let bucket = time - time % window_usec;
+        let timers = buckets.entry(bucket).or_default();
+        if !timers.contains(timer) {
+            timers.push(timer.clone());
+        }
+    }
+
+    buckets
+        .into_iter()
+        .filter(|(_, timers)| timers.len() >= threshold)
+        .map(|(window_start, timers)| TimerCollision {
+            window_start,
+            timers,
+        })
+        .collect()
+}
+
+/// Fetch the schedule of every loaded timer of the given bus level
+pub fn fetch_timer_schedules(level: UnitDBusLevel) -> Result<Vec<TimerSchedule>, SystemdErrors> {
+    crate::backend::ensure_live()?;
+
+    let levels: &[UnitDBusLevel] = match level {
+        UnitDBusLevel::Both => &[UnitDBusLevel::System, UnitDBusLevel::UserSession],
+        _ => &[level],
+    };
+
+    let mut schedules = Vec::new();
+    for level in levels {
+        let timers = match systemd_manager_blocking(*level)
+            .and_then(|manager| Ok(manager.list_units_by_patterns(&[], &["*.timer"])?))
+        {
+            Ok(timers) => timers,
+            Err(err) if levels.len() > 1 => {
+                warn!("List timers {level:?} {err:?}");
+                continue;
+            }
+            Err(err) => return Err(err),
+        };
+
+        for timer in timers {
+            match sysdbus::fetch_system_unit_info_native_map(
+                *level,
+                timer.unit_object_path.as_str(),
+                UnitType::Timer,
+            ) {
+                Ok(map) => schedules.push(timer_schedule_from_map(
+                    timer.primary_unit_name,
+                    *level,
+                    &map,
+                )),
+                Err(err) => warn!("Timer {:?} properties {err:?}", timer.primary_unit_name),
+            }
+        }
+    }
+
+    Ok(schedules)
+}
+
+/// Fetch the schedule of one timer
+pub fn fetch_timer_schedule(
+    level: UnitDBusLevel,
+    timer: &str,
+) -> Result<TimerSchedule, SystemdErrors> {
+    crate::backend::ensure_live()?;
+
+    let object_path = sysdbus::unit_dbus_path_from_name(timer);
+    let map = sysdbus::fetch_system_unit_info_native_map(level, &object_path, UnitType::Timer)?;
+    Ok(timer_schedule_from_map(timer.to_owned(), level, &map))
+}
+
+fn timer_schedule_from_map(
+    timer: String,
+    level: UnitDBusLevel,
+    map: &HashMap<String, OwnedValue>,
+) -> TimerSchedule {
+    let get_u64 = |key: &str| -> u64 {
+        map.get(key)
+            .and_then(|value| u64::try_from(value).ok())
+            .unwrap_or_default()
+    };
+
+    let unit = map
+        .get(UNIT)
+        .and_then(|value| String::try_from(value.clone()).ok())
+        .unwrap_or_default();
+
+    let persistent = map
+        .get(PERSISTENT)
+        .and_then(|value| bool::try_from(value).ok())
+        .unwrap_or_default();
+
+    let calendar = struct_array(map.get(TIMERS_CALENDAR))
+        .into_iter()
+        .filter_map(|fields| match fields.as_slice() {
+            [_base, Value::Str(spec), _next] => Some(spec.to_string()),
+            _ => None,
+        })
+        .collect();
+
+    let monotonic = struct_array(map.get(TIMERS_MONOTONIC))
+        .into_iter()
+        .filter_map(|fields| match fields.as_slice() {
+            [
+                Value::Str(base),
+                Value::U64(offset),
+                Value::U64(next_elapse),
+            ] => Some(MonotonicTrigger {
+                base: base.to_string(),
+                offset: *offset,
+                next_elapse: *next_elapse,
+            }),
+            _ => None,
+        })
+        .collect();
+
+    TimerSchedule {
+        timer,
+        unit,
+        level,
+        calendar,
+        monotonic,
+        next_elapse_realtime: get_u64(NEXT_ELAPSE_USEC_REALTIME),
+        next_elapse_monotonic: get_u64(NEXT_ELAPSE_USEC_MONOTONIC),
+        last_trigger: get_u64(LAST_TRIGGER_USEC),
+        randomized_delay: get_u64(RANDOMIZED_DELAY_USEC),
+        accuracy: get_u64(ACCURACY_USEC),
+        persistent,
+    }
+}
+
+fn struct_array(value: Option<&OwnedValue>) -> Vec<Vec<Value<'_>>> {
+    let Some(value) = value else {
+        return vec![];
+    };
+
+    let Value::Array(array) = value as &Value else {
+        return vec![];
+    };
+
+    array
+        .iter()
+        .filter_map(|item| match item {
+            Value::Structure(structure) => Some(structure.fields().to_vec()),
+            _ => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_collisions() {
+        let occurrences = vec![
+            ("a.timer".to_owned(), 60_000_000),
+            ("b.timer".to_owned(), 60_500_000),
+            ("b.timer".to_owned(), 60_600_000),
+            ("c.timer".to_owned(), 200_000_000),
+        ];
+
+        let collisions = find_collisions(&occurrences, 60_000_000, 2);
+        assert_eq!(
+            collisions,
+            vec![TimerCollision {
+                window_start: 60_000_000,
+                timers: vec!["a.timer".to_owned(), "b.timer".to_owned()]
+            }]
+        );
+    }
+
+    #[test]
+    fn test_occurrences() {
+        let schedule = TimerSchedule {
+            timer: "a.timer".to_owned(),
+            calendar: vec!["hourly UTC".to_owned()],
+            ..Default::default()
+        };
+
+        // 2024-03-09 00:30:00 UTC
+        let from = 1_709_944_200_000_000;
+        let occurrences = schedule.occurrences(from, from + 3 * 3_600_000_000, 10);
+        assert_eq!(occurrences.len(), 3);
+    }
+
+    #[test]
+    fn test_occurrences_repeating_monotonic() {
+        let hour = 3_600_000_000;
+        // 2024-03-09 00:30:00 UTC
+        let from = 1_709_944_200_000_000;
+
+        let schedule = TimerSchedule {
+            timer: "a.timer".to_owned(),
+            monotonic: vec![
+                MonotonicTrigger {
+                    base: "OnUnitActiveUSec".to_owned(),
+                    offset: 6 * hour,
+                    next_elapse: 0,
+                },
+                MonotonicTrigger {
+                    base: "OnBootUSec".to_owned(),
+                    offset: 15 * 60_000_000,
+                    next_elapse: 0,
+                },
+            ],
+            last_trigger: from - hour,
+            ..Default::default()
+        };
+
+        let occurrences = schedule.occurrences(from, from + 24 * hour, 10);
+        assert_eq!(
+            occurrences,
+            vec![
+                from + 5 * hour,
+                from + 11 * hour,
+                from + 17 * hour,
+                from + 23 * hour
+            ]
+        );
+    }
+
+    #[test]
+    fn test_collisions_repeating_monotonic() {
+        let hour = 3_600_000_000;
+        // 2024-03-09 00:00:00 UTC
+        let from = 1_709_942_400_000_000;
+
+        let active = TimerSchedule {
+            timer: "a.timer".to_owned(),
+            monotonic: vec![MonotonicTrigger {
+                base: "OnUnitActiveUSec".to_owned(),
+                offset: 2 * hour,
+                next_elapse: 0,
+            }],
+            last_trigger: from,
+            ..Default::default()
+        };
+        let inactive = TimerSchedule {
+            timer: "b.timer".to_owned(),
+            monotonic: vec![MonotonicTrigger {
+                base: "OnUnitInactiveUSec".to_owned(),
+                offset: 3 * hour,
+                next_elapse: 0,
+            }],
+            last_trigger: from,
+            ..Default::default()
+        };
+
+        let occurrences: Vec<(String, u64)> = [active, inactive]
+            .iter()
+            .flat_map(|schedule| {
+                schedule
+                    .occurrences(from, from + 12 * hour, 20)
+                    .into_iter()
+                    .map(|time| (schedule.timer.clone(), time))
+            })
+            .collect();
+
+        let windows: Vec<u64> = find_collisions(&occurrences, 60_000_000, 2)
+            .into_iter()
+            .map(|collision| collision.window_start)
+            .collect();
+        assert_eq!(windows, vec![from + 6 * hour, from + 12 * hour]);
+    }
+}
@@ -0,0 +1,429 @@
+use crate::{
+    PolkitAction,
+    write_policy::{WriteError, WritePolicy},
+};
+use base::consts::{
+    AUDIT_KEY_ACTION, AUDIT_KEY_AUTHORIZED, AUDIT_KEY_CALLER_PID, AUDIT_KEY_CALLER_UID,
+    AUDIT_KEY_CONTENT_SHA256, AUDIT_KEY_FILE, AUDIT_KEY_METHOD, AUDIT_KEY_PREVIOUS_SHA256,
+    AUDIT_KEY_RESULT, AUDIT_KEY_UNIT, AUDIT_MESSAGE_ID, AUDIT_RESULT_SUCCESS,
+    AUDIT_SYSLOG_IDENTIFIER,
+};
+use std::{
+    collections::HashMap,
+    fs::OpenOptions,
+    io::Read,
+    os::unix::{fs::OpenOptionsExt, net::UnixDatagram},
+    path::Path,
+};
+use tokio::sync::OnceCell;
+use tracing::{info, warn};
+use zbus::{Connection, fdo::DBusProxy, message::Header, names::BusName};
+
+const JOURNAL_SOCKET: &str = "/run/systemd/journal/socket";
+/// syslog priorities
+const PRIORITY_WARNING: &str = "4";
+const PRIORITY_NOTICE: &str = "5";
+/// Unit files are small, a bigger previous file is not read to be hashed
+const MAX_HASHED_FILE_SIZE: u64 = 1024 * 1024;
+/// Previous hash of a file too big or not a regular file
+const NOT_HASHED: &str = "not hashed";
+
+static DBUS_PROXY: OnceCell<DBusProxy<'static>> = OnceCell::const_new();
+
+/// The process calling the proxy
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Caller {
+    pub uid: Option<u32>,
+    pub pid: Option<u32>,
+}
+
+impl Caller {
+    /// Ask the bus for the credentials of the message sender
+    pub async fn from_header(header: &Header<'_>) -> Self {
+        let Some(sender) = header.sender() else {
+            return Caller::default();
+        };
+
+        let proxy = match dbus_proxy().await {
+            Ok(proxy) => proxy,
+            Err(err) => {
+                warn!("Audit DBus proxy {err:?}");
+                return Caller::default();
+            }
+        };
+
+        let bus_name = BusName::from(sender.clone());
+        Caller {
+            uid: proxy
+                .get_connection_unix_user(bus_name.clone())
+                .await
+                .inspect_err(|err| warn!("Caller uid {err:?}"))
+                .ok(),
+            pid: proxy
+                .get_connection_unix_process_id(bus_name)
+                .await
+                .inspect_err(|err| warn!("Caller pid {err:?}"))
+                .ok(),
+        }
+    }
+}
+
+async fn dbus_proxy() -> Result<&'static DBusProxy<'static>, zbus::Error> {
+    DBUS_PROXY
+        .get_or_try_init(async || -> Result<DBusProxy<'static>, zbus::Error> {
+            let connection = Connection::system().await?;
+            DBusProxy::new(&connection).await
+        })
+        .await
+}
+
+/// One privileged call, written in the journal once its outcome is known
+#[derive(Debug)]
+pub struct Audit {
+    caller: Caller,
+    action: PolkitAction,
+    method: String,
+    unit: String,
+    file_path: String,
+    content_sha256: String,
+    previous_sha256: String,
+}
+
+impl Audit {
+    pub fn new(caller: Caller, action: PolkitAction, details: &HashMap<&str, &str>) -> Self {
+        let detail = |key: &str| details.get(key).map(|value| value.to_string());
+
+        Audit {
+            caller,
+            action,
+            method: detail("operation").unwrap_or_default(),
+            unit: detail("unit")
+                .or_else(|| detail("variables"))
+                .unwrap_or_default(),
+            file_path: String::new(),
+            content_sha256: String::new(),
+            previous_sha256: String::new(),
+        }
+    }
+
+    /// Keep the hashes of the file content before and after the write.
+    ///
+    /// The previous file is read only once its path passes the write policy.
+    pub fn with_file(
+        &mut self,
+        policy: &WritePolicy,
+        file_path: &str,
+        content: &str,
+    ) -> Result<(), WriteError> {
+        self.file_path = file_path.to_owned();
+        self.content_sha256 = sha256(content.as_bytes());
+
+        let path = Path::new(file_path);
+        policy.check(path)?;
+        self.previous_sha256 = previous_sha256(path);
+        Ok(())
+    }
+
+    pub fn denied(self, err: &zbus::fdo::Error) {
+        self.send(false, &err.to_string());
+    }
+
+    /// Write the call result in the journal and give it back
+    pub fn record<T, E: std::fmt::Display>(self, result: Result<T, E>) -> Result<T, E> {
+        match &result {
+            Ok(_) => self.send(true, AUDIT_RESULT_SUCCESS),
+            Err(err) => self.send(true, &err.to_string()),
+        }
+        result
+    }
+
+    fn send(&self, authorized: bool, result: &str) {
+        let fields = self.fields(authorized, result);
+        if let Err(err) = journal_send(&fields) {
+            warn!("Audit journal entry {err:?} {fields:?}");
+        }
+    }
+
+    fn fields(&self, authorized: bool, result: &str) -> Vec<(&'static str, String)> {
+        let uid = self
+            .caller
+            .uid
+            .map(|uid| uid.to_string())
+            .unwrap_or_default();
+        let pid = self
+            .caller
+            .pid
+            .map(|pid| pid.to_string())
+            .unwrap_or_default();
+
+        let target = if self.file_path.is_empty() {
+            &self.unit
+        } else {
+            &self.file_path
+        };
+
+        let message = if authorized {
+            format!("{} {target} by uid {uid} pid {pid}: {result}", self.method)
+        } else {
+            format!(
+                "{} {target} by uid {uid} pid {pid} denied: {result}",
+                self.method
+            )
+        };
+
+        let priority = if authorized && result == AUDIT_RESULT_SUCCESS {
+            PRIORITY_NOTICE
+        } else {
+            PRIORITY_WARNING
+        };
+
+        info!("Audit {message}");
+
+        let mut fields = vec![
+            ("MESSAGE", message),
+            ("MESSAGE_ID", AUDIT_MESSAGE_ID.to_owned()),
+            ("SYSLOG_IDENTIFIER", AUDIT_SYSLOG_IDENTIFIER.to_owned()),
+            ("PRIORITY", priority.to_owned()),
+            (AUDIT_KEY_CALLER_UID, uid),
+            (AUDIT_KEY_CALLER_PID, pid),
+            (AUDIT_KEY_METHOD, self.method.clone()),
+            (AUDIT_KEY_ACTION, self.action.id().to_owned()),
+            (
+                AUDIT_KEY_AUTHORIZED,
+                if authorized { "yes" } else { "no" }.to_owned(),
+            ),
+            (AUDIT_KEY_RESULT, result.to_owned()),
+        ];
+
+        for (key, value) in [
+            (AUDIT_KEY_UNIT, &self.unit),
+            (AUDIT_KEY_FILE, &self.file_path),
+            (AUDIT_KEY_CONTENT_SHA256, &self.content_sha256),
+            (AUDIT_KEY_PREVIOUS_SHA256, &self.previous_sha256),
+        ] {
+            if !value.is_empty() {
+                fields.push((key, value.clone()));
+            }
+        }
+
+        fields
+    }
+}
+
+fn sha256(data: &[u8]) -> String {
+    glib::compute_checksum_for_data(glib::ChecksumType::Sha256, data)
+        .map(|checksum| checksum.to_string())
+        .unwrap_or_default()
+}
+
+/// Hash of the regular file about to be overwritten, empty if it does not exist yet
+fn previous_sha256(path: &Path) -> String {
+    match std::fs::symlink_metadata(path) {
+        Ok(metadata) if metadata.is_file() && metadata.len() <= MAX_HASHED_FILE_SIZE => {}
+        Ok(_) => return NOT_HASHED.to_owned(),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return String::new(),
+        Err(err) => {
+            warn!("Audit previous file {path:?} {err:?}");
+            return NOT_HASHED.to_owned();
+        }
+    }
+
+    match read_regular_file(path) {
+        Ok(Some(previous)) => sha256(&previous),
+        Ok(None) => NOT_HASHED.to_owned(),
+        Err(err) => {
+            warn!("Audit previous file {path:?} {err:?}");
+            NOT_HASHED.to_owned()
+        }
+    }
+}
+
+/// The file could have been swapped after the metadata check, so it is opened without
+/// following a link nor blocking on a FIFO and checked again
+fn read_regular_file(path: &Path) -> std::io::Result<Option<Vec<u8>>> {
+    let file = OpenOptions::new()
+        .read(true)
+        .custom_flags(libc::O_NOFOLLOW | libc::O_NONBLOCK)
+        .open(path)?;
+
+    if !file.metadata()?.is_file() {
+        return Ok(None);
+    }
+
+    let mut data = Vec::new();
+    file.take(MAX_HASHED_FILE_SIZE + 1).read_to_end(&mut data)?;
+
+    if data.len() as u64 > MAX_HASHED_FILE_SIZE {
+        return Ok(None);
+    }
+
+    Ok(Some(data))
+}
+
+fn journal_send(fields: &[(&str, String)]) -> std::io::Result<()> {
+    let socket = UnixDatagram::unbound()?;
+    socket.send_to(&journal_entry(fields), JOURNAL_SOCKET)?;
+    Ok(())
+}
+
+/// Journal native protocol, the values with a new line are sent with their length
+fn journal_entry(fields: &[(&str, String)]) -> Vec<u8> {
+    let mut data = Vec::new();
+
+    for (key, value) in fields {
+        data.extend_from_slice(key.as_bytes());
+
+        if value.contains('\n') {
+            data.push(b'\n');
+            data.extend_from_slice(&(value.len() as u64).to_le_bytes());
+        } else {
+            data.push(b'=');
+        }
+
+        data.extend_from_slice(value.as_bytes());
+        data.push(b'\n');
+    }
+
+    data
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_journal_entry() {
+        let data = journal_entry(&[
+            ("MESSAGE", "one line".to_owned()),
+            ("SYSD_MANAGER_RESULT", "a\nb".to_owned()),
+        ]);
+
+        let mut expected = b"MESSAGE=one line\nSYSD_MANAGER_RESULT\n".to_vec();
+        expected.extend_from_slice(&3u64.to_le_bytes());
+        expected.extend_from_slice(b"a\nb\n");
+
+        assert_eq!(data, expected);
+    }
+
+    #[test]
+    fn test_fields() {
+        let details = crate::map("restart_unit", "nginx.service");
+        let audit = Audit::new(
+            Caller {
+                uid: Some(1000),
+                pid: Some(42),
+            },
+            PolkitAction::ManageUnits,
+            &details,
+        );
+
+        let fields: HashMap<_, _> = audit
+            .fields(true, AUDIT_RESULT_SUCCESS)
+            .into_iter()
+            .collect();
+        assert_eq!(
+            fields["MESSAGE"],
+            "restart_unit nginx.service by uid 1000 pid 42: success"
+        );
+        assert_eq!(fields[AUDIT_KEY_UNIT], "nginx.service");
+        assert_eq!(fields[AUDIT_KEY_AUTHORIZED], "yes");
+        assert_eq!(fields["PRIORITY"], PRIORITY_NOTICE);
+        assert!(!fields.contains_key(AUDIT_KEY_FILE));
+
+        let fields: HashMap<_, _> = audit.fields(false, "denied").into_iter().collect();
+        assert_eq!(fields[AUDIT_KEY_AUTHORIZED], "no");
+        assert_eq!(fields["PRIORITY"], PRIORITY_WARNING);
+    }
+
+    fn test_dir(name: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("sysd-audit-{name}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn file_audit() -> Audit {
+        Audit::new(
+            Caller::default(),
+            PolkitAction::WriteFiles,
+            &crate::map_file("save_file", ""),
+        )
+    }
+
+    #[test]
+    fn test_with_file_rejected_never_opened() {
+        let dir = test_dir("rejected");
+        let rejected = dir.join("script.sh");
+        std::fs::write(&rejected, "#!/bin/sh\n").unwrap();
+
+        let policy = WritePolicy {
+            allowed_dirs: vec![dir.clone()],
+            ..Default::default()
+        };
+
+        let inotify = unsafe { libc::inotify_init1(libc::IN_NONBLOCK | libc::IN_CLOEXEC) };
+        assert!(inotify >= 0);
+        let watched = std::ffi::CString::new(rejected.to_str().unwrap()).unwrap();
+        let watch = unsafe {
+            libc::inotify_add_watch(inotify, watched.as_ptr(), libc::IN_OPEN | libc::IN_ACCESS)
+        };
+        assert!(watch >= 0);
+
+        let mut audit = file_audit();
+        let result = audit.with_file(&policy, rejected.to_str().unwrap(), "content");
+        assert!(matches!(result, Err(WriteError::ExtensionRejected(_))));
+        assert!(audit.previous_sha256.is_empty());
+
+        let mut events = [0u8; 256];
+        let read = unsafe { libc::read(inotify, events.as_mut_ptr().cast(), events.len()) };
+        unsafe { libc::close(inotify) };
+        assert_eq!(read, -1, "the rejected file has been opened");
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_with_file_previous_sha256() {
+        let dir = test_dir("previous");
+        let policy = WritePolicy {
+            allowed_dirs: vec![dir.clone()],
+            ..Default::default()
+        };
+
+        let unit = dir.join("a.service");
+        std::fs::write(&unit, "[Unit]\n").unwrap();
+        let mut audit = file_audit();
+        audit
+            .with_file(&policy, unit.to_str().unwrap(), "[Service]\n")
+            .unwrap();
+        assert_eq!(audit.previous_sha256, sha256(b"[Unit]\n"));
+        assert_eq!(audit.content_sha256, sha256(b"[Service]\n"));
+
+        let new_unit = dir.join("b.service");
+        let mut audit = file_audit();
+        audit
+            .with_file(&policy, new_unit.to_str().unwrap(), "")
+            .unwrap();
+        assert!(audit.previous_sha256.is_empty());
+
+        let fifo = dir.join("c.service");
+        let fifo_path = std::ffi::CString::new(fifo.to_str().unwrap()).unwrap();
+        assert_eq!(unsafe { libc::mkfifo(fifo_path.as_ptr(), 0o644) }, 0);
+        let mut audit = file_audit();
+        audit
+            .with_file(&policy, fifo.to_str().unwrap(), "")
+            .unwrap();
+        assert_eq!(audit.previous_sha256, NOT_HASHED);
+
+        let big = dir.join("d.service");
+        std::fs::File::create(&big)
+            .unwrap()
+            .set_len(MAX_HASHED_FILE_SIZE + 1)
+            .unwrap();
+        let mut audit = file_audit();
+        audit.with_file(&policy, big.to_str().unwrap(), "").unwrap();
+        assert_eq!(audit.previous_sha256, NOT_HASHED);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,5 +1,6 @@
-use base::file::SysdBaseError;
-use base::file::{create_drop_in_io, save_io};
+use crate::write_policy::{WriteError, write_policy};
+use base::file::{determine_drop_in_path_dir, save_io};
+use std::path::Path;
 use tracing::info;
 use tracing::warn;
 
@@ -8,59 +9,43 @@ pub async fn create_drop_in(
     unit_name: &str,
     file_path: &str,
     content: &str,
-) -> zbus::fdo::Result<()> {
+) -> Result<(), WriteError> {
     info!(
         "Creating Drop-in: unit {unit_name:?} runtime {runtime:?}, file_path {file_path:?} , content {} bytes",
         content.len()
     );
 
-    create_drop_in_io(file_path, content, false)
-        .await
-        .map_err(|err| {
-            let fdo: FdoError = err.into();
-            fdo.0
-        })
+    check_drop_in_path(runtime, unit_name, file_path)?;
+    create(file_path, content).await?;
+    Ok(())
 }
 
-struct FdoError(zbus::fdo::Error);
+/// The drop-in has to be a `.conf` file of the unit drop-in directory, in the runtime or
+/// persistent system directory selected by `runtime`
+fn check_drop_in_path(runtime: bool, unit_name: &str, file_path: &str) -> Result<(), WriteError> {
+    if unit_name.is_empty() || unit_name.contains('/') {
+        return Err(WriteError::PathNotAllowed(format!(
+            "The unit name {unit_name:?} is not valid"
+        )));
+    }
 
-impl From<SysdBaseError> for FdoError {
-    fn from(value: SysdBaseError) -> Self {
-        warn!("{value:?}");
-        match value {
-            SysdBaseError::Custom(s) => FdoError(zbus::fdo::Error::Failed(s)),
-            SysdBaseError::IoError(err) => match err.kind() {
-                std::io::ErrorKind::PermissionDenied => FdoError(zbus::fdo::Error::AccessDenied(
-                    format!("{:?} {err:?}", err.kind()),
-                )),
+    let drop_in_dir = determine_drop_in_path_dir(unit_name, runtime, false)
+        .map_err(|err| WriteError::PathNotAllowed(err.to_string()))?;
 
-                std::io::ErrorKind::InvalidData => FdoError(zbus::fdo::Error::InvalidArgs(
-                    format!("{:?} {err:?}", err.kind()),
-                )),
+    let path = Path::new(file_path);
+    if path.parent() != Some(Path::new(&drop_in_dir)) {
+        return Err(WriteError::PathNotAllowed(format!(
+            "The drop-in {file_path:?} is not in the directory {drop_in_dir:?}"
+        )));
+    }
 
-                kind => FdoError(zbus::fdo::Error::IOError(format!("{:?}", kind))),
-            },
-            SysdBaseError::NotAuthorizedAuthentificationDismissed => {
-                FdoError(zbus::fdo::Error::AuthFailed("Not Authentified".to_string()))
-            }
-            SysdBaseError::NotAuthorized => {
-                FdoError(zbus::fdo::Error::AccessDenied("Not Auhorised".to_string()))
-            }
-            SysdBaseError::CmdNoFreedesktopFlatpakPermission => FdoError(
-                zbus::fdo::Error::NotSupported("Flatpak Permission".to_string()),
-            ),
-            SysdBaseError::CommandCallError(_, _, _, _) => {
-                FdoError(zbus::fdo::Error::Failed("Internal issue".to_string()))
-            }
-            SysdBaseError::Tokio(_) => {
-                FdoError(zbus::fdo::Error::Failed("Internal issue".to_string()))
-            }
-            SysdBaseError::InvalidPath(msg) => FdoError(zbus::fdo::Error::AccessDenied(msg)),
-            SysdBaseError::ErrorExit(exit_code) => FdoError(zbus::fdo::Error::SpawnExecFailed(
-                format!("Exit code {}", exit_code),
-            )),
-        }
+    if path.extension().is_none_or(|extension| extension != "conf") {
+        return Err(WriteError::ExtensionRejected(format!(
+            "The drop-in {file_path:?} has to have the .conf extension"
+        )));
     }
+
+    Ok(())
 }
 
 fn transform_error<T>(result: Result<T, std::io::Error>) -> Result<T, zbus::fdo::Error> {
@@ -84,20 +69,104 @@ fn transform_error<T>(result: Result<T, std::io::Error>) -> Result<T, zbus::fdo:
     }
 }
 
-pub async fn save(file_path: &str, content: &str) -> zbus::fdo::Result<u64> {
+pub async fn save(file_path: &str, content: &str) -> Result<u64, WriteError> {
+    write_policy().check(Path::new(file_path))?;
+
     let result = save_io(file_path, false, content).await;
-    transform_error(result)
+    transform_error(result).map_err(WriteError::from)
 }
 
-pub async fn create(file_path: &str, content: &str) -> zbus::fdo::Result<u64> {
-    let result = save_io(file_path, true, content).await;
-    transform_error(result)
+/// Create the file, and its drop-in directory, with the policy mode and owner
+pub async fn create(file_path: &str, content: &str) -> Result<u64, WriteError> {
+    let policy = write_policy();
+    let path = Path::new(file_path);
+    policy.check(path)?;
+
+    if let Some(dir_path) = path.parent()
+        && !dir_path.exists()
+    {
+        info!("Creating dir {}", dir_path.display());
+        transform_error(tokio::fs::create_dir_all(dir_path).await)?;
+        transform_error(policy.apply_ownership(dir_path, true).await)?;
+    }
+
+    let created = !path.exists();
+    let bytes_written = transform_error(save_io(path, true, content).await)?;
+
+    if created {
+        transform_error(policy.apply_ownership(path, false).await)?;
+    }
+
+    Ok(bytes_written)
 }
 
 #[cfg(test)]
 mod test {
+    use super::*;
     use std::path::PathBuf;
 
+    #[test]
+    fn test_check_drop_in_path() {
+        let unit = "nginx.service";
+
+        assert!(
+            check_drop_in_path(
+                false,
+                unit,
+                "/etc/systemd/system/nginx.service.d/override.conf"
+            )
+            .is_ok()
+        );
+        assert!(
+            check_drop_in_path(
+                true,
+                unit,
+                "/run/systemd/system/nginx.service.d/override.conf"
+            )
+            .is_ok()
+        );
+
+        // runtime selects /run, not /etc
+        assert!(matches!(
+            check_drop_in_path(
+                true,
+                unit,
+                "/etc/systemd/system/nginx.service.d/override.conf"
+            ),
+            Err(WriteError::PathNotAllowed(_))
+        ));
+
+        // the drop-in directory of another unit
+        assert!(matches!(
+            check_drop_in_path(
+                false,
+                unit,
+                "/etc/systemd/system/sshd.service.d/override.conf"
+            ),
+            Err(WriteError::PathNotAllowed(_))
+        ));
+
+        // the unit file itself
+        assert!(matches!(
+            check_drop_in_path(false, unit, "/etc/systemd/system/nginx.service"),
+            Err(WriteError::PathNotAllowed(_))
+        ));
+
+        assert!(matches!(
+            check_drop_in_path(
+                false,
+                unit,
+                "/etc/systemd/system/nginx.service.d/override.sh"
+            ),
+            Err(WriteError::ExtensionRejected(_))
+        ));
+
+        assert!(matches!(
+            check_drop_in_path(false, "../../tmp/x", "/etc/systemd/tmp/x.d/override.conf"),
+            Err(WriteError::PathNotAllowed(_))
+        ));
+    }
+
     #[test]
     fn test_parent_dir() {
         let p = PathBuf::from("/home/plr/../.config");
@@ -1,12 +1,20 @@
 use base::proxy::{DisEnAbleUnitFiles, DisEnAbleUnitFilesResponse};
+use std::collections::HashMap;
 use tokio::{sync::OnceCell, time::Instant};
 use tracing::{debug, info, warn};
 use zbus::{
-    Connection, ObjectServer, interface, message::Header, object_server::SignalEmitter,
-    zvariant::OwnedObjectPath,
+    Connection, ObjectServer, interface,
+    message::Header,
+    object_server::SignalEmitter,
+    zvariant::{OwnedObjectPath, OwnedValue},
 };
 
-use crate::{SysDManagerProxy, file, sysdcom};
+use crate::{
+    PolkitAction, SysDManagerProxy, file, journal, logind, map, map_file, map_user, map_variables,
+    sysdcom,
+    user_manager::{ListedUnit, unit_properties, user_manager},
+    write_policy::{WriteError, write_policy},
+};
 
 #[interface(name = "io.github.plrigaux.SysDManager", introspection_docs = true)]
 impl SysDManagerProxy {
@@ -17,12 +25,20 @@ impl SysDManagerProxy {
         unit_name: &str,
         file_path: &str,
         content: &str,
-    ) -> zbus::fdo::Result<()> {
-        //self.
-        self.check_autorisation(header).await?;
+    ) -> Result<(), WriteError> {
+        let mut audit = self
+            .check_autorisation(
+                header,
+                PolkitAction::WriteFiles,
+                &map_file("create_drop_in", file_path),
+            )
+            .await?;
+        if let Err(err) = audit.with_file(write_policy(), file_path, content) {
+            return audit.record(Err(err));
+        }
 
         //   self.get_all(object_server, connection, header, emitter)
-        file::create_drop_in(runtime, unit_name, file_path, content).await
+        audit.record(file::create_drop_in(runtime, unit_name, file_path, content).await)
     }
 
     pub async fn save_file(
@@ -31,9 +47,18 @@ impl SysDManagerProxy {
 
         file_path: &str,
         content: &str,
-    ) -> zbus::fdo::Result<u64> {
-        self.check_autorisation(header).await?;
-        file::save(file_path, content).await
+    ) -> Result<u64, WriteError> {
+        let mut audit = self
+            .check_autorisation(
+                header,
+                PolkitAction::WriteFiles,
+                &map_file("save_file", file_path),
+            )
+            .await?;
+        if let Err(err) = audit.with_file(write_policy(), file_path, content) {
+            return audit.record(Err(err));
+        }
+        audit.record(file::save(file_path, content).await)
     }
 
     pub async fn create_file(
@@ -42,21 +67,66 @@ impl SysDManagerProxy {
 
         file_path: &str,
         content: &str,
-    ) -> zbus::fdo::Result<u64> {
-        self.check_autorisation(header).await?;
-        file::create(file_path, content).await
+    ) -> Result<u64, WriteError> {
+        let mut audit = self
+            .check_autorisation(
+                header,
+                PolkitAction::WriteFiles,
+                &map_file("create_file", file_path),
+            )
+            .await?;
+        if let Err(err) = audit.with_file(write_policy(), file_path, content) {
+            return audit.record(Err(err));
+        }
+        audit.record(file::create(file_path, content).await)
+    }
+
+    pub async fn journal_rotate(
+        &mut self,
+        #[zbus(header)] header: Header<'_>,
+    ) -> zbus::fdo::Result<()> {
+        info!("journal_rotate");
+        let audit = self
+            .check_autorisation(
+                header,
+                PolkitAction::ManageJournal,
+                &map("journal_rotate", ""),
+            )
+            .await?;
+        audit.record(journal::rotate().await)
+    }
+
+    pub async fn journal_vacuum(
+        &mut self,
+        #[zbus(header)] header: Header<'_>,
+        rotate: bool,
+        max_size: u64,
+        max_age: u64,
+        max_files: u64,
+    ) -> zbus::fdo::Result<String> {
+        info!("journal_vacuum rotate {rotate} size {max_size} age {max_age} files {max_files}");
+        let audit = self
+            .check_autorisation(
+                header,
+                PolkitAction::ManageJournal,
+                &map("journal_vacuum", ""),
+            )
+            .await?;
+        audit.record(journal::vacuum(rotate, max_size, max_age, max_files).await)
     }
 
     pub async fn my_user_id(
         &mut self,
         #[zbus(header)] header: Header<'_>,
     ) -> zbus::fdo::Result<u32> {
-        self.check_autorisation(header).await?;
+        let audit = self
+            .check_autorisation(header, PolkitAction::Proxy, &map("my_user_id", ""))
+            .await?;
 
-        let id = unsafe { libc::getegid() };
+        let id = unsafe { libc::geteuid() };
         info!("ids {}", id);
 
-        Ok(id)
+        audit.record(Ok(id))
     }
 
     // "Bye" signal (note: no implementation body).
@@ -96,12 +166,15 @@ impl SysDManagerProxy {
         val: u32,
     ) -> zbus::fdo::Result<u32> {
         info!("even_ping {val}");
-        self.check_autorisation(header).await?;
-        if val.is_multiple_of(2) {
+        let audit = self
+            .check_autorisation(header, PolkitAction::Proxy, &map("even_ping", ""))
+            .await?;
+        let result = if val.is_multiple_of(2) {
             Ok(val)
         } else {
             Err(zbus::fdo::Error::Failed(format!("{val} not even!")))
-        }
+        };
+        audit.record(result)
     }
 
     async fn start_unit(
@@ -112,13 +185,20 @@ impl SysDManagerProxy {
     ) -> zbus::fdo::Result<OwnedObjectPath> {
         info!("start_unit {} {:?}", unit_name, mode);
 
-        self.check_autorisation(header).await?;
+        let audit = self
+            .check_autorisation(
+                header,
+                PolkitAction::ManageUnits,
+                &map("start_unit", unit_name),
+            )
+            .await?;
 
         let proxy = get_proxy().await?;
-        proxy
+        let result = proxy
             .start_unit(unit_name, mode)
             .await
-            .inspect_err(|e| warn!("Error while calling clean_unit on sysdbus proxy: {:?}", e))
+            .inspect_err(|e| warn!("Error while calling clean_unit on sysdbus proxy: {:?}", e));
+        audit.record(result)
     }
 
     async fn stop_unit(
@@ -129,13 +209,20 @@ impl SysDManagerProxy {
     ) -> zbus::fdo::Result<OwnedObjectPath> {
         info!("stop_unit {} {:?}", unit_name, mode);
 
-        self.check_autorisation(header).await?;
+        let audit = self
+            .check_autorisation(
+                header,
+                PolkitAction::ManageUnits,
+                &map("stop_unit", unit_name),
+            )
+            .await?;
 
         let proxy = get_proxy().await?;
-        proxy
+        let result = proxy
             .stop_unit(unit_name, mode)
             .await
-            .inspect_err(|e| warn!("Error while calling clean_unit on sysdbus proxy: {:?}", e))
+            .inspect_err(|e| warn!("Error while calling clean_unit on sysdbus proxy: {:?}", e));
+        audit.record(result)
     }
 
     async fn restart_unit(
@@ -146,13 +233,20 @@ impl SysDManagerProxy {
     ) -> zbus::fdo::Result<OwnedObjectPath> {
         info!("restart_unit {} {:?}", unit_name, mode);
 
-        self.check_autorisation(header).await?;
+        let audit = self
+            .check_autorisation(
+                header,
+                PolkitAction::ManageUnits,
+                &map("restart_unit", unit_name),
+            )
+            .await?;
 
         let proxy = get_proxy().await?;
-        proxy
+        let result = proxy
             .restart_unit(unit_name, mode)
             .await
-            .inspect_err(|e| warn!("Error while calling clean_unit on sysdbus proxy: {:?}", e))
+            .inspect_err(|e| warn!("Error while calling clean_unit on sysdbus proxy: {:?}", e));
+        audit.record(result)
     }
 
     async fn reload_unit(
@@ -163,13 +257,20 @@ impl SysDManagerProxy {
     ) -> zbus::fdo::Result<OwnedObjectPath> {
         info!("reload_unit {} {:?}", unit_name, mode);
 
-        self.check_autorisation(header).await?;
+        let audit = self
+            .check_autorisation(
+                header,
+                PolkitAction::ManageUnits,
+                &map("reload_unit", unit_name),
+            )
+            .await?;
 
         let proxy = get_proxy().await?;
-        proxy
+        let result = proxy
             .reload_unit(unit_name, mode)
             .await
-            .inspect_err(|e| warn!("Error while calling clean_unit on sysdbus proxy: {:?}", e))
+            .inspect_err(|e| warn!("Error while calling clean_unit on sysdbus proxy: {:?}", e));
+        audit.record(result)
     }
 
     async fn clean_unit(
@@ -181,13 +282,20 @@ impl SysDManagerProxy {
     ) -> zbus::fdo::Result<()> {
         info!("clean_unit {} {:?}", unit_name, what);
 
-        self.check_autorisation(header).await?;
+        let audit = self
+            .check_autorisation(
+                header,
+                PolkitAction::ManageUnits,
+                &map("clean_unit", unit_name),
+            )
+            .await?;
 
         let proxy = get_proxy().await?;
-        proxy
+        let result = proxy
             .clean_unit(unit_name, &what)
             .await
-            .inspect_err(|e| warn!("Error while calling clean_unit on sysdbus proxy: {:?}", e))
+            .inspect_err(|e| warn!("Error while calling clean_unit on sysdbus proxy: {:?}", e));
+        audit.record(result)
     }
 
     async fn freeze_unit(
@@ -196,13 +304,20 @@ impl SysDManagerProxy {
         unit_name: &str,
     ) -> zbus::fdo::Result<()> {
         info!("freeze_unit {}", unit_name);
-        self.check_autorisation(header).await?;
+        let audit = self
+            .check_autorisation(
+                header,
+                PolkitAction::ManageUnits,
+                &map("freeze_unit", unit_name),
+            )
+            .await?;
 
         let proxy = get_proxy().await?;
-        proxy
+        let result = proxy
             .freeze_unit(unit_name)
             .await
-            .inspect_err(|e| warn!("Error while calling freeze_unit on sysdbus proxy: {:?}", e))
+            .inspect_err(|e| warn!("Error while calling freeze_unit on sysdbus proxy: {:?}", e));
+        audit.record(result)
     }
 
     async fn thaw_unit(
@@ -211,13 +326,20 @@ impl SysDManagerProxy {
         unit_name: &str,
     ) -> zbus::fdo::Result<()> {
         info!("thaw_unit {}", unit_name);
-        self.check_autorisation(header).await?;
+        let audit = self
+            .check_autorisation(
+                header,
+                PolkitAction::ManageUnits,
+                &map("thaw_unit", unit_name),
+            )
+            .await?;
 
         let proxy = get_proxy().await?;
-        proxy
+        let result = proxy
             .thaw_unit(unit_name)
             .await
-            .inspect_err(|e| warn!("Error while calling thaw_unit on sysdbus proxy: {:?}", e))
+            .inspect_err(|e| warn!("Error while calling thaw_unit on sysdbus proxy: {:?}", e));
+        audit.record(result)
     }
 
     async fn revert_unit_files(
@@ -230,9 +352,16 @@ impl SysDManagerProxy {
         let proxy: &sysdcom::SysDManagerComLinkProxy<'_> = get_proxy().await?;
 
         debug!("Proxy {:?}", proxy);
-        self.check_autorisation(header).await?;
+        let units = file_names.join(" ");
+        let audit = self
+            .check_autorisation(
+                header,
+                PolkitAction::ManageUnitFiles,
+                &map("revert_unit_files", &units),
+            )
+            .await?;
         debug!("Polkit autorized");
-        match proxy.revert_unit_files(&file_names).await {
+        let result = match proxy.revert_unit_files(&file_names).await {
             Ok(vec) => {
                 info!("revert_unit_files {:?} --> {:?}", file_names, vec);
                 Ok(vec)
@@ -244,18 +373,22 @@ impl SysDManagerProxy {
                 );
                 Err(err)
             }
-        }
+        };
+        audit.record(result)
     }
 
     async fn reload(&self, #[zbus(header)] header: Header<'_>) -> zbus::fdo::Result<()> {
         info!("Reload");
         let proxy: &sysdcom::SysDManagerComLinkProxy<'_> = get_proxy().await?;
-        self.check_autorisation(header).await?;
+        let audit = self
+            .check_autorisation(header, PolkitAction::ReloadDaemon, &map("reload", ""))
+            .await?;
         debug!("Polkit autorized");
-        proxy
+        let result = proxy
             .reload()
             .await
-            .inspect_err(|e| warn!("Error while calling reload on sysdbus proxy: {:?}", e))
+            .inspect_err(|e| warn!("Error while calling reload on sysdbus proxy: {:?}", e));
+        audit.record(result)
     }
 
     async fn enable_unit_files_with_flags(
@@ -268,10 +401,17 @@ impl SysDManagerProxy {
             "enable_unit_files_with_flags {:?} flags {}",
             unit_files, flags
         );
-        self.check_autorisation(header).await?;
+        let units = unit_files.join(" ");
+        let audit = self
+            .check_autorisation(
+                header,
+                PolkitAction::ManageUnitFiles,
+                &map("enable_unit_files_with_flags", &units),
+            )
+            .await?;
 
         let proxy = get_proxy().await?;
-        proxy
+        let result = proxy
             .enable_unit_files_with_flags(&unit_files, flags)
             .await
             .inspect_err(|e| {
@@ -279,7 +419,8 @@ impl SysDManagerProxy {
                     "Error while calling disable_unit_files_with_flags on sysdbus proxy: {:?}",
                     e
                 )
-            })
+            });
+        audit.record(result)
     }
 
     async fn disable_unit_files_with_flags(
@@ -292,10 +433,17 @@ impl SysDManagerProxy {
             "disable_unit_files_with_flags {:?} flags {}",
             unit_files, flags
         );
-        self.check_autorisation(header).await?;
+        let units = unit_files.join(" ");
+        let audit = self
+            .check_autorisation(
+                header,
+                PolkitAction::ManageUnitFiles,
+                &map("disable_unit_files_with_flags", &units),
+            )
+            .await?;
 
         let proxy = get_proxy().await?;
-        proxy
+        let result = proxy
             .disable_unit_files_with_flags_and_install_info(&unit_files, flags)
             .await
             .inspect_err(|e| {
@@ -303,7 +451,356 @@ impl SysDManagerProxy {
                     "Error while calling disable_unit_files_with_flags on sysdbus proxy: {:?}",
                     e
                 )
+            });
+        audit.record(result)
+    }
+
+    async fn set_default_target(
+        &self,
+        #[zbus(header)] header: Header<'_>,
+        name: &str,
+        force: bool,
+    ) -> zbus::fdo::Result<Vec<DisEnAbleUnitFiles>> {
+        info!("set_default_target {:?} force {}", name, force);
+        let audit = self
+            .check_autorisation(
+                header,
+                PolkitAction::ManageUnitFiles,
+                &map("set_default_target", name),
+            )
+            .await?;
+
+        let proxy = get_proxy().await?;
+        let result = proxy
+            .set_default_target(name, force)
+            .await
+            .inspect_err(|e| {
+                warn!(
+                    "Error while calling set_default_target on sysdbus proxy: {:?}",
+                    e
+                )
+            });
+        audit.record(result)
+    }
+
+    async fn set_environment(
+        &self,
+        #[zbus(header)] header: Header<'_>,
+        assignments: Vec<&str>,
+    ) -> zbus::fdo::Result<()> {
+        let names = assignments
+            .iter()
+            .map(|assignment| {
+                assignment
+                    .split_once('=')
+                    .map_or(*assignment, |(name, _)| name)
             })
+            .collect::<Vec<_>>()
+            .join(" ");
+        info!("set_environment {}", names);
+        let audit = self
+            .check_autorisation(
+                header,
+                PolkitAction::ManageEnvironment,
+                &map_variables("set_environment", &names),
+            )
+            .await?;
+
+        let proxy = get_proxy().await?;
+        let result = proxy.set_environment(&assignments).await.inspect_err(|e| {
+            warn!(
+                "Error while calling set_environment on sysdbus proxy: {:?}",
+                e
+            )
+        });
+        audit.record(result)
+    }
+
+    async fn unset_environment(
+        &self,
+        #[zbus(header)] header: Header<'_>,
+        names: Vec<&str>,
+    ) -> zbus::fdo::Result<()> {
+        info!("unset_environment {:?}", names);
+        let audit = self
+            .check_autorisation(
+                header,
+                PolkitAction::ManageEnvironment,
+                &map_variables("unset_environment", &names.join(" ")),
+            )
+            .await?;
+
+        let proxy = get_proxy().await?;
+        let result = proxy.unset_environment(&names).await.inspect_err(|e| {
+            warn!(
+                "Error while calling unset_environment on sysdbus proxy: {:?}",
+                e
+            )
+        });
+        audit.record(result)
+    }
+
+    async fn user_list_units(
+        &self,
+        #[zbus(header)] header: Header<'_>,
+        uid: u32,
+    ) -> zbus::fdo::Result<Vec<ListedUnit>> {
+        info!("user_list_units {uid}");
+        let uid_str = uid.to_string();
+        let audit = self
+            .check_autorisation(
+                header,
+                PolkitAction::ManageUserUnits,
+                &map_user("user_list_units", "", &uid_str),
+            )
+            .await?;
+
+        let result = match user_manager(uid).await {
+            Ok(manager) => manager.list_units().await,
+            Err(err) => Err(err),
+        };
+        audit.record(result)
+    }
+
+    async fn user_list_unit_files(
+        &self,
+        #[zbus(header)] header: Header<'_>,
+        uid: u32,
+    ) -> zbus::fdo::Result<Vec<(String, String)>> {
+        info!("user_list_unit_files {uid}");
+        let uid_str = uid.to_string();
+        let audit = self
+            .check_autorisation(
+                header,
+                PolkitAction::ManageUserUnits,
+                &map_user("user_list_unit_files", "", &uid_str),
+            )
+            .await?;
+
+        let result = match user_manager(uid).await {
+            Ok(manager) => manager.list_unit_files().await,
+            Err(err) => Err(err),
+        };
+        audit.record(result)
+    }
+
+    async fn user_unit_properties(
+        &self,
+        #[zbus(header)] header: Header<'_>,
+        uid: u32,
+        unit_name: &str,
+        interface: &str,
+    ) -> zbus::fdo::Result<HashMap<String, OwnedValue>> {
+        info!("user_unit_properties {uid} {unit_name} {interface}");
+        let uid_str = uid.to_string();
+        let audit = self
+            .check_autorisation(
+                header,
+                PolkitAction::ManageUserUnits,
+                &map_user("user_unit_properties", unit_name, &uid_str),
+            )
+            .await?;
+
+        audit.record(unit_properties(uid, unit_name, interface).await)
+    }
+
+    async fn user_start_unit(
+        &self,
+        #[zbus(header)] header: Header<'_>,
+        uid: u32,
+        unit_name: &str,
+        mode: &str,
+    ) -> zbus::fdo::Result<OwnedObjectPath> {
+        self.user_unit_job(header, UserJob::Start, uid, unit_name, mode)
+            .await
+    }
+
+    async fn user_stop_unit(
+        &self,
+        #[zbus(header)] header: Header<'_>,
+        uid: u32,
+        unit_name: &str,
+        mode: &str,
+    ) -> zbus::fdo::Result<OwnedObjectPath> {
+        self.user_unit_job(header, UserJob::Stop, uid, unit_name, mode)
+            .await
+    }
+
+    async fn user_restart_unit(
+        &self,
+        #[zbus(header)] header: Header<'_>,
+        uid: u32,
+        unit_name: &str,
+        mode: &str,
+    ) -> zbus::fdo::Result<OwnedObjectPath> {
+        self.user_unit_job(header, UserJob::Restart, uid, unit_name, mode)
+            .await
+    }
+
+    async fn user_reload_unit(
+        &self,
+        #[zbus(header)] header: Header<'_>,
+        uid: u32,
+        unit_name: &str,
+        mode: &str,
+    ) -> zbus::fdo::Result<OwnedObjectPath> {
+        self.user_unit_job(header, UserJob::Reload, uid, unit_name, mode)
+            .await
+    }
+
+    async fn user_enable_unit_files_with_flags(
+        &self,
+        #[zbus(header)] header: Header<'_>,
+        uid: u32,
+        unit_files: Vec<&str>,
+        flags: u64,
+    ) -> zbus::fdo::Result<DisEnAbleUnitFilesResponse> {
+        info!("user_enable_unit_files_with_flags {uid} {unit_files:?} flags {flags}");
+        let uid_str = uid.to_string();
+        let units = unit_files.join(" ");
+        let audit = self
+            .check_autorisation(
+                header,
+                PolkitAction::ManageUserUnits,
+                &map_user("user_enable_unit_files_with_flags", &units, &uid_str),
+            )
+            .await?;
+
+        let result = match user_manager(uid).await {
+            Ok(manager) => {
+                manager
+                    .enable_unit_files_with_flags(&unit_files, flags)
+                    .await
+            }
+            Err(err) => Err(err),
+        };
+        audit.record(result)
+    }
+
+    async fn user_disable_unit_files_with_flags(
+        &self,
+        #[zbus(header)] header: Header<'_>,
+        uid: u32,
+        unit_files: Vec<&str>,
+        flags: u64,
+    ) -> zbus::fdo::Result<DisEnAbleUnitFilesResponse> {
+        info!("user_disable_unit_files_with_flags {uid} {unit_files:?} flags {flags}");
+        let uid_str = uid.to_string();
+        let units = unit_files.join(" ");
+        let audit = self
+            .check_autorisation(
+                header,
+                PolkitAction::ManageUserUnits,
+                &map_user("user_disable_unit_files_with_flags", &units, &uid_str),
+            )
+            .await?;
+
+        let result = match user_manager(uid).await {
+            Ok(manager) => {
+                manager
+                    .disable_unit_files_with_flags_and_install_info(&unit_files, flags)
+                    .await
+            }
+            Err(err) => Err(err),
+        };
+        audit.record(result)
+    }
+
+    async fn set_user_linger(
+        &self,
+        #[zbus(header)] header: Header<'_>,
+        uid: u32,
+        enable: bool,
+    ) -> zbus::fdo::Result<()> {
+        info!("set_user_linger {uid} {enable}");
+        let uid_str = uid.to_string();
+        let operation = if enable {
+            "enable_linger"
+        } else {
+            "disable_linger"
+        };
+        let audit = self
+            .check_autorisation(
+                header,
+                PolkitAction::UserLinger,
+                &map_user(operation, "", &uid_str),
+            )
+            .await?;
+
+        audit.record(logind::set_user_linger(uid, enable).await)
+    }
+
+    async fn user_reload(
+        &self,
+        #[zbus(header)] header: Header<'_>,
+        uid: u32,
+    ) -> zbus::fdo::Result<()> {
+        info!("user_reload {uid}");
+        let uid_str = uid.to_string();
+        let audit = self
+            .check_autorisation(
+                header,
+                PolkitAction::ManageUserUnits,
+                &map_user("user_reload", "", &uid_str),
+            )
+            .await?;
+
+        let result = match user_manager(uid).await {
+            Ok(manager) => manager.reload().await,
+            Err(err) => Err(err),
+        };
+        audit.record(result)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum UserJob {
+    Start,
+    Stop,
+    Restart,
+    Reload,
+}
+
+impl UserJob {
+    fn operation(&self) -> &'static str {
+        match self {
+            UserJob::Start => "user_start_unit",
+            UserJob::Stop => "user_stop_unit",
+            UserJob::Restart => "user_restart_unit",
+            UserJob::Reload => "user_reload_unit",
+        }
+    }
+}
+
+impl SysDManagerProxy {
+    async fn user_unit_job(
+        &self,
+        header: Header<'_>,
+        job: UserJob,
+        uid: u32,
+        unit_name: &str,
+        mode: &str,
+    ) -> zbus::fdo::Result<OwnedObjectPath> {
+        info!("{} {uid} {unit_name} {mode:?}", job.operation());
+        let uid_str = uid.to_string();
+        let audit = self
+            .check_autorisation(
+                header,
+                PolkitAction::ManageUserUnits,
+                &map_user(job.operation(), unit_name, &uid_str),
+            )
+            .await?;
+
+        let result = match user_manager(uid).await {
+            Ok(manager) => match job {
+                UserJob::Start => manager.start_unit(unit_name, mode).await,
+                UserJob::Stop => manager.stop_unit(unit_name, mode).await,
+                UserJob::Restart => manager.restart_unit(unit_name, mode).await,
+                UserJob::Reload => manager.reload_unit(unit_name, mode).await,
+            },
+            Err(err) => Err(err),
+        };
+        audit.record(result)
     }
 }
 
@@ -0,0 +1,97 @@
+use tokio::process::Command;
+use tracing::{info, warn};
+
+const JOURNALCTL: &str = "journalctl";
+
+/// Ask journald to archive the active journal files and to start new ones
+pub async fn rotate() -> zbus::fdo::Result<()> {
+    journalctl(&["--rotate".to_owned()]).await.map(|_| ())
+}
+
+/// Remove the oldest archived journal files until under every given threshold, `0` means not applied.
+///
+/// Returns the `journalctl` report
+pub async fn vacuum(
+    rotate: bool,
+    max_size: u64,
+    max_age: u64,
+    max_files: u64,
+) -> zbus::fdo::Result<String> {
+    let mut args = vacuum_args(max_size, max_age, max_files);
+
+    if args.is_empty() {
+        return Err(zbus::fdo::Error::InvalidArgs(
+            "No vacuum threshold".to_owned(),
+        ));
+    }
+
+    if rotate {
+        args.insert(0, "--rotate".to_owned());
+    }
+
+    journalctl(&args).await
+}
+
+fn vacuum_args(max_size: u64, max_age: u64, max_files: u64) -> Vec<String> {
+    let mut args = Vec::with_capacity(3);
+
+    if max_size != 0 {
+        args.push(format!("--vacuum-size={max_size}"));
+    }
+
+    if max_age != 0 {
+        args.push(format!("--vacuum-time={max_age}s"));
+    }
+
+    if max_files != 0 {
+        args.push(format!("--vacuum-files={max_files}"));
+    }
+
+    args
+}
+
+async fn journalctl(args: &[String]) -> zbus::fdo::Result<String> {
+    info!("{JOURNALCTL} {args:?}");
+
+    let output = Command::new(JOURNALCTL)
+        .args(args)
+        .output()
+        .await
+        .map_err(|err| {
+            warn!("{JOURNALCTL} spawn {err:?}");
+            zbus::fdo::Error::SpawnFailed(format!("{JOURNALCTL}: {err}"))
+        })?;
+
+    // journalctl reports on stderr
+    let report = [
+        String::from_utf8_lossy(&output.stderr),
+        String::from_utf8_lossy(&output.stdout),
+    ]
+    .concat();
+
+    if output.status.success() {
+        Ok(report)
+    } else {
+        warn!("{JOURNALCTL} {:?} {report}", output.status);
+        Err(zbus::fdo::Error::SpawnChildExited(report))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_vacuum_args() {
+        assert!(vacuum_args(0, 0, 0).is_empty());
+        assert_eq!(
+            vacuum_args(500_000_000, 86_400, 5),
+            vec![
+                "--vacuum-size=500000000",
+                "--vacuum-time=86400s",
+                "--vacuum-files=5"
+            ]
+        );
+        assert_eq!(vacuum_args(0, 0, 2), vec!["--vacuum-files=2"]);
+    }
+}
@@ -1,6 +1,11 @@
+mod audit;
 mod file;
 mod interface;
+mod journal;
+mod logind;
 mod sysdcom;
+mod user_manager;
+mod write_policy;
 use base::{
     RunMode,
     consts::{DBUS_NAME, DBUS_NAME_DEV, DBUS_PATH, MAX_HEART_BEAT_ELAPSE, MIN_HEART_BEAT_ELAPSE},
@@ -10,7 +15,10 @@ use tokio::{
     time::{self, Instant, sleep},
 };
 pub mod install;
-use crate::interface::SysDManagerProxySignals;
+use crate::{
+    audit::{Audit, Caller},
+    interface::SysDManagerProxySignals,
+};
 use std::{
     borrow::Cow,
     collections::HashMap,
@@ -49,9 +57,101 @@ pub fn conn() -> &'static Connection {
     CONNECTION.get().expect("REASON")
 }
 
-pub fn map() -> &'static HashMap<&'static str, &'static str> {
-    static MAP: OnceLock<HashMap<&str, &str>> = OnceLock::new();
-    MAP.get_or_init(HashMap::new)
+/// The polkit actions declared in the policy file, one per kind of operation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PolkitAction {
+    Proxy,
+    ManageUnits,
+    ManageUnitFiles,
+    WriteFiles,
+    ReloadDaemon,
+    ManageJournal,
+    ManageUserUnits,
+    UserLinger,
+    ManageEnvironment,
+}
+
+impl PolkitAction {
+    pub const ALL: [PolkitAction; 9] = [
+        PolkitAction::Proxy,
+        PolkitAction::ManageUnits,
+        PolkitAction::ManageUnitFiles,
+        PolkitAction::WriteFiles,
+        PolkitAction::ReloadDaemon,
+        PolkitAction::ManageJournal,
+        PolkitAction::ManageUserUnits,
+        PolkitAction::UserLinger,
+        PolkitAction::ManageEnvironment,
+    ];
+
+    pub fn id(&self) -> &'static str {
+        match self {
+            PolkitAction::Proxy => "io.github.plrigaux.SysDManager",
+            PolkitAction::ManageUnits => "io.github.plrigaux.SysDManager.manage-units",
+            PolkitAction::ManageUnitFiles => "io.github.plrigaux.SysDManager.manage-unit-files",
+            PolkitAction::WriteFiles => "io.github.plrigaux.SysDManager.write-files",
+            PolkitAction::ReloadDaemon => "io.github.plrigaux.SysDManager.reload-daemon",
+            PolkitAction::ManageJournal => "io.github.plrigaux.SysDManager.manage-journal",
+            PolkitAction::ManageUserUnits => "io.github.plrigaux.SysDManager.manage-user-units",
+            PolkitAction::UserLinger => "io.github.plrigaux.SysDManager.user-linger",
+            PolkitAction::ManageEnvironment => "io.github.plrigaux.SysDManager.manage-environment",
+        }
+    }
+}
+
+/// The polkit details of a call, readable by the rules with `action.lookup("operation")`
+/// and `action.lookup("unit")`
+pub fn map<'a>(operation: &'a str, unit: &'a str) -> HashMap<&'static str, &'a str> {
+    let mut details = HashMap::with_capacity(2);
+    details.insert("operation", operation);
+
+    if !unit.is_empty() {
+        details.insert("unit", unit);
+    }
+
+    details
+}
+
+/// The polkit details of a call on the user manager of `uid`, also readable with
+/// `action.lookup("uid")`
+pub fn map_user<'a>(
+    operation: &'a str,
+    unit: &'a str,
+    uid: &'a str,
+) -> HashMap<&'static str, &'a str> {
+    let mut details = map(operation, unit);
+    details.insert("uid", uid);
+    details
+}
+
+/// The polkit details of a file write, the path readable with `action.lookup("path")` and
+/// the unit, the file name or the drop-in directory name without `.d`, with
+/// `action.lookup("unit")`
+pub fn map_file<'a>(operation: &'a str, file_path: &'a str) -> HashMap<&'static str, &'a str> {
+    let mut details = map(operation, file_unit_name(file_path));
+    details.insert("path", file_path);
+    details
+}
+
+fn file_unit_name(file_path: &str) -> &str {
+    let path = std::path::Path::new(file_path);
+    let drop_in_unit = path
+        .parent()
+        .and_then(|dir| dir.file_name())
+        .and_then(|dir| dir.to_str())
+        .and_then(|dir| dir.strip_suffix(".d"));
+
+    drop_in_unit
+        .or_else(|| path.file_name().and_then(|name| name.to_str()))
+        .unwrap_or_default()
+}
+
+/// The polkit details of a change of the manager environment, the space separated
+/// variable names readable with `action.lookup("variables")`
+pub fn map_variables<'a>(operation: &'a str, variables: &'a str) -> HashMap<&'static str, &'a str> {
+    let mut details = map(operation, "");
+    details.insert("variables", variables);
+    details
 }
 
 pub struct SysDManagerProxy {
@@ -77,7 +177,30 @@ impl SysDManagerProxy {
         Ok(proxy)
     }
 
-    async fn check_autorisation(&self, header: Header<'_>) -> Result<(), zbus::fdo::Error> {
+    /// Check the caller authorization, the denied calls are audited
+    async fn check_autorisation(
+        &self,
+        header: Header<'_>,
+        action: PolkitAction,
+        details: &HashMap<&str, &str>,
+    ) -> Result<Audit, zbus::fdo::Error> {
+        let audit = Audit::new(Caller::from_header(&header).await, action, details);
+
+        match self.authorize(header, action, details).await {
+            Ok(()) => Ok(audit),
+            Err(err) => {
+                audit.denied(&err);
+                Err(err)
+            }
+        }
+    }
+
+    async fn authorize(
+        &self,
+        header: Header<'_>,
+        action: PolkitAction,
+        details: &HashMap<&str, &str>,
+    ) -> Result<(), zbus::fdo::Error> {
         let autority = AUTHORITY.get().expect("REASON");
 
         let subject = Subject::new_for_message_header(&header).map_err(|err| {
@@ -87,8 +210,8 @@ impl SysDManagerProxy {
         let authorization_result = autority
             .check_authorization(
                 &subject,
-                "io.github.plrigaux.SysDManager",
-                map(),
+                action.id(),
+                details,
                 CheckAuthorizationFlags::AllowUserInteraction.into(),
                 "",
             )
@@ -96,7 +219,7 @@ impl SysDManagerProxy {
 
         match authorization_result {
             Ok(a) => {
-                debug!("is_authorized {}", a.is_authorized);
+                debug!("{} is_authorized {}", action.id(), a.is_authorized);
                 if a.is_authorized {
                     Ok(())
                 } else if a.is_challenge {
@@ -212,3 +335,51 @@ fn get_env<'a>(key: &str, default: &'a str) -> Cow<'a, str> {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_polkit_actions_declared() {
+        let policy = include_str!("../data/io.github.plrigaux.SysDManager.policy");
+
+        for action in PolkitAction::ALL {
+            assert!(
+                policy.contains(&format!("<action id=\"{}\">", action.id())),
+                "{action:?} not in the policy file"
+            );
+        }
+    }
+
+    #[test]
+    fn test_map() {
+        let details = map("restart_unit", "nginx.service");
+        assert_eq!(details.get("operation"), Some(&"restart_unit"));
+        assert_eq!(details.get("unit"), Some(&"nginx.service"));
+
+        let details = map("reload", "");
+        assert_eq!(details.len(), 1);
+
+        let details = map_user("user_restart_unit", "syncthing.service", "1000");
+        assert_eq!(details.get("uid"), Some(&"1000"));
+        assert_eq!(details.len(), 3);
+
+        let details = map_file("save_file", "/etc/systemd/system/nginx.service");
+        assert_eq!(
+            details.get("path"),
+            Some(&"/etc/systemd/system/nginx.service")
+        );
+        assert_eq!(details.get("unit"), Some(&"nginx.service"));
+
+        let details = map_file(
+            "create_drop_in",
+            "/etc/systemd/system/nginx.service.d/override.conf",
+        );
+        assert_eq!(details.get("unit"), Some(&"nginx.service"));
+
+        let details = map_variables("set_environment", "HTTP_PROXY NO_PROXY");
+        assert_eq!(details.get("variables"), Some(&"HTTP_PROXY NO_PROXY"));
+        assert_eq!(details.get("unit"), None);
+    }
+}
@@ -0,0 +1,27 @@
+use tokio::sync::OnceCell;
+use zbus::{Connection, proxy};
+
+#[proxy(
+    interface = "org.freedesktop.login1.Manager",
+    default_service = "org.freedesktop.login1",
+    default_path = "/org/freedesktop/login1"
+)]
+pub trait Login1Manager {
+    fn set_user_linger(&self, uid: u32, enable: bool, interactive: bool) -> zbus::fdo::Result<()>;
+}
+
+static LOGIN1: OnceCell<Login1ManagerProxy> = OnceCell::const_new();
+
+async fn login1() -> Result<&'static Login1ManagerProxy<'static>, zbus::Error> {
+    LOGIN1
+        .get_or_try_init(async || -> Result<Login1ManagerProxy, zbus::Error> {
+            let connection = Connection::system().await?;
+            Login1ManagerProxy::new(&connection).await
+        })
+        .await
+}
+
+/// Not interactive, the authorization was already checked by the proxy
+pub(crate) async fn set_user_linger(uid: u32, enable: bool) -> zbus::fdo::Result<()> {
+    login1().await?.set_user_linger(uid, enable, false).await
+}
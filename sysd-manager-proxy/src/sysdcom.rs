@@ -32,4 +32,13 @@ pub trait SysDManagerComLink {
         files: &[&str],
         flags: u64,
     ) -> zbus::fdo::Result<DisEnAbleUnitFilesResponse>;
+
+    fn set_default_target(
+        &self,
+        name: &str,
+        force: bool,
+    ) -> zbus::fdo::Result<Vec<DisEnAbleUnitFiles>>;
+
+    fn set_environment(&self, assignments: &[&str]) -> zbus::fdo::Result<()>;
+    fn unset_environment(&self, names: &[&str]) -> zbus::fdo::Result<()>;
 }
@@ -0,0 +1,242 @@
+//! The `systemd --user` managers of the other users, reached on their own session bus.
+//!
+//! A user bus only accepts its owner, even root is refused. The proxy reaches it through a
+//! `systemd-stdio-bridge` spawned with the owner credentials by `setpriv`, the GUI asks it for
+//! the units of a user it can't reach by itself.
+
+use base::proxy::{DisEnAbleUnitFiles, DisEnAbleUnitFilesResponse};
+use std::{
+    collections::HashMap, ffi::OsString, io::ErrorKind, os::unix::fs::MetadataExt, path::PathBuf,
+};
+use tracing::{info, warn};
+use zbus::{
+    Connection,
+    address::{
+        Address,
+        transport::{Transport, Unixexec},
+    },
+    proxy,
+    zvariant::{OwnedObjectPath, OwnedValue},
+};
+
+const SETPRIV: &str = "setpriv";
+const STDIO_BRIDGE: &str = "systemd-stdio-bridge";
+
+/// `ListUnits` entry, `(ssssssouso)`
+pub type ListedUnit = (
+    String,
+    String,
+    String,
+    String,
+    String,
+    String,
+    OwnedObjectPath,
+    u32,
+    String,
+    OwnedObjectPath,
+);
+
+#[proxy(
+    interface = "org.freedesktop.systemd1.Manager",
+    default_service = "org.freedesktop.systemd1",
+    default_path = "/org/freedesktop/systemd1"
+)]
+pub trait UserManager {
+    fn list_units(&self) -> zbus::fdo::Result<Vec<ListedUnit>>;
+    fn list_unit_files(&self) -> zbus::fdo::Result<Vec<(String, String)>>;
+    fn load_unit(&self, unit_name: &str) -> zbus::fdo::Result<OwnedObjectPath>;
+    fn start_unit(&self, unit_name: &str, mode: &str) -> zbus::fdo::Result<OwnedObjectPath>;
+    fn stop_unit(&self, unit_name: &str, mode: &str) -> zbus::fdo::Result<OwnedObjectPath>;
+    fn restart_unit(&self, unit_name: &str, mode: &str) -> zbus::fdo::Result<OwnedObjectPath>;
+    fn reload_unit(&self, unit_name: &str, mode: &str) -> zbus::fdo::Result<OwnedObjectPath>;
+    fn reload(&self) -> zbus::fdo::Result<()>;
+
+    fn enable_unit_files_with_flags(
+        &self,
+        files: &[&str],
+        flags: u64,
+    ) -> zbus::fdo::Result<DisEnAbleUnitFilesResponse>;
+
+    fn disable_unit_files_with_flags_and_install_info(
+        &self,
+        files: &[&str],
+        flags: u64,
+    ) -> zbus::fdo::Result<DisEnAbleUnitFilesResponse>;
+
+    fn revert_unit_files(&self, files: &[&str]) -> zbus::fdo::Result<Vec<DisEnAbleUnitFiles>>;
+}
+
+#[proxy(
+    interface = "org.freedesktop.DBus.Properties",
+    default_service = "org.freedesktop.systemd1"
+)]
+pub trait UnitProperties {
+    fn get_all(&self, interface: &str) -> zbus::fdo::Result<HashMap<String, OwnedValue>>;
+}
+
+/// Directory holding the runtime files of the user, `/run/user/<uid>`
+fn user_runtime_dir(uid: u32) -> PathBuf {
+    PathBuf::from(format!("/run/user/{uid}"))
+}
+
+pub(crate) fn user_bus_address(uid: u32) -> String {
+    format!("unix:path={}/bus", user_runtime_dir(uid).display())
+}
+
+/// Manager of the user `uid`, its bus exists only while the user is logged in or lingering
+pub(crate) async fn user_manager(uid: u32) -> zbus::fdo::Result<UserManagerProxy<'static>> {
+    let connection = user_connection(uid).await?;
+    let proxy = UserManagerProxy::builder(&connection)
+        .cache_properties(zbus::proxy::CacheProperties::No)
+        .build()
+        .await?;
+    Ok(proxy)
+}
+
+async fn user_connection(uid: u32) -> zbus::fdo::Result<Connection> {
+    let bus_path = user_runtime_dir(uid).join("bus");
+    let gid = match std::fs::metadata(&bus_path) {
+        Ok(metadata) => metadata.gid(),
+        Err(err) if err.kind() == ErrorKind::NotFound => {
+            warn!("No bus for user {uid}");
+            return Err(zbus::fdo::Error::FileNotFound(format!(
+                "User {uid} has no running user manager, they need to be logged in or lingering"
+            )));
+        }
+        Err(err) => return Err(zbus::fdo::Error::IOError(err.to_string())),
+    };
+
+    // no cache, the bus goes away when the user logs out
+    let address = user_bus_address(uid);
+    info!("Connect to the user manager of {uid} at {address}");
+    let connection = connect_as(uid, gid, &address).await?;
+    Ok(connection)
+}
+
+/// The bridge process to `bus_address` running as `uid` and `gid`, the group of the bus socket
+fn user_bridge(uid: u32, gid: u32, bus_address: &str) -> Unixexec {
+    let args = [
+        format!("--reuid={uid}"),
+        format!("--regid={gid}"),
+        "--clear-groups".to_owned(),
+        "--".to_owned(),
+        STDIO_BRIDGE.to_owned(),
+        format!("--bus-path={bus_address}"),
+    ];
+
+    Unixexec::new(
+        SETPRIV.into(),
+        None,
+        args.into_iter().map(OsString::from).collect(),
+    )
+}
+
+/// Connect to the bus at `bus_address` authenticated as `uid`
+pub(crate) async fn connect_as(uid: u32, gid: u32, bus_address: &str) -> zbus::Result<Connection> {
+    let address = Address::from(Transport::Unixexec(user_bridge(uid, gid, bus_address)));
+    zbus::connection::Builder::address(address)?.build().await
+}
+
+/// All the properties of the unit on `interface`, loading the unit if needed
+pub(crate) async fn unit_properties(
+    uid: u32,
+    unit_name: &str,
+    interface: &str,
+) -> zbus::fdo::Result<HashMap<String, OwnedValue>> {
+    let manager = user_manager(uid).await?;
+    let path = manager.load_unit(unit_name).await?;
+
+    let properties = UnitPropertiesProxy::builder(manager.inner().connection())
+        .path(path)?
+        .cache_properties(zbus::proxy::CacheProperties::No)
+        .build()
+        .await?;
+
+    properties.get_all(interface).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_base::{
+        TEST_SERVICE,
+        dbus_daemon::PrivateBus,
+        fake_systemd::{FakeSystemd, SYSTEMD_NAME},
+        harness::default_units,
+        init_logs,
+    };
+
+    #[test]
+    fn test_user_bus_address() {
+        assert_eq!(user_bus_address(1000), "unix:path=/run/user/1000/bus");
+    }
+
+    #[test]
+    fn test_user_bridge() {
+        let bridge = user_bridge(1000, 1001, "unix:path=/run/user/1000/bus");
+        assert_eq!(bridge.path(), &PathBuf::from(SETPRIV));
+        assert_eq!(
+            bridge.args(),
+            [
+                "--reuid=1000",
+                "--regid=1001",
+                "--clear-groups",
+                "--",
+                STDIO_BRIDGE,
+                "--bus-path=unix:path=/run/user/1000/bus",
+            ]
+        );
+    }
+
+    /// A bus owned by `nobody` serving the fake manager, reached by root through the bridge
+    #[tokio::test]
+    async fn test_bus_of_other_owner() -> zbus::Result<()> {
+        const NOBODY: u32 = 65534;
+        init_logs();
+
+        if unsafe { libc::geteuid() } != 0 {
+            warn!("Not root, can't start a bus owned by another user");
+            return Ok(());
+        }
+
+        let bus = match PrivateBus::start_as(NOBODY, NOBODY) {
+            Ok(bus) => bus,
+            Err(err) => {
+                warn!("Can't start the private bus {err:?}");
+                return Ok(());
+            }
+        };
+
+        let direct = zbus::connection::Builder::address(bus.address())?
+            .build()
+            .await;
+        assert!(direct.is_err(), "root should be refused by the user bus");
+
+        let service = connect_as(NOBODY, NOBODY, bus.address()).await?;
+        let systemd = FakeSystemd::with_units(default_units());
+        systemd.serve(&service).await?;
+        service.request_name(SYSTEMD_NAME).await?;
+
+        let connection = connect_as(NOBODY, NOBODY, bus.address()).await?;
+        let unique_name = connection.unique_name().expect("unique name").clone();
+        let dbus = zbus::fdo::DBusProxy::new(&connection).await?;
+        assert_eq!(
+            dbus.get_connection_unix_user(unique_name.into()).await?,
+            NOBODY
+        );
+
+        let manager = UserManagerProxy::builder(&connection)
+            .cache_properties(zbus::proxy::CacheProperties::No)
+            .build()
+            .await?;
+        let units = manager.list_units().await?;
+        assert!(units.iter().any(|unit| unit.0 == TEST_SERVICE));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_user_without_bus() {
+        let result = user_manager(u32::MAX - 1).await;
+        assert!(matches!(result, Err(zbus::fdo::Error::FileNotFound(_))));
+    }
+}
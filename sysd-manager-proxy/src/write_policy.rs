@@ -0,0 +1,366 @@
+use base::consts::PROXY_WRITE_POLICY_PATH;
+use serde::Deserialize;
+use std::{
+    os::unix::fs::PermissionsExt,
+    path::{Component, Path, PathBuf},
+    sync::LazyLock,
+};
+use tracing::{info, warn};
+use zbus::DBusError;
+
+/// Drop-in files extension
+const DROP_IN_EXTENSION: &str = "conf";
+/// Suffix of the drop-in directories
+const DROP_IN_DIR_SUFFIX: &str = ".d";
+
+static POLICY: LazyLock<WritePolicy> = LazyLock::new(load_write_policy);
+
+/// Writes refused by the policy, the names are the `PROXY_ERROR_*` base constants
+#[derive(Debug, DBusError)]
+#[zbus(prefix = "io.github.plrigaux.SysDManager.Error")]
+pub enum WriteError {
+    #[zbus(error)]
+    ZBus(zbus::Error),
+    PathNotAllowed(String),
+    SymlinkRejected(String),
+    ExtensionRejected(String),
+}
+
+impl From<zbus::fdo::Error> for WriteError {
+    fn from(error: zbus::fdo::Error) -> Self {
+        WriteError::ZBus(zbus::Error::FDO(Box::new(error)))
+    }
+}
+
+/// Which files the proxy can write, read from `PROXY_WRITE_POLICY_PATH`
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct WritePolicy {
+    /// Unit directories, their drop-in directories are allowed too
+    pub allowed_dirs: Vec<PathBuf>,
+    /// Unit file extensions, the drop-ins are always `.conf`
+    pub extensions: Vec<String>,
+    /// Mode of the created files
+    pub file_mode: u32,
+    /// Mode of the created drop-in directories
+    pub dir_mode: u32,
+    pub owner_uid: u32,
+    pub owner_gid: u32,
+}
+
+impl Default for WritePolicy {
+    fn default() -> Self {
+        let allowed_dirs = [
+            "/etc/systemd/system",
+            "/etc/systemd/system.control",
+            "/run/systemd/system",
+            "/run/systemd/system.control",
+            "/usr/lib/systemd/system",
+            "/lib/systemd/system",
+            "/usr/local/lib/systemd/system",
+            "/etc/systemd/user",
+            "/usr/lib/systemd/user",
+        ];
+
+        let extensions = [
+            "service",
+            "socket",
+            "device",
+            "mount",
+            "automount",
+            "swap",
+            "target",
+            "path",
+            "timer",
+            "slice",
+            "scope",
+        ];
+
+        WritePolicy {
+            allowed_dirs: allowed_dirs.iter().map(PathBuf::from).collect(),
+            extensions: extensions.iter().map(|ext| ext.to_string()).collect(),
+            file_mode: 0o644,
+            dir_mode: 0o755,
+            owner_uid: 0,
+            owner_gid: 0,
+        }
+    }
+}
+
+pub fn write_policy() -> &'static WritePolicy {
+    &POLICY
+}
+
+fn load_write_policy() -> WritePolicy {
+    let content = match std::fs::read_to_string(PROXY_WRITE_POLICY_PATH) {
+        Ok(content) => content,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+            info!("No write policy file {PROXY_WRITE_POLICY_PATH}, default policy");
+            return WritePolicy::default();
+        }
+        Err(err) => {
+            warn!("Can't read the write policy {PROXY_WRITE_POLICY_PATH} {err:?}, default policy");
+            return WritePolicy::default();
+        }
+    };
+
+    match toml::from_str::<WritePolicy>(&content) {
+        Ok(policy) => {
+            info!("Write policy {policy:?}");
+            policy
+        }
+        Err(err) => {
+            warn!("Can't parse the write policy {PROXY_WRITE_POLICY_PATH} {err}, default policy");
+            WritePolicy::default()
+        }
+    }
+}
+
+impl WritePolicy {
+    /// Accept the unit files of an allowed directory and the `.conf` files of their
+    /// drop-in directories, nothing through a symbolic link
+    pub fn check(&self, file_path: &Path) -> Result<(), WriteError> {
+        let normalized = file_path.is_absolute()
+            && file_path
+                .components()
+                .all(|component| matches!(component, Component::RootDir | Component::Normal(_)));
+
+        if !normalized {
+            return Err(WriteError::PathNotAllowed(format!(
+                "The file path {:?} is not absolute and normalized",
+                file_path
+            )));
+        }
+
+        let Some((dir, relative)) = self.allowed_dirs.iter().find_map(|dir| {
+            file_path
+                .strip_prefix(dir)
+                .ok()
+                .map(|relative| (dir, relative))
+        }) else {
+            return Err(WriteError::PathNotAllowed(format!(
+                "The file {:?} is not located in any of the allowed directories: {}",
+                file_path,
+                self.allowed_dirs_list()
+            )));
+        };
+
+        let Some(names) = relative
+            .components()
+            .map(|component| component.as_os_str().to_str())
+            .collect::<Option<Vec<&str>>>()
+        else {
+            return Err(WriteError::PathNotAllowed(format!(
+                "The file path {:?} is not valid UTF-8",
+                file_path
+            )));
+        };
+
+        match names.as_slice() {
+            [file_name] => {
+                if !self.is_unit_name(file_name) {
+                    return Err(WriteError::ExtensionRejected(format!(
+                        "The file {:?} is not a unit file, the accepted extensions are: {}",
+                        file_path,
+                        self.extensions.join(" ")
+                    )));
+                }
+            }
+            [drop_in_dir, file_name] => {
+                let unit_name = drop_in_dir
+                    .strip_suffix(DROP_IN_DIR_SUFFIX)
+                    .filter(|unit_name| {
+                        self.is_unit_name(unit_name)
+                            || self.extensions.iter().any(|e| e == unit_name)
+                    });
+
+                if unit_name.is_none() {
+                    return Err(WriteError::PathNotAllowed(format!(
+                        "The directory {drop_in_dir:?} of the file {:?} is not a drop-in directory",
+                        file_path
+                    )));
+                }
+
+                if extension(file_name) != Some(DROP_IN_EXTENSION) {
+                    return Err(WriteError::ExtensionRejected(format!(
+                        "The drop-in file {:?} has to have the .{DROP_IN_EXTENSION} extension",
+                        file_path
+                    )));
+                }
+            }
+            _ => {
+                return Err(WriteError::PathNotAllowed(format!(
+                    "The file {:?} is too deep in the directory {:?}",
+                    file_path, dir
+                )));
+            }
+        }
+
+        let mut path = dir.clone();
+        for name in names {
+            path.push(name);
+            if std::fs::symlink_metadata(&path).is_ok_and(|metadata| metadata.is_symlink()) {
+                return Err(WriteError::SymlinkRejected(format!(
+                    "The path {:?} is a symbolic link, the proxy does not write through it",
+                    path
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Set the policy mode and owner to a created file or directory
+    pub async fn apply_ownership(&self, path: &Path, is_dir: bool) -> std::io::Result<()> {
+        let mode = if is_dir {
+            self.dir_mode
+        } else {
+            self.file_mode
+        };
+
+        tokio::fs::set_permissions(path, std::fs::Permissions::from_mode(mode)).await?;
+        std::os::unix::fs::chown(path, Some(self.owner_uid), Some(self.owner_gid))
+    }
+
+    fn is_unit_name(&self, file_name: &str) -> bool {
+        extension(file_name).is_some_and(|ext| self.extensions.iter().any(|e| e == ext))
+    }
+
+    fn allowed_dirs_list(&self) -> String {
+        self.allowed_dirs
+            .iter()
+            .map(|dir| dir.display().to_string())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+fn extension(file_name: &str) -> Option<&str> {
+    file_name
+        .rsplit_once('.')
+        .filter(|(stem, _)| !stem.is_empty())
+        .map(|(_, ext)| ext)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use base::consts::{
+        PROXY_ERROR_EXTENSION_REJECTED, PROXY_ERROR_PATH_NOT_ALLOWED, PROXY_ERROR_SYMLINK_REJECTED,
+    };
+
+    fn check(policy: &WritePolicy, path: &str) -> Result<(), WriteError> {
+        policy.check(Path::new(path))
+    }
+
+    #[test]
+    fn test_error_names() {
+        let err = WriteError::PathNotAllowed(String::new());
+        assert_eq!(err.name().as_str(), PROXY_ERROR_PATH_NOT_ALLOWED);
+        let err = WriteError::SymlinkRejected(String::new());
+        assert_eq!(err.name().as_str(), PROXY_ERROR_SYMLINK_REJECTED);
+        let err = WriteError::ExtensionRejected(String::new());
+        assert_eq!(err.name().as_str(), PROXY_ERROR_EXTENSION_REJECTED);
+    }
+
+    #[test]
+    fn test_check() {
+        let policy = WritePolicy::default();
+
+        assert!(check(&policy, "/etc/systemd/system/nginx.service").is_ok());
+        assert!(check(&policy, "/etc/systemd/system/nginx.service.d/override.conf").is_ok());
+        assert!(check(&policy, "/etc/systemd/system/service.d/10-all.conf").is_ok());
+        assert!(
+            check(
+                &policy,
+                "/run/systemd/system.control/a.slice.d/50-CPUQuota.conf"
+            )
+            .is_ok()
+        );
+
+        assert!(matches!(
+            check(&policy, "/etc/systemd/system/../../shadow"),
+            Err(WriteError::PathNotAllowed(_))
+        ));
+        assert!(matches!(
+            check(&policy, "etc/systemd/system/nginx.service"),
+            Err(WriteError::PathNotAllowed(_))
+        ));
+        assert!(matches!(
+            check(&policy, "/etc/shadow"),
+            Err(WriteError::PathNotAllowed(_))
+        ));
+        assert!(matches!(
+            check(&policy, "/etc/systemd/system-generators/nginx.service"),
+            Err(WriteError::PathNotAllowed(_))
+        ));
+        assert!(matches!(
+            check(&policy, "/etc/systemd/system/nginx.service.wants/a.service"),
+            Err(WriteError::PathNotAllowed(_))
+        ));
+        assert!(matches!(
+            check(&policy, "/etc/systemd/system/a/b/c.service"),
+            Err(WriteError::PathNotAllowed(_))
+        ));
+        assert!(matches!(
+            check(&policy, "/etc/systemd/system/script.sh"),
+            Err(WriteError::ExtensionRejected(_))
+        ));
+        assert!(matches!(
+            check(&policy, "/etc/systemd/system/.service"),
+            Err(WriteError::ExtensionRejected(_))
+        ));
+        assert!(matches!(
+            check(
+                &policy,
+                "/etc/systemd/system/nginx.service.d/override.service"
+            ),
+            Err(WriteError::ExtensionRejected(_))
+        ));
+    }
+
+    #[test]
+    fn test_check_symlink() {
+        let dir = std::env::temp_dir().join(format!("sysd-write-policy-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::os::unix::fs::symlink("/tmp", dir.join("a.service.d")).unwrap();
+        std::os::unix::fs::symlink("/dev/null", dir.join("b.service")).unwrap();
+
+        let policy = WritePolicy {
+            allowed_dirs: vec![dir.clone()],
+            ..Default::default()
+        };
+
+        assert!(policy.check(&dir.join("c.service")).is_ok());
+        assert!(matches!(
+            policy.check(&dir.join("a.service.d/override.conf")),
+            Err(WriteError::SymlinkRejected(_))
+        ));
+        assert!(matches!(
+            policy.check(&dir.join("b.service")),
+            Err(WriteError::SymlinkRejected(_))
+        ));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_policy_toml() {
+        let policy: WritePolicy =
+            toml::from_str("allowed_dirs = [\"/etc/systemd/system\"]\nfile_mode = 0o600\n")
+                .unwrap();
+
+        assert_eq!(
+            policy.allowed_dirs,
+            vec![PathBuf::from("/etc/systemd/system")]
+        );
+        assert_eq!(policy.file_mode, 0o600);
+        assert_eq!(policy.dir_mode, 0o755);
+        assert!(policy.extensions.iter().any(|ext| ext == "timer"));
+
+        let sample: WritePolicy =
+            toml::from_str(include_str!("../data/proxy-write-policy.toml")).unwrap();
+        assert_eq!(sample, WritePolicy::default());
+    }
+}
@@ -1,8 +1,14 @@
-use base::{consts::DBUS_DESTINATION_DEV, enums::UnitDBusLevel, proxy::DisEnAbleUnitFiles};
+use base::{consts::DBUS_DESTINATION_DEV, proxy::DisEnAbleUnitFiles};
+use std::{os::unix::process::CommandExt, process::Command, sync::OnceLock, time::Duration};
+use tokio::sync::Mutex;
 use tracing::info;
 
-use test_base::init_logs;
-use zbus::proxy;
+use test_base::{
+    TestHarness,
+    harness::{TIMER_SERVICE, TIMER_SERVICE_DROP_IN},
+    init_logs,
+};
+use zbus::{names::BusName, proxy, zvariant::OwnedObjectPath};
 
 /* pub const DBUS_NAME: &str = "io.github.plrigaux.SysDManager";
 pub const DBUS_NAME_DEV: &str = concat!(DBUS_NAME, "Dev");
@@ -24,107 +30,219 @@ pub trait SysDProxyTester {
 
     fn even_ping(&self, val: u32) -> zbus::Result<u32>;
 
-    fn revert_unit_files(
-        &self,
-        bus: u8,
-        unit_names: &[&str],
-    ) -> zbus::Result<Vec<DisEnAbleUnitFiles>>;
+    fn start_unit(&self, unit_name: &str, mode: &str) -> zbus::Result<OwnedObjectPath>;
+
+    fn revert_unit_files(&self, file_names: &[&str]) -> zbus::Result<Vec<DisEnAbleUnitFiles>>;
+}
+
+/// The proxy binary serving the private test bus, shared by the tests of this file
+static PROXY: OnceLock<Result<(), String>> = OnceLock::new();
+
+/// The tests changing the fake polkit answer must not overlap the others
+static SERIAL: Mutex<()> = Mutex::const_new(());
+
+/// Spawn the proxy from a thread living as long as the test process, the proxy is
+/// killed when the process ends
+fn spawn_proxy(harness: &'static TestHarness) -> std::io::Result<()> {
+    let (sender, receiver) = std::sync::mpsc::channel();
+
+    std::thread::Builder::new()
+        .name("test-proxy".to_owned())
+        .spawn(move || {
+            let mut command = Command::new(env!("CARGO_BIN_EXE_sysd-manager-proxy"));
+            command
+                .args(["--dev", "--no-heart-beat", "serve"])
+                .envs(harness.bus_env());
+
+            unsafe {
+                command.pre_exec(|| {
+                    libc::prctl(libc::PR_SET_PDEATHSIG, libc::SIGTERM);
+                    Ok(())
+                });
+            }
+
+            match command.spawn() {
+                Ok(_child) => {
+                    let _ = sender.send(Ok(()));
+                    loop {
+                        std::thread::park();
+                    }
+                }
+                Err(err) => {
+                    let _ = sender.send(Err(err));
+                }
+            }
+        })?;
+
+    receiver.recv().map_err(std::io::Error::other)?
 }
 
-async fn system() -> zbus::Result<zbus::Connection> {
-    zbus::Connection::system().await
+async fn wait_proxy_name(connection: &zbus::Connection) -> zbus::Result<()> {
+    let dbus = zbus::fdo::DBusProxy::new(connection).await?;
+    let name = BusName::try_from(DBUS_DESTINATION_DEV)?;
+
+    for _ in 0..50 {
+        if dbus.name_has_owner(name.clone()).await? {
+            return Ok(());
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+
+    Err(zbus::Error::Failure(format!(
+        "{DBUS_DESTINATION_DEV} not on the test bus"
+    )))
 }
 
-async fn system_proxy<'a>() -> zbus::Result<SysDProxyTesterProxy<'a>> {
-    let conn = system().await?;
-    let proxy = SysDProxyTesterProxy::builder(&conn)
+/// The proxy on the private test bus, `None` only when the bus tests are skipped
+async fn test_bus_proxy()
+-> zbus::Result<Option<(&'static TestHarness, SysDProxyTesterProxy<'static>)>> {
+    init_logs();
+    let Some(harness) = test_base::harness() else {
+        return Ok(None);
+    };
+
+    PROXY
+        .get_or_init(|| spawn_proxy(harness).map_err(|err| err.to_string()))
+        .clone()
+        .map_err(zbus::Error::Failure)?;
+
+    let connection = harness.connect().await?;
+    wait_proxy_name(&connection).await?;
+
+    let proxy = SysDProxyTesterProxy::builder(&connection)
         .destination(DBUS_DESTINATION_DEV)?
         .build()
         .await?;
 
-    Ok(proxy)
+    Ok(Some((harness, proxy)))
 }
 
-/* async fn session() -> zbus::Result<zbus::Connection> {
-    zbus::Connection::session().await
-} */
-
-#[ignore = "need a connection to a service"]
 #[tokio::test]
 async fn test_sysd_proxy_tester() -> zbus::Result<()> {
-    init_logs();
-    let conn = system().await?;
-    let _proxy = SysDProxyTesterProxy::builder(&conn).build().await?;
+    let _serial = SERIAL.lock().await;
+    let Some((_harness, proxy)) = test_bus_proxy().await? else {
+        return Ok(());
+    };
+
+    assert_eq!(proxy.inner().destination().as_str(), DBUS_DESTINATION_DEV);
     Ok(())
 }
 
-#[ignore = "need a connection to a service"]
 #[tokio::test]
 async fn test_get_user_id() -> zbus::Result<()> {
-    init_logs();
-    let proxy = system_proxy().await?;
+    let _serial = SERIAL.lock().await;
+    let Some((_harness, proxy)) = test_bus_proxy().await? else {
+        return Ok(());
+    };
 
     let uid = proxy.my_user_id().await?;
 
     info!("User id from proxy: {}", uid);
+    // The proxy runs as the user of the tests
+    assert_eq!(uid, unsafe { libc::geteuid() });
     Ok(())
 }
 
-#[ignore = "need a connection to a service"]
 #[tokio::test]
 async fn test_even_ping() -> zbus::Result<()> {
-    init_logs();
-    let proxy = system_proxy().await?;
+    let _serial = SERIAL.lock().await;
+    let Some((_harness, proxy)) = test_bus_proxy().await? else {
+        return Ok(());
+    };
 
     let val = proxy.even_ping(42).await?;
 
     info!("Value returned {}", val);
+    assert_eq!(val, 42);
     Ok(())
 }
 
-#[ignore = "need a connection to a service"]
 #[tokio::test]
 async fn test_even_ping_fail() -> zbus::Result<()> {
-    init_logs();
-    let proxy = system_proxy().await?;
+    let _serial = SERIAL.lock().await;
+    let Some((_harness, proxy)) = test_bus_proxy().await? else {
+        return Ok(());
+    };
 
     match proxy.even_ping(43).await {
         Ok(val) => {
             panic!("Should not succeed, got {}", val);
         }
-        Err(zbus::Error::MethodError(a, _b, _c)) => {
-            let inner: &zbus::names::ErrorName<'_> = a.inner();
-            info!("Expected error received: {}", inner);
+        Err(zbus::Error::MethodError(name, description, _reply)) => {
+            info!("Expected error received: {}", name);
+            assert_eq!(name.as_str(), "org.freedesktop.DBus.Error.Failed");
+            assert_eq!(description.as_deref(), Some("43 not even!"));
         }
         Err(e) => {
-            info!("Expected error received: {}", e);
+            panic!("Expected a method error, got {e:?}");
         }
     }
 
     Ok(())
 }
 
-#[ignore = "need a connection to a service"]
 #[tokio::test]
 async fn test_revert_unit_files() -> zbus::Result<()> {
-    init_logs();
-    let proxy = system_proxy().await?;
-
-    match proxy
-        .revert_unit_files(
-            UnitDBusLevel::UserSession.index(),
-            &["tiny_plr_daemon.service"],
-        )
-        .await
-    {
+    let _serial = SERIAL.lock().await;
+    let Some((_harness, proxy)) = test_bus_proxy().await? else {
+        return Ok(());
+    };
+
+    match proxy.revert_unit_files(&["not_there.service"]).await {
         Ok(val) => {
             panic!("Should not succeed, got {:?}", val);
         }
-
+        // The proxy forwards the manager error as its own
+        Err(zbus::Error::MethodError(name, description, _reply)) => {
+            info!("Expected error received: {}", name);
+            assert_eq!(name.as_str(), "org.freedesktop.zbus.Error");
+            assert_eq!(
+                description.as_deref(),
+                Some("Unit not_there.service not loaded.")
+            );
+        }
         Err(e) => {
-            info!("Expected error received: {}", e);
+            panic!("Expected a method error, got {e:?}");
         }
     }
 
+    let changes = proxy.revert_unit_files(&[TIMER_SERVICE]).await?;
+    info!("Reverted {:?}", changes);
+    assert_eq!(changes.len(), 1);
+    assert_eq!(changes[0].change_type, "unlink");
+    assert_eq!(changes[0].file_name, TIMER_SERVICE_DROP_IN);
+
+    // Nothing left to revert
+    let changes = proxy.revert_unit_files(&[TIMER_SERVICE]).await?;
+    assert!(changes.is_empty(), "{changes:?}");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_proxy_on_test_bus() -> zbus::Result<()> {
+    let _serial = SERIAL.lock().await;
+    let Some((harness, proxy)) = test_bus_proxy().await? else {
+        return Ok(());
+    };
+
+    let job = proxy.start_unit(TIMER_SERVICE, "fail").await?;
+    assert!(job.as_str().starts_with("/org/freedesktop/systemd1/job/"));
+    let unit = harness.systemd().unit(TIMER_SERVICE).expect("fake unit");
+    assert_eq!(unit.active_state, "active");
+
+    harness.polkit().set_authorized(false);
+    let denied = proxy.even_ping(44).await;
+    harness.polkit().set_authorized(true);
+    assert!(denied.is_err());
+
+    let checked_actions = harness.polkit().checked_actions();
+    assert!(
+        checked_actions
+            .iter()
+            .any(|action| action == "io.github.plrigaux.SysDManager.manage-units"),
+        "{checked_actions:?}"
+    );
+
     Ok(())
 }
@@ -0,0 +1,118 @@
+use std::{
+    io::{self, BufRead, BufReader},
+    os::unix::process::CommandExt,
+    path::PathBuf,
+    process::{Child, Command, Stdio},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+use tracing::{info, warn};
+
+const DBUS_DAEMON: &str = "dbus-daemon";
+
+/// Anyone can own a name and talk to anyone, the bus only lives for the tests
+const BUS_CONFIG: &str = r#"<!DOCTYPE busconfig PUBLIC "-//freedesktop//DTD D-Bus Bus Configuration 1.0//EN"
+ "http://www.freedesktop.org/standards/dbus/1.0/busconfig.dtd">
+<busconfig>
+  <type>session</type>
+  <listen>unix:tmpdir=/tmp</listen>
+  <auth>EXTERNAL</auth>
+  <policy context="default">
+    <allow send_destination="*" eavesdrop="true"/>
+    <allow eavesdrop="true"/>
+    <allow own="*"/>
+  </policy>
+</busconfig>
+"#;
+
+/// A `dbus-daemon` process private to the tests
+#[derive(Debug)]
+pub struct PrivateBus {
+    child: Child,
+    address: String,
+}
+
+impl PrivateBus {
+    /// Start the daemon, it gets killed with the thread calling this function
+    pub fn start() -> io::Result<Self> {
+        Self::spawn(None)
+    }
+
+    /// Start the daemon as another user, like a user session bus it then only accepts the
+    /// connections of that user
+    pub fn start_as(uid: u32, gid: u32) -> io::Result<Self> {
+        Self::spawn(Some((uid, gid)))
+    }
+
+    fn spawn(owner: Option<(u32, u32)>) -> io::Result<Self> {
+        let config_path = config_path();
+        std::fs::write(&config_path, BUS_CONFIG)?;
+
+        let mut command = Command::new(DBUS_DAEMON);
+        command
+            .arg(format!("--config-file={}", config_path.display()))
+            .arg("--nofork")
+            .arg("--print-address")
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit());
+
+        if let Some((uid, gid)) = owner {
+            command.uid(uid).gid(gid);
+        }
+
+        // No dbus-daemon left behind when the test process ends
+        unsafe {
+            command.pre_exec(|| {
+                libc::prctl(libc::PR_SET_PDEATHSIG, libc::SIGTERM);
+                Ok(())
+            });
+        }
+
+        let result = command.spawn().and_then(|mut child| {
+            let stdout = child
+                .stdout
+                .take()
+                .ok_or_else(|| io::Error::other("No dbus-daemon stdout"))?;
+
+            let mut address = String::new();
+            BufReader::new(stdout).read_line(&mut address)?;
+            let address = address.trim().to_owned();
+
+            if address.is_empty() {
+                let _ = child.kill();
+                return Err(io::Error::other("dbus-daemon did not print its address"));
+            }
+
+            Ok(PrivateBus { child, address })
+        });
+
+        if let Err(err) = std::fs::remove_file(&config_path) {
+            warn!("Remove {} {err:?}", config_path.display());
+        }
+
+        let bus = result?;
+        info!("Private bus {}", bus.address);
+        Ok(bus)
+    }
+
+    pub fn address(&self) -> &str {
+        &self.address
+    }
+}
+
+impl Drop for PrivateBus {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// One file per daemon, the tests of a process can start several
+fn config_path() -> PathBuf {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let index = COUNTER.fetch_add(1, Ordering::Relaxed);
+    std::env::temp_dir().join(format!(
+        "sysd-manager-test-bus-{}-{index}.conf",
+        std::process::id()
+    ))
+}
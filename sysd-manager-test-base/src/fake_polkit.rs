@@ -0,0 +1,78 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+use tracing::info;
+use zbus::{interface, zvariant::OwnedValue};
+
+pub const POLKIT_NAME: &str = "org.freedesktop.PolicyKit1";
+pub const POLKIT_PATH: &str = "/org/freedesktop/PolicyKit1/Authority";
+
+/// The answers of the fake authority and the actions it was asked for
+#[derive(Debug)]
+pub struct PolkitState {
+    pub authorized: bool,
+    pub checked_actions: Vec<String>,
+}
+
+impl Default for PolkitState {
+    fn default() -> Self {
+        PolkitState {
+            authorized: true,
+            checked_actions: Vec::new(),
+        }
+    }
+}
+
+/// `org.freedesktop.PolicyKit1.Authority`, authorizes every call unless told otherwise
+#[derive(Debug, Clone, Default)]
+pub struct FakePolkit {
+    state: Arc<Mutex<PolkitState>>,
+}
+
+impl FakePolkit {
+    pub fn set_authorized(&self, authorized: bool) {
+        self.state.lock().expect("polkit state").authorized = authorized;
+    }
+
+    pub fn checked_actions(&self) -> Vec<String> {
+        self.state
+            .lock()
+            .expect("polkit state")
+            .checked_actions
+            .clone()
+    }
+}
+
+#[interface(name = "org.freedesktop.PolicyKit1.Authority")]
+impl FakePolkit {
+    fn check_authorization(
+        &self,
+        _subject: (String, HashMap<String, OwnedValue>),
+        action_id: String,
+        details: HashMap<String, String>,
+        _flags: u32,
+        _cancellation_id: String,
+    ) -> (bool, bool, HashMap<String, String>) {
+        info!("Fake polkit {action_id} {details:?}");
+
+        let mut state = self.state.lock().expect("polkit state");
+        state.checked_actions.push(action_id);
+        (state.authorized, false, HashMap::new())
+    }
+
+    #[zbus(property)]
+    fn backend_name(&self) -> String {
+        "fake".to_owned()
+    }
+
+    #[zbus(property)]
+    fn backend_version(&self) -> String {
+        env!("CARGO_PKG_VERSION").to_owned()
+    }
+
+    #[zbus(property)]
+    fn backend_features(&self) -> u32 {
+        0
+    }
+}
@@ -0,0 +1,1006 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+use tracing::{info, warn};
+use zbus::{
+    Connection, DBusError, interface,
+    names::BusName,
+    zvariant::{ObjectPath, OwnedObjectPath, Value},
+};
+
+pub const SYSTEMD_NAME: &str = "org.freedesktop.systemd1";
+pub const MANAGER_PATH: &str = "/org/freedesktop/systemd1";
+const MANAGER_INTERFACE: &str = "org.freedesktop.systemd1.Manager";
+const UNIT_INTERFACE: &str = "org.freedesktop.systemd1.Unit";
+const SERVICE_SUFFIX: &str = ".service";
+const PROPERTIES_INTERFACE: &str = "org.freedesktop.DBus.Properties";
+const UNIT_PATH_PREFIX: &str = "/org/freedesktop/systemd1/unit/";
+const JOB_PATH_PREFIX: &str = "/org/freedesktop/systemd1/job/";
+
+pub const JOB_DONE: &str = "done";
+pub const JOB_FAILED: &str = "failed";
+
+/// ListUnits entry: name, description, load, active, sub, following, unit path, job id, job type, job path
+type ListedUnit = (
+    String,
+    String,
+    String,
+    String,
+    String,
+    String,
+    OwnedObjectPath,
+    u32,
+    String,
+    OwnedObjectPath,
+);
+
+/// Unit file change: type, file name, destination
+type UnitFileChange = (String, String, String);
+
+/// GetUnitProcesses entry: cgroup path, pid, command line
+type UnitProcess = (String, u32, String);
+
+#[derive(Debug, DBusError)]
+#[zbus(prefix = "org.freedesktop.systemd1")]
+pub enum SystemdError {
+    #[zbus(error)]
+    ZBus(zbus::Error),
+    NoSuchUnit(String),
+}
+
+/// A unit known by the fake manager
+#[derive(Debug, Clone, PartialEq)]
+pub struct FakeUnit {
+    pub name: String,
+    pub description: String,
+    pub load_state: String,
+    pub active_state: String,
+    pub sub_state: String,
+    pub fragment_path: String,
+    pub unit_file_state: String,
+    pub unit_file_preset: String,
+    pub drop_in_paths: Vec<String>,
+    /// JobRemoved result of the start and restart jobs
+    pub start_result: String,
+    pub requires: Vec<String>,
+    pub wants: Vec<String>,
+    pub after: Vec<String>,
+    /// Main process first: pid and command line
+    pub processes: Vec<(u32, String)>,
+}
+
+impl FakeUnit {
+    /// A loaded, inactive and enabled unit
+    pub fn new(name: &str) -> Self {
+        FakeUnit {
+            name: name.to_owned(),
+            description: format!("Fake {name}"),
+            load_state: "loaded".to_owned(),
+            active_state: "inactive".to_owned(),
+            sub_state: "dead".to_owned(),
+            fragment_path: format!("/etc/systemd/system/{name}"),
+            unit_file_state: "enabled".to_owned(),
+            unit_file_preset: "enabled".to_owned(),
+            drop_in_paths: Vec::new(),
+            start_result: JOB_DONE.to_owned(),
+            requires: Vec::new(),
+            wants: Vec::new(),
+            after: Vec::new(),
+            processes: Vec::new(),
+        }
+    }
+
+    pub fn active(mut self, sub_state: &str) -> Self {
+        self.active_state = "active".to_owned();
+        self.sub_state = sub_state.to_owned();
+        self
+    }
+
+    /// Its start jobs end with the `failed` result
+    pub fn failing(mut self) -> Self {
+        self.start_result = JOB_FAILED.to_owned();
+        self
+    }
+
+    pub fn unit_file_state(mut self, unit_file_state: &str) -> Self {
+        self.unit_file_state = unit_file_state.to_owned();
+        self
+    }
+
+    pub fn drop_in(mut self, path: &str) -> Self {
+        self.drop_in_paths.push(path.to_owned());
+        self
+    }
+
+    pub fn requires(mut self, names: &[&str]) -> Self {
+        self.requires = to_strings(names);
+        self
+    }
+
+    pub fn wants(mut self, names: &[&str]) -> Self {
+        self.wants = to_strings(names);
+        self
+    }
+
+    pub fn after(mut self, names: &[&str]) -> Self {
+        self.after = to_strings(names);
+        self
+    }
+
+    pub fn process(mut self, pid: u32, command: &str) -> Self {
+        self.processes.push((pid, command.to_owned()));
+        self
+    }
+
+    pub fn object_path(&self) -> String {
+        unit_object_path(&self.name)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum JobKind {
+    Start,
+    Stop,
+    Restart,
+    Reload,
+}
+
+impl JobKind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            JobKind::Start => "start",
+            JobKind::Stop => "stop",
+            JobKind::Restart => "restart",
+            JobKind::Reload => "reload",
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct SystemdState {
+    units: Vec<FakeUnit>,
+    last_job_id: u32,
+    /// Jobs queued since the start: id, unit and kind
+    jobs: Vec<(u32, String, String)>,
+    /// Signals sent since the start: unit, who and signal
+    signals: Vec<(String, String, i32)>,
+    /// Cleanings since the start: unit and resources
+    cleanings: Vec<(String, Vec<String>)>,
+    /// Unit file state of the masked units before their masking
+    masked: HashMap<String, String>,
+}
+
+/// Scripted `org.freedesktop.systemd1` manager and unit objects, the units change state with
+/// the jobs and the JobNew, JobRemoved and PropertiesChanged signals are emitted
+#[derive(Debug, Clone, Default)]
+pub struct FakeSystemd {
+    state: Arc<Mutex<SystemdState>>,
+}
+
+impl FakeSystemd {
+    pub fn with_units(units: Vec<FakeUnit>) -> Self {
+        let fake = FakeSystemd::default();
+        fake.state.lock().expect("systemd state").units = units;
+        fake
+    }
+
+    pub fn unit(&self, name: &str) -> Option<FakeUnit> {
+        self.state
+            .lock()
+            .expect("systemd state")
+            .units
+            .iter()
+            .find(|unit| unit.name == name)
+            .cloned()
+    }
+
+    /// The jobs queued so far: id, unit and job type
+    pub fn jobs(&self) -> Vec<(u32, String, String)> {
+        self.state.lock().expect("systemd state").jobs.clone()
+    }
+
+    /// The signals sent so far: unit, who and signal
+    pub fn signals(&self) -> Vec<(String, String, i32)> {
+        self.state.lock().expect("systemd state").signals.clone()
+    }
+
+    /// The cleanings so far: unit and resources
+    pub fn cleanings(&self) -> Vec<(String, Vec<String>)> {
+        self.state.lock().expect("systemd state").cleanings.clone()
+    }
+
+    /// Serve the manager and the unit objects on the connection
+    pub async fn serve(&self, connection: &Connection) -> zbus::Result<()> {
+        let object_server = connection.object_server();
+        object_server
+            .at(
+                MANAGER_PATH,
+                ManagerObject {
+                    systemd: self.clone(),
+                },
+            )
+            .await?;
+
+        let names: Vec<String> = self
+            .state
+            .lock()
+            .expect("systemd state")
+            .units
+            .iter()
+            .map(|unit| unit.name.clone())
+            .collect();
+
+        for name in names {
+            self.serve_unit(connection, name).await?;
+        }
+        Ok(())
+    }
+
+    /// Add a unit while the tests run, with the UnitNew signal
+    pub async fn add_unit(&self, connection: &Connection, unit: FakeUnit) -> zbus::Result<()> {
+        let name = unit.name.clone();
+        let path = unit.object_path();
+        {
+            let mut state = self.state.lock().expect("systemd state");
+            state.units.retain(|known| known.name != name);
+            state.units.push(unit);
+        }
+
+        self.serve_unit(connection, name.clone()).await?;
+        emit(
+            connection,
+            MANAGER_PATH,
+            MANAGER_INTERFACE,
+            "UnitNew",
+            &(name, object_path(path)),
+        )
+        .await
+    }
+
+    async fn serve_unit(&self, connection: &Connection, name: String) -> zbus::Result<()> {
+        let path = unit_object_path(&name);
+        let object_server = connection.object_server();
+
+        if name.ends_with(SERVICE_SUFFIX) {
+            object_server
+                .at(
+                    path.as_str(),
+                    ServiceObject {
+                        systemd: self.clone(),
+                        name: name.clone(),
+                    },
+                )
+                .await?;
+        }
+
+        object_server
+            .at(
+                path,
+                UnitObject {
+                    systemd: self.clone(),
+                    name,
+                },
+            )
+            .await?;
+        Ok(())
+    }
+
+    fn with_unit<T>(
+        &self,
+        name: &str,
+        read: impl FnOnce(&FakeUnit) -> T,
+    ) -> Result<T, SystemdError> {
+        let state = self.state.lock().expect("systemd state");
+        state
+            .units
+            .iter()
+            .find(|unit| unit.name == name)
+            .map(read)
+            .ok_or_else(|| no_such_unit(name))
+    }
+
+    fn with_unit_mut<T>(
+        &self,
+        name: &str,
+        change: impl FnOnce(&mut FakeUnit) -> T,
+    ) -> Result<T, SystemdError> {
+        let mut state = self.state.lock().expect("systemd state");
+        state
+            .units
+            .iter_mut()
+            .find(|unit| unit.name == name)
+            .map(change)
+            .ok_or_else(|| no_such_unit(name))
+    }
+
+    /// The units naming `name` in the `forward` dependency list
+    fn reverse_dependencies(
+        &self,
+        name: &str,
+        forward: impl Fn(&FakeUnit) -> &Vec<String>,
+    ) -> Vec<String> {
+        let state = self.state.lock().expect("systemd state");
+        state
+            .units
+            .iter()
+            .filter(|unit| forward(unit).iter().any(|dependency| dependency == name))
+            .map(|unit| unit.name.clone())
+            .collect()
+    }
+
+    fn signal(&self, name: &str, who: &str, signal: i32) -> Result<(), SystemdError> {
+        self.with_unit(name, |_| ())?;
+        info!("Fake signal {signal} to {who} of {name}");
+        self.state.lock().expect("systemd state").signals.push((
+            name.to_owned(),
+            who.to_owned(),
+            signal,
+        ));
+        Ok(())
+    }
+
+    fn clean(&self, name: &str, what: Vec<String>) -> Result<(), SystemdError> {
+        self.with_unit(name, |_| ())?;
+        info!("Fake clean {what:?} of {name}");
+        self.state
+            .lock()
+            .expect("systemd state")
+            .cleanings
+            .push((name.to_owned(), what));
+        Ok(())
+    }
+
+    /// Link the unit files to `/dev/null`, their state is kept to unmask them
+    fn mask(&self, files: &[String], runtime: bool) -> Result<Vec<UnitFileChange>, SystemdError> {
+        let mut changes = Vec::new();
+        for file in files {
+            let name = file_unit_name(file);
+            let previous = self.with_unit_mut(name, |unit| {
+                std::mem::replace(&mut unit.unit_file_state, masked_state(runtime).to_owned())
+            })?;
+
+            let mut state = self.state.lock().expect("systemd state");
+            if !state.masked.contains_key(name) {
+                state.masked.insert(name.to_owned(), previous);
+            }
+
+            changes.push((
+                "symlink".to_owned(),
+                unit_file_path(name, runtime),
+                "/dev/null".to_owned(),
+            ));
+        }
+        Ok(changes)
+    }
+
+    fn unmask(&self, files: &[String], runtime: bool) -> Result<Vec<UnitFileChange>, SystemdError> {
+        let mut changes = Vec::new();
+        for file in files {
+            let name = file_unit_name(file);
+            let masked =
+                self.with_unit(name, |unit| unit.unit_file_state == masked_state(runtime))?;
+            if !masked {
+                continue;
+            }
+
+            let previous = self
+                .state
+                .lock()
+                .expect("systemd state")
+                .masked
+                .remove(name)
+                .unwrap_or_default();
+            self.with_unit_mut(name, |unit| unit.unit_file_state = previous)?;
+
+            changes.push((
+                "unlink".to_owned(),
+                unit_file_path(name, runtime),
+                String::new(),
+            ));
+        }
+        Ok(changes)
+    }
+
+    /// Remove the drop-ins of the units
+    fn revert(&self, files: &[String]) -> Result<Vec<UnitFileChange>, SystemdError> {
+        let mut changes = Vec::new();
+        for file in files {
+            let drop_ins = self.with_unit_mut(file_unit_name(file), |unit| {
+                std::mem::take(&mut unit.drop_in_paths)
+            })?;
+
+            changes.extend(
+                drop_ins
+                    .into_iter()
+                    .map(|path| ("unlink".to_owned(), path, String::new())),
+            );
+        }
+        Ok(changes)
+    }
+
+    fn processes(&self, name: &str) -> Result<Vec<UnitProcess>, SystemdError> {
+        self.with_unit(name, |unit| {
+            unit.processes
+                .iter()
+                .map(|(pid, command)| (format!("/system.slice/{name}"), *pid, command.clone()))
+                .collect()
+        })
+    }
+
+    fn listed_units(&self, keep: impl Fn(&FakeUnit) -> bool) -> Vec<ListedUnit> {
+        let state = self.state.lock().expect("systemd state");
+        state
+            .units
+            .iter()
+            .filter(|unit| keep(unit))
+            .map(|unit| {
+                (
+                    unit.name.clone(),
+                    unit.description.clone(),
+                    unit.load_state.clone(),
+                    unit.active_state.clone(),
+                    unit.sub_state.clone(),
+                    String::new(),
+                    object_path(unit.object_path()),
+                    0,
+                    String::new(),
+                    object_path("/".to_owned()),
+                )
+            })
+            .collect()
+    }
+
+    fn listed_unit_files(&self, keep: impl Fn(&FakeUnit) -> bool) -> Vec<(String, String)> {
+        let state = self.state.lock().expect("systemd state");
+        state
+            .units
+            .iter()
+            .filter(|unit| keep(unit))
+            .map(|unit| (unit.fragment_path.clone(), unit.unit_file_state.clone()))
+            .collect()
+    }
+
+    /// Apply the job to the unit right away, the signals follow the method reply
+    async fn queue_job(
+        &self,
+        connection: &Connection,
+        name: &str,
+        kind: JobKind,
+    ) -> Result<OwnedObjectPath, SystemdError> {
+        let (id, result, unit) = {
+            let mut state = self.state.lock().expect("systemd state");
+            state.last_job_id += 1;
+            let id = state.last_job_id;
+            state
+                .jobs
+                .push((id, name.to_owned(), kind.as_str().to_owned()));
+
+            let unit = state
+                .units
+                .iter_mut()
+                .find(|unit| unit.name == name)
+                .ok_or_else(|| no_such_unit(name))?;
+
+            let result = match kind {
+                JobKind::Start | JobKind::Restart if unit.start_result == JOB_DONE => {
+                    unit.active_state = "active".to_owned();
+                    unit.sub_state = "running".to_owned();
+                    JOB_DONE
+                }
+                JobKind::Start | JobKind::Restart => {
+                    unit.active_state = "failed".to_owned();
+                    unit.sub_state = "failed".to_owned();
+                    JOB_FAILED
+                }
+                JobKind::Stop => {
+                    unit.active_state = "inactive".to_owned();
+                    unit.sub_state = "dead".to_owned();
+                    JOB_DONE
+                }
+                JobKind::Reload => JOB_DONE,
+            };
+
+            (id, result, unit.clone())
+        };
+
+        info!("Fake job {id} {} {name} {result}", kind.as_str());
+
+        let job_path = format!("{JOB_PATH_PREFIX}{id}");
+        let connection = connection.clone();
+        let signal_job_path = job_path.clone();
+        tokio::spawn(async move {
+            if let Err(err) = job_signals(&connection, id, signal_job_path, &unit, result).await {
+                warn!("Fake job signals {err:?}");
+            }
+        });
+
+        Ok(object_path(job_path))
+    }
+}
+
+async fn job_signals(
+    connection: &Connection,
+    id: u32,
+    job_path: String,
+    unit: &FakeUnit,
+    result: &str,
+) -> zbus::Result<()> {
+    emit(
+        connection,
+        MANAGER_PATH,
+        MANAGER_INTERFACE,
+        "JobNew",
+        &(id, object_path(job_path.clone()), unit.name.as_str()),
+    )
+    .await?;
+
+    let changed = HashMap::from([
+        ("ActiveState", Value::from(unit.active_state.as_str())),
+        ("SubState", Value::from(unit.sub_state.as_str())),
+    ]);
+    emit(
+        connection,
+        &unit.object_path(),
+        PROPERTIES_INTERFACE,
+        "PropertiesChanged",
+        &(UNIT_INTERFACE, changed, Vec::<&str>::new()),
+    )
+    .await?;
+
+    emit(
+        connection,
+        MANAGER_PATH,
+        MANAGER_INTERFACE,
+        "JobRemoved",
+        &(id, object_path(job_path), unit.name.as_str(), result),
+    )
+    .await
+}
+
+async fn emit<B>(
+    connection: &Connection,
+    path: &str,
+    interface: &str,
+    signal_name: &str,
+    body: &B,
+) -> zbus::Result<()>
+where
+    B: serde::ser::Serialize + zbus::zvariant::DynamicType,
+{
+    connection
+        .emit_signal(None::<BusName<'_>>, path, interface, signal_name, body)
+        .await
+}
+
+struct ManagerObject {
+    systemd: FakeSystemd,
+}
+
+#[interface(name = "org.freedesktop.systemd1.Manager")]
+impl ManagerObject {
+    fn list_units(&self) -> Vec<ListedUnit> {
+        self.systemd.listed_units(|_| true)
+    }
+
+    fn list_units_filtered(&self, states: Vec<String>) -> Vec<ListedUnit> {
+        self.systemd
+            .listed_units(|unit| states.is_empty() || has_state(unit, &states))
+    }
+
+    fn list_units_by_patterns(
+        &self,
+        states: Vec<String>,
+        patterns: Vec<String>,
+    ) -> Vec<ListedUnit> {
+        self.systemd.listed_units(|unit| {
+            (states.is_empty() || has_state(unit, &states)) && matches_any(&unit.name, &patterns)
+        })
+    }
+
+    fn list_units_by_names(&self, names: Vec<String>) -> Vec<ListedUnit> {
+        self.systemd.listed_units(|unit| names.contains(&unit.name))
+    }
+
+    fn list_unit_files(&self) -> Vec<(String, String)> {
+        self.systemd.listed_unit_files(|_| true)
+    }
+
+    fn list_unit_files_by_patterns(
+        &self,
+        states: Vec<String>,
+        patterns: Vec<String>,
+    ) -> Vec<(String, String)> {
+        self.systemd.listed_unit_files(|unit| {
+            (states.is_empty() || states.contains(&unit.unit_file_state))
+                && matches_any(&unit.name, &patterns)
+        })
+    }
+
+    fn get_unit(&self, name: String) -> Result<OwnedObjectPath, SystemdError> {
+        self.systemd
+            .with_unit(&name, |unit| object_path(unit.object_path()))
+    }
+
+    fn load_unit(&self, name: String) -> Result<OwnedObjectPath, SystemdError> {
+        self.get_unit(name)
+    }
+
+    fn get_unit_file_state(&self, file: String) -> Result<String, SystemdError> {
+        self.systemd
+            .with_unit(file_unit_name(&file), |unit| unit.unit_file_state.clone())
+    }
+
+    async fn start_unit(
+        &self,
+        #[zbus(connection)] connection: &Connection,
+        name: String,
+        _mode: String,
+    ) -> Result<OwnedObjectPath, SystemdError> {
+        self.systemd
+            .queue_job(connection, &name, JobKind::Start)
+            .await
+    }
+
+    async fn stop_unit(
+        &self,
+        #[zbus(connection)] connection: &Connection,
+        name: String,
+        _mode: String,
+    ) -> Result<OwnedObjectPath, SystemdError> {
+        self.systemd
+            .queue_job(connection, &name, JobKind::Stop)
+            .await
+    }
+
+    async fn restart_unit(
+        &self,
+        #[zbus(connection)] connection: &Connection,
+        name: String,
+        _mode: String,
+    ) -> Result<OwnedObjectPath, SystemdError> {
+        self.systemd
+            .queue_job(connection, &name, JobKind::Restart)
+            .await
+    }
+
+    async fn reload_unit(
+        &self,
+        #[zbus(connection)] connection: &Connection,
+        name: String,
+        _mode: String,
+    ) -> Result<OwnedObjectPath, SystemdError> {
+        self.systemd
+            .queue_job(connection, &name, JobKind::Reload)
+            .await
+    }
+
+    fn kill_unit(&self, name: String, who: String, signal: i32) -> Result<(), SystemdError> {
+        self.systemd.signal(&name, &who, signal)
+    }
+
+    fn queue_signal_unit(
+        &self,
+        name: String,
+        who: String,
+        signal: i32,
+        _value: i32,
+    ) -> Result<(), SystemdError> {
+        self.systemd.signal(&name, &who, signal)
+    }
+
+    fn clean_unit(&self, name: String, what: Vec<String>) -> Result<(), SystemdError> {
+        self.systemd.clean(&name, what)
+    }
+
+    fn mask_unit_files(
+        &self,
+        files: Vec<String>,
+        runtime: bool,
+        _force: bool,
+    ) -> Result<Vec<UnitFileChange>, SystemdError> {
+        self.systemd.mask(&files, runtime)
+    }
+
+    fn unmask_unit_files(
+        &self,
+        files: Vec<String>,
+        runtime: bool,
+    ) -> Result<Vec<UnitFileChange>, SystemdError> {
+        self.systemd.unmask(&files, runtime)
+    }
+
+    fn revert_unit_files(&self, files: Vec<String>) -> Result<Vec<UnitFileChange>, SystemdError> {
+        self.systemd.revert(&files)
+    }
+
+    fn get_unit_processes(&self, name: String) -> Result<Vec<UnitProcess>, SystemdError> {
+        self.systemd.processes(&name)
+    }
+
+    fn subscribe(&self) {}
+
+    fn unsubscribe(&self) {}
+
+    async fn reload(&self, #[zbus(connection)] connection: &Connection) -> zbus::fdo::Result<()> {
+        for active in [true, false] {
+            emit(
+                connection,
+                MANAGER_PATH,
+                MANAGER_INTERFACE,
+                "Reloading",
+                &active,
+            )
+            .await?;
+        }
+        Ok(())
+    }
+
+    #[zbus(property)]
+    fn version(&self) -> String {
+        "fake".to_owned()
+    }
+
+    #[zbus(property)]
+    fn log_level(&self) -> String {
+        "info".to_owned()
+    }
+}
+
+struct UnitObject {
+    systemd: FakeSystemd,
+    name: String,
+}
+
+impl UnitObject {
+    fn read<T: Default>(&self, read: impl FnOnce(&FakeUnit) -> T) -> T {
+        self.systemd.with_unit(&self.name, read).unwrap_or_default()
+    }
+}
+
+#[interface(name = "org.freedesktop.systemd1.Unit")]
+impl UnitObject {
+    #[zbus(property)]
+    fn id(&self) -> String {
+        self.name.clone()
+    }
+
+    #[zbus(property)]
+    fn description(&self) -> String {
+        self.read(|unit| unit.description.clone())
+    }
+
+    #[zbus(property)]
+    fn load_state(&self) -> String {
+        self.read(|unit| unit.load_state.clone())
+    }
+
+    #[zbus(property)]
+    fn active_state(&self) -> String {
+        self.read(|unit| unit.active_state.clone())
+    }
+
+    #[zbus(property)]
+    fn sub_state(&self) -> String {
+        self.read(|unit| unit.sub_state.clone())
+    }
+
+    #[zbus(property)]
+    fn following(&self) -> String {
+        String::new()
+    }
+
+    #[zbus(property)]
+    fn fragment_path(&self) -> String {
+        self.read(|unit| unit.fragment_path.clone())
+    }
+
+    #[zbus(property)]
+    fn unit_file_state(&self) -> String {
+        self.read(|unit| unit.unit_file_state.clone())
+    }
+
+    #[zbus(property)]
+    fn unit_file_preset(&self) -> String {
+        self.read(|unit| unit.unit_file_preset.clone())
+    }
+
+    #[zbus(property)]
+    fn drop_in_paths(&self) -> Vec<String> {
+        self.read(|unit| unit.drop_in_paths.clone())
+    }
+
+    #[zbus(property)]
+    fn requires(&self) -> Vec<String> {
+        self.read(|unit| unit.requires.clone())
+    }
+
+    #[zbus(property)]
+    fn wants(&self) -> Vec<String> {
+        self.read(|unit| unit.wants.clone())
+    }
+
+    #[zbus(property)]
+    fn after(&self) -> Vec<String> {
+        self.read(|unit| unit.after.clone())
+    }
+
+    #[zbus(property)]
+    fn required_by(&self) -> Vec<String> {
+        self.systemd
+            .reverse_dependencies(&self.name, |unit| &unit.requires)
+    }
+
+    #[zbus(property)]
+    fn wanted_by(&self) -> Vec<String> {
+        self.systemd
+            .reverse_dependencies(&self.name, |unit| &unit.wants)
+    }
+
+    #[zbus(property)]
+    fn before(&self) -> Vec<String> {
+        self.systemd
+            .reverse_dependencies(&self.name, |unit| &unit.after)
+    }
+
+    fn kill(&self, who: String, signal: i32) -> Result<(), SystemdError> {
+        self.systemd.signal(&self.name, &who, signal)
+    }
+
+    fn clean(&self, what: Vec<String>) -> Result<(), SystemdError> {
+        self.systemd.clean(&self.name, what)
+    }
+
+    async fn start(
+        &self,
+        #[zbus(connection)] connection: &Connection,
+        _mode: String,
+    ) -> Result<OwnedObjectPath, SystemdError> {
+        self.systemd
+            .queue_job(connection, &self.name, JobKind::Start)
+            .await
+    }
+
+    async fn stop(
+        &self,
+        #[zbus(connection)] connection: &Connection,
+        _mode: String,
+    ) -> Result<OwnedObjectPath, SystemdError> {
+        self.systemd
+            .queue_job(connection, &self.name, JobKind::Stop)
+            .await
+    }
+
+    async fn restart(
+        &self,
+        #[zbus(connection)] connection: &Connection,
+        _mode: String,
+    ) -> Result<OwnedObjectPath, SystemdError> {
+        self.systemd
+            .queue_job(connection, &self.name, JobKind::Restart)
+            .await
+    }
+}
+
+/// The service part of the `.service` unit objects
+struct ServiceObject {
+    systemd: FakeSystemd,
+    name: String,
+}
+
+#[interface(name = "org.freedesktop.systemd1.Service")]
+impl ServiceObject {
+    #[zbus(property, name = "Type")]
+    fn service_type(&self) -> String {
+        "simple".to_owned()
+    }
+
+    #[zbus(property)]
+    fn restart(&self) -> String {
+        "no".to_owned()
+    }
+
+    #[zbus(property, name = "MainPID")]
+    fn main_pid(&self) -> u32 {
+        self.systemd
+            .with_unit(&self.name, |unit| {
+                unit.processes.first().map(|(pid, _)| *pid).unwrap_or(0)
+            })
+            .unwrap_or_default()
+    }
+}
+
+fn no_such_unit(name: &str) -> SystemdError {
+    SystemdError::NoSuchUnit(format!("Unit {name} not loaded."))
+}
+
+fn to_strings(names: &[&str]) -> Vec<String> {
+    names.iter().map(|name| (*name).to_owned()).collect()
+}
+
+/// The unit name of a unit file name or path
+fn file_unit_name(file: &str) -> &str {
+    file.rsplit('/').next().unwrap_or(file)
+}
+
+fn masked_state(runtime: bool) -> &'static str {
+    if runtime { "masked-runtime" } else { "masked" }
+}
+
+fn unit_file_path(name: &str, runtime: bool) -> String {
+    let dir = if runtime {
+        "/run/systemd/system"
+    } else {
+        "/etc/systemd/system"
+    };
+    format!("{dir}/{name}")
+}
+
+fn object_path(path: String) -> OwnedObjectPath {
+    ObjectPath::from_string_unchecked(path).into()
+}
+
+fn has_state(unit: &FakeUnit, states: &[String]) -> bool {
+    states.iter().any(|state| {
+        *state == unit.load_state || *state == unit.active_state || *state == unit.sub_state
+    })
+}
+
+fn matches_any(name: &str, patterns: &[String]) -> bool {
+    patterns.is_empty() || patterns.iter().any(|pattern| glob_match(pattern, name))
+}
+
+/// Shell like pattern with `*` and `?`, as systemd accepts for the unit names
+fn glob_match(pattern: &str, text: &str) -> bool {
+    fn matches(pattern: &[u8], text: &[u8]) -> bool {
+        match pattern.split_first() {
+            None => text.is_empty(),
+            Some((b'*', rest)) => (0..=text.len()).any(|skip| matches(rest, &text[skip..])),
+            Some((b'?', rest)) => !text.is_empty() && matches(rest, &text[1..]),
+            Some((c, rest)) => text.first() == Some(c) && matches(rest, &text[1..]),
+        }
+    }
+
+    matches(pattern.as_bytes(), text.as_bytes())
+}
+
+/// Same escaping as systemd for the unit object paths, the digits after the first
+/// character are kept
+pub fn unit_object_path(name: &str) -> String {
+    let mut path = String::from(UNIT_PATH_PREFIX);
+
+    if name.is_empty() {
+        path.push('_');
+        return path;
+    }
+
+    for (idx, byte) in name.bytes().enumerate() {
+        if byte.is_ascii_alphabetic() || (idx != 0 && byte.is_ascii_digit()) {
+            path.push(byte as char);
+        } else {
+            path.push_str(&format!("_{byte:02x}"));
+        }
+    }
+
+    path
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("*.service", "tiny_daemon.service"));
+        assert!(glob_match("tiny_?aemon.*", "tiny_daemon.service"));
+        assert!(!glob_match("*.timer", "tiny_daemon.service"));
+    }
+
+    #[test]
+    fn test_unit_object_path() {
+        assert_eq!(
+            unit_object_path("tiny_daemon.service"),
+            "/org/freedesktop/systemd1/unit/tiny_5fdaemon_2eservice"
+        );
+    }
+}
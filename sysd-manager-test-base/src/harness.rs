@@ -0,0 +1,182 @@
+use crate::{
+    TEST_SERVICE,
+    dbus_daemon::PrivateBus,
+    fake_polkit::{FakePolkit, POLKIT_NAME, POLKIT_PATH},
+    fake_systemd::{FakeSystemd, FakeUnit, SYSTEMD_NAME},
+    init_logs,
+};
+use std::{future::pending, sync::OnceLock};
+use tracing::warn;
+use zbus::Connection;
+
+/// Started once, the tests of a process share the bus and the fakes
+static HARNESS: OnceLock<Option<TestHarness>> = OnceLock::new();
+
+pub const FAILING_SERVICE: &str = "fake_failing.service";
+pub const ACTIVE_SERVICE: &str = "fake_active.service";
+pub const TEST_TIMER: &str = "fake_backup.timer";
+pub const TIMER_SERVICE: &str = "fake_backup.service";
+/// Masked and unmasked by the tests, the other units keep their unit file state
+pub const MASK_SERVICE: &str = "fake_mask.service";
+pub const ACTIVE_SERVICE_PID: u32 = 4242;
+pub const TIMER_SERVICE_DROP_IN: &str = "/etc/systemd/system/fake_backup.service.d/override.conf";
+/// Set it to skip the private bus tests where `dbus-daemon` is not installed
+pub const SKIP_BUS_TESTS_ENV: &str = "SYSD_MANAGER_SKIP_BUS_TESTS";
+
+/// A private bus serving a fake systemd manager and a fake polkit authority
+#[derive(Debug)]
+pub struct TestHarness {
+    address: String,
+    systemd: FakeSystemd,
+    polkit: FakePolkit,
+}
+
+impl TestHarness {
+    /// Address of the private bus, to use as the system and the session bus
+    pub fn address(&self) -> &str {
+        &self.address
+    }
+
+    /// Environment pointing a child process to the private bus
+    pub fn bus_env(&self) -> [(&'static str, &str); 2] {
+        [
+            ("DBUS_SYSTEM_BUS_ADDRESS", &self.address),
+            ("DBUS_SESSION_BUS_ADDRESS", &self.address),
+        ]
+    }
+
+    pub async fn connect(&self) -> zbus::Result<Connection> {
+        zbus::connection::Builder::address(self.address.as_str())?
+            .build()
+            .await
+    }
+
+    pub fn systemd(&self) -> &FakeSystemd {
+        &self.systemd
+    }
+
+    pub fn polkit(&self) -> &FakePolkit {
+        &self.polkit
+    }
+}
+
+/// The units served by the fake manager at start
+pub fn default_units() -> Vec<FakeUnit> {
+    vec![
+        FakeUnit::new(TEST_SERVICE)
+            .requires(&[ACTIVE_SERVICE])
+            .after(&[ACTIVE_SERVICE]),
+        FakeUnit::new(ACTIVE_SERVICE)
+            .active("running")
+            .wants(&[TIMER_SERVICE])
+            .process(ACTIVE_SERVICE_PID, "/usr/bin/fake_active --serve"),
+        FakeUnit::new(FAILING_SERVICE).failing(),
+        FakeUnit::new(TEST_TIMER).active("waiting"),
+        FakeUnit::new(TIMER_SERVICE)
+            .unit_file_state("static")
+            .drop_in(TIMER_SERVICE_DROP_IN),
+        FakeUnit::new(MASK_SERVICE),
+    ]
+}
+
+/// Start the harness on the first call.
+///
+/// Panics when no `dbus-daemon` can be started, so a missing harness fails the tests
+/// instead of passing them. `None` only when `SKIP_BUS_TESTS_ENV` is set.
+pub fn harness() -> Option<&'static TestHarness> {
+    let harness = HARNESS.get_or_init(start_harness).as_ref();
+
+    if harness.is_none() {
+        if std::env::var_os(SKIP_BUS_TESTS_ENV).is_none() {
+            panic!(
+                "Can't start the private test bus, install dbus-daemon or set {SKIP_BUS_TESTS_ENV} to skip the bus tests"
+            );
+        }
+        warn!("No private bus, {SKIP_BUS_TESTS_ENV} set, test skipped");
+    }
+
+    harness
+}
+
+fn start_harness() -> Option<TestHarness> {
+    init_logs();
+
+    let systemd = FakeSystemd::with_units(default_units());
+    let polkit = FakePolkit::default();
+    let (sender, receiver) = std::sync::mpsc::channel();
+
+    {
+        let systemd = systemd.clone();
+        let polkit = polkit.clone();
+
+        // The thread lives as long as the process, the bus dies with it
+        let spawned = std::thread::Builder::new()
+            .name("test-bus".to_owned())
+            .spawn(move || {
+                let runtime = match tokio::runtime::Builder::new_current_thread()
+                    .enable_all()
+                    .build()
+                {
+                    Ok(runtime) => runtime,
+                    Err(err) => {
+                        warn!("Test bus runtime {err:?}");
+                        let _ = sender.send(None);
+                        return;
+                    }
+                };
+
+                runtime.block_on(async move {
+                    let bus = match PrivateBus::start() {
+                        Ok(bus) => bus,
+                        Err(err) => {
+                            warn!("Can't start the private bus {err:?}");
+                            let _ = sender.send(None);
+                            return;
+                        }
+                    };
+
+                    let connection = match serve(bus.address(), &systemd, &polkit).await {
+                        Ok(connection) => connection,
+                        Err(err) => {
+                            warn!("Can't serve the fakes {err:?}");
+                            let _ = sender.send(None);
+                            return;
+                        }
+                    };
+
+                    let _ = sender.send(Some(bus.address().to_owned()));
+                    let _keep = (bus, connection);
+                    pending::<()>().await;
+                });
+            });
+
+        if let Err(err) = spawned {
+            warn!("Can't spawn the test bus thread {err:?}");
+            return None;
+        }
+    }
+
+    let address = receiver.recv().ok().flatten()?;
+
+    Some(TestHarness {
+        address,
+        systemd,
+        polkit,
+    })
+}
+
+async fn serve(
+    address: &str,
+    systemd: &FakeSystemd,
+    polkit: &FakePolkit,
+) -> zbus::Result<Connection> {
+    let connection = zbus::connection::Builder::address(address)?
+        .name(SYSTEMD_NAME)?
+        .name(POLKIT_NAME)?
+        .serve_at(POLKIT_PATH, polkit.clone())?
+        .build()
+        .await?;
+
+    systemd.serve(&connection).await?;
+    Ok(connection)
+}
@@ -1,7 +1,14 @@
+pub mod dbus_daemon;
+pub mod fake_polkit;
+pub mod fake_systemd;
+pub mod harness;
+
 use std::sync::OnceLock;
 
 use tracing::{level_filters::LevelFilter, warn};
 
+pub use harness::{TestHarness, harness};
+
 pub const TEST_SERVICE: &str = "tiny_daemon.service";
 
 //Too avoid duplicate init during series os tests